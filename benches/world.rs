@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sceller::prelude::*;
+
+#[derive(Default)]
+struct Position(f32, f32);
+#[derive(Default)]
+struct Velocity(f32, f32);
+#[derive(Default)]
+struct Health(u32);
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+// runs 'measure' on a fresh bench 'iters' times, adding up only the time of the operation measured
+fn total(iters: u64, mut measure: impl FnMut() -> Measurement) -> Duration {
+    (0..iters).map(|_| measure().elapsed).sum()
+}
+
+fn spawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn");
+    for n in SIZES {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter_custom(|iters| total(iters, || WorldBench::new().spawn_n::<(Position, Velocity)>(n)));
+        });
+    }
+    group.finish();
+}
+
+fn insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    for n in SIZES {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter_custom(|iters| total(iters, || {
+                let mut bench = WorldBench::new();
+                bench.spawn_n::<(Position,)>(n);
+                bench.insert_each(|entity| Health(entity as u32))
+            }));
+        });
+    }
+    group.finish();
+}
+
+fn iterate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("iterate");
+    for n in SIZES {
+        let mut bench = WorldBench::new();
+        bench.spawn_n::<(Position, Velocity)>(n);
+        bench.spawn_n::<(Position, Health)>(n);
+
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("iter", n), &n, |b, _| {
+            b.iter_custom(|iters| total(iters, || {
+                bench.iter_query_with::<(&mut Position, &Velocity), _>(|(mut pos, vel)| {
+                    pos.0 += vel.0;
+                    pos.1 += vel.1;
+                })
+            }));
+        });
+        group.bench_with_input(BenchmarkId::new("single", n), &n, |b, _| {
+            b.iter_custom(|iters| total(iters, || bench.iter_query_with::<&Health, _>(|health| {
+                black_box(health.0);
+            })));
+        });
+        group.bench_with_input(BenchmarkId::new("for_each", n), &n, |b, _| {
+            b.iter_custom(|iters| total(iters, || bench.for_each_query::<(&mut Position, &Velocity)>()));
+        });
+    }
+    group.finish();
+}
+
+fn despawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("despawn");
+    for n in SIZES {
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter_custom(|iters| total(iters, || {
+                let mut bench = WorldBench::new();
+                bench.spawn_n::<(Position, Velocity, Health)>(n);
+                bench.despawn_all()
+            }));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, spawn, insert, iterate, despawn);
+criterion_main!(benches);
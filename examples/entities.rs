@@ -2,6 +2,7 @@ use sceller::prelude::*;
 
 #[derive(Debug)]
 struct Health(u32);
+#[allow(dead_code)]
 #[derive(Debug)]
 struct Speed(u32);
 
@@ -76,7 +77,7 @@ fn main() -> Result<()> {
 
     { // this is in a block to avoid ownership issues and 'stuff'
         let mut borrow_mut = query[0][0].borrow_mut(); // set the second hp to 50 
-        let mut health = borrow_mut.downcast_mut::<Health>().unwrap();
+        let health = borrow_mut.downcast_mut::<Health>().unwrap();
 
         // this is the second Health as we queried for Health AND u32 and only the 
         // second entity has both.
@@ -138,24 +139,24 @@ fn main() -> Result<()> {
 
     println!("Beginning function queries:");
 
-    let query = world.query();
     world.run_system(print_healths); // this will execute this function and fill in the query
 
     // this function works the same for Query Functions taking mutable arguments
 
-    query.query_fn(&change_healths);
+    let query = world.query();
+    query.query_fn(change_healths);
 
-    query.query_fn(&print_healths); // Verify that the health values have changed
+    query.query_fn(print_healths); // Verify that the health values have changed
     
-    // query functions currently support a tuple field of up to three components:
+    // query functions support a tuple field of up to twelve components:
 
-    world.run_system(print_two); // this also works with a function with a tuple of three components right now (maybe more later)
+    world.run_system(print_two); // this also works with a function with a tuple of more components
 
-    // Query Functions as of now can take up to three arguments as queries:
+    // systems can take up to twelve arguments as queries:
 
     world.run_system(print_healths_and_speeds); // this also works with a query taking three arguments    
 
-    // this can also be done with query_fn_mut, and both types can be combines into a single function
+    // mutable and immutable references can be combined in a single FnQuery, e.g: FnQuery<(&mut Health, &Speed)>
 
     Ok(())
 }
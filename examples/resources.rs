@@ -6,9 +6,11 @@ struct PlayerResource {
     name: String,
     speed: u32,
 }
+impl Resource for PlayerResource {}
 
 // Resource that is not registered in the World to demonstrate the error handling.
 struct InvalidResource;
+impl Resource for InvalidResource {}
 
 // function returns result to catch errors. (not strictly neccessary but I will include it for the sake
 // of this example. It helps to know what went wrong.)
@@ -0,0 +1,129 @@
+//! # Sceller Derive
+//!
+//! The derive macros of the sceller ECS, re-exported by sceller with it's `derive` feature.
+//! See the documentation of the traits they implement in sceller for how to use them.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Index, LitStr};
+
+/**
+Implements sceller's Component trait for a type. The type's name defaults to it's identifier,
+and can be changed with `#[component(name = "...")]`. `#[component(unique)]` makes the type unique,
+so that only one entity at a time can have it once it is registered.
+
+With sceller's `auto-register` feature, non generic types are also registered in every new World.
+ */
+#[proc_macro_derive(Component, attributes(component))]
+pub fn derive_component(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+
+    let mut name = ident.to_string();
+    let mut unique = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("component")) {
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("unique") {
+                unique = true;
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("expected `unique` or `name = \"...\"`"))
+            }
+        });
+        if let Err(error) = parsed {
+            return error.to_compile_error().into();
+        }
+    }
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    // generic types can't be registered without knowing their parameters
+    let submit = input.generics.params.is_empty().then(|| quote! {
+        ::sceller::__submit_component!(#ident);
+    });
+
+    quote! {
+        impl #impl_generics ::sceller::component::Component for #ident #type_generics #where_clause {
+            const NAME: &'static str = #name;
+            const UNIQUE: bool = #unique;
+        }
+
+        #submit
+    }.into()
+}
+
+/**
+Implements sceller's Bundle trait for a struct whose fields are components, so that they are all inserted
+into an entity at once. Fields marked with `#[bundle]` are bundles themselves, whose components are inserted
+instead of the bundle.
+ */
+#[proc_macro_derive(Bundle, attributes(bundle))]
+pub fn derive_bundle(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return syn::Error::new_spanned(ident, "Bundle can only be derived for structs").to_compile_error().into(),
+    };
+
+    let mut register = Vec::new();
+    let mut store = Vec::new();
+    let mut bounds = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let ty = &field.ty;
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(index);
+                quote!(#index)
+            },
+        };
+
+        // components are inserted as bundles of one
+        if field.attrs.iter().any(|attr| attr.path().is_ident("bundle")) {
+            register.push(quote!(<#ty as ::sceller::entities::Bundle>::register_components(entities)?));
+            store.push(quote!(::sceller::entities::Bundle::store_components(self.#member, entities, map_index)?;));
+            bounds.push(quote!(#ty: ::sceller::entities::Bundle));
+        } else {
+            register.push(quote!(<(#ty,) as ::sceller::entities::Bundle>::register_components(entities)?));
+            store.push(quote!(::sceller::entities::Bundle::store_components((self.#member,), entities, map_index)?;));
+            bounds.push(quote!(#ty: ::std::any::Any));
+        }
+    }
+
+    let where_clause = input.generics.make_where_clause();
+    for bound in bounds {
+        where_clause.predicates.push(parse_quote!(#bound));
+    }
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::sceller::entities::Bundle for #ident #type_generics #where_clause {
+            fn register_components(entities: &mut ::sceller::entities::Entities) -> ::sceller::Result<u128> {
+                Ok(0 #(| #register)*)
+            }
+
+            fn store_components(self, entities: &mut ::sceller::entities::Entities, map_index: usize) -> ::sceller::Result<()> {
+                #(#store)*
+                Ok(())
+            }
+        }
+    }.into()
+}
+
+/**
+Implements sceller's Resource marker trait for a type, so that it can be stored as a resource of a World.
+ */
+#[proc_macro_derive(Resource)]
+pub fn derive_resource(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::sceller::resources::Resource for #ident #type_generics #where_clause {}
+    }.into()
+}
@@ -0,0 +1,222 @@
+//! # App
+//!
+//! The app module adds [App], a small wrapper around a [World] which drives it frame after frame.
+//! Systems and resources are added to the App, which is then handed to it's runner by [App::run()].
+//! The runner owns the main loop, calling [App::update()] once per frame, so an App can be plugged
+//! into the event loop of a windowing library like winit or SDL with [App::set_runner()].
+//!
+//! The default runner updates the App once, [run_loop()] updates it until an [AppExit] resource is inserted.
+
+use crate::{
+    world::{World, FromWorld},
+    resources::Resource,
+    system::IntoSystem,
+    schedule::Stage,
+    state::{StateSchedule, StateType},
+    error_handler::ErrorHandler,
+};
+
+type Runner = Box<dyn FnOnce(App)>;
+
+/**
+A [World] along with the function running it's main loop.
+
+```
+use sceller::prelude::*;
+
+#[derive(Default)]
+struct Frames(u32);
+impl Resource for Frames {}
+
+fn count_frames(mut frames: ResMut<Frames>, mut commands: Commands) {
+    frames.0 += 1;
+    if frames.0 == 3 {
+        commands.add(|world| {
+            world.insert_resource(AppExit);
+            Ok(())
+        });
+    }
+}
+
+App::new()
+    .init_resource::<Frames>()
+    .add_system(count_frames)
+    .set_runner(run_loop)
+    .run();
+```
+ */
+pub struct App {
+    pub world: World,
+    runner: Runner,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self { world: World::new(), runner: Box::new(run_once) }
+    }
+}
+
+impl std::fmt::Debug for App {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("App").field("world", &self.world).finish_non_exhaustive()
+    }
+}
+
+impl App {
+    /// Creates an App with an empty World, which is updated once when it is run.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a reference to the App's World.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Returns a mutable reference to the App's World.
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    /**
+    Sets the function running the App's main loop, which should call [App::update()] once per frame.
+    The App is given to the runner by value, so that it can be moved into an event loop.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Frames(u32);
+    impl Resource for Frames {}
+
+    let mut app = App::new();
+    app.insert_resource(Frames(0))
+        .add_system(|mut frames: ResMut<Frames>| frames.0 += 1)
+        .set_runner(|mut app| {
+            for _ in 0..10 {
+                app.update();
+            }
+            assert_eq!(app.world.get_resource::<Frames>().unwrap().0, 10);
+        });
+    app.run();
+    ```
+     */
+    pub fn set_runner(&mut self, runner: impl FnOnce(App) + 'static) -> &mut Self {
+        self.runner = Box::new(runner);
+        self
+    }
+
+    /**
+    Runs one frame, which is one call to [World::run_schedule()](struct.World.html#method.run_schedule).
+    The Time resource is updated, the state changes are applied and then every stage of the schedule is run.
+     */
+    pub fn update(&mut self) {
+        self.world.run_schedule();
+    }
+
+    /// Returns true if an [AppExit] resource was inserted into the World.
+    pub fn should_exit(&self) -> bool {
+        self.world.get_resource::<AppExit>().is_ok()
+    }
+
+    /**
+    Gives the App to it's runner. Leaves an empty App with the default runner behind, so it
+    can be called at the end of a chain of methods.
+     */
+    pub fn run(&mut self) {
+        let mut app = self.take();
+        let runner = std::mem::replace(&mut app.runner, Box::new(run_once));
+        runner(app);
+    }
+
+    /// Takes the App out of a mutable reference, leaving an empty App in it's place.
+    pub fn take(&mut self) -> App {
+        std::mem::take(self)
+    }
+
+    /// Adds a system to the [Stage::Update] stage, see [World::add_system()](struct.World.html#method.add_system).
+    pub fn add_system<F, T>(&mut self, system: F) -> &mut Self
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        self.world.add_system(system);
+        self
+    }
+
+    /// Adds a system run once before the first frame, see [World::add_startup_system()](struct.World.html#method.add_startup_system).
+    pub fn add_startup_system<F, T>(&mut self, system: F) -> &mut Self
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        self.world.add_startup_system(system);
+        self
+    }
+
+    /// Adds a system to the given stage, see [World::add_system_to_stage()](struct.World.html#method.add_system_to_stage).
+    pub fn add_system_to_stage<F, T>(&mut self, stage: Stage, system: F) -> &mut Self
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        self.world.add_system_to_stage(stage, system);
+        self
+    }
+
+    /// Inserts a resource into the World, see [World::insert_resource()](struct.World.html#method.insert_resource).
+    pub fn insert_resource<T: Resource>(&mut self, res: T) -> &mut Self {
+        self.world.insert_resource(res);
+        self
+    }
+
+    /// Inserts a resource created with [FromWorld], see [World::init_resource()](struct.World.html#method.init_resource).
+    pub fn init_resource<T: FromWorld + Resource>(&mut self) -> &mut Self {
+        self.world.init_resource::<T>();
+        self
+    }
+
+    /// Sets the function called with the errors returned by systems, see [World::set_error_handler()](struct.World.html#method.set_error_handler).
+    pub fn set_error_handler(&mut self, handler: ErrorHandler) -> &mut Self {
+        self.world.set_error_handler(handler);
+        self
+    }
+
+    /// Adds a state type, see [World::add_state()](struct.World.html#method.add_state).
+    pub fn add_state<T: StateType>(&mut self, initial: T) -> &mut Self {
+        self.world.add_state(initial);
+        self
+    }
+
+    /// Adds a system to the schedule of a state, see [World::add_state_system()](struct.World.html#method.add_state_system).
+    pub fn add_state_system<T, S, F, A>(&mut self, schedule: S, system: F) -> &mut Self
+    where
+        S: StateSchedule<T>,
+        F: IntoSystem<A> + 'static,
+        A: 'static,
+    {
+        self.world.add_state_system(schedule, system);
+        self
+    }
+}
+
+/**
+A resource which, once inserted, tells the runner of an [App] to stop updating it.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AppExit;
+
+impl Resource for AppExit {}
+
+/// The default runner of an [App], which updates it once.
+pub fn run_once(mut app: App) {
+    app.update();
+}
+
+/**
+A runner which updates an [App] as fast as possible until an [AppExit] resource is inserted.
+ */
+pub fn run_loop(mut app: App) {
+    while !app.should_exit() {
+        app.update();
+    }
+}
@@ -0,0 +1,385 @@
+//! # Assets
+//!
+//! The assets module adds the [Assets] resource, which stores big data shared by many entities, like meshes or
+//! sounds, only once. Entities reference an asset through a [Handle] component instead of cloning it's data.
+//!
+//! Handles are reference counted: an asset is kept as long as a handle to it exists, and is freed at the start of the
+//! frame following the drop of it's last handle. Every asset added, modified or removed during a frame is reported
+//! by the [AssetEvents] resource during the next one.
+//!
+//! An Assets storage and it's events are added to the World with [World::add_assets()].
+
+use std::{any::Any, collections::HashMap, hash::{Hash, Hasher}, marker::PhantomData, rc::Rc};
+
+use crate::{world::World, resources::Resource, system::ResMut, schedule::Stage};
+use crate::error::Result;
+
+/// Loads an asset from a path, see [Assets::set_loader()].
+pub type AssetLoader<T> = Box<dyn Fn(&str) -> std::result::Result<T, Box<dyn std::error::Error>>>;
+
+/**
+The id of an asset, unique within it's [Assets] storage. Unlike a [Handle], it doesn't keep the asset alive.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AssetId(u64);
+
+/**
+A reference counted key to an asset stored in [Assets]. The asset is kept as long as a Handle to it exists,
+so Handles are usually stored as components of the entities using the asset.
+
+Cloning a Handle is cheap, and never clones the asset.
+ */
+pub struct Handle<T> {
+    id: AssetId,
+    // shared with the entry of the asset, which counts the handles with it
+    count: Rc<()>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    /// Returns the id of the asset.
+    pub fn id(&self) -> AssetId {
+        self.id
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self { id: self.id, count: self.count.clone(), marker: PhantomData }
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T> std::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Handle").field(&self.id).finish()
+    }
+}
+
+struct AssetEntry<T> {
+    value: T,
+    count: Rc<()>,
+    path: Option<String>,
+}
+
+/**
+A resource storing every asset of type T, accessed with the [Handle]s returned when they are added.
+
+```
+use sceller::prelude::*;
+
+struct Mesh(Vec<f32>);
+
+let mut world = World::new();
+world.add_assets::<Mesh>();
+
+// both entities share the same mesh
+let mesh = world.get_resource_mut::<Assets<Mesh>>().unwrap().insert(Mesh(vec![0.0, 1.0, 2.0]));
+world.spawn().insert(mesh.clone());
+world.spawn().insert(mesh);
+
+world.run_system(|meshes: Res<Assets<Mesh>>, query: FnQuery<&Handle<Mesh>>| {
+    // Res has a get method of it's own
+    let meshes = &*meshes;
+    for handle in query {
+        assert_eq!(meshes.get(&handle).unwrap().0.len(), 3);
+    }
+});
+
+// the mesh is freed on the frame after both entities are gone
+world.despawn(0);
+world.despawn(1);
+world.run_schedule();
+assert!(world.get_resource::<Assets<Mesh>>().unwrap().is_empty());
+```
+ */
+pub struct Assets<T> {
+    assets: HashMap<AssetId, AssetEntry<T>>,
+    // the id of every asset loaded from a path
+    paths: HashMap<String, AssetId>,
+    next_id: u64,
+    loader: Option<AssetLoader<T>>,
+    // the events of the current frame, moved into AssetEvents by the next update
+    pending: Vec<AssetEvent>,
+}
+
+impl<T: Any> Resource for Assets<T> {}
+
+impl<T> Default for Assets<T> {
+    fn default() -> Self {
+        Self { assets: HashMap::new(), paths: HashMap::new(), next_id: 0, loader: None, pending: Vec::new() }
+    }
+}
+
+impl<T> Assets<T> {
+    /// Creates an empty Assets storage, without a loader.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    Sets the function used by [Assets::load()] to load the assets from their paths,
+    replacing the previous one.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Sound(String);
+
+    let mut sounds = Assets::new();
+    sounds.set_loader(|path| Ok(Sound(format!("decoded {}", path))));
+
+    let jump = sounds.load("jump.ogg");
+    assert_eq!(sounds.get(&jump).unwrap().0, "decoded jump.ogg");
+
+    // loading the same path again reuses the asset
+    assert_eq!(sounds.load("jump.ogg"), jump);
+    assert_eq!(sounds.len(), 1);
+    ```
+     */
+    pub fn set_loader(&mut self, loader: impl Fn(&str) -> std::result::Result<T, Box<dyn std::error::Error>> + 'static) {
+        self.loader = Some(Box::new(loader));
+    }
+
+    /// Adds an asset and returns the first handle to it.
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        self.insert_entry(value, None)
+    }
+
+    fn insert_entry(&mut self, value: T, path: Option<String>) -> Handle<T> {
+        let id = AssetId(self.next_id);
+        self.next_id += 1;
+        let count = Rc::new(());
+        self.assets.insert(id, AssetEntry { value, count: count.clone(), path });
+        self.pending.push(AssetEvent::Added(id));
+        Handle { id, count, marker: PhantomData }
+    }
+
+    /**
+    Returns a handle to the asset loaded from 'path', loading it with the loader set by [Assets::set_loader()] if
+    it isn't already. Panics if the asset can't be loaded, see [Assets::load_checked()].
+     */
+    pub fn load(&mut self, path: &str) -> Handle<T> {
+        self.load_checked(path).unwrap()
+    }
+
+    /**
+    Same as [Assets::load()], but returns an error instead of panicking if no loader was set,
+    or if the loader fails.
+     */
+    pub fn load_checked(&mut self, path: &str) -> Result<Handle<T>> {
+        if let Some(handle) = self.paths.get(path).and_then(|id| self.handle(*id)) {
+            return Ok(handle);
+        }
+        let loader = self.loader.as_ref().ok_or(AssetError::MissingLoaderError(std::any::type_name::<T>()))?;
+        let value = loader(path).map_err(|error| AssetError::AssetLoadError { path: path.to_string(), message: error.to_string() })?;
+        let handle = self.insert_entry(value, Some(path.to_string()));
+        self.paths.insert(path.to_string(), handle.id);
+        Ok(handle)
+    }
+
+    /// Returns a reference to an asset, or None if it was removed.
+    pub fn get(&self, handle: &Handle<T>) -> Option<&T> {
+        self.get_by_id(handle.id)
+    }
+
+    /// Returns a reference to the asset with the id 'id', or None if it doesn't exist.
+    pub fn get_by_id(&self, id: AssetId) -> Option<&T> {
+        self.assets.get(&id).map(|entry| &entry.value)
+    }
+
+    /// Returns a mutable reference to an asset, or None if it was removed. The asset is reported as modified.
+    pub fn get_mut(&mut self, handle: &Handle<T>) -> Option<&mut T> {
+        let entry = self.assets.get_mut(&handle.id)?;
+        self.pending.push(AssetEvent::Modified(handle.id));
+        Some(&mut entry.value)
+    }
+
+    /// Returns a new handle to the asset with the id 'id', or None if it doesn't exist.
+    pub fn handle(&self, id: AssetId) -> Option<Handle<T>> {
+        self.assets.get(&id).map(|entry| Handle { id, count: entry.count.clone(), marker: PhantomData })
+    }
+
+    /// Returns the number of handles to the asset with the id 'id', which is zero if it doesn't exist.
+    pub fn handle_count(&self, id: AssetId) -> usize {
+        self.assets.get(&id).map(|entry| Rc::strong_count(&entry.count) - 1).unwrap_or(0)
+    }
+
+    /// Returns true if the asset with the id 'id' exists.
+    pub fn contains(&self, id: AssetId) -> bool {
+        self.assets.contains_key(&id)
+    }
+
+    /**
+    Removes an asset and returns it, even if handles to it still exist. Those handles then
+    no longer refer to any asset.
+     */
+    pub fn remove(&mut self, handle: &Handle<T>) -> Option<T> {
+        let entry = self.assets.remove(&handle.id)?;
+        if let Some(path) = &entry.path {
+            self.paths.remove(path);
+        }
+        self.pending.push(AssetEvent::Removed(handle.id));
+        Some(entry.value)
+    }
+
+    /**
+    Removes every asset that no handle refers to anymore, and returns the number of assets removed.
+    This is done at the start of every frame for the storages added with [World::add_assets()].
+     */
+    pub fn free_unused(&mut self) -> usize {
+        let unused = self.assets.iter()
+            .filter(|(_, entry)| Rc::strong_count(&entry.count) == 1)
+            .map(|(id, _)| *id)
+            .collect::<Vec<AssetId>>();
+        for id in &unused {
+            if let Some(path) = self.assets.remove(id).and_then(|entry| entry.path) {
+                self.paths.remove(&path);
+            }
+            self.pending.push(AssetEvent::Removed(*id));
+        }
+        unused.len()
+    }
+
+    /// Returns the number of assets stored.
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    /// Returns true if no asset is stored.
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Iterates over every asset, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (AssetId, &T)> + '_ {
+        self.assets.iter().map(|(id, entry)| (*id, &entry.value))
+    }
+}
+
+/**
+A change made to an [Assets] storage, reported by [AssetEvents].
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetEvent {
+    /// The asset was inserted or loaded.
+    Added(AssetId),
+    /// The asset was borrowed mutably with [Assets::get_mut()].
+    Modified(AssetId),
+    /// The asset was removed, or freed once it's last handle was dropped.
+    Removed(AssetId),
+}
+
+impl AssetEvent {
+    /// Returns the id of the asset concerned.
+    pub fn id(&self) -> AssetId {
+        match self {
+            Self::Added(id) | Self::Modified(id) | Self::Removed(id) => *id,
+        }
+    }
+}
+
+/**
+A resource reporting the [AssetEvent]s of the assets of type T during the previous frame,
+in the order they happened. It is replaced at the start of every frame.
+
+```
+use sceller::prelude::*;
+
+struct Texture(u32);
+struct Reloaded(Vec<AssetId>);
+impl Resource for Reloaded {}
+
+fn reload_textures(events: Res<AssetEvents<Texture>>, mut reloaded: ResMut<Reloaded>) {
+    for event in events.iter() {
+        if let AssetEvent::Modified(id) = event {
+            reloaded.0.push(*id);
+        }
+    }
+}
+
+let mut world = World::new();
+world.add_assets::<Texture>();
+world.insert_resource(Reloaded(Vec::new()));
+world.add_system(reload_textures);
+
+let texture = world.get_resource_mut::<Assets<Texture>>().unwrap().insert(Texture(0));
+world.get_resource_mut::<Assets<Texture>>().unwrap().get_mut(&texture).unwrap().0 = 1;
+world.run_schedule();
+
+assert_eq!(world.get_resource::<Reloaded>().unwrap().0, vec![texture.id()]);
+```
+ */
+pub struct AssetEvents<T> {
+    events: Vec<AssetEvent>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Any> Resource for AssetEvents<T> {}
+
+impl<T> Default for AssetEvents<T> {
+    fn default() -> Self {
+        Self { events: Vec::new(), marker: PhantomData }
+    }
+}
+
+impl<T> AssetEvents<T> {
+    /// Iterates over the events of the previous frame, in the order they happened.
+    pub fn iter(&self) -> impl Iterator<Item = &AssetEvent> + '_ {
+        self.events.iter()
+    }
+
+    /// Returns the number of events of the previous frame.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if no asset of type T changed during the previous frame.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+// Assets stuff
+impl World {
+    /**
+    Adds an empty [Assets] storage for the assets of type T and it's [AssetEvents], and a system freeing the unused
+    assets and updating the events at the start of every frame, in the [Stage::PreUpdate] stage.
+    Does nothing if the storage was already added.
+     */
+    pub fn add_assets<T: Any>(&mut self) -> &mut Self {
+        if self.get_resource::<Assets<T>>().is_ok() {
+            return self;
+        }
+        self.insert_resource(Assets::<T>::new());
+        self.insert_resource(AssetEvents::<T>::default());
+        self.add_system_to_stage(Stage::PreUpdate, update_assets::<T>)
+    }
+}
+
+// frees the unused assets, and reports the events of the last frame
+fn update_assets<T: Any>(mut assets: ResMut<Assets<T>>, mut events: ResMut<AssetEvents<T>>) {
+    assets.free_unused();
+    events.events = std::mem::take(&mut assets.pending);
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AssetError {
+    #[error("No loader was set for the assets of type {0}.")]
+    MissingLoaderError(&'static str),
+    #[error("Failed to load the asset '{path}': {message}")]
+    AssetLoadError { path: String, message: String },
+}
@@ -0,0 +1,250 @@
+//! # Benchmarks
+//!
+//! The bench module contains [WorldBench], a World wrapper timing the operations whose throughput matters the most:
+//! spawning entities, inserting components, iterating over queries and despawning entities. Each operation returns
+//! a [Measurement] of how long it took and how many items it went through, so that the same benchmark can be
+//! compared across versions of the crate, whatever the storage looks like.
+//!
+//! Only the operation itself is timed: the bundles and components are built before the clock starts, and every
+//! result is passed through [black_box] so that the compiler can't optimize the work away. The measurements can be
+//! reported on their own, or given to a harness like criterion with it's `iter_custom` method.
+
+use std::{any::Any, fmt::Display, time::{Duration, Instant}};
+
+pub use std::hint::black_box;
+
+use crate::{world::World, entities::{Bundle, Entity, FnQuery, FnQueryContainedTupleType}};
+
+/**
+The time taken by an operation of a [WorldBench], and the number of items it went through.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Measurement {
+    /// The number of entities, components or query results the operation went through.
+    pub count: usize,
+    pub elapsed: Duration,
+}
+
+impl Measurement {
+    /// Returns the average time taken by a single item, or zero if there were none.
+    pub fn per_item(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(self.elapsed.as_secs_f64() / self.count as f64)
+    }
+
+    /// Returns the number of items per second, or zero if no time passed.
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.count as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl Display for Measurement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} items in {:?} ({:?} per item, {:.0} items/s)", self.count, self.elapsed, self.per_item(), self.throughput())
+    }
+}
+
+/**
+A World used to benchmark the ECS, which times the operations run on it and remembers the entities it spawned.
+
+```
+use sceller::prelude::*;
+
+#[derive(Default)]
+struct Position(f32);
+#[derive(Default)]
+struct Velocity(f32);
+
+let mut bench = WorldBench::new();
+let spawn = bench.spawn_n::<(Position, Velocity)>(1000);
+assert_eq!(spawn.count, 1000);
+
+let iterate = bench.iter_query_with::<(&mut Position, &Velocity), _>(|(mut pos, vel)| pos.0 += vel.0);
+assert_eq!(iterate.count, 1000);
+
+let despawn = bench.despawn_all();
+assert_eq!(despawn.count, 1000);
+assert_eq!(bench.world().entity_count(), 0);
+
+println!("spawn: {spawn}\niterate: {iterate}\ndespawn: {despawn}");
+```
+ */
+#[derive(Debug, Default)]
+pub struct WorldBench {
+    world: World,
+    // the entities spawned by the bench that weren't despawned yet, in the order they were spawned in
+    spawned: Vec<Entity>,
+}
+
+impl WorldBench {
+    /// Creates a bench with an empty World.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the World of the bench, to check the results of the operations.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Returns the World of the bench mutably, to set it up before timing operations on it.
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    /// Consumes the bench and returns it's World.
+    pub fn into_world(self) -> World {
+        self.world
+    }
+
+    /// Returns the entities spawned by the bench that weren't despawned by it yet.
+    pub fn spawned(&self) -> &[Entity] {
+        &self.spawned
+    }
+
+    /**
+    Spawns 'n' entities with the default value of the bundle B, and times how long it took.
+     */
+    pub fn spawn_n<B: Bundle + Default>(&mut self, n: usize) -> Measurement {
+        self.spawn_n_with(n, |_| B::default())
+    }
+
+    /**
+    Spawns 'n' entities with the bundles returned by 'bundle' for each index from 0 to n, and times how long it took.
+    The bundles are all created before the clock starts.
+     */
+    pub fn spawn_n_with<B: Bundle>(&mut self, n: usize, bundle: impl FnMut(usize) -> B) -> Measurement {
+        let bundles = (0..n).map(bundle).collect::<Vec<B>>();
+
+        let start = Instant::now();
+        let spawned = black_box(self.world.spawn_batch(bundles));
+        let elapsed = start.elapsed();
+
+        self.spawned.extend(spawned);
+        Measurement { count: n, elapsed }
+    }
+
+    /**
+    Inserts the component returned by 'component' into every entity spawned by the bench, replacing the component
+    they had, and times how long it took. The components are all created before the clock starts.
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Default)]
+    struct Health(u32);
+
+    let mut bench = WorldBench::new();
+    bench.spawn_n::<(Health,)>(100);
+
+    let insert = bench.insert_each(|entity| entity as u64);
+    assert_eq!(insert.count, 100);
+    assert_eq!(*bench.world().get::<&u64>(42).unwrap(), 42);
+    ```
+     */
+    pub fn insert_each<T: Any>(&mut self, component: impl FnMut(Entity) -> T) -> Measurement {
+        let components = self.spawned.iter().copied().map(component).collect::<Vec<T>>();
+
+        let start = Instant::now();
+        for (entity, component) in self.spawned.iter().zip(components) {
+            black_box(self.world.insert_component_into_entity(component, *entity));
+        }
+        Measurement { count: self.spawned.len(), elapsed: start.elapsed() }
+    }
+
+    /**
+    Iterates over every result of a query, passing each of them to [black_box], and times how long it took.
+     */
+    pub fn iter_query<'w, T>(&'w self) -> Measurement
+    where T: FnQueryContainedTupleType<'w> + 'w
+    {
+        self.iter_query_with::<T, _>(|result| {
+            black_box(result);
+        })
+    }
+
+    /**
+    Calls 'f' with every result of a query, and times how long it took, including the time taken by 'f'.
+     */
+    pub fn iter_query_with<'w, T, F>(&'w self, mut f: F) -> Measurement
+    where
+        T: FnQueryContainedTupleType<'w> + 'w,
+        F: FnMut(T::ReturnType),
+    {
+        let start = Instant::now();
+        let mut count = 0;
+        for result in self.world.query_typed::<T>() {
+            f(result);
+            count += 1;
+        }
+        Measurement { count, elapsed: start.elapsed() }
+    }
+
+    /**
+    Same as [WorldBench::iter_query()], but with [FnQuery::for_each()], which doesn't collect the matching entities
+    beforehand.
+     */
+    pub fn for_each_query<'w, T>(&'w self) -> Measurement
+    where T: FnQueryContainedTupleType<'w> + 'w
+    {
+        let start = Instant::now();
+        let mut count = 0;
+        FnQuery::<T>::new(&self.world.entities).for_each(|result| {
+            black_box(result);
+            count += 1;
+        });
+        Measurement { count, elapsed: start.elapsed() }
+    }
+
+    /**
+    Despawns every entity spawned by the bench that is still alive, and times how long it took.
+     */
+    pub fn despawn_all(&mut self) -> Measurement {
+        let spawned = std::mem::take(&mut self.spawned);
+
+        let start = Instant::now();
+        let mut count = 0;
+        for entity in spawned {
+            if self.world.contains(entity) {
+                black_box(self.world.despawn(entity));
+                count += 1;
+            }
+        }
+        Measurement { count, elapsed: start.elapsed() }
+    }
+
+    /**
+    Times any operation on the World, for the benchmarks the other methods don't cover. The value returned by
+    'operation' is passed to [black_box], and 'count' is the number of items it goes through.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    let mut bench = WorldBench::new();
+    bench.spawn_n_with(50, |i| (Health(i as u32),));
+
+    let removed = bench.measure(50, |world| world.despawn_where::<Health>(|health| health.0 % 2 == 0));
+    assert_eq!(removed.count, 50);
+    assert_eq!(bench.world().entity_count(), 25);
+    ```
+     */
+    pub fn measure<R>(&mut self, count: usize, operation: impl FnOnce(&mut World) -> R) -> Measurement {
+        let start = Instant::now();
+        black_box(operation(&mut self.world));
+        Measurement { count, elapsed: start.elapsed() }
+    }
+}
+
+impl From<World> for WorldBench {
+    /// Benchmarks an existing World, none of it's entities are seen as spawned by the bench.
+    fn from(world: World) -> Self {
+        Self { world, spawned: Vec::new() }
+    }
+}
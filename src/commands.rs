@@ -0,0 +1,302 @@
+//! # Commands
+//!
+//! The commands module contains [Commands], a system parameter used to queue up structural
+//! changes to the World (spawning and despawning entities, inserting and removing components).
+//! Systems only ever get immutable access to the entities, so these changes are stored in a
+//! [CommandQueue] and applied by the World once the system has finished running.
+
+use std::{any::Any, cell::{Cell, RefCell}, rc::Rc};
+
+use crate::{world::World, entities::{Entities, Entity}};
+use crate::error::Result;
+
+/// A single deferred operation on the World.
+type Command = Box<dyn FnOnce(&mut World) -> Result<()>>;
+
+#[derive(Default)]
+/**
+A queue of deferred operations on the [World].
+
+Commands are pushed through an immutable reference (the queue uses interior mutability) so
+they can be recorded while the World is borrowed, and are later applied in the order they
+were pushed with [CommandQueue::apply()](struct.CommandQueue.html#method.apply).
+ */
+pub struct CommandQueue {
+    commands: RefCell<Vec<Command>>,
+}
+
+impl CommandQueue {
+    /**
+    Creates a new, empty CommandQueue.
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    Pushes a new command to the back of the queue.
+     */
+    pub fn push<F>(&self, command: F)
+    where
+        F: FnOnce(&mut World) -> Result<()> + 'static
+    {
+        self.commands.borrow_mut().push(Box::new(command));
+    }
+
+    /// Returns the number of commands waiting to be applied.
+    pub fn len(&self) -> usize {
+        self.commands.borrow().len()
+    }
+
+    /// Returns true if there are no commands waiting to be applied.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+    Applies every queued command to the World in the order they were pushed, emptying the queue.
+
+    Stops and returns the error of the first command that fails, the commands after it are discarded.
+     */
+    pub fn apply(&self, world: &mut World) -> Result<()> {
+        let commands = self.commands.take();
+        for command in commands {
+            command(world)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for CommandQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandQueue")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/**
+A system parameter used to queue structural changes to the World.
+
+The changes are applied after the system has finished running, when using
+[World::run_system()](struct.World.html#method.run_system).
+
+```
+use sceller::prelude::*;
+
+struct Health(u8);
+struct Dead;
+
+let mut world = World::new();
+
+world.spawn().insert(Health(0));
+world.spawn().insert(Health(10));
+
+world.run_system(spawn_and_kill);
+
+fn spawn_and_kill(mut commands: Commands, healths: FnQuery<&Health>) {
+    // spawn a new entity, this will only exist after the system has run.
+    commands.spawn().insert(Health(20));
+
+    // mark the first entity as dead.
+    commands.insert(0, Dead);
+
+    // the query still only sees the two original entities.
+    assert_eq!(healths.iter().count(), 2);
+}
+
+let query = world.query();
+assert_eq!(query.auto::<Health>().len(), 3);
+assert_eq!(query.auto::<Dead>().len(), 1);
+```
+ */
+pub struct Commands<'a> {
+    queue: &'a CommandQueue,
+    // used to reserve the ids of spawned entities right away
+    entities: Option<&'a Entities>,
+}
+
+impl<'a> Commands<'a> {
+    /**
+    Creates a new Commands struct pushing into the given queue.
+     */
+    pub fn new(queue: &'a CommandQueue) -> Self {
+        Self { queue, entities: None }
+    }
+
+    /**
+    Creates a new Commands struct pushing into the given queue, which reserves the ids of the entities
+    spawned with [Commands::spawn_reserved()](struct.Commands.html#method.spawn_reserved) in 'entities'.
+     */
+    pub fn with_entities(queue: &'a CommandQueue, entities: &'a Entities) -> Self {
+        Self { queue, entities: Some(entities) }
+    }
+
+    /**
+    Queues the creation of a new entity, and returns an [EntityCommands] which
+    can be used to queue components to insert into it.
+
+    The id of the entity is reserved right away, like with [Commands::spawn_reserved()](struct.Commands.html#method.spawn_reserved),
+    so every entity spawned gets a slot of it's own even though none of them exist until the commands are applied.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut world = World::new();
+    world.run_system(|mut commands: Commands| {
+        let mut first = commands.spawn();
+        let mut second = commands.spawn();
+        first.insert(Health(1));
+        second.insert(Health(2));
+    });
+
+    assert_eq!(world.query().auto::<Health>().len(), 2);
+    ```
+     */
+    pub fn spawn(&mut self) -> EntityCommands<'a> {
+        self.spawn_reserved()
+    }
+
+    /**
+    Same as [Commands::spawn()](struct.Commands.html#method.spawn), the id of the new entity is reserved right away
+    with [Entities::reserve_entity()](struct.Entities.html#method.reserve_entity), so it can be read with
+    [EntityCommands::id()](struct.EntityCommands.html#method.id) and used in other commands before the entity exists,
+    even while iterating over a query.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Gun { fired: bool }
+    struct Bullet { shooter: Entity }
+
+    let mut world = World::new();
+    world.spawn().insert(Gun { fired: true });
+    world.spawn().insert(Gun { fired: false });
+
+    world.run_system(|mut commands: Commands, guns: FnQuery<&Gun>| {
+        for (gun, _) in guns.iter_with_ids().filter(|(_, gun)| gun.fired) {
+            let bullet = commands.spawn_reserved().insert(Bullet { shooter: gun }).id().unwrap();
+            assert_eq!(bullet, 2);
+        }
+    });
+
+    assert_eq!(world.get::<&Bullet>(2).unwrap().shooter, 0);
+    ```
+
+    Commands created with [Commands::new()](struct.Commands.html#method.new) don't know the entities to reserve the id in,
+    so the id is only reserved once the commands are applied, and isn't known until then.
+     */
+    pub fn spawn_reserved(&mut self) -> EntityCommands<'a> {
+        let Some(entities) = self.entities else {
+            let entity = Rc::new(Cell::new(0));
+            let spawned = entity.clone();
+            self.queue.push(move |world| {
+                // reserved ids are never vacant slots, which an entity spawned before this one could still be waiting in
+                let reserved = world.entities.reserve_entity();
+                world.entities.spawn_reserved(reserved);
+                spawned.set(reserved);
+                Ok(())
+            });
+            return EntityCommands { queue: self.queue, entity, known: false };
+        };
+
+        let entity = entities.reserve_entity();
+        self.queue.push(move |world| {
+            world.entities.spawn_reserved(entity);
+            Ok(())
+        });
+
+        EntityCommands { queue: self.queue, entity: Rc::new(Cell::new(entity)), known: true }
+    }
+
+    /**
+    Returns an [EntityCommands] for an already existing entity using it's index.
+     */
+    pub fn entity(&mut self, index: usize) -> EntityCommands<'a> {
+        EntityCommands { queue: self.queue, entity: Rc::new(Cell::new(index)), known: true }
+    }
+
+    /**
+    Queues the insertion of a component into an entity using it's index.
+     */
+    pub fn insert<T: Any>(&mut self, index: usize, data: T) {
+        self.queue.push(move |world| world.insert_component_into_entity_checked(data, index).map(|_| ()));
+    }
+
+    /**
+    Queues the deletion of a component from an entity using it's index.
+     */
+    pub fn remove<T: Any>(&mut self, index: usize) {
+        self.queue.push(move |world| world.delete_component_from_ent_checked::<T>(index));
+    }
+
+    /**
+    Queues the deletion of an entity using it's index.
+     */
+    pub fn despawn(&mut self, index: usize) {
+        self.queue.push(move |world| world.delete_entity(index));
+    }
+
+    /**
+    Queues any function taking a mutable reference to the World.
+     */
+    pub fn add<F>(&mut self, command: F)
+    where
+        F: FnOnce(&mut World) -> Result<()> + 'static
+    {
+        self.queue.push(command);
+    }
+}
+
+/**
+A builder returned by [Commands::spawn()](struct.Commands.html#method.spawn) and
+[Commands::entity()](struct.Commands.html#method.entity), used to queue operations on a single entity.
+
+The index of an entity spawned by [Commands] created with [Commands::new()](struct.Commands.html#method.new) is only
+chosen once the commands are applied, so it is shared with the spawn command and read when each of the following
+commands are applied.
+ */
+pub struct EntityCommands<'a> {
+    queue: &'a CommandQueue,
+    entity: Rc<Cell<usize>>,
+    // false for entities spawned by Commands that couldn't reserve their id
+    known: bool,
+}
+
+impl<'a> EntityCommands<'a> {
+    /**
+    Returns the id of this entity, or None if it was spawned by [Commands] created with [Commands::new()](struct.Commands.html#method.new),
+    since it's id is only chosen once the commands are applied.
+     */
+    pub fn id(&self) -> Option<Entity> {
+        self.known.then(|| self.entity.get())
+    }
+
+    /**
+    Queues the insertion of a component into this entity.
+     */
+    pub fn insert<T: Any>(&mut self, data: T) -> &mut Self {
+        let entity = self.entity.clone();
+        self.queue.push(move |world| world.insert_component_into_entity_checked(data, entity.get()).map(|_| ()));
+        self
+    }
+
+    /**
+    Queues the deletion of a component from this entity.
+     */
+    pub fn remove<T: Any>(&mut self) -> &mut Self {
+        let entity = self.entity.clone();
+        self.queue.push(move |world| world.delete_component_from_ent_checked::<T>(entity.get()));
+        self
+    }
+
+    /**
+    Queues the deletion of this entity.
+     */
+    pub fn despawn(&mut self) {
+        let entity = self.entity.clone();
+        self.queue.push(move |world| world.delete_entity(entity.get()));
+    }
+}
@@ -0,0 +1,116 @@
+//! # Component
+//!
+//! The component module adds the [Component] trait, which lets a type carry how it should be registered
+//! in the ECS, and is implemented with `#[derive(Component)]` when the `derive` feature is enabled.
+//! Components don't need to implement it, any `'static` type can be inserted into an entity.
+//!
+//! With the `auto-register` feature, every non generic type deriving Component is registered in the
+//! Worlds created with [World::new()](../struct.World.html#method.new), so that queries for them
+//! don't fail before the first one is inserted.
+
+use std::any::Any;
+
+use crate::{world::World, entities::Entities};
+
+#[cfg(feature = "derive")]
+pub use sceller_derive::Component;
+
+/**
+A trait for the component types that know how they should be registered.
+
+```
+# #[cfg(feature = "derive")]
+# {
+use sceller::prelude::*;
+
+#[derive(Component)]
+struct Health(u32);
+
+#[derive(Component)]
+#[component(unique, name = "The Player")]
+struct Player;
+
+let mut world = World::new();
+Health::register(&mut world);
+Player::register(&mut world);
+
+assert_eq!(Player::NAME, "The Player");
+assert!(world.type_registry().get_by_name("Health").is_some());
+world.spawn().insert(Player);
+assert!(world.spawn().insert_checked(Player).is_err());
+# }
+```
+ */
+pub trait Component: Any {
+    /// The name of the type, which is it's identifier by default.
+    const NAME: &'static str;
+    /// Whether only one entity at a time can have the component, see [Entities::register_unique()].
+    const UNIQUE: bool = false;
+
+    /// Registers the component type in a World.
+    fn register(world: &mut World) where Self: Sized {
+        Self::register_in(&mut world.entities)
+    }
+
+    /// Registers the component type in an [Entities].
+    fn register_in(entities: &mut Entities) where Self: Sized {
+        entities.register_component::<Self>();
+        if Self::UNIQUE {
+            entities.register_unique::<Self>();
+        }
+    }
+}
+
+/**
+A component type registered in every new World by the `auto-register` feature, submitted by `#[derive(Component)]`.
+ */
+#[cfg(feature = "auto-register")]
+#[doc(hidden)]
+pub struct ComponentRegistration {
+    pub name: &'static str,
+    pub register: fn(&mut Entities),
+}
+
+#[cfg(feature = "auto-register")]
+inventory::collect!(ComponentRegistration);
+
+#[cfg(feature = "auto-register")]
+#[doc(hidden)]
+pub use inventory;
+
+// submits a component type to be registered in every new World, used by #[derive(Component)]
+#[cfg(feature = "auto-register")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __submit_component {
+    ($component:ty) => {
+        $crate::component::inventory::submit! {
+            $crate::component::ComponentRegistration {
+                name: <$component as $crate::component::Component>::NAME,
+                register: <$component as $crate::component::Component>::register_in,
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "auto-register"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __submit_component {
+    ($component:ty) => {};
+}
+
+// Component stuff
+#[cfg(feature = "auto-register")]
+impl World {
+    /**
+    Registers every non generic type deriving [Component] in the program. This is done by
+    [World::new()](struct.World.html#method.new), but not by World::default().
+     */
+    pub fn register_components(&mut self) -> &mut Self {
+        for registration in inventory::iter::<ComponentRegistration> {
+            (registration.register)(&mut self.entities);
+        }
+        self
+    }
+}
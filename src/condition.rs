@@ -0,0 +1,149 @@
+//! # Run Conditions
+//!
+//! The condition module allows systems to only be run when a predicate returns true, using
+//! [IntoConditionalSystem::run_if()]. Conditions can either be functions taking [SystemParams] 
+//! and returning a bool, like a system that answers a question, or plain functions over the [World]
+//! like the ones returned by [resource_exists()] and [resource_equals()].
+
+use std::marker::PhantomData;
+
+use crate::{world::World, resources::Resource, system::{IntoSystem, ParamAccess, SystemParams}, ordering::SystemOrdering};
+use crate::error::Result;
+
+/**
+A trait implemented for any function that can be used as a run condition, that is any
+function taking up to five [SystemParams] and returning a bool, or a function
+taking a reference to the [World] and returning a bool.
+ */
+pub trait IntoCondition<Arguments> {
+    fn evaluate(&self, world: &World) -> bool;
+}
+
+/// Marker type for conditions that take a reference to the [World] directly.
+pub struct WorldCondition;
+
+impl<F> IntoCondition<WorldCondition> for F
+where F: Fn(&World) -> bool
+{
+    fn evaluate(&self, world: &World) -> bool {
+        (self)(world)
+    }
+}
+
+macro_rules! impl_into_condition {
+    ($($param:ident),*) => {
+        impl<F, $($param),*> IntoCondition<($($param,)*)> for F
+        where
+            $($param: SystemParams,)*
+            F: Fn($($param),*) -> bool + for<'a> Fn($($param::Item<'a>),*) -> bool,
+        {
+            fn evaluate(&self, world: &World) -> bool {
+                (self)($($param::get(world)),*)
+            }
+        }
+    };
+}
+
+impl_into_condition!(T1);
+impl_into_condition!(T1, T2);
+impl_into_condition!(T1, T2, T3);
+impl_into_condition!(T1, T2, T3, T4);
+impl_into_condition!(T1, T2, T3, T4, T5);
+
+/**
+A system that is only run when it's condition returns true, created with [IntoConditionalSystem::run_if()].
+ */
+pub struct ConditionalSystem<F, T, C, CT> {
+    system: F,
+    condition: C,
+    phantom: PhantomData<fn() -> (T, CT)>,
+}
+
+impl<F, T, C, CT> IntoSystem<(T, CT)> for ConditionalSystem<F, T, C, CT>
+where
+    F: IntoSystem<T>,
+    C: IntoCondition<CT>,
+{
+    fn run(&self, world: &World) {
+        if self.condition.evaluate(world) {
+            self.system.run(world);
+        }
+    }
+
+    fn validate(&self, world: &World) -> Result<()> {
+        self.system.validate(world)
+    }
+
+    fn access(&self) -> Vec<ParamAccess> {
+        self.system.access()
+    }
+
+    fn name(&self) -> &'static str {
+        self.system.name()
+    }
+
+    fn ordering(&self) -> SystemOrdering {
+        self.system.ordering()
+    }
+}
+
+/**
+Adds [run_if()](IntoConditionalSystem::run_if) to every system.
+
+```
+use sceller::prelude::*;
+
+#[derive(PartialEq)]
+enum GameState { Menu, Playing }
+impl Resource for GameState {}
+struct Shots(u32);
+impl Resource for Shots {}
+
+fn shoot(mut shots: ResMut<Shots>) {
+    shots.0 += 1;
+}
+
+let mut world = World::new();
+world.insert_resource(Shots(0));
+world.insert_resource(GameState::Menu);
+
+world.add_system(shoot.run_if(resource_equals(GameState::Playing)));
+
+world.run_schedule();
+assert_eq!(world.get_resource::<Shots>().unwrap().0, 0);
+
+*world.get_resource_mut::<GameState>().unwrap() = GameState::Playing;
+
+world.run_schedule();
+assert_eq!(world.get_resource::<Shots>().unwrap().0, 1);
+```
+ */
+pub trait IntoConditionalSystem<T>: IntoSystem<T> + Sized {
+    /**
+    Wraps the system so that it only runs when 'condition' returns true. Calling run_if
+    more than once requires every condition to be true.
+     */
+    fn run_if<C, CT>(self, condition: C) -> ConditionalSystem<Self, T, C, CT>
+    where C: IntoCondition<CT>
+    {
+        ConditionalSystem { system: self, condition, phantom: PhantomData }
+    }
+}
+
+impl<F, T> IntoConditionalSystem<T> for F
+where F: IntoSystem<T>
+{}
+
+/**
+A condition that returns true if a resource of type T exists in the World.
+ */
+pub fn resource_exists<T: Resource>() -> impl Fn(&World) -> bool {
+    |world: &World| world.get_resource::<T>().is_ok()
+}
+
+/**
+A condition that returns true if a resource of type T exists and is equal to 'value'.
+ */
+pub fn resource_equals<T: Resource + PartialEq>(value: T) -> impl Fn(&World) -> bool {
+    move |world: &World| world.get_resource::<T>().map(|res| *res == value).unwrap_or(false)
+}
@@ -0,0 +1,184 @@
+//! # Diagnostics
+//!
+//! The diagnostics module adds the [Diagnostics] resource, which records the wall-clock time taken by
+//! every system run by the schedule, to find the systems taking up most of the frame. Systems are only
+//! timed while the resource exists, see [World::enable_diagnostics()].
+//! On `wasm32-unknown-unknown`, which has no clock to read without JavaScript, no system is ever timed.
+//!
+//! With the `tracing` feature, every system run by the schedule is also wrapped in a tracing span named
+//! after it, whether the Diagnostics resource exists or not.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::{world::World, resources::Resource, system::BoxedSystem, ordering::{IntoSystemLabel, SystemLabel}};
+
+/**
+The time a system took to run once.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemTiming {
+    pub system: &'static str,
+    pub duration: Duration,
+}
+
+/**
+The time taken by a system over every frame since the [Diagnostics] were created or cleared.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemStats {
+    pub runs: u64,
+    pub total: Duration,
+    pub max: Duration,
+    pub last: Duration,
+}
+
+impl SystemStats {
+    /// Returns the average time the system took to run, or zero if it was never run.
+    pub fn average(&self) -> Duration {
+        match self.runs {
+            0 => Duration::ZERO,
+            runs => self.total / runs as u32,
+        }
+    }
+}
+
+/**
+A resource recording the time taken by every system run by the schedule, inserted with [World::enable_diagnostics()].
+
+```
+use sceller::prelude::*;
+
+struct Position(f32);
+
+fn movement(query: FnQuery<&mut Position>) {
+    for mut pos in query {
+        pos.0 += 1.0;
+    }
+}
+
+let mut world = World::new();
+world.spawn().insert(Position(0.0));
+world.add_system(movement);
+world.enable_diagnostics();
+
+world.run_schedule();
+world.run_schedule();
+
+let diagnostics = world.get_resource::<Diagnostics>().unwrap();
+assert_eq!(diagnostics.frame().len(), 1);
+assert_eq!(diagnostics.slowest().unwrap().system, diagnostics.frame()[0].system);
+assert_eq!(diagnostics.stats(movement).unwrap().runs, 2);
+```
+ */
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    frame_count: u64,
+    frame: Vec<SystemTiming>,
+    stats: HashMap<&'static str, SystemStats>,
+}
+
+impl Resource for Diagnostics {}
+
+impl Diagnostics {
+    /// Returns the number of frames recorded since the Diagnostics were created or cleared.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Returns the time taken by each system during the last frame, in the order they were run.
+    pub fn frame(&self) -> &[SystemTiming] {
+        &self.frame
+    }
+
+    /// Returns the total time taken by the systems during the last frame.
+    pub fn frame_time(&self) -> Duration {
+        self.frame.iter().map(|timing| timing.duration).sum()
+    }
+
+    /// Returns the system that took the longest to run during the last frame.
+    pub fn slowest(&self) -> Option<&SystemTiming> {
+        self.frame.iter().max_by_key(|timing| timing.duration)
+    }
+
+    /// Returns the systems of the last frame which took longer than 'budget' to run, slowest first.
+    pub fn over_budget(&self, budget: Duration) -> Vec<&SystemTiming> {
+        let mut over = self.frame.iter().filter(|timing| timing.duration > budget).collect::<Vec<&SystemTiming>>();
+        over.sort_by_key(|timing| std::cmp::Reverse(timing.duration));
+        over
+    }
+
+    /**
+    Returns the time taken by a system over every recorded frame, where 'system' is either the system itself
+    or it's name, or None if it was never run.
+     */
+    pub fn stats<M>(&self, system: impl IntoSystemLabel<M>) -> Option<&SystemStats> {
+        // a string is taken as the name of the system rather than a set
+        match system.label() {
+            SystemLabel::System(name) | SystemLabel::Set(name) => self.stats.get(name),
+        }
+    }
+
+    /// Returns the time taken by every system over every recorded frame, by name.
+    pub fn all_stats(&self) -> &HashMap<&'static str, SystemStats> {
+        &self.stats
+    }
+
+    /// Forgets every recorded timing.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn begin_frame(&mut self) {
+        self.frame_count += 1;
+        self.frame.clear();
+    }
+
+    fn record(&mut self, system: &'static str, duration: Duration) {
+        self.frame.push(SystemTiming { system, duration });
+        let stats = self.stats.entry(system).or_default();
+        stats.runs += 1;
+        stats.total += duration;
+        stats.max = stats.max.max(duration);
+        stats.last = duration;
+    }
+}
+
+// Diagnostics stuff
+impl World {
+    /**
+    Starts recording the time taken by every system run by the schedule into the [Diagnostics] resource,
+    inserting it if it doesn't exist. Deleting the resource stops the recording.
+     */
+    pub fn enable_diagnostics(&mut self) -> &mut Self {
+        self.init_resource::<Diagnostics>();
+        self
+    }
+
+    // starts a new frame of the Diagnostics, if there are any
+    pub(crate) fn begin_diagnostics_frame(&self) {
+        if let Ok(mut diagnostics) = self.get_resource_mut::<Diagnostics>() {
+            diagnostics.begin_frame();
+        }
+    }
+
+    // runs a system, timing it if there are Diagnostics. The resource isn't borrowed while the system runs,
+    // so that systems can read it
+    pub(crate) fn run_instrumented(&self, system: &BoxedSystem) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("system", name = system.name()).entered();
+
+        let start = match crate::time::now() {
+            Some(start) if self.resources.contains::<Diagnostics>() => start,
+            _ => {
+                system.run(self);
+                return;
+            }
+        };
+        system.run(self);
+        let duration = start.elapsed();
+        if let Ok(mut diagnostics) = self.get_resource_mut::<Diagnostics>() {
+            diagnostics.record(system.name(), duration);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! # Egui Inspector
+//!
+//! The egui inspector module adds the [WorldInspectorWindow], an [egui] window listing the entities of
+//! a World, which can be filtered by the names of their components. The components of the selected entity
+//! are shown as the JSON produced by [World::inspect()](../struct.World.html#method.inspect), and the fields
+//! of the types registered with [World::register_serde_component()](../struct.World.html#method.register_serde_component)
+//! can be edited in place.
+//!
+//! The window only borrows the World immutably, editing components through their RefCells, so it can be
+//! shown from anywhere the World is available, such as a system taking the egui Context as a resource.
+
+use egui::{CollapsingHeader, Context, DragValue, ScrollArea, Ui, Window};
+use serde_json::Value;
+
+use crate::{world::World, entities::{short_type_name, Entity}, name::Name};
+use crate::error::Result;
+
+/**
+An egui window listing the entities of a World, and showing and editing the components of the selected one.
+
+```
+use sceller::prelude::*;
+use sceller::egui_inspector::WorldInspectorWindow;
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize)]
+struct Health(u32);
+struct Mesh;
+
+let mut world = World::new();
+world.register_serde_component::<Health>();
+world.spawn().insert(Name::new("player")).insert(Health(10));
+world.spawn().insert(Mesh);
+
+let mut inspector = WorldInspectorWindow::new();
+inspector.filter = "health".to_owned();
+assert_eq!(inspector.entities(&world), vec![0]);
+
+// every frame of the program's egui context
+let ctx = egui::Context::default();
+let _ = ctx.run(egui::RawInput::default(), |ctx| inspector.show(ctx, &world));
+```
+ */
+#[derive(Debug, Clone)]
+pub struct WorldInspectorWindow {
+    pub title: String,
+    /// Whether the window is shown, the window's close button sets it to false.
+    pub open: bool,
+    /// Only entities with a component whose name contains the filter are listed, ignoring case.
+    pub filter: String,
+    /// The entity whose components are shown.
+    pub selected: Option<Entity>,
+    // the last error returned while editing a component
+    error: Option<String>,
+}
+
+impl Default for WorldInspectorWindow {
+    fn default() -> Self {
+        Self {
+            title: "World Inspector".to_owned(),
+            open: true,
+            filter: String::new(),
+            selected: None,
+            error: None,
+        }
+    }
+}
+
+impl WorldInspectorWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shows the window if it is open.
+    pub fn show(&mut self, ctx: &Context, world: &World) {
+        let mut open = self.open;
+        Window::new(self.title.clone())
+            .open(&mut open)
+            .show(ctx, |ui| self.ui(ui, world));
+        self.open = open;
+    }
+
+    /// Draws the contents of the window into 'ui', to put them inside a panel instead of a window.
+    pub fn ui(&mut self, ui: &mut Ui, world: &World) {
+        ui.horizontal(|ui| {
+            ui.label("Filter:");
+            ui.text_edit_singleline(&mut self.filter);
+        });
+        ui.separator();
+
+        let entities = self.entities(world);
+        if self.selected.is_some_and(|selected| !world.entities.is_alive(selected)) {
+            self.selected = None;
+        }
+
+        ui.columns(2, |columns| {
+            ScrollArea::vertical().id_salt("entities").show(&mut columns[0], |ui| {
+                for entity in entities {
+                    let selected = self.selected == Some(entity);
+                    if ui.selectable_label(selected, entity_label(world, entity)).clicked() {
+                        self.selected = Some(entity);
+                        self.error = None;
+                    }
+                }
+            });
+
+            ScrollArea::vertical().id_salt("components").show(&mut columns[1], |ui| {
+                match self.selected {
+                    Some(entity) => self.components_ui(ui, world, entity),
+                    None => { ui.label("No entity selected."); },
+                }
+            });
+        });
+    }
+
+    /// Returns the entities listed by the window, that is every entity with a component matching the filter.
+    pub fn entities(&self, world: &World) -> Vec<Entity> {
+        let filter = self.filter.to_lowercase();
+        world.entities.iter_ids()
+            .map(|(entity, _)| entity)
+            .filter(|entity| filter.is_empty() || world.entities.components_of(*entity).iter()
+                .any(|(_, name, _)| short_type_name(name).to_lowercase().contains(&filter)))
+            .collect()
+    }
+
+    fn components_ui(&mut self, ui: &mut Ui, world: &World, entity: Entity) {
+        ui.heading(entity_label(world, entity));
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        for (type_id, name, component) in world.entities.components_of(entity) {
+            let registration = world.type_registry().get(&type_id);
+            CollapsingHeader::new(short_type_name(name))
+                .id_salt((entity, name))
+                .default_open(true)
+                .show(ui, |ui| {
+                    let (Some(to_json), Some(from_json)) = (registration.and_then(|r| r.to_json), registration.and_then(|r| r.from_json)) else {
+                        ui.weak("Not registered for reflection.");
+                        return;
+                    };
+                    let Ok(mut component) = component.try_borrow_mut() else {
+                        ui.weak("Borrowed elsewhere.");
+                        return;
+                    };
+
+                    let edited = to_json(&*component).map(|mut value| edit_value(ui, &mut value).then_some(value));
+                    let result: Result<()> = match edited {
+                        Ok(Some(value)) => from_json(&mut *component, value),
+                        Ok(None) => Ok(()),
+                        Err(error) => Err(error),
+                    };
+                    if let Err(error) = result {
+                        self.error = Some(format!("{}: {}", short_type_name(name), error));
+                    }
+                });
+        }
+    }
+}
+
+// the name of an entity if it has one, or it's id
+fn entity_label(world: &World, entity: Entity) -> String {
+    match world.get::<&Name>(entity) {
+        Ok(name) => format!("{} ({})", name.as_str(), entity),
+        Err(_) => format!("Entity {}", entity),
+    }
+}
+
+// draws an editor for a JSON value, returns true if it was changed
+fn edit_value(ui: &mut Ui, value: &mut Value) -> bool {
+    match value {
+        Value::Null => {
+            ui.weak("null");
+            false
+        },
+        Value::Bool(boolean) => ui.checkbox(boolean, "").changed(),
+        Value::Number(number) => {
+            if let Some(mut integer) = number.as_i64() {
+                let changed = ui.add(DragValue::new(&mut integer)).changed();
+                *number = integer.into();
+                changed
+            } else if let Some(mut integer) = number.as_u64() {
+                let changed = ui.add(DragValue::new(&mut integer)).changed();
+                *number = integer.into();
+                changed
+            } else {
+                let mut float = number.as_f64().unwrap_or_default();
+                let changed = ui.add(DragValue::new(&mut float).speed(0.1)).changed();
+                if let Some(float) = serde_json::Number::from_f64(float) {
+                    *number = float;
+                }
+                changed
+            }
+        },
+        Value::String(string) => ui.text_edit_singleline(string).changed(),
+        Value::Array(items) => {
+            let mut changed = false;
+            for (index, item) in items.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}:", index));
+                    changed |= edit_value(ui, item);
+                });
+            }
+            changed
+        },
+        Value::Object(fields) => {
+            let mut changed = false;
+            for (key, field) in fields.iter_mut() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}:", key));
+                    changed |= edit_value(ui, field);
+                });
+            }
+            changed
+        },
+    }
+}
@@ -0,0 +1,67 @@
+//! # Archetypes
+//!
+//! An archetype is the set of entities sharing the exact same components, identified by their
+//! bitmask (their 'signature'). [Entities](super::Entities) keeps every living entity sorted into it's
+//! archetype, so that a query only has to look at the archetypes whose signature contains the query's
+//! bitmask instead of scanning the bitmask of every single entity.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::Entity;
+
+#[derive(Debug, Default)]
+pub(crate) struct Archetypes {
+    tables: HashMap<u128, BTreeSet<Entity>>,
+}
+
+impl Archetypes {
+    /// Moves 'entity' from the archetype of it's old signature to the one of it's new signature.
+    pub(crate) fn moved(&mut self, entity: Entity, old: u128, new: u128) {
+        if old == new {
+            return;
+        }
+        if old != 0 {
+            if let Some(table) = self.tables.get_mut(&old) {
+                table.remove(&entity);
+                if table.is_empty() {
+                    self.tables.remove(&old);
+                }
+            }
+        }
+        if new != 0 {
+            self.tables.entry(new).or_default().insert(entity);
+        }
+    }
+
+    /// Returns the ids of every entity whose signature contains 'mask', in ascending order.
+    pub(crate) fn matching(&self, mask: u128) -> Vec<Entity> {
+        let mut entities = self.matching_tables(mask)
+            .flat_map(|table| table.iter().copied())
+            .collect::<Vec<Entity>>();
+        entities.sort_unstable();
+        entities
+    }
+
+    /// Calls 'f' with the id of every entity whose signature contains 'mask', one archetype after the other.
+    pub(crate) fn for_each_matching(&self, mask: u128, mut f: impl FnMut(Entity)) {
+        for table in self.matching_tables(mask) {
+            table.iter().copied().for_each(&mut f);
+        }
+    }
+
+    /// Returns the number of entities whose signature contains 'mask'.
+    pub(crate) fn count_matching(&self, mask: u128) -> usize {
+        self.matching_tables(mask).map(BTreeSet::len).sum()
+    }
+
+    /// Returns the number of distinct signatures among the living entities.
+    pub(crate) fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    fn matching_tables(&self, mask: u128) -> impl Iterator<Item = &BTreeSet<Entity>> {
+        self.tables.iter()
+            .filter(move |(signature, _)| *signature & mask == mask)
+            .map(|(_, table)| table)
+    }
+}
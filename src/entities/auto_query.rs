@@ -1,6 +1,7 @@
-use std::{marker::PhantomData, cell::{Ref, RefMut, RefCell}, any::{TypeId, Any}, rc::Rc};
+use std::{marker::PhantomData, cell::{Ref, RefMut}, any::{TypeId, Any}};
 
-use super::{Entities};
+use super::{Entities, Entity, QueryError, column::{self, Column, ComponentCell, ComponentColumn}};
+use crate::error::Result;
 
 /**
     AutoQuery is a struct that allows quick access of every instance of a single component immutably.
@@ -30,19 +31,75 @@ impl<'a, T: 'static> AutoQuery<'a, T> {
     /// Returns the number of items of this type in the ECS.
     pub fn len(&self) -> usize {
         let typeid = TypeId::of::<T>();
-        // let components = self.entities.components.get(&typeid).unwrap();
         
-        let selfmap = self.entities.bit_masks.get(&typeid).unwrap();
-
-        self.entities.map.iter().fold(0, |aggr, bitmask| {
-            if bitmask & selfmap == *selfmap {
-                aggr + 1
-            } else {
-                aggr
-            }
-        })
+        let selfmap = self.entities.get_bitmask(&typeid).unwrap();
+
+        self.entities.count_matching(selfmap)
+    }
+
+    /// Returns true if there are no items of this type in the ECS.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+    Same as iterating over the AutoQuery, but each component is paired with the id of it's entity.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(10));
+    world.spawn().insert(Name::new("wall"));
+    world.spawn().insert(Health(0));
+
+    let dead = world.query().auto::<Health>().iter_with_ids()
+        .filter(|(_, health)| health.0 == 0)
+        .map(|(entity, _)| entity)
+        .collect::<Vec<Entity>>();
+    assert_eq!(dead, vec![2]);
+    ```
+     */
+    pub fn iter_with_ids(self) -> AutoQueryIdIterator<'a, T> {
+        let (column, ids) = matching_ids::<T>(self.entities);
+        AutoQueryIdIterator { column, ids: ids.into_iter() }
+    }
 
-        // components.iter().fold(0, |aggregate, comp| if comp.is_some() { aggregate + 1 } else { aggregate })
+    /**
+    Returns the component of the n-th entity having one, in ascending order of entity id.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(10));
+    world.spawn().insert(Name::new("wall"));
+    world.spawn().insert(Health(4));
+
+    let query = world.query();
+    assert_eq!(query.auto::<Health>().nth(1).unwrap().0, 4);
+    assert!(query.auto::<Health>().nth(2).is_none());
+    assert_eq!(query.auto::<Health>().get(0).unwrap().0, 10);
+    assert!(query.auto::<Health>().get(1).is_err());
+    ```
+     */
+    pub fn nth(&self, n: usize) -> Option<Ref<'a, T>> {
+        let (column, ids) = matching_ids::<T>(self.entities);
+        ids.get(n).and_then(|id| Some(column::borrow(column.get_typed(*id)?, std::any::type_name::<T>(), *id)))
+    }
+
+    /**
+    Returns the component of the entity 'entity', see [AutoQuery::nth()].
+
+    Returns an error if the entity doesn't have one.
+     */
+    pub fn get(&self, entity: Entity) -> Result<Ref<'a, T>> {
+        get_component::<T>(self.entities, entity)
+            .and_then(|component| column::try_borrow(component, std::any::type_name::<T>(), entity))
     }
 }
 
@@ -51,76 +108,120 @@ impl<'a, T: 'static> std::iter::IntoIterator for AutoQuery<'a, T> {
     type Item = Ref<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        let typeid = TypeId::of::<T>();
-
-        let selfmap = self.entities.bit_masks.get(&typeid).unwrap();
-
-        let all_components = self.entities.components.get(&typeid).unwrap();
-        // get all components with the type of this AutoQuery
-
-        // get all valid components (not deleted or None)
-        let components = all_components.into_iter().enumerate()
-            .map(|(ind, c)| {
-                if (self.entities.map[ind] & selfmap == *selfmap) && c.is_some() {
-                    Some(c.as_ref().unwrap())
-                } else {
-                    None
-                }
-            })
-            .flatten()
-            .collect::<Vec<&Rc<RefCell<dyn Any>>>>();
-
-        AutoQueryIntoIterator {
-            components: components.into_iter()
-                .map(|c| {
-                    let component = c.as_ref();
-                    let borrow = component.borrow();
-
-                    Ref::map(borrow, |any| {
-                        any.downcast_ref::<T>().unwrap()
-                    })
-                })
-                .collect::<Vec<Ref<T>>>()
-        }
+        let (column, ids) = matching_ids::<T>(self.entities);
+        AutoQueryIntoIterator { column, ids: ids.into_iter() }
     }
 }
 
+// the column of 'T' and the ids of all the entities with a valid component of that type (whose bit is set, 
+// and not deleted or None), used by both AutoQuery and AutoQueryMut. They are only borrowed once reached
+fn matching_ids<T: 'static>(entities: &Entities) -> (&Column<T>, Vec<Entity>) {
+    let typeid = TypeId::of::<T>();
+
+    let selfmap = entities.get_bitmask(&typeid).unwrap();
+    let column = entities.typed_column::<T>().unwrap();
+
+    let ids = entities.matching_entities(selfmap).into_iter()
+        .filter(|ind| column.get_typed(*ind).is_some())
+        .collect::<Vec<Entity>>();
+
+    (column, ids)
+}
+
+// the component of 'entity', if it has one
+fn get_component<T: 'static>(entities: &Entities, entity: Entity) -> Result<ComponentCell<'_, T>> {
+    let component = match entities.has_component::<T>(entity) {
+        true => entities.typed_column::<T>().and_then(|column| column.get_typed(entity)),
+        false => None,
+    };
+    component.ok_or_else(|| QueryError::NonMatchingEntityError(entity).into())
+}
+
+/**
+The iterator of an [AutoQuery], which borrows each component only once it is reached.
+ */
 pub struct AutoQueryIntoIterator<'a, T> {
-    components: Vec<Ref<'a, T>>,
+    column: &'a Column<T>,
+    ids: std::vec::IntoIter<Entity>,
 }
 
 impl<'a, T: 'static> std::iter::Iterator for AutoQueryIntoIterator<'a, T> {
     type Item = Ref<'a, T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.components.pop()
+        let id = self.ids.next()?;
+        self.column.get_typed(id).map(|component| column::borrow(component, std::any::type_name::<T>(), id))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<'a, T: 'static> std::iter::DoubleEndedIterator for AutoQueryIntoIterator<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let id = self.ids.next_back()?;
+        self.column.get_typed(id).map(|component| column::borrow(component, std::any::type_name::<T>(), id))
+    }
+}
+
+impl<'a, T: 'static> std::iter::ExactSizeIterator for AutoQueryIntoIterator<'a, T> {}
+
+/**
+An iterator over the components of an [AutoQuery] paired with the id of their entity,
+returned by [AutoQuery::iter_with_ids()].
+ */
+pub struct AutoQueryIdIterator<'a, T> {
+    column: &'a Column<T>,
+    ids: std::vec::IntoIter<Entity>,
+}
+
+impl<'a, T: 'static> std::iter::Iterator for AutoQueryIdIterator<'a, T> {
+    type Item = (Entity, Ref<'a, T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.ids.next()?;
+        self.column.get_typed(id).map(|component| (id, column::borrow(component, std::any::type_name::<T>(), id)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
     }
 }
 
+impl<'a, T: 'static> std::iter::DoubleEndedIterator for AutoQueryIdIterator<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let id = self.ids.next_back()?;
+        self.column.get_typed(id).map(|component| (id, column::borrow(component, std::any::type_name::<T>(), id)))
+    }
+}
+
+impl<'a, T: 'static> std::iter::ExactSizeIterator for AutoQueryIdIterator<'a, T> {}
+
 /**
 AutoQueryMut is a struct that allows quick access of every instance of a single component mutably.
 (The immutable variant is [AutoQuery](struct.AutoQuery.html))
 
 # WARNING
 
-If you ever get an error: "thread 'main' panicked at 'already borrowed: BorrowMutError'"
-this means you have two mutable borrows to the same data. This could be because you have two mutable
-auto queries in the same scope.
-The solution is to either drop them manually or to enclose them in a block:
+Only one mutable auto query of a component type can be alive at a time, including the iterators created from it,
+so creating a second one panics, or returns an error with [Query::auto_mut_checked()](struct.Query.html#method.auto_mut_checked).
+The solution is to either drop the first one manually or to enclose it in a block:
 
 ```
 use sceller::prelude::*;
 
 struct Health; // example struct
 
+let ents = Entities::default();
+let query = Query::new(&ents);
 {
-    let ents = Entities::default();
-
-    let query = Query::new(&ents);
-    let mut auto = query.auto::<Health>();
+    let mut auto = query.auto_mut::<Health>();
+    assert!(query.auto_mut_checked::<Health>().is_err());
     
     // <snip!>
-} //<- ensures that the mutable borrow is dropped at the end of this block
+} //<- ensures that the mutable auto query is dropped at the end of this block
+assert!(query.auto_mut_checked::<Health>().is_ok());
 ```
 
 It contains 'phantom' which is a PhantomData<T>, since the query needs to contain a type 
@@ -132,15 +233,80 @@ the reference to Entities is used to get all components of the AutoQueryMut's ty
 pub struct AutoQueryMut<'a, T: Any> 
 {
     entities: &'a Entities,
+    guard: AutoMutGuard<'a>,
     phantom: PhantomData<T>,
 }
 
 impl<'a, T: 'static> AutoQueryMut<'a, T> {
+    /// Creates a mutable auto query, panics if another one of T is still alive, see [AutoQueryMut::new_checked()].
     pub fn new(entities: &'a Entities) -> Self {
-        Self {
+        Self::new_checked(entities).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Creates a mutable auto query, or returns an error if another one of T is still alive.
+    pub fn new_checked(entities: &'a Entities) -> Result<Self> {
+        Ok(Self {
             entities,
+            guard: AutoMutGuard::acquire::<T>(entities)?,
             phantom: PhantomData
-        }
+        })
+    }
+
+    /**
+    Same as iterating over the AutoQueryMut, but each component is paired with the id of it's entity.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(10));
+    world.spawn().insert(Health(3));
+
+    for (entity, mut health) in world.query().auto_mut::<Health>().iter_with_ids() {
+        health.0 += entity as u32;
+    }
+    assert_eq!(world.get::<&Health>(1).unwrap().0, 4);
+    ```
+     */
+    pub fn iter_with_ids(self) -> AutoQueryMutIdIterator<'a, T> {
+        let (column, ids) = matching_ids::<T>(self.entities);
+        AutoQueryMutIdIterator { column, ids: ids.into_iter(), guard: self.guard }
+    }
+
+    /**
+    Mutably borrows the component of the n-th entity having one, in ascending order of entity id.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    let mut world = World::new();
+    world.spawn().insert(Name::new("wall"));
+    world.spawn().insert(Health(10));
+
+    let query = world.query();
+    query.auto_mut::<Health>().nth(0).unwrap().0 -= 1;
+    query.auto_mut::<Health>().get(1).unwrap().0 -= 1;
+    assert_eq!(query.auto::<Health>().get(1).unwrap().0, 8);
+    ```
+     */
+    pub fn nth(&self, n: usize) -> Option<RefMut<'a, T>> {
+        let (column, ids) = matching_ids::<T>(self.entities);
+        ids.get(n).and_then(|id| borrow_changed(self.entities, column, *id))
+    }
+
+    /**
+    Mutably borrows the component of the entity 'entity', see [AutoQueryMut::nth()].
+
+    Returns an error if the entity doesn't have one.
+     */
+    pub fn get(&self, entity: Entity) -> Result<RefMut<'a, T>> {
+        get_component::<T>(self.entities, entity)
+            .and_then(|component| column::try_borrow_mut(component, std::any::type_name::<T>(), entity))
+            .inspect(|_| self.entities.mark_changed::<T>(entity))
     }
 }
 
@@ -149,35 +315,99 @@ impl<'a, T: 'static> std::iter::IntoIterator for AutoQueryMut<'a, T> {
     type Item = RefMut<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        let typeid = TypeId::of::<T>();
-        let components = self.entities.components.get(&typeid).unwrap();
-        // get all components with the type of this AutoQuery
-
-        AutoQueryMutIntoIterator {
-            components: components.into_iter()
-                .flatten()
-                .map(|c| {
-                    let component = c.as_ref();
-                    let borrow = component.borrow_mut();
-
-                    RefMut::map(borrow, |any| {
-                        any.downcast_mut::<T>().unwrap()
-                    })
-                })
-                .collect::<Vec<RefMut<T>>>()
-        }
+        let (column, ids) = matching_ids::<T>(self.entities);
+        AutoQueryMutIntoIterator { column, ids: ids.into_iter(), guard: self.guard }
     }
 }
 
-
+/**
+The iterator of an [AutoQueryMut], which mutably borrows each component only once it is reached.
+ */
 pub struct AutoQueryMutIntoIterator<'a, T> {
-    components: Vec<RefMut<'a, T>>,
+    column: &'a Column<T>,
+    ids: std::vec::IntoIter<Entity>,
+    guard: AutoMutGuard<'a>,
 }
 
 impl<'a, T: 'static> std::iter::Iterator for AutoQueryMutIntoIterator<'a, T> {
     type Item = RefMut<'a, T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.components.pop()
+        let id = self.ids.next()?;
+        borrow_changed(self.guard.entities, self.column, id)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<'a, T: 'static> std::iter::DoubleEndedIterator for AutoQueryMutIntoIterator<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let id = self.ids.next_back()?;
+        borrow_changed(self.guard.entities, self.column, id)
+    }
+}
+
+impl<'a, T: 'static> std::iter::ExactSizeIterator for AutoQueryMutIntoIterator<'a, T> {}
+
+/**
+An iterator over the components of an [AutoQueryMut] paired with the id of their entity,
+returned by [AutoQueryMut::iter_with_ids()].
+ */
+pub struct AutoQueryMutIdIterator<'a, T> {
+    column: &'a Column<T>,
+    ids: std::vec::IntoIter<Entity>,
+    guard: AutoMutGuard<'a>,
+}
+
+impl<'a, T: 'static> std::iter::Iterator for AutoQueryMutIdIterator<'a, T> {
+    type Item = (Entity, RefMut<'a, T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.ids.next()?;
+        borrow_changed(self.guard.entities, self.column, id).map(|component| (id, component))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<'a, T: 'static> std::iter::DoubleEndedIterator for AutoQueryMutIdIterator<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let id = self.ids.next_back()?;
+        borrow_changed(self.guard.entities, self.column, id).map(|component| (id, component))
+    }
+}
+
+impl<'a, T: 'static> std::iter::ExactSizeIterator for AutoQueryMutIdIterator<'a, T> {}
+
+// mutably borrows the component of the entity 'id', which is marked as changed during the current tick
+fn borrow_changed<'a, T: Any>(entities: &Entities, components: &'a Column<T>, id: Entity) -> Option<RefMut<'a, T>> {
+    let component = column::borrow_mut(components.get_typed(id)?, std::any::type_name::<T>(), id);
+    components.mark_changed(id, entities.tick());
+    Some(component)
+}
+
+// marks the component type of a mutable auto query as taken for as long as the query or it's iterator is alive
+struct AutoMutGuard<'a> {
+    entities: &'a Entities,
+    type_id: TypeId,
+}
+
+impl<'a> AutoMutGuard<'a> {
+    fn acquire<T: Any>(entities: &'a Entities) -> Result<Self> {
+        let type_id = TypeId::of::<T>();
+        if !entities.auto_mut_queries.borrow_mut().insert(type_id) {
+            return Err(QueryError::AliasedAutoQueryError(std::any::type_name::<T>()).into());
+        }
+        Ok(Self { entities, type_id })
+    }
+}
+
+impl Drop for AutoMutGuard<'_> {
+    fn drop(&mut self) {
+        self.entities.auto_mut_queries.borrow_mut().remove(&self.type_id);
     }
-}
\ No newline at end of file
+}
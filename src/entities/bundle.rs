@@ -0,0 +1,131 @@
+//! # Bundles
+//!
+//! A Bundle is a group of components that are inserted into an entity all at once,
+//! for example a tuple like '(Health(10), Position(0, 0), Enemy)'.
+//!
+//! With the `derive` feature, structs whose fields are components can be made into bundles
+//! with `#[derive(Bundle)]`, marking the fields that are bundles themselves with `#[bundle]`.
+
+use std::any::Any;
+
+use super::Entities;
+use crate::error::Result;
+
+#[cfg(feature = "derive")]
+pub use sceller_derive::Bundle;
+
+/**
+A trait implemented for tuples of up to twelve components, allowing them to be
+inserted into an entity with a single call to [Entities::insert_bundle()](struct.Entities.html#method.insert_bundle).
+
+```
+use sceller::prelude::*;
+
+struct Health(u8);
+struct Position(i32, i32);
+struct Enemy;
+
+let mut world = World::new();
+
+world.spawn().insert_bundle((Health(10), Position(0, 0), Enemy));
+
+let query = world.query();
+assert_eq!(query.auto::<Health>().len(), 1);
+assert_eq!(query.auto::<Enemy>().len(), 1);
+```
+
+With the `derive` feature, it can be derived for structs:
+
+```
+# #[cfg(feature = "derive")]
+# {
+use sceller::prelude::*;
+
+struct Health(u8);
+struct Position(i32, i32);
+struct Sprite(&'static str);
+
+#[derive(Bundle)]
+struct Visuals {
+    position: Position,
+    sprite: Sprite,
+}
+
+#[derive(Bundle)]
+struct EnemyBundle {
+    health: Health,
+    #[bundle]
+    visuals: Visuals,
+}
+
+let mut world = World::new();
+world.spawn().insert_bundle(EnemyBundle {
+    health: Health(10),
+    visuals: Visuals { position: Position(0, 0), sprite: Sprite("enemy.png") },
+});
+assert_eq!(world.query().auto::<Sprite>().len(), 1);
+# }
+```
+ */
+pub trait Bundle {
+    /**
+    Registers every component type of the bundle that isn't registered yet, and returns the
+    combined bitmask of the bundle.
+     */
+    fn register_components(entities: &mut Entities) -> Result<u128>;
+
+    /**
+    Stores every component of the bundle into the entity at 'map_index', every component type
+    must already be registered with [Bundle::register_components()].
+
+    Note: the entity's bitmask in the map is left untouched, so that it can be set in one go.
+     */
+    fn store_components(self, entities: &mut Entities, map_index: usize) -> Result<()>;
+
+    /**
+    Writes every component of the bundle into the entity at 'map_index', registering any new
+    component types along the way, and returns the combined bitmask of the bundle. Every component
+    is checked before the first one is written, so that nothing is written if one of them can't be.
+
+    Note: the entity's bitmask in the map is left untouched, so that it can be set in one go.
+     */
+    fn write_components(self, entities: &mut Entities, map_index: usize) -> Result<u128>
+    where Self: Sized
+    {
+        let bitmask = Self::register_components(entities)?;
+        entities.check_bundle(bitmask, map_index)?;
+        self.store_components(entities, map_index)?;
+        Ok(bitmask)
+    }
+}
+
+macro_rules! impl_bundle {
+    ($($component:ident),*) => {
+        impl<$($component: Any),*> Bundle for ($($component,)*) {
+            fn register_components(entities: &mut Entities) -> Result<u128> {
+                let mut bitmask = 0;
+                $( bitmask |= entities.register_if_new::<$component>()?; )*
+                Ok(bitmask)
+            }
+
+            #[allow(non_snake_case)]
+            fn store_components(self, entities: &mut Entities, map_index: usize) -> Result<()> {
+                let ($($component,)*) = self;
+                $( entities.store_component($component, map_index)?; )*
+                Ok(())
+            }
+        }
+    };
+}
+impl_bundle!(A);
+impl_bundle!(A, B);
+impl_bundle!(A, B, C);
+impl_bundle!(A, B, C, D);
+impl_bundle!(A, B, C, D, E);
+impl_bundle!(A, B, C, D, E, F);
+impl_bundle!(A, B, C, D, E, F, G);
+impl_bundle!(A, B, C, D, E, F, G, H);
+impl_bundle!(A, B, C, D, E, F, G, H, I);
+impl_bundle!(A, B, C, D, E, F, G, H, I, J);
+impl_bundle!(A, B, C, D, E, F, G, H, I, J, K);
+impl_bundle!(A, B, C, D, E, F, G, H, I, J, K, L);
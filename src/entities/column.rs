@@ -0,0 +1,792 @@
+//! # Columns
+//!
+//! Every component type is stored in it's own [Column]. The components owned by entities are packed one after the
+//! other in a single vector, in no particular order, so iterating over a column walks through contiguous memory
+//! without skipping the entities that don't have the component. The column remembers the entity owning each
+//! component, and the position of the component of each entity. Removing a component moves the last one into
+//! it's place.
+//!
+//! Borrows are tracked by the column rather than by the components: a vector of borrow flags runs alongside the
+//! components, so that queries can still borrow the components of different entities one at a time, and one more
+//! flag lets the whole column be borrowed as a slice, see [Entities::column()](super::Entities::column). Components
+//! [Shared](super::Shared) with other entities aren't part of the packed vector, the column only keeps a pointer to
+//! them for each of their entities.
+//!
+//! Zero sized tag components, like `struct Enemy;`, take no memory at all, so their column only keeps a bit per
+//! entity telling whether it has one, next to the borrow flag of every entity's tag.
+//!
+//! The columns of a [group](super::groups) are reordered so that the grouped entities come first.
+//! [Entities](super::Entities) stores the columns type erased behind the [ComponentColumn] trait, and downcasts
+//! them back to a `Column<T>` when the type is known.
+//!
+//! Every column also keeps the [tick](crate::tick) during which the component of each entity last changed, for the
+//! [Changed](super::Changed) filter. The columns of the types that are indexed by the World, like the components of
+//! [relationships](crate::relationship), also log the entities whose component was written, until the index reads them.
+
+use std::{any::{Any, TypeId}, cell::{BorrowError, BorrowMutError, Cell, Ref, RefCell, RefMut, UnsafeCell}, collections::{BTreeSet, HashMap}, fmt::Debug, ptr::NonNull, rc::Rc};
+
+use super::{ComponentError, Entities, Entity, Shared};
+use crate::error::Result;
+
+/**
+The type erased interface of a [Column], used for every operation that doesn't know the
+type of the component stored.
+ */
+pub(crate) trait ComponentColumn: Debug {
+    /// Returns the number of slots in the column, which is the number of entities, whether they have the component or not.
+    fn len(&self) -> usize;
+
+    /// Grows the column with empty slots until it has 'len' slots.
+    fn resize(&mut self, len: usize);
+
+    /// Returns the component at 'index', if the slot isn't empty.
+    fn get(&self, index: usize) -> Option<ComponentCell<'_>>;
+
+    /// Empties the slot at 'index' and returns the component that was in it, or it's [Shared] handle if it was shared.
+    fn remove(&mut self, index: usize) -> Option<Box<dyn Any>>;
+
+    /// Stores a type erased component or [Shared] handle in the slot at 'index', which must be in bounds, and returns
+    /// the component that was in it. Panics if the component isn't of the column's type.
+    fn put(&mut self, index: usize, component: Box<dyn Any>) -> Option<Box<dyn Any>>;
+
+    /// Moves the component at 'index' into the entity 'target_index' of another ECS, registering it's type there if needed.
+    /// Does nothing if the slot is empty.
+    fn move_to(&mut self, index: usize, target: &mut Entities, target_index: Entity) -> Result<()>;
+
+    /// Drops every component and removes every slot.
+    fn clear(&mut self);
+
+    /// Reserves capacity for at least 'additional' more slots.
+    fn reserve(&mut self, additional: usize);
+
+    /// Shrinks the capacity of the column as much as possible.
+    fn shrink_to_fit(&mut self);
+
+    /// Returns the number of slots holding a component.
+    fn count(&self) -> usize;
+
+    /// Returns the number of bytes allocated by the column, including the empty slots and the unused capacity.
+    fn allocated_bytes(&self) -> usize;
+
+    /// Returns the number of bytes allocated for the change ticks of every entity.
+    fn tick_bytes(&self) -> usize;
+
+    /// Returns the size in bytes of a single component.
+    fn component_size(&self) -> usize;
+
+    /// Returns the TypeId of the components stored in the column.
+    fn component_type(&self) -> TypeId;
+
+    /// Returns the type name of the components stored in the column.
+    fn component_name(&self) -> &'static str;
+
+    /// Moves the component of 'entity' to 'slot', the component that was in 'slot' takes it's old place.
+    /// Panics if the entity doesn't own a component.
+    fn move_slot(&mut self, entity: Entity, slot: usize);
+
+    /// Returns true if the component of at least one entity is [Shared].
+    fn has_shared(&self) -> bool;
+
+    /// Returns the entity whose component is in 'slot'.
+    fn entity_in(&self, slot: usize) -> Entity;
+
+    /// Records that the component of 'entity' changed during 'tick'.
+    fn mark_changed(&self, entity: Entity, tick: u64);
+
+    /// Records that 'entity' gained the component during 'tick', forgetting when the component it had before changed.
+    fn mark_inserted(&self, entity: Entity, tick: u64);
+
+    /// Returns the last tick during which the component of 'entity' changed.
+    fn changed_tick(&self, entity: Entity) -> Option<u64>;
+
+    /// Returns true if the component of 'entity' changed during 'tick', even if it changed again since then.
+    fn changed_during(&self, entity: Entity, tick: u64) -> bool;
+
+    /// Starts logging the entities whose component is marked as changed, which inserting it or borrowing it mutably does.
+    fn log_writes(&mut self);
+
+    /// Returns the entities logged since the log was last taken, in ascending order, and empties the log.
+    fn take_writes(&self) -> Vec<Entity>;
+
+    /// Returns the entities logged since the log was last taken, in ascending order.
+    fn writes(&self) -> Vec<Entity>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/**
+A column of components of type T.
+ */
+pub(crate) struct Column<T> {
+    storage: Storage<T>,
+    // the ticks of every entity, in the order of the entities rather than of the components
+    ticks: Vec<ChangeTicks>,
+    // the entities whose component was written since the World last read them, if the column's type is indexed
+    writes: Option<RefCell<BTreeSet<Entity>>>,
+}
+
+// the last two ticks during which a component changed, so that a change stays visible for the whole next tick
+#[derive(Debug, Default)]
+struct ChangeTicks {
+    latest: Cell<u64>,
+    prior: Cell<u64>,
+}
+
+enum Storage<T> {
+    Packed(Packed<T>),
+    Tags(Tags<T>),
+}
+
+/*
+    the components owned by entities, packed in the same order as their borrow flags and their entities. The
+    components are in UnsafeCells, which have the same layout as T, since they are written through the borrow flags
+*/
+struct Packed<T> {
+    values: Vec<UnsafeCell<T>>,
+    borrows: Vec<RefCell<()>>,
+    // borrowed while every component is borrowed at once as a slice
+    whole: RefCell<()>,
+    entities: Vec<Entity>,
+    // the position of the component of every entity in 'values', one for every slot
+    positions: Vec<Option<usize>>,
+    shared: HashMap<Entity, Rc<RefCell<T>>>,
+}
+
+impl<T> Packed<T> {
+    fn position(&self, entity: Entity) -> Option<usize> {
+        self.positions.get(entity).copied().flatten()
+    }
+
+    // appends the component of 'entity', which doesn't own one yet
+    fn push(&mut self, entity: Entity, value: T) {
+        self.positions[entity] = Some(self.values.len());
+        self.values.push(UnsafeCell::new(value));
+        self.borrows.push(RefCell::new(()));
+        self.entities.push(entity);
+    }
+
+    // removes the component at 'position', moving the last one into it's place
+    fn swap_remove(&mut self, position: usize) -> T {
+        self.positions[self.entities[position]] = None;
+        self.borrows.swap_remove(position);
+        self.entities.swap_remove(position);
+        let value = self.values.swap_remove(position).into_inner();
+        if let Some(moved) = self.entities.get(position) {
+            self.positions[*moved] = Some(position);
+        }
+        value
+    }
+}
+
+// the component in a slot, owned by the entity or shared with others
+pub(crate) enum Slot<T> {
+    Empty,
+    Owned(T),
+    Shared(Rc<RefCell<T>>),
+}
+
+impl<T> Slot<T> {
+    // calls 'f' with the component, unless the slot is empty or the shared component is borrowed mutably
+    pub(crate) fn map<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        match self {
+            Slot::Empty => None,
+            Slot::Owned(component) => Some(f(component)),
+            Slot::Shared(component) => component.try_borrow().ok().map(|component| f(&component)),
+        }
+    }
+
+    // the component, unless it is shared with another entity or handle
+    pub(crate) fn into_inner(self) -> Option<T> {
+        match self {
+            Slot::Empty => None,
+            Slot::Owned(component) => Some(component),
+            Slot::Shared(component) => Rc::try_unwrap(component).ok().map(RefCell::into_inner),
+        }
+    }
+}
+
+/*
+    the components of a zero sized type: a bit per slot telling whether it holds one, and the borrow flag of every
+    slot, so that the tags of different entities can be borrowed at the same time like any other component. The
+    instances take no memory but are kept, so that every component inserted is given back or dropped exactly once.
+*/
+struct Tags<T> {
+    bits: Vec<u64>,
+    borrows: Vec<RefCell<()>>,
+    instances: Vec<T>,
+    // the slot of every entity once the column was reordered by a group, the slots are in the order of the entities otherwise
+    order: Option<SlotOrder>,
+}
+
+// the slot of every entity and the entity in every slot, each the inverse of the other
+#[derive(Debug, Default)]
+struct SlotOrder {
+    slot_of: Vec<usize>,
+    entity_in: Vec<Entity>,
+}
+
+impl<T> Tags<T> {
+    // the slot holding the tag of the entity 'index'
+    fn slot(&self, index: usize) -> usize {
+        match &self.order {
+            Some(order) => order.slot_of.get(index).copied().unwrap_or(index),
+            None => index,
+        }
+    }
+
+    fn has(&self, slot: usize) -> bool {
+        slot < self.borrows.len() && self.bits[slot / 64] & 1 << (slot % 64) != 0
+    }
+
+    fn set(&mut self, slot: usize, value: bool) {
+        if value {
+            self.bits[slot / 64] |= 1 << (slot % 64);
+        } else {
+            self.bits[slot / 64] &= !(1 << (slot % 64));
+        }
+    }
+
+    // the tag in 'slot', a zero sized value which can be read and written through any aligned pointer
+    fn get(&self, slot: usize) -> Option<ComponentCell<'_, T>> {
+        // SAFETY: T is zero sized, so a dangling pointer is a valid reference to it
+        let tag = unsafe { NonNull::<UnsafeCell<T>>::dangling().as_ref() };
+        self.has(slot).then(|| ComponentCell(CellKind::Flagged { flag: &self.borrows[slot], column: None, value: tag }))
+    }
+}
+
+/**
+A single component in a column, which is borrowed like a RefCell. It is either stored along with the components of
+the other entities, in which case the column tracks it's borrows, or in a RefCell of it's own, like a [Shared]
+component.
+
+The type erased form `ComponentCell<'a>`, which is returned by [Query::run()](super::Query::run) as a
+[ComponentType](super::ComponentType), borrows the component as `dyn Any`.
+ */
+pub struct ComponentCell<'a, T: ?Sized = dyn Any>(CellKind<'a, T>);
+
+enum CellKind<'a, T: ?Sized> {
+    // the value is only read or written while 'flag' is borrowed the same way, and 'column' isn't borrowed in a
+    // way that conflicts with it
+    Flagged { flag: &'a RefCell<()>, column: Option<&'a RefCell<()>>, value: &'a UnsafeCell<T> },
+    Cell(&'a RefCell<T>),
+}
+
+impl<'a, T: ?Sized> ComponentCell<'a, T> {
+    /// Borrows the component, panicking if it is already borrowed mutably.
+    pub fn borrow(&self) -> Ref<'a, T> {
+        self.try_borrow().expect("The component is already borrowed mutably")
+    }
+
+    /// Mutably borrows the component, panicking if it is already borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'a, T> {
+        self.try_borrow_mut().expect("The component is already borrowed")
+    }
+
+    /// Borrows the component, returning an error if it is already borrowed mutably.
+    pub fn try_borrow(&self) -> std::result::Result<Ref<'a, T>, BorrowError> {
+        match self.0 {
+            CellKind::Flagged { flag, column, value } => {
+                // a column borrowed mutably as a slice can't be borrowed again, which the flag of the value doesn't know
+                if let Some(column) = column {
+                    column.try_borrow()?;
+                }
+                // SAFETY: the flag is borrowed for as long as the reference, so the value isn't borrowed mutably elsewhere
+                flag.try_borrow().map(|flag| Ref::map(flag, |_| unsafe { &*value.get() }))
+            },
+            CellKind::Cell(cell) => cell.try_borrow(),
+        }
+    }
+
+    /// Mutably borrows the component, returning an error if it is already borrowed.
+    pub fn try_borrow_mut(&self) -> std::result::Result<RefMut<'a, T>, BorrowMutError> {
+        match self.0 {
+            CellKind::Flagged { flag, column, value } => {
+                if let Some(column) = column {
+                    column.try_borrow_mut()?;
+                }
+                // SAFETY: the flag is borrowed mutably for as long as the reference, so the value isn't borrowed elsewhere
+                flag.try_borrow_mut().map(|flag| RefMut::map(flag, |_| unsafe { &mut *value.get() }))
+            },
+            CellKind::Cell(cell) => cell.try_borrow_mut(),
+        }
+    }
+}
+
+impl<'a, T: Any> ComponentCell<'a, T> {
+    // the same component, borrowed as 'dyn Any'
+    pub(crate) fn erase(self) -> ComponentCell<'a> {
+        ComponentCell(match self.0 {
+            CellKind::Flagged { flag, column, value } => CellKind::Flagged { flag, column, value },
+            CellKind::Cell(cell) => CellKind::Cell(cell),
+        })
+    }
+}
+
+impl<T: ?Sized> Clone for ComponentCell<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for ComponentCell<'_, T> {}
+
+impl<T: ?Sized> Clone for CellKind<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for CellKind<'_, T> {}
+
+impl<T: ?Sized> Debug for ComponentCell<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComponentCell")
+            .field("type", &std::any::type_name::<T>())
+            .field("borrowed", &self.try_borrow_mut().is_err())
+            .finish()
+    }
+}
+
+impl<T: Any> Column<T> {
+    pub(crate) fn new() -> Self {
+        let storage = if std::mem::size_of::<T>() == 0 {
+            Storage::Tags(Tags { bits: Vec::new(), borrows: Vec::new(), instances: Vec::new(), order: None })
+        } else {
+            Storage::Packed(Packed {
+                values: Vec::new(),
+                borrows: Vec::new(),
+                whole: RefCell::new(()),
+                entities: Vec::new(),
+                positions: Vec::new(),
+                shared: HashMap::new(),
+            })
+        };
+        Self { storage, ticks: Vec::new(), writes: None }
+    }
+
+    /// Returns the component at 'index', if the slot isn't empty.
+    pub(crate) fn get_typed(&self, index: usize) -> Option<ComponentCell<'_, T>> {
+        match &self.storage {
+            Storage::Packed(packed) => match packed.position(index) {
+                Some(position) => Some(ComponentCell(CellKind::Flagged {
+                    flag: &packed.borrows[position],
+                    column: Some(&packed.whole),
+                    value: &packed.values[position],
+                })),
+                None => packed.shared.get(&index).map(|component| ComponentCell(CellKind::Cell(component))),
+            },
+            Storage::Tags(tags) => tags.get(tags.slot(index)),
+        }
+    }
+
+    /**
+    Stores an owned or shared component in the slot at 'index', which must be in bounds, and returns what was in it.
+    An owned component replacing another one keeps it's place, so that the order of a group isn't changed.
+    Panics if a shared component is stored in a column of tags, which take no memory anyway.
+     */
+    pub(crate) fn replace_slot(&mut self, index: usize, component: Slot<T>) -> Slot<T> {
+        if let Slot::Empty = component {
+            return self.take_slot(index);
+        }
+        match (&mut self.storage, component) {
+            (_, Slot::Empty) => unreachable!(),
+            (Storage::Packed(packed), Slot::Owned(value)) => match packed.position(index) {
+                Some(position) => Slot::Owned(std::mem::replace(packed.values[position].get_mut(), value)),
+                None => {
+                    let replaced = packed.shared.remove(&index).map_or(Slot::Empty, Slot::Shared);
+                    packed.push(index, value);
+                    replaced
+                },
+            },
+            (Storage::Packed(packed), Slot::Shared(component)) => {
+                let replaced = match packed.position(index) {
+                    Some(position) => Slot::Owned(packed.swap_remove(position)),
+                    None => Slot::Empty,
+                };
+                packed.shared.insert(index, component).map_or(replaced, Slot::Shared)
+            },
+            (Storage::Tags(_), Slot::Shared(_)) => panic!("Attempt to share the zero sized component {}", std::any::type_name::<T>()),
+            (Storage::Tags(tags), Slot::Owned(tag)) => {
+                let slot = tags.slot(index);
+                // a tag replacing another one is the same as keeping the old one
+                if tags.has(slot) {
+                    Slot::Owned(tag)
+                } else {
+                    tags.set(slot, true);
+                    tags.instances.push(tag);
+                    Slot::Empty
+                }
+            },
+        }
+    }
+
+    /// Empties the slot at 'index' and returns the component that was in it, unless it is still shared elsewhere.
+    pub(crate) fn take(&mut self, index: usize) -> Option<T> {
+        self.take_slot(index).into_inner()
+    }
+
+    /// Empties the slot at 'index' and returns what was in it.
+    pub(crate) fn take_slot(&mut self, index: usize) -> Slot<T> {
+        match &mut self.storage {
+            Storage::Packed(packed) => match packed.position(index) {
+                Some(position) => Slot::Owned(packed.swap_remove(position)),
+                None => packed.shared.remove(&index).map_or(Slot::Empty, Slot::Shared),
+            },
+            Storage::Tags(tags) => {
+                let slot = tags.slot(index);
+                if !tags.has(slot) {
+                    return Slot::Empty;
+                }
+                tags.set(slot, false);
+                tags.instances.pop().map_or(Slot::Empty, Slot::Owned)
+            },
+        }
+    }
+
+    /**
+    Borrows every component owned by an entity as a slice, along with the entity owning each of them. Returns an error
+    if one of them is already borrowed mutably, or if the components are zero sized and aren't stored at all.
+     */
+    pub(crate) fn borrow_all(&self) -> Result<(Ref<'_, [T]>, &[Entity])> {
+        let packed = self.packed()?;
+        let whole = packed.whole.try_borrow().map_err(|_| column_borrowed::<T>())?;
+        if packed.borrows.iter().any(|flag| flag.try_borrow().is_err()) {
+            return Err(column_borrowed::<T>());
+        }
+        // SAFETY: an UnsafeCell has the same layout as it's value, and no value can be borrowed mutably while 'whole' is borrowed
+        let values = unsafe { std::slice::from_raw_parts(UnsafeCell::raw_get(packed.values.as_ptr()), packed.values.len()) };
+        Ok((Ref::map(whole, |_| values), &packed.entities))
+    }
+
+    // same as 'borrow_all', but borrows the components mutably, which fails if one of them is borrowed at all
+    pub(crate) fn borrow_all_mut(&self) -> Result<(RefMut<'_, [T]>, &[Entity])> {
+        let packed = self.packed()?;
+        let whole = packed.whole.try_borrow_mut().map_err(|_| column_borrowed::<T>())?;
+        if packed.borrows.iter().any(|flag| flag.try_borrow_mut().is_err()) {
+            return Err(column_borrowed::<T>());
+        }
+        // SAFETY: an UnsafeCell has the same layout as it's value, and no value can be borrowed while 'whole' is borrowed mutably
+        let values = unsafe { std::slice::from_raw_parts_mut(UnsafeCell::raw_get(packed.values.as_ptr()), packed.values.len()) };
+        Ok((RefMut::map(whole, |_| values), &packed.entities))
+    }
+
+    fn packed(&self) -> Result<&Packed<T>> {
+        match &self.storage {
+            Storage::Packed(packed) => Ok(packed),
+            Storage::Tags(_) => Err(ComponentError::TagColumnError(std::any::type_name::<T>()).into()),
+        }
+    }
+
+    /// Returns true if the component at 'index' is shared with another entity or [Shared] handle.
+    pub(crate) fn is_shared(&self, index: usize) -> bool {
+        match &self.storage {
+            Storage::Packed(packed) => packed.shared.get(&index).is_some_and(|component| Rc::strong_count(component) > 1),
+            Storage::Tags(_) => false,
+        }
+    }
+
+    /**
+    Returns a mutable reference to the component of every entity in 'ids', which must be unique and
+    only contain entities that have a component. The borrow flags aren't used since the column
+    is borrowed mutably. Panics if one of the components is shared, since it could be reached twice.
+     */
+    #[cfg(feature = "rayon")]
+    pub(crate) fn get_many_mut(&mut self, ids: &[usize]) -> Vec<&mut T> {
+        match &mut self.storage {
+            Storage::Packed(packed) => {
+                let positions = ids.iter()
+                    .map(|id| match packed.position(*id) {
+                        Some(position) => position,
+                        None if packed.shared.contains_key(id) => panic!("Attempt to query the shared component {} in parallel", std::any::type_name::<T>()),
+                        None => panic!("Entity ids must be unique and have a component"),
+                    })
+                    .collect::<Vec<usize>>();
+                let mut values = packed.values.iter_mut().map(|value| Some(value.get_mut())).collect::<Vec<Option<&mut T>>>();
+                positions.into_iter()
+                    .map(|position| values[position].take().expect("Entity ids must be unique and have a component"))
+                    .collect()
+            },
+            Storage::Tags(tags) => {
+                assert!(ids.iter().all(|id| tags.has(tags.slot(*id))), "Entity ids must be unique and have a component");
+                tags.instances.iter_mut().take(ids.len()).collect()
+            },
+        }
+    }
+}
+
+impl<T: Any> ComponentColumn for Column<T> {
+    fn len(&self) -> usize {
+        match &self.storage {
+            Storage::Packed(packed) => packed.positions.len(),
+            Storage::Tags(tags) => tags.borrows.len(),
+        }
+    }
+
+    fn resize(&mut self, len: usize) {
+        self.ticks.resize_with(self.ticks.len().max(len), ChangeTicks::default);
+        match &mut self.storage {
+            Storage::Packed(packed) => packed.positions.resize(packed.positions.len().max(len), None),
+            Storage::Tags(tags) => while tags.borrows.len() < len {
+                if let Some(order) = tags.order.as_mut() {
+                    let slot = order.slot_of.len();
+                    order.slot_of.push(slot);
+                    order.entity_in.push(slot);
+                }
+                if tags.borrows.len() % 64 == 0 {
+                    tags.bits.push(0);
+                }
+                tags.borrows.push(RefCell::new(()));
+            },
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<ComponentCell<'_>> {
+        self.get_typed(index).map(ComponentCell::erase)
+    }
+
+    fn remove(&mut self, index: usize) -> Option<Box<dyn Any>> {
+        boxed(self.take_slot(index))
+    }
+
+    fn put(&mut self, index: usize, component: Box<dyn Any>) -> Option<Box<dyn Any>> {
+        let component = match component.downcast::<T>() {
+            Ok(component) => Slot::Owned(*component),
+            Err(component) => {
+                let shared = component.downcast::<Shared<T>>()
+                    .unwrap_or_else(|_| panic!("Attempt to store a component of another type in a column of {}", std::any::type_name::<T>()));
+                Slot::Shared(shared.into_rc())
+            },
+        };
+        boxed(self.replace_slot(index, component))
+    }
+
+    fn move_to(&mut self, index: usize, target: &mut Entities, target_index: Entity) -> Result<()> {
+        match self.take_slot(index) {
+            Slot::Empty => Ok(()),
+            Slot::Owned(component) => target.insert_component_into_entity_by_id_checked(component, target_index).map(|_| ()),
+            Slot::Shared(component) => target.insert_shared_checked(target_index, &Shared::from_rc(component)),
+        }
+    }
+
+    fn clear(&mut self) {
+        match &mut self.storage {
+            Storage::Packed(packed) => {
+                packed.values.clear();
+                packed.borrows.clear();
+                packed.entities.clear();
+                packed.positions.clear();
+                packed.shared.clear();
+            },
+            Storage::Tags(tags) => {
+                tags.bits.clear();
+                tags.borrows.clear();
+                tags.instances.clear();
+                if let Some(order) = tags.order.as_mut() {
+                    order.slot_of.clear();
+                    order.entity_in.clear();
+                }
+            },
+        }
+        self.ticks.clear();
+        self.take_writes();
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        match &mut self.storage {
+            Storage::Packed(packed) => {
+                packed.values.reserve(additional);
+                packed.borrows.reserve(additional);
+                packed.entities.reserve(additional);
+                packed.positions.reserve(additional);
+            },
+            Storage::Tags(tags) => {
+                tags.bits.reserve(additional.div_ceil(64));
+                tags.borrows.reserve(additional);
+            },
+        }
+        self.ticks.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        match &mut self.storage {
+            Storage::Packed(packed) => {
+                packed.values.shrink_to_fit();
+                packed.borrows.shrink_to_fit();
+                packed.entities.shrink_to_fit();
+                packed.positions.shrink_to_fit();
+                packed.shared.shrink_to_fit();
+            },
+            Storage::Tags(tags) => {
+                tags.bits.shrink_to_fit();
+                tags.borrows.shrink_to_fit();
+            },
+        }
+        self.ticks.shrink_to_fit();
+    }
+
+    fn count(&self) -> usize {
+        match &self.storage {
+            Storage::Packed(packed) => packed.values.len() + packed.shared.len(),
+            Storage::Tags(tags) => tags.bits.iter().map(|bits| bits.count_ones() as usize).sum(),
+        }
+    }
+
+    fn allocated_bytes(&self) -> usize {
+        match &self.storage {
+            Storage::Packed(packed) => packed.values.capacity() * std::mem::size_of::<T>()
+                + packed.borrows.capacity() * std::mem::size_of::<RefCell<()>>()
+                + packed.entities.capacity() * std::mem::size_of::<Entity>()
+                + packed.positions.capacity() * std::mem::size_of::<Option<usize>>()
+                + packed.shared.capacity() * std::mem::size_of::<(Entity, Rc<RefCell<T>>)>(),
+            Storage::Tags(tags) => tags.bits.capacity() * std::mem::size_of::<u64>() + tags.borrows.capacity() * std::mem::size_of::<RefCell<()>>(),
+        }
+    }
+
+    fn tick_bytes(&self) -> usize {
+        self.ticks.capacity() * std::mem::size_of::<ChangeTicks>()
+    }
+
+    fn component_size(&self) -> usize {
+        std::mem::size_of::<T>()
+    }
+
+    fn component_type(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn component_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn move_slot(&mut self, entity: Entity, slot: usize) {
+        match &mut self.storage {
+            Storage::Packed(packed) => {
+                let old = packed.position(entity)
+                    .unwrap_or_else(|| panic!("Attempt to move the component {} of entity {entity}, which doesn't own one", std::any::type_name::<T>()));
+                let other = packed.entities[slot];
+                packed.values.swap(old, slot);
+                packed.borrows.swap(old, slot);
+                packed.entities.swap(old, slot);
+                packed.positions[entity] = Some(slot);
+                packed.positions[other] = Some(old);
+            },
+            Storage::Tags(tags) => {
+                let len = tags.borrows.len();
+                let order = tags.order.get_or_insert_with(|| SlotOrder { slot_of: (0..len).collect(), entity_in: (0..len).collect() });
+                let old = order.slot_of[entity];
+                let other = order.entity_in[slot];
+                order.slot_of.swap(entity, other);
+                order.entity_in.swap(old, slot);
+                let (had_old, had_slot) = (tags.has(old), tags.has(slot));
+                tags.set(old, had_slot);
+                tags.set(slot, had_old);
+            },
+        }
+    }
+
+    fn has_shared(&self) -> bool {
+        matches!(&self.storage, Storage::Packed(packed) if !packed.shared.is_empty())
+    }
+
+    fn entity_in(&self, slot: usize) -> Entity {
+        match &self.storage {
+            Storage::Packed(packed) => packed.entities[slot],
+            Storage::Tags(tags) => tags.order.as_ref().map_or(slot, |order| order.entity_in[slot]),
+        }
+    }
+
+    fn mark_changed(&self, entity: Entity, tick: u64) {
+        if let Some(ticks) = self.ticks.get(entity) {
+            if ticks.latest.get() != tick {
+                ticks.prior.set(ticks.latest.replace(tick));
+            }
+        }
+        if let Some(writes) = &self.writes {
+            writes.borrow_mut().insert(entity);
+        }
+    }
+
+    fn mark_inserted(&self, entity: Entity, tick: u64) {
+        if let Some(ticks) = self.ticks.get(entity) {
+            ticks.latest.set(tick);
+            ticks.prior.set(tick);
+        }
+    }
+
+    fn changed_tick(&self, entity: Entity) -> Option<u64> {
+        self.ticks.get(entity).map(|ticks| ticks.latest.get())
+    }
+
+    fn changed_during(&self, entity: Entity, tick: u64) -> bool {
+        self.ticks.get(entity).is_some_and(|ticks| ticks.latest.get() == tick || ticks.prior.get() == tick)
+    }
+
+    fn log_writes(&mut self) {
+        self.writes.get_or_insert_with(RefCell::default);
+    }
+
+    fn take_writes(&self) -> Vec<Entity> {
+        self.writes.as_ref().map(|writes| writes.take().into_iter().collect()).unwrap_or_default()
+    }
+
+    fn writes(&self) -> Vec<Entity> {
+        self.writes.as_ref().map(|writes| writes.borrow().iter().copied().collect()).unwrap_or_default()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+fn column_borrowed<T>() -> crate::error::Error {
+    ComponentError::ColumnAlreadyBorrowedError(std::any::type_name::<T>()).into()
+}
+
+// the type erased component or Shared handle in a slot
+fn boxed<T: Any>(component: Slot<T>) -> Option<Box<dyn Any>> {
+    match component {
+        Slot::Empty => None,
+        Slot::Owned(component) => Some(Box::new(component)),
+        Slot::Shared(component) => Some(Box::new(Shared::from_rc(component))),
+    }
+}
+
+impl<T: Any> Debug for Column<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Column")
+            .field("type", &std::any::type_name::<T>())
+            .field("len", &self.len())
+            .field("occupied", &self.count())
+            .field("tags", &matches!(self.storage, Storage::Tags(_)))
+            .finish()
+    }
+}
+
+/*
+    borrows a component, typed or not, returning an error naming it's type and entity if it is already
+    borrowed mutably, instead of the context free panic of RefCell
+*/
+pub(crate) fn try_borrow<'a, T: ?Sized>(component: ComponentCell<'a, T>, type_name: &'static str, entity: Entity) -> Result<Ref<'a, T>> {
+    component.try_borrow().map_err(|_| ComponentError::ComponentAlreadyBorrowedError { type_name, entity }.into())
+}
+
+// same as 'try_borrow', but borrows mutably, which fails if the component is borrowed at all
+pub(crate) fn try_borrow_mut<'a, T: ?Sized>(component: ComponentCell<'a, T>, type_name: &'static str, entity: Entity) -> Result<RefMut<'a, T>> {
+    component.try_borrow_mut().map_err(|_| ComponentError::ComponentAlreadyBorrowedError { type_name, entity }.into())
+}
+
+// same as 'try_borrow', but panics with the error, for the iterators that can't return one
+pub(crate) fn borrow<'a, T: ?Sized>(component: ComponentCell<'a, T>, type_name: &'static str, entity: Entity) -> Ref<'a, T> {
+    try_borrow(component, type_name, entity).unwrap_or_else(|error| panic!("{error}"))
+}
+
+// same as 'try_borrow_mut', but panics with the error, for the iterators that can't return one
+pub(crate) fn borrow_mut<'a, T: ?Sized>(component: ComponentCell<'a, T>, type_name: &'static str, entity: Entity) -> RefMut<'a, T> {
+    try_borrow_mut(component, type_name, entity).unwrap_or_else(|error| panic!("{error}"))
+}
@@ -0,0 +1,87 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    rc::Rc
+};
+
+use super::{ComponentError, Entities, Entity};
+use crate::error::Result;
+
+// inserts a new component of one type into an entity, created by the initializer it was registered with
+type InsertDefaultFn = Rc<dyn Fn(&mut Entities, Entity) -> Result<()>>;
+
+/*
+    The initializers of the component types registered with a default value, keyed by their TypeId,
+    so that components can be created from their type alone.
+*/
+#[derive(Default)]
+pub(crate) struct ComponentDefaults {
+    defaults: HashMap<TypeId, (&'static str, InsertDefaultFn)>,
+}
+
+impl std::fmt::Debug for ComponentDefaults {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.defaults.values().map(|(name, _)| name)).finish()
+    }
+}
+
+impl Entities {
+    /**
+    Registers a component type along with it's [Default] value, which can then be inserted into entities
+    with [insert_default()](struct.Entities.html#method.insert_default).
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Default)]
+    struct Score(u32);
+
+    let mut ents = Entities::default();
+    ents.register_component_with_default::<Score>();
+
+    ents.create_entity();
+    ents.insert_default::<Score>(0).unwrap();
+    assert!(ents.has_component::<Score>(0));
+    ```
+     */
+    pub fn register_component_with_default<T: Default + Any>(&mut self) {
+        self.register_component_with(T::default)
+    }
+
+    /**
+    Registers a component type along with the function creating it's default value, for types that don't
+    implement [Default] or whose default value should be different in this ECS. Registering the type again
+    replaces the function.
+     */
+    pub fn register_component_with<T: Any>(&mut self, init: impl Fn() -> T + 'static) {
+        self.register_component::<T>();
+        let insert: InsertDefaultFn = Rc::new(move |entities, entity| {
+            entities.insert_component_into_entity_by_id_checked(init(), entity).map(|_| ())
+        });
+        self.defaults.defaults.insert(TypeId::of::<T>(), (std::any::type_name::<T>(), insert));
+    }
+
+    /// Returns true if the component type with this TypeId was registered with a default value.
+    pub fn has_default(&self, type_id: &TypeId) -> bool {
+        self.defaults.defaults.contains_key(type_id)
+    }
+
+    /**
+    Inserts the default value of the component T into an entity, replacing the component it already has.
+    Returns an error if T wasn't registered with a default value, or if the entity doesn't exist.
+     */
+    pub fn insert_default<T: Any>(&mut self, entity: Entity) -> Result<()> {
+        self.insert_default_by_type_id(&TypeId::of::<T>(), entity)
+    }
+
+    /**
+    Same as [insert_default()](struct.Entities.html#method.insert_default), but the component type is given
+    by it's TypeId, so that it can be used when the type isn't known, for example to fill in the components
+    missing from deserialized entities.
+     */
+    pub fn insert_default_by_type_id(&mut self, type_id: &TypeId, entity: Entity) -> Result<()> {
+        let (_, insert) = self.defaults.defaults.get(type_id).ok_or(ComponentError::NoDefaultError)?;
+        let insert = Rc::clone(insert);
+        insert(self, entity)
+    }
+}
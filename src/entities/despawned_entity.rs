@@ -0,0 +1,95 @@
+//! # Despawned Entities
+//!
+//! A [DespawnedEntity] holds on to the components of an entity that was removed from the ECS,
+//! so that they can be taken out before they are dropped.
+
+use std::{any::{Any, TypeId}, collections::HashMap};
+
+use super::{ComponentError, Entity, Shared};
+use crate::error::Result;
+
+/**
+The components an entity had when it was despawned with [Entities::despawn()](struct.Entities.html#method.despawn).
+
+Any component that isn't taken out is dropped along with the DespawnedEntity.
+
+```
+use sceller::prelude::*;
+
+#[derive(Debug, PartialEq)]
+struct Item(&'static str);
+struct Position(i32, i32);
+
+let mut world = World::new();
+world.spawn().insert(Item("Sword")).insert(Position(3, 4));
+
+let mut inventory = Vec::new();
+
+let mut despawned = world.despawn(0);
+inventory.push(despawned.take::<Item>().unwrap());
+
+assert_eq!(inventory, vec![Item("Sword")]);
+assert!(despawned.has::<Position>());
+assert_eq!(world.query().auto::<Position>().len(), 0);
+```
+ */
+pub struct DespawnedEntity {
+    pub id: Entity,
+    components: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl DespawnedEntity {
+    pub(crate) fn new(id: Entity, components: HashMap<TypeId, Box<dyn Any>>) -> Self {
+        Self { id, components }
+    }
+
+    /**
+    Returns true if the entity had a component of type T that hasn't been taken yet.
+     */
+    pub fn has<T: Any>(&self) -> bool {
+        self.components.contains_key(&TypeId::of::<T>())
+    }
+
+    /**
+    Takes a component out of the despawned entity and returns the owned data.
+
+    Returns an error if the entity had no such component, or it was already taken.
+     */
+    pub fn take<T: Any>(&mut self) -> Result<T> {
+        let component = self.components.remove(&TypeId::of::<T>()).ok_or(ComponentError::NonexistentComponentDataError)?;
+        component.downcast::<T>()
+            .map(|component| *component)
+            .map_err(|_| ComponentError::NonexistentComponentDataError.into())
+    }
+
+    /**
+    Takes a component that the entity shared with others out of the despawned entity, and returns it's [Shared] handle.
+
+    Returns an error if the entity had no such component, if it was already taken, or if it wasn't shared.
+     */
+    pub fn take_shared<T: Any>(&mut self) -> Result<Shared<T>> {
+        let component = self.components.remove(&TypeId::of::<T>()).ok_or(ComponentError::NonexistentComponentDataError)?;
+        component.downcast::<Shared<T>>()
+            .map(|component| *component)
+            .map_err(|_| ComponentError::NonexistentComponentDataError.into())
+    }
+
+    /// Returns the number of components left in the despawned entity.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns true if every component has been taken out of the despawned entity.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl std::fmt::Debug for DespawnedEntity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DespawnedEntity")
+            .field("id", &self.id)
+            .field("components", &self.components.len())
+            .finish()
+    }
+}
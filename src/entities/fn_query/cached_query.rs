@@ -0,0 +1,165 @@
+use std::{
+    any::TypeId,
+    borrow::Cow,
+    cell::RefCell,
+    cmp::Ordering,
+    collections::HashMap,
+    marker::PhantomData,
+    rc::Rc
+};
+
+use super::{FnQueryContainedTupleType, FnQueryIdIterator, FnQueryIterator};
+use crate::entities::{ComponentId, Entities, Entity};
+
+/**
+A query that remembers which entities it matched between frames, and only looks for them again
+after a structural change, that is after an entity is spawned with or despawned with components,
+or gains or loses a component.
+
+It is used just like an [FnQuery](struct.FnQuery.html), either as a system parameter or with
+[World::query_cached()](struct.World.html#method.query_cached). Every CachedQuery of the same type
+shares the same cache, which is kept in the World.
+
+```
+use sceller::prelude::*;
+
+struct Position(i32);
+struct Velocity(i32);
+
+fn movement(query: CachedQuery<(&mut Position, &Velocity)>) {
+    for (mut pos, vel) in query.iter() {
+        pos.0 += vel.0;
+    }
+}
+
+let mut world = World::new();
+world.spawn().insert(Position(0)).insert(Velocity(2));
+world.spawn().insert(Position(0));
+
+// the matching entities are found on the first run, and reused on the second one
+world.run_system(movement);
+world.run_system(movement);
+
+// spawning an entity invalidates the cache
+world.spawn().insert(Position(0)).insert(Velocity(1));
+world.run_system(movement);
+
+let positions = world.query_typed::<&Position>().map(|pos| pos.0).collect::<Vec<i32>>();
+assert_eq!(positions, vec![6, 0, 1]);
+```
+ */
+pub struct CachedQuery<'a, T> {
+    entities: &'a Entities,
+    cache: Rc<QueryCache>,
+    phantom: PhantomData<&'a T>,
+}
+
+impl<'a, T> CachedQuery<'a, T>
+where T: 'static + for<'b> FnQueryContainedTupleType<'b>
+{
+    pub(crate) fn new(entities: &'a Entities, caches: &QueryCaches) -> Self {
+        Self {
+            entities, cache: caches.get::<T>(entities), phantom: PhantomData
+        }
+    }
+}
+
+impl<'a, T> CachedQuery<'a, T>
+where T: for<'b> FnQueryContainedTupleType<'b>
+{
+    /// Returns an iterator over the components of every matching entity, in ascending order of entity id.
+    pub fn iter(&self) -> FnQueryIterator<'_, T> {
+        FnQueryIterator::from_ids(self.entities, self.cache.components.clone(), Cow::Borrowed(&self.cache.ids))
+    }
+
+    /// Same as [CachedQuery::iter()], but each result is paired with the id of the entity it belongs to.
+    pub fn iter_with_ids(&self) -> FnQueryIdIterator<'_, T> {
+        FnQueryIdIterator { inner: self.iter() }
+    }
+
+    /// Same as [CachedQuery::iter()], but the results are returned in the order given by 'compare', see [FnQuery::iter_sorted_by()](struct.FnQuery.html#method.iter_sorted_by).
+    pub fn iter_sorted_by<'q, F>(&'q self, compare: F) -> FnQueryIterator<'q, T>
+    where F: FnMut(&<T as FnQueryContainedTupleType<'q>>::ReturnType, &<T as FnQueryContainedTupleType<'q>>::ReturnType) -> Ordering
+    {
+        self.iter().sorted_by(compare)
+    }
+
+    /// Same as [CachedQuery::iter()], but the results are sorted by the key returned by 'key' for each of them.
+    pub fn iter_sorted_by_key<'q, K, F>(&'q self, key: F) -> FnQueryIterator<'q, T>
+    where
+        K: Ord,
+        F: FnMut(&<T as FnQueryContainedTupleType<'q>>::ReturnType) -> K,
+    {
+        self.iter().sorted_by_key(key)
+    }
+
+    /// Returns the number of matching entities.
+    pub fn len(&self) -> usize {
+        self.cache.ids.len()
+    }
+
+    /// Returns true if no entity matches the query.
+    pub fn is_empty(&self) -> bool {
+        self.cache.ids.is_empty()
+    }
+}
+
+impl<'q, 'a, T> std::iter::IntoIterator for &'q CachedQuery<'a, T>
+where T: for<'b> FnQueryContainedTupleType<'b>
+{
+    type Item = <T as FnQueryContainedTupleType<'q>>::ReturnType;
+    type IntoIter = FnQueryIterator<'q, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// the matching entities of one query type, and the generation and tick of Entities they were found at,
+// since the entities matching a Changed filter can only change with the tick
+#[derive(Debug)]
+pub(crate) struct QueryCache {
+    generation: u64,
+    tick: u64,
+    components: Vec<ComponentId>,
+    ids: Vec<Entity>,
+}
+
+impl QueryCache {
+    fn new<T>(entities: &Entities) -> Self
+    where T: for<'b> FnQueryContainedTupleType<'b>
+    {
+        let components = T::component_ids(entities);
+        let ids = T::ids(entities, &components);
+        Self { generation: entities.generation(), tick: entities.tick(), components, ids }
+    }
+}
+
+/*
+    The caches of every CachedQuery type, stored in the World. They are behind a RefCell
+    since systems only get an immutable reference to the World.
+*/
+#[derive(Debug, Default)]
+pub(crate) struct QueryCaches {
+    caches: RefCell<HashMap<TypeId, Rc<QueryCache>>>,
+}
+
+impl QueryCaches {
+    // returns the cache of the query type T, finding the matching entities again if it's out of date
+    fn get<T>(&self, entities: &Entities) -> Rc<QueryCache>
+    where T: 'static + for<'b> FnQueryContainedTupleType<'b>
+    {
+        let mut caches = self.caches.borrow_mut();
+        let cache = caches.entry(TypeId::of::<T>())
+            .or_insert_with(|| Rc::new(QueryCache::new::<T>(entities)));
+
+        if cache.generation != entities.generation() || cache.tick != entities.tick() {
+            *cache = Rc::new(QueryCache::new::<T>(entities));
+        }
+        Rc::clone(cache)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.caches.get_mut().clear();
+    }
+}
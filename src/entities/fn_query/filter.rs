@@ -0,0 +1,211 @@
+use std::{
+    any::{Any, TypeId},
+    marker::PhantomData
+};
+
+use super::{FnQueryContainedIndividualType, FnQueryContainedTupleType};
+use crate::entities::{ComponentCell, ComponentId, Entities, Entity};
+use crate::system::Access;
+use crate::error::Result;
+
+/**
+A type used inside of an [FnQuery](struct.FnQuery.html) to only match entities that have the component T,
+without borrowing it. Useful for tag components, the result in it's place is always '()'.
+
+```
+use sceller::prelude::*;
+
+struct Position(i32);
+struct Enemy;
+
+let mut world = World::new();
+world.spawn().insert(Position(1));
+world.spawn().insert(Position(2)).insert(Enemy);
+
+world.run_system(|enemies: FnQuery<(&mut Position, With<Enemy>)>| {
+    for (mut pos, _) in enemies {
+        pos.0 *= 10;
+    }
+});
+
+let positions = world.query_typed::<&Position>().map(|pos| pos.0).collect::<Vec<i32>>();
+assert_eq!(positions, vec![1, 20]);
+```
+ */
+pub struct With<T>(PhantomData<T>);
+
+impl<'a, T: Any> FnQueryContainedIndividualType<'a> for With<T> {
+    type ReturnType = ();
+
+    fn type_id_new() -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn type_name_new() -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    // only checks that the component exists, so nothing is borrowed
+    fn access() -> Option<Access> {
+        None
+    }
+
+    fn map_ref(_reference: ComponentCell<'a>, _id: Entity) -> Result<Self::ReturnType> {
+        Ok(())
+    }
+}
+
+/**
+A type used inside of an [FnQuery](struct.FnQuery.html) to only match entities whose component T changed during
+the previous [tick](crate::tick), that is was inserted, replaced or borrowed mutably, without borrowing it.
+Every system of a tick sees the same changes, including the changes made during the tick before it ran,
+since the tick only advances when the [Schedule](crate::schedule::Schedule) runs again.
+
+```
+use sceller::prelude::*;
+
+struct Health(u32);
+struct Hurt(bool);
+
+fn hurt(query: FnQuery<(&Health, &mut Hurt, Changed<Health>)>) {
+    for (_, mut hurt, _) in query {
+        hurt.0 = true;
+    }
+}
+
+let mut world = World::new();
+world.spawn().insert(Health(10)).insert(Hurt(false));
+world.spawn().insert(Health(10)).insert(Hurt(false));
+let mut schedule = Schedule::new();
+schedule.add_system(hurt);
+
+// every component was inserted during the first tick, and is seen as changed during the second one
+schedule.run_n(&mut world, 2).unwrap();
+world.query_typed::<&mut Hurt>().for_each(|mut hurt| hurt.0 = false);
+
+world.get::<&mut Health>(1).unwrap().0 -= 1;
+schedule.run(&mut world).unwrap();
+let hurt = world.query_typed::<&Hurt>().map(|hurt| hurt.0).collect::<Vec<bool>>();
+assert_eq!(hurt, vec![false, true]);
+```
+
+Borrowing a component mutably counts as a change even if it's value stays the same.
+ */
+pub struct Changed<T>(PhantomData<T>);
+
+impl<'a, T: Any> FnQueryContainedIndividualType<'a> for Changed<T> {
+    type ReturnType = ();
+
+    fn type_id_new() -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn type_name_new() -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    // only checks when the component changed, so nothing is borrowed
+    fn access() -> Option<Access> {
+        None
+    }
+
+    fn filter(entities: &'a Entities, component: ComponentId, id: Entity) -> bool {
+        entities.changed_last_tick(component, id)
+    }
+
+    fn map_ref(_reference: ComponentCell<'a>, _id: Entity) -> Result<Self::ReturnType> {
+        Ok(())
+    }
+}
+
+/**
+A type used inside of an [FnQuery](struct.FnQuery.html) to match entities that have at least one of the
+components in a tuple, instead of all of them. Each element of the result is an Option, which is Some
+if the entity has that component.
+
+```
+use sceller::prelude::*;
+
+struct Button(&'static str);
+struct Slider(f32);
+struct Label;
+
+let mut world = World::new();
+world.spawn().insert(Button("play"));
+world.spawn().insert(Slider(0.5));
+world.spawn().insert(Button("volume")).insert(Slider(1.0));
+world.spawn().insert(Label);
+
+world.run_system(|widgets: FnQuery<Or<(&Button, &Slider)>>| {
+    let mut found = Vec::new();
+    for (button, slider) in widgets {
+        found.push((button.map(|b| b.0), slider.map(|s| s.0)));
+    }
+    assert_eq!(found, vec![(Some("play"), None), (None, Some(0.5)), (Some("volume"), Some(1.0))]);
+});
+```
+ */
+pub struct Or<T>(PhantomData<T>);
+
+macro_rules! impl_or_tuple {
+    ($($t:ident $index:tt),+) => {
+        impl<'a, $($t),+> FnQueryContainedTupleType<'a> for Or<($($t,)+)>
+        where
+            $($t: FnQueryContainedIndividualType<'a>,)+
+        {
+            type ReturnType = ($(Option<$t::ReturnType>,)+);
+
+            fn type_ids() -> Vec<TypeId> {
+                vec![$($t::type_id_new()),+]
+            }
+
+            fn type_names() -> Vec<&'static str> {
+                vec![$($t::type_name_new()),+]
+            }
+
+            fn access() -> Vec<Access> {
+                [$($t::access()),+].into_iter().flatten().collect()
+            }
+
+            // every entity matching the mask of at least one of the components
+            fn ids(entities: &'a Entities, components: &[ComponentId]) -> Vec<Entity> {
+                let mut ids = components.iter()
+                    .flat_map(|component| entities.matching_entities(1 << component).into_iter()
+                        .filter(|ind| entities.component_at_id(*component, *ind).is_some()))
+                    .collect::<Vec<Entity>>();
+                ids.retain(|id| Self::filter(entities, components, *id));
+                ids.sort_unstable();
+                ids.dedup();
+                ids
+            }
+
+            fn matches(entities: &'a Entities, components: &[ComponentId], id: Entity) -> bool {
+                components.iter().any(|component| entities.has_component_id(*component, id)
+                    && entities.component_at_id(*component, id).is_some())
+                    && Self::filter(entities, components, id)
+            }
+
+            // at least one of the components the entity has passes it's filter
+            fn filter(entities: &'a Entities, components: &[ComponentId], id: Entity) -> bool {
+                $((entities.has_component_id(components[$index], id) && $t::filter(entities, components[$index], id)))||+
+            }
+
+            fn try_fetch(entities: &'a Entities, components: &[ComponentId], id: Entity) -> Result<Self::ReturnType> {
+                // a removed component can still be in it's slot, so the entity's mask is checked too
+                Ok(($((entities.has_component_id(components[$index], id) && $t::filter(entities, components[$index], id))
+                    .then(|| entities.component_at_id(components[$index], id))
+                    .flatten()
+                    .map(|_| $t::try_fetch(entities, components[$index], id))
+                    .transpose()?,)+))
+            }
+        }
+    };
+}
+
+impl_or_tuple!(T1 0, T2 1);
+impl_or_tuple!(T1 0, T2 1, T3 2);
+impl_or_tuple!(T1 0, T2 1, T3 2, T4 3);
+impl_or_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4);
+impl_or_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5);
+impl_or_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6);
+impl_or_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7);
@@ -1,18 +1,119 @@
 use std::{
     any::{Any, TypeId},
-    cell::{Ref, RefCell, RefMut},
-    marker::PhantomData, rc::Rc
+    borrow::Cow,
+    cell::{Ref, RefMut},
+    cmp::Ordering,
+    marker::PhantomData
 };
 
-use super::{Entities, Query};
+use super::{ComponentCell, ComponentId, Entities, Entity, Query, QueryError};
+use super::column::{try_borrow, try_borrow_mut};
+use crate::error::Result;
+use crate::system::Access;
+
+mod cached_query;
+pub use cached_query::CachedQuery;
+mod filter;
+pub use filter::{Changed, Or, With};
+mod trait_query;
+#[doc(hidden)]
+pub use trait_query::TraitQuery;
+pub(crate) use trait_query::ComponentTraits;
+pub(crate) use cached_query::QueryCaches;
 
 impl<'a> Query<'a> {
+    /**
+    Runs a function taking an [FnQuery] as its parameter, the query can contain
+    both immutable and mutable references, e.g: `FnQuery<(&Health, &mut Position)>`.
+     */
     pub fn query_fn<F, T: 'a>(&self, gen: F)
     where
         F: IntoFnQuery<'a, T>
     {
         gen.run(self.entities)
     }
+
+    /**
+    Same as [Query::query_fn()], mutability is decided by the references inside of the [FnQuery],
+    so this is only here for readability.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(5));
+
+    world.query().query_fn_mut(|healths: FnQuery<&mut Health>| {
+        for mut hp in healths {
+            hp.0 += 1;
+        }
+    });
+
+    assert_eq!(world.query_typed::<&Health>().next().unwrap().0, 6);
+    ```
+     */
+    pub fn query_fn_mut<F, T: 'a>(&self, gen: F)
+    where
+        F: IntoFnQuery<'a, T>
+    {
+        self.query_fn(gen)
+    }
+
+    /**
+    Fetches the components of a single entity, taking the same types as an [FnQuery], 
+    so '&T' yields a [Ref] and '&mut T' yields a [RefMut].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Position(i32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(3));
+    world.spawn().insert(Health(9)).insert(Position(0));
+
+    let query = world.query();
+    let (health, mut pos) = query.get::<(&Health, &mut Position)>(1).unwrap();
+    pos.0 += health.0 as i32;
+    assert_eq!(pos.0, 9);
+
+    // entity 0 has no Position
+    assert!(query.get::<(&Health, &mut Position)>(0).is_err());
+    ```
+
+    Returns an error if one of the components isn't registered, if the entity doesn't have all of them,
+    or if one of them is already borrowed in a way that conflicts with the query.
+     */
+    pub fn get<T>(&self, entity: Entity) -> Result<T::ReturnType>
+    where T: FnQueryContainedTupleType<'a>
+    {
+        get_entity::<T>(self.entities, entity)
+    }
+
+    /**
+    Iterates over the entities of the group made of exactly the components of the query, registered with
+    [World::register_group()](struct.World.html#method.register_group), in the order they are stored in.
+    Unlike [World::query_typed()](struct.World.html#method.query_typed), the bitmask of every entity isn't
+    compared with the one of the query: the ids of the group's entities are collected from the first slots
+    of one of it's columns. They still go through the filters of the query, like [Changed], and each
+    component is still fetched through the slot of it's entity, as with any other query.
+
+    Returns an error if one of the components isn't registered, or if no group is made of exactly the components
+    of the query. A group can't answer a query on some of it's components, since entities outside of the group
+    can have them too.
+     */
+    pub fn group<T>(&self) -> Result<FnQueryIterator<'a, T>>
+    where T: FnQueryContainedTupleType<'a>
+    {
+        let components = T::component_ids_checked(self.entities)?;
+        let mut ids = self.entities.group_entities(&components)
+            .ok_or_else(|| QueryError::UngroupedQueryError(T::type_names()))?;
+        ids.retain(|id| T::filter(self.entities, &components, *id));
+        Ok(FnQueryIterator::from_ids(self.entities, components, Cow::Owned(ids)))
+    }
 }
 
 //
@@ -63,7 +164,70 @@ where T: FnQueryContainedTupleType<'a>
 pub trait FnQueryContainedTupleType<'a> {
     type ReturnType;
 
-    fn map(entities: &'a Entities) -> Vec<Self::ReturnType>;
+    // the type ids of every element of the tuple
+    fn type_ids() -> Vec<TypeId>;
+
+    // the type names of every element of the tuple, in the same order as the type ids
+    fn type_names() -> Vec<&'static str>;
+
+    // the components borrowed by the elements of the tuple
+    fn access() -> Vec<Access>;
+
+    // the component ids of every element of the tuple, looked up once so that 
+    // fetching components doesn't have to hash their type ids
+    fn component_ids(entities: &'a Entities) -> Vec<ComponentId> {
+        Self::component_ids_checked(entities).unwrap()
+    }
+
+    // same as 'component_ids', but returns an error if one of the components isn't registered
+    fn component_ids_checked(entities: &'a Entities) -> Result<Vec<ComponentId>, QueryError> {
+        Self::type_ids().iter()
+            .map(|typeid| entities.component_id(typeid).ok_or(QueryError::UnregisteredComponentError))
+            .collect()
+    }
+
+    // the ids of the entities that have a valid component for every element of the tuple,
+    // found with one bitmask combining the masks of all the elements
+    fn ids(entities: &'a Entities, components: &[ComponentId]) -> Vec<Entity> {
+        let mask = components.iter().fold(0, |mask, component| mask | 1 << component);
+
+        entities.matching_entities(mask).into_iter()
+            .filter(|ind| components.iter().all(|component| entities.component_at_id(*component, *ind).is_some()))
+            .filter(|ind| Self::filter(entities, components, *ind))
+            .collect()
+    }
+
+    // whether the entity 'id' has a valid component for every element of the tuple, the same check as 'ids'
+    fn matches(entities: &'a Entities, components: &[ComponentId], id: Entity) -> bool {
+        components.iter().all(|component| entities.has_component_id(*component, id) 
+            && entities.component_at_id(*component, id).is_some())
+            && Self::filter(entities, components, id)
+    }
+
+    // whether the entity 'id', which has every component, passes the filters of the tuple, like [Changed]
+    fn filter(_entities: &'a Entities, _components: &[ComponentId], _id: Entity) -> bool {
+        true
+    }
+
+    // fetches every element of the tuple from the same entity 'id', panicking with the error of 'try_fetch'
+    fn fetch(entities: &'a Entities, components: &[ComponentId], id: Entity) -> Self::ReturnType {
+        Self::try_fetch(entities, components, id).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    // same as 'fetch', but returns an error naming the first component that is already borrowed
+    fn try_fetch(entities: &'a Entities, components: &[ComponentId], id: Entity) -> Result<Self::ReturnType>;
+}
+
+// fetches the components of a single entity, if it matches the query
+fn get_entity<'a, T>(entities: &'a Entities, entity: Entity) -> Result<T::ReturnType>
+where T: FnQueryContainedTupleType<'a>
+{
+    let components = T::component_ids_checked(entities)?;
+
+    if !T::matches(entities, &components, entity) {
+        return Err(QueryError::NonMatchingEntityError(entity).into());
+    }
+    T::try_fetch(entities, &components, entity)
 }
 
 /*
@@ -75,40 +239,72 @@ where T: FnQueryContainedIndividualType<'a>
 {
     type ReturnType = T::ReturnType;
 
-    fn map(entities: &'a Entities) -> Vec<Self::ReturnType> {
-        T::map(entities)
+    fn type_ids() -> Vec<TypeId> {
+        vec![T::type_id_new()]
     }
-}
 
-impl<'a, T1, T2> FnQueryContainedTupleType<'a> for (T1, T2)
-where 
-    T1: FnQueryContainedIndividualType<'a>,
-    T2: FnQueryContainedIndividualType<'a>,
-{
-    type ReturnType = (T1::ReturnType, T2::ReturnType);
+    fn type_names() -> Vec<&'static str> {
+        vec![T::type_name_new()]
+    }
 
-    fn map(entities: &'a Entities) -> Vec<Self::ReturnType> {
-        T1::map(entities).into_iter().zip(T2::map(entities)).collect()
+    fn access() -> Vec<Access> {
+        T::access().into_iter().collect()
     }
-}
 
-impl<'a, T1, T2, T3> FnQueryContainedTupleType<'a> for (T1, T2, T3)
-where 
-    T1: FnQueryContainedIndividualType<'a>,
-    T2: FnQueryContainedIndividualType<'a>,
-    T3: FnQueryContainedIndividualType<'a>,
-{
-    type ReturnType = (T1::ReturnType, T2::ReturnType, T3::ReturnType);
+    fn filter(entities: &'a Entities, components: &[ComponentId], id: Entity) -> bool {
+        T::filter(entities, components[0], id)
+    }
 
-    fn map(entities: &'a Entities) -> Vec<Self::ReturnType> {
-        T1::map(entities).into_iter()
-            .zip(T2::map(entities))
-            .zip(T3::map(entities))
-            .map(|((x, y), z)| (x, y, z))
-            .collect()
+    fn try_fetch(entities: &'a Entities, components: &[ComponentId], id: Entity) -> Result<Self::ReturnType> {
+        T::try_fetch(entities, components[0], id)
     }
 }
 
+// implements FnQueryContainedTupleType for a tuple of individual types
+macro_rules! impl_fn_query_tuple {
+    ($($t:ident $index:tt),+) => {
+        impl<'a, $($t),+> FnQueryContainedTupleType<'a> for ($($t,)+)
+        where 
+            $($t: FnQueryContainedIndividualType<'a>,)+
+        {
+            type ReturnType = ($($t::ReturnType,)+);
+
+            fn type_ids() -> Vec<TypeId> {
+                vec![$($t::type_id_new()),+]
+            }
+
+            fn type_names() -> Vec<&'static str> {
+                vec![$($t::type_name_new()),+]
+            }
+
+            fn access() -> Vec<Access> {
+                [$($t::access()),+].into_iter().flatten().collect()
+            }
+
+            fn filter(entities: &'a Entities, components: &[ComponentId], id: Entity) -> bool {
+                $($t::filter(entities, components[$index], id))&&+
+            }
+
+            fn try_fetch(entities: &'a Entities, components: &[ComponentId], id: Entity) -> Result<Self::ReturnType> {
+                Ok(($($t::try_fetch(entities, components[$index], id)?,)+))
+            }
+        }
+    };
+}
+
+impl_fn_query_tuple!(T1 0);
+impl_fn_query_tuple!(T1 0, T2 1);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2, T4 3);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9, T11 10);
+impl_fn_query_tuple!(T1 0, T2 1, T3 2, T4 3, T5 4, T6 5, T7 6, T8 7, T9 8, T10 9, T11 10, T12 11);
+
 // A trait implemented that abstracts over all the different types 
 // an FnQuery<> can contain:
 //
@@ -119,32 +315,29 @@ pub trait FnQueryContainedIndividualType<'a>
 
     fn type_id_new() -> TypeId;
 
-    fn map(entities: &'a Entities) -> Vec<Self::ReturnType> {
-        let typeid = Self::type_id_new();
-
-        let selfmap = entities.bit_masks.get(&typeid).unwrap();
+    fn type_name_new() -> &'static str;
 
-        let all_components = entities.components.get(&typeid).unwrap();
-        // get all components with the type of this AutoQuery
+    // the component borrowed by this type, if it borrows one
+    fn access() -> Option<Access>;
 
-        // get all valid components (not deleted or None)
-        let components = all_components.into_iter().enumerate()
-            .map(|(ind, c)| {
-                if (entities.map[ind] & selfmap == *selfmap) && c.is_some() {
-                    Some(c.as_ref().unwrap())
-                } else {
-                    None
-                }
-            })
-            .flatten()
-            .collect::<Vec<&Rc<RefCell<dyn Any>>>>();
+    // whether the component of this type, whose id is 'component', passes the filter of this type
+    fn filter(_entities: &'a Entities, _component: ComponentId, _id: Entity) -> bool {
+        true
+    }
 
-        components.into_iter().map(|component| {
-            Self::map_ref(&component.as_ref())
-        }).collect()
+    // borrows the component of this type, whose id is 'component', belonging to the entity 'id',
+    // which is marked as changed if it is borrowed mutably
+    fn try_fetch(entities: &'a Entities, component: ComponentId, id: Entity) -> Result<Self::ReturnType> {
+        let reference = entities.component_at_id(component, id).ok_or(QueryError::NonMatchingEntityError(id))?;
+        let fetched = Self::map_ref(reference, id)?;
+        if Self::access().is_some_and(|access| access.mutable) {
+            entities.mark_changed_id(component, id);
+        }
+        Ok(fetched)
     }
 
-    fn map_ref(reference: &'a RefCell<dyn Any>) -> Self::ReturnType;
+    // borrows the component of the entity 'id', returning an error if it is already borrowed
+    fn map_ref(reference: ComponentCell<'a>, id: Entity) -> Result<Self::ReturnType>;
 }
 
 impl<'a, T: Any> FnQueryContainedIndividualType<'a> for &T 
@@ -155,10 +348,18 @@ impl<'a, T: Any> FnQueryContainedIndividualType<'a> for &T
         TypeId::of::<T>()
     }
 
-    fn map_ref(reference: &'a RefCell<dyn Any>) -> Self::ReturnType {
-        Ref::map(reference.borrow(), |any| {
+    fn type_name_new() -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn access() -> Option<Access> {
+        Some(Access::component::<T>(false))
+    }
+
+    fn map_ref(reference: ComponentCell<'a>, id: Entity) -> Result<Self::ReturnType> {
+        Ok(Ref::map(try_borrow(reference, std::any::type_name::<T>(), id)?, |any| {
             any.downcast_ref::<T>().unwrap()
-        })
+        }))
     }
 }
 
@@ -170,10 +371,18 @@ impl<'a, T: Any> FnQueryContainedIndividualType<'a> for &mut T
         TypeId::of::<T>()
     }
 
-    fn map_ref(reference: &'a RefCell<dyn Any>) -> Self::ReturnType {
-        RefMut::map(reference.borrow_mut(), |any| {
+    fn type_name_new() -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn access() -> Option<Access> {
+        Some(Access::component::<T>(true))
+    }
+
+    fn map_ref(reference: ComponentCell<'a>, id: Entity) -> Result<Self::ReturnType> {
+        Ok(RefMut::map(try_borrow_mut(reference, std::any::type_name::<T>(), id)?, |any| {
             any.downcast_mut::<T>().unwrap()
-        })
+        }))
     }
 }
 
@@ -190,11 +399,177 @@ where
 impl<'a, T> FnQuery<'a, T> 
 where T: FnQueryContainedTupleType<'a>
 {
-    pub fn iter(&self) -> FnQueryIterator<'a, T::ReturnType> {
-        FnQueryIterator {
-            components: T::map(self.entities),
-            phantom: PhantomData,
+    pub fn iter(&self) -> FnQueryIterator<'a, T> {
+        FnQueryIterator::new(self.entities)
+    }
+
+    /**
+    Same as [FnQuery::iter()], but each result is paired with the id of the entity it belongs to,
+    so that it can be used later on, for example to delete the entity with [Commands](struct.Commands.html).
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(i32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(10));
+    world.spawn().insert(Health(0));
+
+    world.run_system(|mut commands: Commands, healths: FnQuery<&Health>| {
+        for (entity, health) in healths.iter_with_ids() {
+            if health.0 <= 0 {
+                commands.despawn(entity);
+            }
         }
+    });
+
+    assert_eq!(world.query().auto::<Health>().len(), 1);
+    ```
+     */
+    pub fn iter_with_ids(&self) -> FnQueryIdIterator<'a, T> {
+        FnQueryIdIterator { inner: FnQueryIterator::new(self.entities) }
+    }
+
+    /**
+    Calls 'f' with the result of every matching entity, borrowing it's components only for the duration of the call.
+    Unlike [FnQuery::iter()], the matching entities aren't collected beforehand, which saves an allocation
+    in queries run every frame, but they are visited one archetype at a time instead of in order of their ids.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+    struct Regeneration(u32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(10)).insert(Regeneration(2));
+    world.spawn().insert(Health(5));
+    world.spawn().insert(Health(1)).insert(Regeneration(3));
+
+    world.run_system(|query: FnQuery<(&mut Health, &Regeneration)>| {
+        query.for_each(|(mut health, regen)| health.0 += regen.0);
+    });
+
+    let mut healths = world.query_typed::<&Health>().map(|health| health.0).collect::<Vec<u32>>();
+    healths.sort();
+    assert_eq!(healths, vec![4, 5, 12]);
+    ```
+
+    Panics if a component is already borrowed in a way that conflicts with the query.
+     */
+    pub fn for_each<F>(&self, mut f: F)
+    where F: FnMut(T::ReturnType)
+    {
+        let components = T::component_ids(self.entities);
+        let mask = components.iter().fold(0, |mask, component| mask | 1 << component);
+
+        self.entities.for_each_matching(mask, |id| {
+            if T::matches(self.entities, &components, id) {
+                f(T::fetch(self.entities, &components, id));
+            }
+        });
+    }
+
+    /**
+    Same as [FnQuery::for_each()], mutability is decided by the references inside of the [FnQuery],
+    so this is only here for readability.
+     */
+    pub fn for_each_mut<F>(&self, f: F)
+    where F: FnMut(T::ReturnType)
+    {
+        self.for_each(f)
+    }
+
+    /**
+    Fetches the components of a single entity, instead of iterating over every matching entity.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Target(Entity);
+    struct Position(i32);
+    struct Velocity(i32);
+
+    let mut world = World::new();
+    world.spawn().insert(Position(5));
+    world.spawn().insert(Position(0)).insert(Velocity(0)).insert(Target(0));
+
+    world.run_system(|followers: FnQuery<(&mut Velocity, &Position, &Target)>, targets: FnQuery<&Position>| {
+        for (mut vel, pos, target) in followers.iter() {
+            if let Ok(target_pos) = targets.get(target.0) {
+                vel.0 = target_pos.0 - pos.0;
+            }
+        }
+    });
+
+    assert_eq!(world.get::<&Velocity>(1).unwrap().0, 5);
+    ```
+
+    Returns an error if the entity doesn't match the query, see [Query::get()].
+     */
+    pub fn get(&self, entity: Entity) -> Result<T::ReturnType> {
+        get_entity::<T>(self.entities, entity)
+    }
+
+    /**
+    Same as [FnQuery::iter()], but the results are returned in the order given by 'compare' instead of
+    by entity id. Results comparing equal keep the order of their entity ids.
+
+    Every result is fetched once to be sorted, then borrowed again when it is reached, so the components are
+    only kept borrowed for as long as the results are kept around, just like with [FnQuery::iter()].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Initiative(u32);
+    struct Name(&'static str);
+
+    let mut world = World::new();
+    world.spawn().insert(Name("goblin")).insert(Initiative(4));
+    world.spawn().insert(Name("knight")).insert(Initiative(12));
+    world.spawn().insert(Name("archer")).insert(Initiative(8));
+
+    world.run_system(|fighters: FnQuery<(&Name, &Initiative)>| {
+        let turns = fighters.iter_sorted_by(|(_, a), (_, b)| b.0.cmp(&a.0))
+            .map(|(name, _)| name.0)
+            .collect::<Vec<&str>>();
+        assert_eq!(turns, vec!["knight", "archer", "goblin"]);
+    });
+    ```
+     */
+    pub fn iter_sorted_by<F>(&self, compare: F) -> FnQueryIterator<'a, T>
+    where F: FnMut(&T::ReturnType, &T::ReturnType) -> Ordering
+    {
+        self.iter().sorted_by(compare)
+    }
+
+    /**
+    Same as [FnQuery::iter_sorted_by()], but the results are sorted by the key returned by 'key' for each of them.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Sprite(&'static str);
+    struct ZIndex(i32);
+
+    let mut world = World::new();
+    world.spawn().insert(Sprite("player")).insert(ZIndex(1));
+    world.spawn().insert(Sprite("background")).insert(ZIndex(-5));
+    world.spawn().insert(Sprite("ui")).insert(ZIndex(10));
+
+    world.run_system(|sprites: FnQuery<(&Sprite, &ZIndex)>| {
+        let drawn = sprites.iter_sorted_by_key(|(_, z)| z.0).map(|(sprite, _)| sprite.0).collect::<Vec<&str>>();
+        assert_eq!(drawn, vec!["background", "player", "ui"]);
+    });
+    ```
+     */
+    pub fn iter_sorted_by_key<K, F>(&self, key: F) -> FnQueryIterator<'a, T>
+    where
+        K: Ord,
+        F: FnMut(&T::ReturnType) -> K,
+    {
+        self.iter().sorted_by_key(key)
     }
 }
 
@@ -202,25 +577,144 @@ impl<'a, T> std::iter::IntoIterator for FnQuery<'a, T>
 where T: FnQueryContainedTupleType<'a>
 {
     type Item = T::ReturnType;
-    type IntoIter = FnQueryIterator<'a, T::ReturnType>;
+    type IntoIter = FnQueryIterator<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        FnQueryIterator {
-            components: T::map(self.entities),
-            phantom: PhantomData
-        }
+        FnQueryIterator::new(self.entities)
     }
 }
 
+/**
+An iterator over the results of an [FnQuery].
+
+Only the ids of the matching entities are collected up front, each result is borrowed
+when it is reached, so components are only borrowed for as long as the results are kept around.
+
+Results are returned in ascending order of entity id, unless the iterator was created by
+[FnQuery::iter_sorted_by()] or [FnQuery::iter_sorted_by_key()].
+ */
 pub struct FnQueryIterator<'a, T> {
-    components: Vec<T>,
-    phantom: PhantomData<&'a T>,
+    entities: &'a Entities,
+    components: Vec<ComponentId>,
+    // borrowed when the ids come from a CachedQuery
+    ids: Cow<'a, [Entity]>,
+    // the rows front..back are the ones not yet returned
+    front: usize,
+    back: usize,
+    phantom: PhantomData<T>,
 }
 
-impl<'a, T> std::iter::Iterator for FnQueryIterator<'a, T> {
-    type Item = T;
+impl<'a, T> FnQueryIterator<'a, T> 
+where T: FnQueryContainedTupleType<'a>
+{
+    fn new(entities: &'a Entities) -> Self {
+        let components = T::component_ids(entities);
+        let ids = T::ids(entities, &components);
+        Self::from_ids(entities, components, Cow::Owned(ids))
+    }
+
+    fn from_ids(entities: &'a Entities, components: Vec<ComponentId>, ids: Cow<'a, [Entity]>) -> Self {
+        let back = ids.len();
+        Self { entities, components, ids, front: 0, back, phantom: PhantomData }
+    }
+
+    // reorders the rows not yet returned, fetching each of them once to compare them
+    pub(crate) fn sorted_by<F>(self, mut compare: F) -> Self
+    where F: FnMut(&T::ReturnType, &T::ReturnType) -> Ordering
+    {
+        let mut entries = (self.front..self.back).map(|row| self.entry(row)).collect::<Vec<(Entity, T::ReturnType)>>();
+        entries.sort_by(|(_, a), (_, b)| compare(a, b));
+        let ids = entries.into_iter().map(|(id, _)| id).collect::<Vec<Entity>>();
+        Self::from_ids(self.entities, self.components, Cow::Owned(ids))
+    }
+
+    pub(crate) fn sorted_by_key<K, F>(self, mut key: F) -> Self
+    where
+        K: Ord,
+        F: FnMut(&T::ReturnType) -> K,
+    {
+        self.sorted_by(|a, b| key(a).cmp(&key(b)))
+    }
+
+    fn entry(&self, row: usize) -> (Entity, T::ReturnType) {
+        let id = self.ids[row];
+        (id, T::fetch(self.entities, &self.components, id))
+    }
+
+    fn next_entry(&mut self) -> Option<(Entity, T::ReturnType)> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.entry(self.front - 1))
+    }
+
+    fn next_back_entry(&mut self) -> Option<(Entity, T::ReturnType)> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.entry(self.back))
+    }
+}
+
+impl<'a, T> std::iter::Iterator for FnQueryIterator<'a, T> 
+where T: FnQueryContainedTupleType<'a>
+{
+    type Item = T::ReturnType;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.components.pop()
+        self.next_entry().map(|(_, result)| result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> std::iter::DoubleEndedIterator for FnQueryIterator<'a, T> 
+where T: FnQueryContainedTupleType<'a>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.next_back_entry().map(|(_, result)| result)
     }
-}
\ No newline at end of file
+}
+
+impl<'a, T> std::iter::ExactSizeIterator for FnQueryIterator<'a, T> 
+where T: FnQueryContainedTupleType<'a>
+{}
+
+/**
+An iterator over the results of an [FnQuery] paired with the id of their entity, 
+returned by [FnQuery::iter_with_ids()].
+ */
+pub struct FnQueryIdIterator<'a, T> {
+    inner: FnQueryIterator<'a, T>,
+}
+
+impl<'a, T> std::iter::Iterator for FnQueryIdIterator<'a, T> 
+where T: FnQueryContainedTupleType<'a>
+{
+    type Item = (Entity, T::ReturnType);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next_entry()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> std::iter::DoubleEndedIterator for FnQueryIdIterator<'a, T> 
+where T: FnQueryContainedTupleType<'a>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back_entry()
+    }
+}
+
+impl<'a, T> std::iter::ExactSizeIterator for FnQueryIdIterator<'a, T> 
+where T: FnQueryContainedTupleType<'a>
+{}
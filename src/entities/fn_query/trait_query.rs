@@ -0,0 +1,292 @@
+use std::{
+    any::{Any, TypeId},
+    cell::{Ref, RefMut},
+    collections::HashMap,
+    marker::PhantomData
+};
+
+use crate::entities::{ComponentCell, ComponentId, Entities, Entity};
+use crate::entities::column::{try_borrow, try_borrow_mut};
+use crate::error::Result;
+use crate::system::{Access, AccessKind};
+
+/**
+Implements the query types `&dyn Trait` and `&mut dyn Trait` for a trait, so that an [FnQuery](struct.FnQuery.html)
+can iterate over the components of every type registered as implementing it with
+[World::register_trait()](struct.World.html#method.register_trait).
+
+Each result is a Vec of every component of the entity implementing the trait, in the order their
+types were registered, and only the entities with at least one of them are matched.
+
+```
+use sceller::prelude::*;
+
+trait Weapon {
+    fn damage(&self) -> u32;
+}
+
+struct Sword(u32);
+struct Bow;
+
+impl Weapon for Sword {
+    fn damage(&self) -> u32 { self.0 }
+}
+impl Weapon for Bow {
+    fn damage(&self) -> u32 { 4 }
+}
+
+query_trait!(Weapon);
+
+let mut world = World::new();
+world.register_trait::<dyn Weapon, Sword>(|c| c, |c| c)
+    .register_trait::<dyn Weapon, Bow>(|c| c, |c| c);
+
+world.spawn().insert(Sword(10));
+world.spawn().insert(Bow).insert(Sword(2));
+world.spawn().insert(Name::new("unarmed"));
+
+world.run_system(|weapons: FnQuery<&dyn Weapon>| {
+    let damage = weapons.iter().flatten().map(|weapon| weapon.damage()).collect::<Vec<u32>>();
+    assert_eq!(damage, vec![10, 2, 4]);
+});
+```
+
+Since the types implementing the trait are only known once they are registered, a query of `&mut dyn Trait`
+isn't checked for conflicts with the queries of those types, only with the other queries of the trait.
+A trait query can't be part of a tuple.
+ */
+#[macro_export]
+macro_rules! query_trait {
+    ($trait:path) => {
+        impl<'a, 'r> $crate::entities::FnQueryContainedTupleType<'a> for &'r (dyn $trait + 'r) {
+            type ReturnType = ::std::vec::Vec<::std::cell::Ref<'a, dyn $trait + 'static>>;
+
+            fn type_ids() -> ::std::vec::Vec<::std::any::TypeId> {
+                ::std::vec::Vec::new()
+            }
+
+            fn type_names() -> ::std::vec::Vec<&'static str> {
+                ::std::vec::Vec::new()
+            }
+
+            fn access() -> ::std::vec::Vec<$crate::system::Access> {
+                $crate::entities::TraitQuery::<dyn $trait>::access(false)
+            }
+
+            fn component_ids_checked(entities: &'a $crate::entities::Entities)
+                -> ::std::result::Result<::std::vec::Vec<$crate::entities::ComponentId>, $crate::entities::QueryError>
+            {
+                ::std::result::Result::Ok($crate::entities::TraitQuery::<dyn $trait>::component_ids(entities))
+            }
+
+            fn ids(entities: &'a $crate::entities::Entities, components: &[$crate::entities::ComponentId]) -> ::std::vec::Vec<$crate::entities::Entity> {
+                $crate::entities::TraitQuery::<dyn $trait>::ids(entities, components)
+            }
+
+            fn matches(entities: &'a $crate::entities::Entities, components: &[$crate::entities::ComponentId], id: $crate::entities::Entity) -> bool {
+                $crate::entities::TraitQuery::<dyn $trait>::matches(entities, components, id)
+            }
+
+            fn try_fetch(entities: &'a $crate::entities::Entities, components: &[$crate::entities::ComponentId], id: $crate::entities::Entity)
+                -> $crate::error::Result<Self::ReturnType>
+            {
+                $crate::entities::TraitQuery::<dyn $trait>::fetch(entities, components, id)
+            }
+        }
+
+        impl<'a, 'r> $crate::entities::FnQueryContainedTupleType<'a> for &'r mut (dyn $trait + 'r) {
+            type ReturnType = ::std::vec::Vec<::std::cell::RefMut<'a, dyn $trait + 'static>>;
+
+            fn type_ids() -> ::std::vec::Vec<::std::any::TypeId> {
+                ::std::vec::Vec::new()
+            }
+
+            fn type_names() -> ::std::vec::Vec<&'static str> {
+                ::std::vec::Vec::new()
+            }
+
+            fn access() -> ::std::vec::Vec<$crate::system::Access> {
+                $crate::entities::TraitQuery::<dyn $trait>::access(true)
+            }
+
+            fn component_ids_checked(entities: &'a $crate::entities::Entities)
+                -> ::std::result::Result<::std::vec::Vec<$crate::entities::ComponentId>, $crate::entities::QueryError>
+            {
+                ::std::result::Result::Ok($crate::entities::TraitQuery::<dyn $trait>::component_ids(entities))
+            }
+
+            fn ids(entities: &'a $crate::entities::Entities, components: &[$crate::entities::ComponentId]) -> ::std::vec::Vec<$crate::entities::Entity> {
+                $crate::entities::TraitQuery::<dyn $trait>::ids(entities, components)
+            }
+
+            fn matches(entities: &'a $crate::entities::Entities, components: &[$crate::entities::ComponentId], id: $crate::entities::Entity) -> bool {
+                $crate::entities::TraitQuery::<dyn $trait>::matches(entities, components, id)
+            }
+
+            fn try_fetch(entities: &'a $crate::entities::Entities, components: &[$crate::entities::ComponentId], id: $crate::entities::Entity)
+                -> $crate::error::Result<Self::ReturnType>
+            {
+                $crate::entities::TraitQuery::<dyn $trait>::fetch_mut(entities, components, id)
+            }
+        }
+    };
+}
+
+// a component implementing Tr, with the cast of it's type and the name of it's type
+type TraitComponent<'a, Tr> = (ComponentCell<'a>, &'a dyn TraitCast<Tr>, &'static str);
+
+// casts a type erased component of one type to the trait object Tr
+trait TraitCast<Tr: ?Sized> {
+    fn cast<'a>(&self, component: &'a dyn Any) -> &'a Tr;
+
+    fn cast_mut<'a>(&self, component: &'a mut dyn Any) -> &'a mut Tr;
+}
+
+struct TypedCast<T, Tr: ?Sized> {
+    cast: fn(&T) -> &Tr,
+    cast_mut: fn(&mut T) -> &mut Tr,
+}
+
+impl<T: Any, Tr: ?Sized> TraitCast<Tr> for TypedCast<T, Tr> {
+    fn cast<'a>(&self, component: &'a dyn Any) -> &'a Tr {
+        (self.cast)(component.downcast_ref::<T>().unwrap())
+    }
+
+    fn cast_mut<'a>(&self, component: &'a mut dyn Any) -> &'a mut Tr {
+        (self.cast_mut)(component.downcast_mut::<T>().unwrap())
+    }
+}
+
+// the component types implementing the trait Tr, in the order they were registered
+struct TraitImpls<Tr: ?Sized> {
+    impls: Vec<(TypeId, Box<dyn TraitCast<Tr>>)>,
+}
+
+impl<Tr: ?Sized> TraitImpls<Tr> {
+    fn cast_of(&self, type_id: TypeId) -> Option<&dyn TraitCast<Tr>> {
+        self.impls.iter()
+            .find(|(id, _)| *id == type_id)
+            .map(|(_, cast)| cast.as_ref())
+    }
+}
+
+/*
+    The component types registered as implementing each trait, keyed by the TypeId of the trait object,
+    with the functions casting them to it. Stored in Entities since queries only have access to them.
+*/
+#[derive(Default)]
+pub(crate) struct ComponentTraits {
+    traits: HashMap<TypeId, Box<dyn Any>>,
+    names: HashMap<TypeId, (&'static str, Vec<&'static str>)>,
+}
+
+impl ComponentTraits {
+    fn impls<Tr: ?Sized + 'static>(&self) -> Option<&TraitImpls<Tr>> {
+        self.traits.get(&TypeId::of::<Tr>())?.downcast_ref::<TraitImpls<Tr>>()
+    }
+}
+
+impl std::fmt::Debug for ComponentTraits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.names.values().map(|(name, impls)| (name, impls))).finish()
+    }
+}
+
+impl Entities {
+    /**
+    Registers the component type T as implementing the trait Tr, so that it is part of the queries
+    of `&dyn Tr` and `&mut dyn Tr`, see [query_trait!](crate::query_trait). Registering the same type twice
+    replaces it's casts.
+     */
+    pub fn register_trait<Tr: ?Sized + 'static, T: Any>(&mut self, cast: fn(&T) -> &Tr, cast_mut: fn(&mut T) -> &mut Tr) -> &mut Self {
+        let impls = self.traits.traits.entry(TypeId::of::<Tr>())
+            .or_insert_with(|| Box::new(TraitImpls::<Tr> { impls: Vec::new() }))
+            .downcast_mut::<TraitImpls<Tr>>()
+            .unwrap();
+        let cast = Box::new(TypedCast { cast, cast_mut });
+        match impls.impls.iter_mut().find(|(id, _)| *id == TypeId::of::<T>()) {
+            Some((_, existing)) => *existing = cast,
+            None => {
+                impls.impls.push((TypeId::of::<T>(), cast));
+                self.traits.names.entry(TypeId::of::<Tr>())
+                    .or_insert_with(|| (std::any::type_name::<Tr>(), Vec::new()))
+                    .1.push(std::any::type_name::<T>());
+            },
+        }
+        // the cached queries of the trait have to look for entities again
+        self.generation += 1;
+        self
+    }
+
+    // moves the traits registered in 'other' into these entities
+    pub(crate) fn take_traits_from(&mut self, other: &mut Entities) {
+        self.traits = std::mem::take(&mut other.traits);
+    }
+}
+
+/**
+The implementation of the queries of a trait object Tr, used by the query types generated by [query_trait!](crate::query_trait).
+ */
+#[doc(hidden)]
+pub struct TraitQuery<Tr: ?Sized>(PhantomData<Tr>);
+
+impl<Tr: ?Sized + 'static> TraitQuery<Tr> {
+    pub fn access(mutable: bool) -> Vec<Access> {
+        vec![Access { kind: AccessKind::Component, type_id: TypeId::of::<Tr>(), name: std::any::type_name::<Tr>(), mutable }]
+    }
+
+    // the ids of the registered components implementing Tr, the types that aren't components yet can't match anything
+    pub fn component_ids(entities: &Entities) -> Vec<ComponentId> {
+        entities.traits.impls::<Tr>()
+            .map(|impls| impls.impls.iter()
+                .filter_map(|(type_id, _)| entities.component_id(type_id))
+                .collect())
+            .unwrap_or_default()
+    }
+
+    // every entity with at least one of the components
+    pub fn ids(entities: &Entities, components: &[ComponentId]) -> Vec<Entity> {
+        let mut ids = components.iter()
+            .flat_map(|component| entities.matching_entities(1 << component))
+            .collect::<Vec<Entity>>();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.retain(|id| Self::matches(entities, components, *id));
+        ids
+    }
+
+    pub fn matches(entities: &Entities, components: &[ComponentId], id: Entity) -> bool {
+        components.iter().any(|component| entities.has_component_id(*component, id)
+            && entities.component_at_id(*component, id).is_some())
+    }
+
+    pub fn fetch<'a>(entities: &'a Entities, components: &[ComponentId], id: Entity) -> Result<Vec<Ref<'a, Tr>>> {
+        Self::casts(entities, components, id).into_iter()
+            .map(|(component, cast, name)| Ok(Ref::map(try_borrow(component, name, id)?, |component| cast.cast(component))))
+            .collect()
+    }
+
+    // borrows the components mutably, marking every one of them as changed
+    pub fn fetch_mut<'a>(entities: &'a Entities, components: &[ComponentId], id: Entity) -> Result<Vec<RefMut<'a, Tr>>> {
+        let fetched = Self::casts(entities, components, id).into_iter()
+            .map(|(component, cast, name)| Ok(RefMut::map(try_borrow_mut(component, name, id)?, |component| cast.cast_mut(component))))
+            .collect::<Result<Vec<RefMut<'a, Tr>>>>()?;
+        components.iter()
+            .filter(|component| entities.has_component_id(**component, id))
+            .for_each(|component| entities.mark_changed_id(*component, id));
+        Ok(fetched)
+    }
+
+    // the components of the entity implementing Tr
+    fn casts<'a>(entities: &'a Entities, components: &[ComponentId], id: Entity) -> Vec<TraitComponent<'a, Tr>> {
+        let impls = entities.traits.impls::<Tr>();
+        components.iter()
+            .filter(|component| entities.has_component_id(**component, id))
+            .filter_map(|component| {
+                let column = entities.column_by_id(*component)?;
+                let cast = impls?.cast_of(column.component_type())?;
+                Some((entities.component_at_id(*component, id)?, cast, column.component_name()))
+            })
+            .collect()
+    }
+}
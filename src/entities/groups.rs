@@ -0,0 +1,177 @@
+//! # Groups
+//!
+//! A group owns the columns of a few component types that are often queried together, and keeps them sorted so
+//! that the entities having all of them are stored in the same order at the start of every column, like the groups
+//! of EnTT. [Query::group()](super::Query::group) reads the entities of the group from the first slots of one of
+//! it's columns, instead of comparing the bitmask of every entity with the one of the query. Their components are
+//! then fetched one entity at a time, like with any other query.
+//!
+//! A component type can only be part of one group, since the columns of two groups couldn't both be sorted. The
+//! components of a group can't be [Shared](super::Shared) either, since a shared component isn't stored along with
+//! the others.
+
+use super::{Bundle, ComponentError, ComponentId, Entities, Entity};
+use super::column::ComponentColumn;
+use crate::error::Result;
+
+// registers the same group on another Entities
+#[cfg(feature = "serde")]
+type RegisterGroupFn = fn(&mut Entities) -> Result<()>;
+
+#[derive(Debug, Default)]
+pub(crate) struct Groups {
+    groups: Vec<Group>,
+}
+
+#[derive(Debug)]
+struct Group {
+    // the combined bitmask of the components of the group
+    mask: u128,
+    // the number of entities having every component, which are in the first slots of the columns
+    len: usize,
+    #[cfg(feature = "serde")]
+    register: RegisterGroupFn,
+}
+
+impl Groups {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+impl Entities {
+    /**
+    Registers a group of component types, whose columns are kept sorted so that the entities having every component
+    of the group are stored first, in the same order in each column. Panics if one of the components is already part
+    of a group, see [Entities::register_group_checked()].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Position(i32, i32);
+    struct Velocity(i32, i32);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Position(0, 0));
+    ents.create_entity().insert(Position(0, 0)).insert(Velocity(1, 1));
+    ents.register_group::<(Position, Velocity)>();
+
+    let query = Query::new(&ents);
+    for (mut position, velocity) in query.group::<(&mut Position, &Velocity)>().unwrap() {
+        position.0 += velocity.0;
+        position.1 += velocity.1;
+    }
+    assert_eq!(query.get::<&Position>(1).unwrap().0, 1);
+    ```
+     */
+    pub fn register_group<B: Bundle>(&mut self) {
+        self.register_group_checked::<B>().unwrap()
+    }
+
+    /**
+    Same as [Entities::register_group()], but returns an error instead of panicking if one of the components is
+    already part of a group, or if an entity has a [Shared](super::Shared) one. Does nothing if the exact same
+    group is already registered.
+     */
+    pub fn register_group_checked<B: Bundle>(&mut self) -> Result<()> {
+        let mask = B::register_components(self)?;
+        if let Some(group) = self.groups.groups.iter().find(|group| group.mask & mask != 0) {
+            if group.mask == mask {
+                return Ok(());
+            }
+            let id = (group.mask & mask).trailing_zeros() as ComponentId;
+            let name = self.column_by_id(id).map(|column| column.component_name()).unwrap_or_default();
+            return Err(ComponentError::GroupedComponentError(name).into());
+        }
+        // a shared component isn't stored with the others, so it can't be moved to the start of it's column
+        let shared = (0..u128::BITS as ComponentId)
+            .filter(|id| mask & 1 << id != 0)
+            .filter_map(|id| self.column_by_id(id))
+            .find(|column| column.has_shared());
+        if let Some(column) = shared {
+            return Err(ComponentError::SharedGroupedComponentError(column.component_name()).into());
+        }
+
+        self.groups.groups.push(Group {
+            mask,
+            len: 0,
+            #[cfg(feature = "serde")]
+            register: Self::register_group_checked::<B>,
+        });
+        let group = self.groups.groups.len() - 1;
+        for entity in self.matching_entities(mask) {
+            self.join_group(group, entity);
+        }
+        Ok(())
+    }
+
+    // moves 'entity' into the first slot after the entities of the group
+    fn join_group(&mut self, group: usize, entity: Entity) {
+        let group = &mut self.groups.groups[group];
+        move_slots(&mut self.components, group.mask, entity, group.len);
+        group.len += 1;
+    }
+
+    // moves 'entity' into the last slot of the entities of the group, which then no longer includes it
+    fn leave_group(&mut self, group: usize, entity: Entity) {
+        let group = &mut self.groups.groups[group];
+        group.len -= 1;
+        move_slots(&mut self.components, group.mask, entity, group.len);
+    }
+
+    // moves 'entity' in and out of the groups when it's mask went from 'old' to 'new'
+    pub(super) fn regroup(&mut self, entity: Entity, old: u128, new: u128) {
+        for group in 0..self.groups.groups.len() {
+            let mask = self.groups.groups[group].mask;
+            match (old & mask == mask, new & mask == mask) {
+                (false, true) => self.join_group(group, entity),
+                (true, false) => self.leave_group(group, entity),
+                _ => (),
+            }
+        }
+    }
+
+    // whether one of the components in 'mask' is part of a group
+    pub(super) fn is_grouped(&self, mask: u128) -> bool {
+        self.groups.groups.iter().any(|group| group.mask & mask != 0)
+    }
+
+    // forgets the group of the component 'id', which is about to be deleted
+    pub(super) fn ungroup(&mut self, id: ComponentId) {
+        self.groups.groups.retain(|group| group.mask & 1 << id == 0);
+    }
+
+    // empties every group, once all the entities are cleared
+    pub(super) fn clear_groups(&mut self) {
+        self.groups.groups.iter_mut().for_each(|group| group.len = 0);
+    }
+
+    /*
+        the entities of the group made of exactly the components in 'components', in the order of their slots,
+        or None if there is no such group. A group only holds the entities having every one of it's components,
+        so it can't answer a query on some of them, which other entities can match too
+    */
+    pub(crate) fn group_entities(&self, components: &[ComponentId]) -> Option<Vec<Entity>> {
+        let mask = components.iter().fold(0, |mask, component| mask | 1 << component);
+        let group = self.groups.groups.iter().find(|group| group.mask == mask)?;
+        let column = self.column_by_id(group.mask.trailing_zeros() as ComponentId)?;
+        Some((0..group.len).map(|slot| column.entity_in(slot)).collect())
+    }
+
+    // registers the groups of 'other' on these entities restored from a snapshot, which don't have any group yet
+    #[cfg(feature = "serde")]
+    pub(crate) fn take_groups_from(&mut self, other: &Entities) {
+        for group in &other.groups.groups {
+            (group.register)(self).expect("Failed to register a group on entities without groups");
+        }
+    }
+}
+
+// moves the slot of 'entity' to 'slot' in the column of every component in 'mask'
+fn move_slots(components: &mut [Option<Box<dyn ComponentColumn>>], mask: u128, entity: Entity, slot: usize) {
+    for (_, column) in components.iter_mut().enumerate().filter(|(id, _)| mask & 1 << id != 0) {
+        if let Some(column) = column {
+            column.move_slot(entity, slot);
+        }
+    }
+}
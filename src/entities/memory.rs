@@ -0,0 +1,124 @@
+use std::any::{Any, TypeId};
+use std::fmt::Display;
+
+use super::{short_type_name, Entities};
+
+/**
+The memory used by the column of one component type, see [MemoryReport].
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentMemory {
+    pub name: &'static str,
+    pub type_id: TypeId,
+    /// The size of a single component, as given by `size_of`.
+    pub size: usize,
+    /// The number of entities holding the component.
+    pub count: usize,
+    /// The number of slots in the column, one for every entity, whether they hold the component or not.
+    /// The slots of a zero sized tag component only take up a bit and a borrow flag.
+    pub slots: usize,
+    /// The bytes allocated by the column, including the empty slots and the capacity not used yet.
+    pub bytes: usize,
+    /// The bytes allocated to remember the tick during which the component of every entity changed.
+    pub tick_bytes: usize,
+}
+
+impl ComponentMemory {
+    /// Returns the bytes taken up by the components themselves, ignoring empty slots and unused capacity.
+    pub fn used_bytes(&self) -> usize {
+        self.size * self.count
+    }
+}
+
+/**
+A breakdown of the memory allocated for the components of an ECS, one entry per registered component type,
+sorted from the type using the most memory to the one using the least. Only the memory of the columns is counted,
+not the memory the components themselves point to, like the buffer of a `Vec`.
+
+Zero sized tag components only take up a bit per entity, and the flag tracking the borrows of the entity's tag,
+since the tags themselves take no memory.
+
+```
+use sceller::prelude::*;
+
+struct Position(f32, f32);
+struct Tag;
+
+let mut world = World::new();
+for i in 0..10 {
+    world.spawn().insert(Position(i as f32, 0.0));
+}
+world.spawn().insert(Tag);
+
+let report = world.memory_report();
+let positions = report.get::<Position>().unwrap();
+assert_eq!(positions.size, 8);
+assert_eq!(positions.count, 10);
+assert_eq!(positions.used_bytes(), 80);
+assert_eq!(report.components[0].name, std::any::type_name::<Position>());
+assert!(report.get::<Tag>().unwrap().bytes < positions.bytes);
+
+println!("{report}");
+```
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryReport {
+    pub components: Vec<ComponentMemory>,
+    /// The bytes allocated for the bitmasks of the entities.
+    pub entity_bytes: usize,
+}
+
+impl MemoryReport {
+    /// Returns the memory used by the component T, if it is registered.
+    pub fn get<T: Any>(&self) -> Option<&ComponentMemory> {
+        self.components.iter().find(|component| component.type_id == TypeId::of::<T>())
+    }
+
+    /// Returns the bytes allocated for the entities and all of their components.
+    pub fn total_bytes(&self) -> usize {
+        self.entity_bytes + self.components.iter().map(|component| component.bytes + component.tick_bytes).sum::<usize>()
+    }
+}
+
+impl Display for MemoryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = self.components.iter().map(|component| short_type_name(component.name)).collect::<Vec<String>>();
+        let width = names.iter().map(String::len).max().unwrap_or(0).max("Entities".len());
+
+        writeln!(f, "Memory: {} bytes", self.total_bytes())?;
+        writeln!(f, "  {:width$}  {} bytes", "Entities", self.entity_bytes)?;
+        for (component, name) in self.components.iter().zip(&names) {
+            writeln!(f, "  {name:width$}  {} bytes  ({} x {} bytes used, {} bytes of ticks)", component.bytes, component.count, component.size, component.tick_bytes)?;
+        }
+        Ok(())
+    }
+}
+
+impl Entities {
+    /**
+    Returns the memory allocated for every registered component type, see [MemoryReport].
+     */
+    pub fn memory_report(&self) -> MemoryReport {
+        let mut components = self.components.iter()
+            .flatten()
+            .map(|column| {
+                ComponentMemory {
+                    name: column.component_name(),
+                    type_id: column.component_type(),
+                    size: column.component_size(),
+                    count: self.component_id(&column.component_type())
+                        .map_or(0, |id| self.map.iter().filter(|mask| **mask & 1 << id != 0).count()),
+                    slots: column.len(),
+                    bytes: column.allocated_bytes(),
+                    tick_bytes: column.tick_bytes(),
+                }
+            })
+            .collect::<Vec<ComponentMemory>>();
+        components.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.name.cmp(b.name)));
+
+        MemoryReport {
+            components,
+            entity_bytes: self.map.capacity() * std::mem::size_of::<u128>(),
+        }
+    }
+}
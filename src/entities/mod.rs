@@ -5,18 +5,56 @@
 
 mod query;
 mod query_entity;
+mod query_result;
 pub mod auto_query;
 mod fn_query;
-
-use std::{any::{Any, TypeId}, rc::Rc, cell::{RefCell}, collections::HashMap};
-use eyre::*;
-
-pub use self::query::Query;
+mod bundle;
+mod despawned_entity;
+mod column;
+mod archetypes;
+mod groups;
+mod shared;
+mod defaults;
+mod type_registry;
+mod memory;
+mod recording;
+#[cfg(feature = "rayon")]
+mod par_query;
+
+use std::{any::{Any, TypeId}, cell::{Ref, RefCell, RefMut}, collections::{BTreeSet, HashMap, HashSet}};
+
+use self::column::{Column, ComponentColumn, Slot};
+pub(crate) use self::column::{try_borrow, try_borrow_mut};
+use self::archetypes::Archetypes;
+use self::groups::Groups;
+use self::fn_query::ComponentTraits;
+use self::defaults::ComponentDefaults;
+use crate::name::{Name, NameIndex};
+use crate::recording::Recorder;
+use crate::observer::{ComponentInserted, ComponentRemoved, EntityDespawned, EntitySpawned, StorageEvent};
+use crate::error::Result;
+
+pub use self::query::{Query, QueryError, QueryEntityIter};
 pub use self::query_entity::QueryEntity;
+pub use self::query_result::QueryResult;
 pub use self::auto_query::*;
 pub use self::fn_query::*;
-
-pub type ComponentType = Rc<RefCell<dyn Any>>;
+pub use self::bundle::Bundle;
+pub use self::despawned_entity::DespawnedEntity;
+pub use self::type_registry::*;
+pub use self::memory::*;
+pub use self::shared::Shared;
+pub use self::column::ComponentCell;
+#[cfg(feature = "rayon")]
+pub use self::par_query::*;
+
+/// A type erased reference to a single component, as returned by [Query::run()].
+pub type ComponentType<'a> = ComponentCell<'a>;
+/// The id of an entity, which is it's index in the [Entities] map.
+pub type Entity = usize;
+/// The id of a registered component type, which is the position of it's bit in entity bitmasks
+/// and the index of it's column.
+pub type ComponentId = usize;
 
 
 #[derive(Debug, Default)]
@@ -37,20 +75,107 @@ pub type ComponentType = Rc<RefCell<dyn Any>>;
   
    map [...0010_1101, ...0111_1111, ...0101_000, ...] where each 1 corresponds to a component the entity has.
   
-  In contrast, the 'bit_masks' hashmap will ressemble this:
+  In contrast, the 'component_ids' hashmap will ressemble this:
   
-   bit_masks { Component1: ...0000_0001, Component2: ...0000_0010, Component3: ...0000_0100, ... } where a 1 corresponds to the component's 'id.
+   component_ids { Component1: 0, Component2: 1, Component3: 2, ... } where the id is the position of the component's bit,
+   so the bitmask of Component3 is ...0000_0100.
   
   Note: in the place of 'Component1' the code actually uses TypeIds, so it would be TypeId::of::<Component1>().
+
+  The columns of components are stored in a vector indexed by [ComponentId], so once the ids of a query's 
+  components are known, fetching components doesn't need to hash any TypeId.
+  
+  The components themselves are stored in one column per type, where each column packs the components 
+  of the entities having one into a single vector, instead of storing each of them behind a pointer.
+  
+  Living entities are also grouped by their bitmask into archetypes, which is what queries use to 
+  find the entities they match without checking every entity in the map.
  */
 pub struct Entities {
-    components: HashMap<TypeId, Vec<Option<ComponentType>>>,
+    components: Vec<Option<Box<dyn ComponentColumn>>>,
     entity_count: usize,
 
-    bit_masks: HashMap<TypeId, u128>,
+    component_ids: HashMap<TypeId, ComponentId>,
     map: Vec<u128>,
 
     insert_cursor: usize,
+
+    archetypes: Archetypes,
+
+    // bumped on every structural change, so that cached queries know when to look for entities again
+    generation: u64,
+
+    // the current tick of the World, which components are marked with when they change
+    tick: u64,
+
+    // the component types that have hooks, and the times they were added to or removed from an entity
+    // that are waiting for the World to run their hooks
+    watched: HashSet<TypeId>,
+    events: Vec<ComponentEvent>,
+
+    // whether the World has observers, and the structural changes waiting for the World to give them to it's observers
+    observed: bool,
+    storage_events: Vec<StorageEvent>,
+
+    // the entity of every Name component, which is updated by lookups with the names written in place
+    names: RefCell<NameIndex>,
+
+    // the component types whose columns log the entities whose component was written, for the indexes of the World,
+    // and whether the entities despawned since the World last read them are recorded along with them
+    logged: HashSet<TypeId>,
+    tracks_despawns: bool,
+    despawned: Vec<Entity>,
+
+    // the component types recorded by the World's journal with the functions cloning them, and the values 
+    // components had before they changed, waiting for the World to add them to it's journal
+    journaled: HashMap<TypeId, CloneFn>,
+    changes: Vec<ComponentChange>,
+
+    // the component types registered as implementing each trait, for the queries of trait objects
+    traits: ComponentTraits,
+
+    // the component types that only one entity at a time can have
+    unique: HashSet<TypeId>,
+
+    // the component types that are only accessed on the thread owning the ECS, even by parallel queries
+    main_thread: HashSet<TypeId>,
+
+    // the functions creating the default value of the component types registered with one
+    defaults: ComponentDefaults,
+
+    // the name, TypeId and reflection functions of every registered component type
+    types: TypeRegistry,
+
+    // the ids handed out by reserve_entity() that weren't spawned yet, which no other entity can take
+    reserved: RefCell<BTreeSet<Entity>>,
+
+    // the component types recorded by the World's mutation log, and the log while it is recording
+    recorder: Recorder,
+
+    // the component types of the mutable auto queries that are still alive, which can't be created twice
+    auto_mut_queries: RefCell<HashSet<TypeId>>,
+
+    // the groups of component types whose columns are sorted so that the entities having all of them come first
+    groups: Groups,
+}
+
+/// Clones a component without knowing it's type, see [TypeRegistration].
+pub type CloneFn = fn(&dyn Any) -> Box<dyn Any>;
+
+/// The value a component of a journaled type had before it was inserted, replaced or removed.
+#[derive(Debug)]
+pub(crate) struct ComponentChange {
+    pub(crate) entity: Entity,
+    pub(crate) type_id: TypeId,
+    pub(crate) value: Option<Box<dyn Any>>,
+}
+
+/// A component of a watched type being added to or removed from an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ComponentEvent {
+    pub(crate) type_id: TypeId,
+    pub(crate) entity: Entity,
+    pub(crate) added: bool,
 }
 
 impl Entities {
@@ -59,9 +184,128 @@ impl Entities {
      */
     pub fn register_component<T: Any + 'static>(&mut self) {
         let typeid = TypeId::of::<T>();
-        let bitmask = 2_u128.pow(self.components.len() as u32);
-        self.components.insert(typeid, Vec::new());
-        self.bit_masks.insert(typeid, bitmask);
+        if self.component_ids.contains_key(&typeid) {
+            return;
+        }
+
+        // the lowest id that isn't used by a registered component, deleted components free up their id
+        let id = self.components.iter().position(Option::is_none).unwrap_or(self.components.len());
+        assert!(id < u128::BITS as usize, "Cannot register more than 128 component types.");
+        if id == self.components.len() {
+            self.components.push(None);
+        }
+
+        let mut column = Column::<T>::new();
+        column.resize(self.entity_count);
+        // names written in place are indexed again when an entity is looked up by name
+        if self.logged.contains(&typeid) || typeid == TypeId::of::<Name>() {
+            column.log_writes();
+        }
+        self.components[id] = Some(Box::new(column));
+        self.component_ids.insert(typeid, id);
+        self.types.register::<T>();
+        self.generation += 1;
+    }
+
+    /**
+    Registers a component type that only one entity at a time can have, inserting it into a second entity
+    returns a [ComponentError::DuplicateUniqueComponentError]. Panics if more than one entity already has it.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Player;
+
+    let mut ents = Entities::default();
+    ents.register_unique::<Player>();
+
+    ents.create_entity().insert(Player);
+    assert!(ents.create_entity().insert_checked(Player).is_err());
+    assert_eq!(ents.single::<Player>().unwrap(), 0);
+    ```
+     */
+    pub fn register_unique<T: Any>(&mut self) {
+        self.register_unique_checked::<T>().unwrap()
+    }
+
+    /**
+    Same as [register_unique()](struct.Entities.html#method.register_unique), but returns an error 
+    instead of panicking if more than one entity already has the component.
+     */
+    pub fn register_unique_checked<T: Any>(&mut self) -> Result<()> {
+        self.register_component::<T>();
+        let holders = self.matching_entities(self.get_bitmask(&TypeId::of::<T>()).unwrap());
+        if holders.len() > 1 {
+            return Err(ComponentError::DuplicateUniqueComponentError { name: std::any::type_name::<T>(), holder: holders[0] }.into());
+        }
+        self.unique.insert(TypeId::of::<T>());
+        Ok(())
+    }
+
+    /// Returns true if the component type was registered with [register_unique()](struct.Entities.html#method.register_unique).
+    pub fn is_unique<T: Any>(&self) -> bool {
+        self.unique.contains(&TypeId::of::<T>())
+    }
+
+    /**
+    Registers a component type that must only be accessed on the thread owning the ECS, even if it is Send
+    or Sync. See [World::register_main_thread()](struct.World.html#method.register_main_thread).
+     */
+    pub fn register_main_thread<T: Any>(&mut self) {
+        self.register_component::<T>();
+        self.main_thread.insert(TypeId::of::<T>());
+    }
+
+    /// Returns true if the component type was registered with [register_main_thread()](struct.Entities.html#method.register_main_thread).
+    pub fn is_main_thread<T: Any>(&self) -> bool {
+        self.main_thread.contains(&TypeId::of::<T>())
+    }
+
+    // same as 'is_main_thread', for a component type that isn't known
+    #[cfg(feature = "rayon")]
+    pub(crate) fn is_main_thread_id(&self, type_id: &TypeId) -> bool {
+        self.main_thread.contains(type_id)
+    }
+
+    /**
+    Returns the only entity with the component T. Returns an error if the component isn't registered,
+    or if there isn't exactly one entity with it.
+     */
+    pub fn single<T: Any>(&self) -> Result<Entity> {
+        let bitmask = self.get_bitmask(&TypeId::of::<T>()).ok_or(QueryError::UnregisteredComponentError)?;
+        match self.matching_entities(bitmask).as_slice() {
+            [entity] => Ok(*entity),
+            holders => Err(QueryError::SingleEntityError { name: std::any::type_name::<T>(), count: holders.len() }.into()),
+        }
+    }
+
+    // returns an error if the component type is unique and an entity other than 'map_index' has it
+    // returns an error if any component of 'bitmask' can't be stored into the entity at 'map_index',
+    // checked before storing a bundle so that a failing bundle leaves nothing behind
+    pub(crate) fn check_bundle(&self, bitmask: u128, map_index: usize) -> Result<()> {
+        if map_index >= self.map.len() {
+            return Err(ComponentError::NonexistentEntity.into());
+        }
+        if self.unique.is_empty() {
+            return Ok(());
+        }
+        let mut mask = bitmask;
+        while mask != 0 {
+            let column = self.column_by_id(mask.trailing_zeros() as usize).ok_or(ComponentError::UnregisteredComponentError)?;
+            self.check_unique(&column.component_type(), column.component_name(), map_index)?;
+            mask &= mask - 1;
+        }
+        Ok(())
+    }
+
+    fn check_unique(&self, type_id: &TypeId, name: &'static str, map_index: usize) -> Result<()> {
+        let Some(bitmask) = self.get_bitmask(type_id).filter(|_| self.unique.contains(type_id)) else {
+            return Ok(());
+        };
+        match self.matching_entities(bitmask).into_iter().find(|holder| *holder != map_index) {
+            Some(holder) => Err(ComponentError::DuplicateUniqueComponentError { name, holder }.into()),
+            None => Ok(()),
+        }
     }
 
     // #[allow(dead_code)]
@@ -88,8 +332,9 @@ impl Entities {
       |-----------------------------------------------|
      */
     fn fill_new_component_checked<T: Any>(&mut self) -> Result<()> {
-        let comps = self.components.get_mut(&TypeId::of::<T>()).ok_or(ComponentError::AutomaticRegistrationError)?;
-        for _ in 0..self.entity_count { comps.push(None); }
+        let len = self.entity_count;
+        let comps = self.typed_column_mut::<T>().ok_or(ComponentError::AutomaticRegistrationError)?;
+        comps.resize(len);
         Ok(())
     }
 
@@ -114,22 +359,110 @@ impl Entities {
       ```
      */
     pub fn create_entity(&mut self) -> &mut Self {
-        if let Some((index, _)) = self.map.iter().enumerate().find(|(_index, map_val)| **map_val == 0) {
+        self.flush_reserved();
+        let reserved = self.reserved.get_mut();
+        if let Some((index, _)) = self.map.iter().enumerate().find(|(index, map_val)| **map_val == 0 && !reserved.contains(index)) {
             self.insert_cursor = index;
         } else {
-            self.components.iter_mut().for_each(|(_key, value)| {
-                value.push(None);
-            });
-    
             self.map.push(0);
     
             self.entity_count += 1;
 
+            let len = self.entity_count;
+            self.columns_mut().for_each(|column| column.resize(len));
+
             self.insert_cursor = self.entity_count - 1;
         }
         self
     }
 
+    /**
+      Makes sure an entity slot exists at 'index', creating empty slots up to it if needed, and points
+      the insert cursor at it. Unlike [create_entity()](struct.Entities.html#method.create_entity) this never 
+      reuses a vacant slot, which is needed when rebuilding entities with known indexes.
+     */
+    pub(crate) fn create_entity_at(&mut self, index: usize) -> &mut Self {
+        while self.entity_count <= index {
+            self.map.push(0);
+            self.entity_count += 1;
+        }
+        let len = self.entity_count;
+        self.columns_mut().for_each(|column| column.resize(len));
+        self.insert_cursor = index;
+        self
+    }
+
+    /**
+      Reserves the id of an entity without spawning it, through an immutable reference, so that it can be done
+      while the entities are borrowed by a query. The id isn't given to any other entity until it is spawned with
+      [spawn_reserved()](struct.Entities.html#method.spawn_reserved), which is usually done by the command queued by
+      [Commands::spawn_reserved()](struct.Commands.html#method.spawn_reserved).
+
+      ```
+      use sceller::prelude::*;
+
+      struct Health(u8);
+
+      let mut ents = Entities::default();
+      ents.create_entity().insert(Health(1));
+
+      let reserved = ents.reserve_entity();
+      assert_eq!(reserved, 1);
+
+      // other entities don't take the reserved id
+      assert_eq!(ents.create_entity().insert(Health(2)).current_entity(), 2);
+
+      ents.spawn_reserved(reserved).insert(Health(3));
+      assert_eq!(Query::new(&ents).auto::<Health>().len(), 3);
+      ```
+     */
+    pub fn reserve_entity(&self) -> Entity {
+        let mut reserved = self.reserved.borrow_mut();
+        let entity = reserved.last().map_or(self.entity_count, |last| self.entity_count.max(last + 1));
+        reserved.insert(entity);
+        entity
+    }
+
+    /**
+      Creates the entity whose id was reserved with [reserve_entity()](struct.Entities.html#method.reserve_entity),
+      and points the insert cursor at it, so that it can be given components like with
+      [create_entity()](struct.Entities.html#method.create_entity).
+     */
+    pub fn spawn_reserved(&mut self, entity: Entity) -> &mut Self {
+        self.reserved.get_mut().remove(&entity);
+        self.create_entity_at(entity)
+    }
+
+    // makes sure the slots of every reserved id exist, so that new entities are pushed after them
+    fn flush_reserved(&mut self) {
+        if let Some(&last) = self.reserved.get_mut().last() {
+            if last >= self.entity_count {
+                self.create_entity_at(last);
+            }
+        }
+    }
+
+    /**
+      Returns the index of the entity that calls to [insert()](struct.Entities.html#method.insert) currently affect,
+      which is the entity most recently created with [create_entity()](struct.Entities.html#method.create_entity).
+      
+      ```
+      use sceller::prelude::*;
+      
+      struct Health(u8);
+      
+      let mut ents = Entities::default();
+      
+      ents.create_entity().insert(Health(1));
+      let second = ents.create_entity().insert(Health(2)).current_entity();
+      
+      assert_eq!(second, 1);
+      ```
+     */
+    pub fn current_entity(&self) -> usize {
+        self.insert_cursor
+    }
+
     /**
       Inserts a component into whatever is the newest newly created entity. Returns Err if the component 
       
@@ -176,27 +509,194 @@ impl Entities {
           .insert_checked(Speed(-16)).unwrap();
       ```
      */
-    pub fn insert_checked<T: Any>(&mut self, data: T) -> eyre::Result<&mut Self> {
+    pub fn insert_checked<T: Any>(&mut self, data: T) -> Result<&mut Self> {
+        let map_index = self.insert_cursor;
+        let (bitmask, _) = self.write_component(data, map_index)?;
+        self.set_mask(map_index, self.map[map_index] | bitmask);
+        Ok(self)
+    }
+
+    /**
+      Inserts every component of a [Bundle] into whatever is the newest newly created entity. 
+      
+      Note: automatically registers any new component types, and sets the entity's bitmask once for the whole bundle.
+      
+      ```
+      use sceller::prelude::*;
+      
+      struct Health(u8);
+      struct Speed(i8);
+      
+      let mut ents = Entities::default();
+      
+      ents.create_entity()
+          .insert_bundle((Health(10_u8), Speed(-16)));
+      ```
+      
+      Panics if one of the components can't be inserted.
+     */
+    pub fn insert_bundle<B: Bundle>(&mut self, bundle: B) -> &mut Self {
+        self.insert_bundle_checked(bundle).unwrap()
+    }
+
+    /**
+      Inserts every component of a [Bundle] into whatever is the newest newly created entity. 
+      
+      Note: automatically registers any new component types, and sets the entity's bitmask once for the whole bundle.
+      
+      Returns an error if one of the components can't be inserted, in which case none of the bundle's
+      components are stored into the entity.
+     */
+    pub fn insert_bundle_checked<B: Bundle>(&mut self, bundle: B) -> Result<&mut Self> {
+        let map_index = self.insert_cursor;
+        let bitmask = bundle.write_components(self, map_index)?;
+        self.set_mask(map_index, self.map[map_index] | bitmask);
+        Ok(self)
+    }
+
+    /**
+      Registers a component type if it isn't registered yet, filling it's new vector with 'None' values,
+      and returns the component's bitmask.
+     */
+    fn register_if_new<T: Any>(&mut self) -> Result<u128> {
         // auto register new component types
-        if !self.bit_masks.contains_key(&TypeId::of::<T>()) {
+        if !self.component_ids.contains_key(&TypeId::of::<T>()) {
             // register and initialize with default value of none
             self.register_component::<T>();
             self.fill_new_component_checked::<T>()?;
         }
+        let bitmask = self.get_bitmask(&TypeId::of::<T>()).ok_or(ComponentError::UnregisteredComponentError)?;
+        Ok(bitmask)
+    }
 
-        let map_index = self.insert_cursor;
+    /**
+      Stores a component in the slot of the entity at 'map_index', the component must already be registered.
+      
+      Note: this does not update the entity's bitmask in the map, that is left to the caller.
+     */
+    fn store_component<T: Any>(&mut self, data: T, map_index: usize) -> Result<Option<T>> {
+        self.store_slot(Slot::Owned(data), map_index)
+    }
+
+    // same as 'store_component', but stores a component owned by the entity or shared with others
+    fn store_slot<T: Any>(&mut self, component: Slot<T>, map_index: usize) -> Result<Option<T>> {
+        if !self.unique.is_empty() {
+            self.check_unique(&TypeId::of::<T>(), std::any::type_name::<T>(), map_index)?;
+        }
 
-        if let Some(components) = self.components.get_mut(&data.type_id()) {
-            let component = components.get_mut(map_index).ok_or(ComponentError::NonexistentEntity)?;
-            let typeid = data.type_id();
-            *component = Some(Rc::new(RefCell::new(data)));
+        if !self.journaled.is_empty() {
+            if let Some(id) = self.component_id(&TypeId::of::<T>()) {
+                self.record_change(map_index, id);
+            }
+        }
 
-            let bitmask = self.bit_masks.get(&typeid).unwrap();
-            self.map[map_index] |= *bitmask;
+        let name = component.map(|component| (component as &dyn Any).downcast_ref::<Name>().map(|name| name.to_string())).flatten();
+        let tick = self.tick;
+        // only a component the entity has is replaced, anything else left in the slot isn't it's own
+        let had = self.component_id(&TypeId::of::<T>()).is_some_and(|id| self.has_component_id(id, map_index));
+        let replaced = if let Some(components) = self.typed_column_mut::<T>() {
+            if map_index >= components.len() {
+                return Err(ComponentError::NonexistentEntity.into());
+            }
+            // replaced in place, so that an entity of a group keeps it's place in the column
+            let replaced = components.replace_slot(map_index, component).into_inner().filter(|_| had);
+            components.mark_changed(map_index, tick);
+            replaced
         } else {
-            bail!("Attempted to add a component that was not registered to an entity.");
+            return Err(ComponentError::UnregisteredComponentError.into());
+        };
+        // the components the entity didn't have yet are recorded along with it's new mask
+        if self.is_recording() {
+            if let Some(id) = self.component_id(&TypeId::of::<T>()).filter(|id| self.has_component_id(*id, map_index)) {
+                self.record_insert(map_index, id);
+            }
         }
-        Ok(self)
+
+        if let Some(name) = name {
+            self.names.get_mut().insert(map_index, &name);
+        }
+        Ok(replaced)
+    }
+
+    /**
+      Stores a component in the slot of the entity at 'map_index', auto registering it if needed, and returns 
+      the component's bitmask along with the component it replaced, if there was one. 
+      
+      Note: this does not update the entity's bitmask in the map, that is left to the caller.
+     */
+    fn write_component<T: Any>(&mut self, data: T, map_index: usize) -> Result<(u128, Option<T>)> {
+        let bitmask = self.register_if_new::<T>()?;
+        let replaced = self.store_component(data, map_index)?;
+        Ok((bitmask, replaced))
+    }
+
+    /**
+      Spawns an entity for every [Bundle] of the iterator, and returns their ids.
+
+      Component types are registered once for the whole batch, and every component vector is grown
+      once to fit all the new entities, so this is a lot faster than spawning the entities one by one.
+      Vacant entity slots are filled first, like [create_entity()](struct.Entities.html#method.create_entity) would.
+
+      ```
+      use sceller::prelude::*;
+
+      struct Position(f32, f32);
+      struct Velocity(f32, f32);
+
+      let mut ents = Entities::default();
+
+      let ids = ents.spawn_batch((0..1000).map(|i| (Position(i as f32, 0.0), Velocity(0.0, 1.0))));
+
+      assert_eq!(ids.len(), 1000);
+      assert_eq!(Query::new(&ents).auto::<Velocity>().len(), 1000);
+      ```
+
+      Panics if one of the bundles can't be inserted.
+     */
+    pub fn spawn_batch<B, I>(&mut self, bundles: I) -> Vec<Entity>
+    where
+        B: Bundle,
+        I: IntoIterator<Item = B>,
+    {
+        self.spawn_batch_checked(bundles).unwrap()
+    }
+
+    /**
+      Same as [spawn_batch()](struct.Entities.html#method.spawn_batch), but returns an error if one of 
+      the bundles can't be inserted. The entities spawned before the error stay in the ECS.
+     */
+    pub fn spawn_batch_checked<B, I>(&mut self, bundles: I) -> Result<Vec<Entity>>
+    where
+        B: Bundle,
+        I: IntoIterator<Item = B>,
+    {
+        let bundles = bundles.into_iter().collect::<Vec<B>>();
+        let bitmask = B::register_components(self)?;
+
+        self.flush_reserved();
+        let reserved = self.reserved.get_mut();
+        let mut ids = self.map.iter().enumerate()
+            .filter(|(index, map_val)| **map_val == 0 && !reserved.contains(index))
+            .map(|(index, _)| index)
+            .take(bundles.len())
+            .collect::<Vec<Entity>>();
+        let new_entities = bundles.len() - ids.len();
+        ids.extend(self.entity_count..self.entity_count + new_entities);
+
+        // grow everything once for the whole batch
+        self.entity_count += new_entities;
+        self.map.resize(self.entity_count, 0);
+        let len = self.entity_count;
+        self.columns_mut().for_each(|column| column.resize(len));
+
+        for (bundle, &index) in bundles.into_iter().zip(&ids) {
+            self.check_bundle(bitmask, index)?;
+            bundle.store_components(self, index)?;
+            self.set_mask(index, self.map[index] | bitmask);
+            self.insert_cursor = index;
+        }
+
+        Ok(ids)
     }
 
     /**
@@ -225,32 +725,16 @@ impl Entities {
       
       Returns an error if the component that is trying to be deleted isn't registered.
 
-      This operation is fast, because there are no big read or writes to memory. All this function does 
-      is do an xOr operation on the bitmask of the entity's index given, making this a cheap operation. 
+      This clears the component's bit from the bitmask of the entity and drops it's component, nothing
+      happens if the entity doesn't have the component.
      */
     pub fn delete_component_by_entity_id_checked<T: Any>(&mut self, index: usize) -> Result<()> {
         let typeid = TypeId::of::<T>();
-        let mask = self.bit_masks.get(&typeid).ok_or(ComponentError::UnregisteredComponentError)?;
-
-        // 3 ^= 1 = 2
-        // 2 ^= 1 = 3
-        //
-        // 0011 ^= 0001 = 0010
-        // 0010 ^= 0001 = 0011 
-
-        // ^ operator is: 1 ^ 0 = 1, 0 ^ 0 = 0 
-        //
-        // desired behaviour:
-        //
-        // 0011 ? 0001 = 0010
-        // 0010 ? 0001 = 0010
-        //
-        // 0011 & 0001 = 0001 / 0011 | 0001 = 0011 
-        // 0010 | 0001 = 0011 / 0010 & 0001 = 0000
-
-        // this executes if the entity does contain this component
+        let mask = &self.get_bitmask(&typeid).ok_or(ComponentError::UnregisteredComponentError)?;
+
         if self.map[index] & *mask != 0 {
-            self.map[index] ^= *mask;
+            self.set_mask(index, self.map[index] & !*mask);
+            self.drop_components(index, *mask);
         }
 
         Ok(())
@@ -282,8 +766,8 @@ impl Entities {
       
       Panics if the component that is trying to be deleted isn't registered.
 
-      This operation is fast, because there are no big read or writes to memory. All this function does 
-      is do an xOr operation on the bitmask of the entity's index given, making this a cheap operation. 
+      This clears the component's bit from the bitmask of the entity and drops it's component, nothing
+      happens if the entity doesn't have the component.
      */
     pub fn delete_component_by_entity_id<T: Any>(&mut self, index: usize) {
         self.delete_component_by_entity_id_checked::<T>(index).unwrap()
@@ -318,9 +802,24 @@ impl Entities {
       assert_eq!(query1[0].len(), 1);
       ```
 
+      Returns the component the entity already had, if it had one, which is replaced by the new one.
+
+      ```
+      use sceller::prelude::*;
+
+      #[derive(Debug, PartialEq)]
+      struct Weapon(&'static str);
+
+      let mut ents = Entities::default();
+      ents.create_entity().insert(Weapon("sword"));
+
+      let old = ents.insert_component_into_entity_by_id(Weapon("axe"), 0);
+      assert_eq!(old, Some(Weapon("sword")));
+      ```
+
       Panics when applying this function without first creating a new entity with [creat_entity()](struct.Entities.html#method.create_entity).
      */
-    pub fn insert_component_into_entity_by_id<T: Any>(&mut self, data: T, map_index: usize) {
+    pub fn insert_component_into_entity_by_id<T: Any>(&mut self, data: T, map_index: usize) -> Option<T> {
         self.insert_component_into_entity_by_id_checked(data, map_index).unwrap()
     }
 
@@ -353,28 +852,14 @@ impl Entities {
       assert_eq!(query1[0].len(), 1);
       ```
 
-      Returns an error if the component inserted is unregistered (which should never happen, as this function auto-registers components like [insert()](struct.Entities.html#method.insert))
+      Returns the component the entity already had, if it had one. Returns an error if the component inserted is unregistered 
+      (which should never happen, as this function auto-registers components like [insert()](struct.Entities.html#method.insert))
       or if the user tries to insert a component without creating a new entity.
      */
-    pub fn insert_component_into_entity_by_id_checked<T: Any>(&mut self, data: T, map_index: usize) -> eyre::Result<()> {
-        // auto register new component types
-        if !self.bit_masks.contains_key(&TypeId::of::<T>()) {
-            // register and initialize with default value of none
-            self.register_component::<T>();
-            self.fill_new_component_checked::<T>()?;
-        }
-
-        if let Some(components) = self.components.get_mut(&data.type_id()) {
-            let replaced_component = components.get_mut(map_index).ok_or(ComponentError::NonexistentEntity)?;
-            let typeid = data.type_id();
-            *replaced_component = Some(Rc::new(RefCell::new(data)));
-
-            let bitmask = self.bit_masks.get(&typeid).ok_or(ComponentError::UnregisteredComponentError)?;
-            self.map[map_index] |= *bitmask;
-        } else {
-            bail!("Attempted to add a component that was not registered to an entity.");
-        }
-        Ok(())
+    pub fn insert_component_into_entity_by_id_checked<T: Any>(&mut self, data: T, map_index: usize) -> Result<Option<T>> {
+        let (bitmask, replaced) = self.write_component(data, map_index)?;
+        self.set_mask(map_index, self.map[map_index] | bitmask);
+        Ok(replaced)
     }
 
     /**
@@ -408,8 +893,8 @@ impl Entities {
 
     This function will panic if the component entered doesn't exist.
 
-    This operation is fast, because there are no heavy read/writes to memory. This function
-    simply xOrs the bitmask of every entity to remove this component from it.
+    This clears the component's bit from the bitmask of every entity that has it and drops every
+    stored instance of the component, the bit can then be reused by a component registered later on.
      */
     pub fn delete_component<T: Any>(&mut self) {
         self.delete_component_checked::<T>().unwrap()
@@ -446,54 +931,1092 @@ impl Entities {
 
     This function will return an error if the component entered doesn't exist.
 
-    This operation is fast, because there are no heavy read/writes to memory. This function
-    simply xOrs the bitmask of every entity to remove this component from it.
+    This clears the component's bit from the bitmask of every entity that has it and drops every
+    stored instance of the component, the bit can then be reused by a component registered later on.
      */
-    pub fn delete_component_checked<T: Any>(&mut self) -> eyre::Result<()> {
-        let (_, bitmask) = self.bit_masks.remove_entry(&TypeId::of::<T>()).ok_or(ComponentError::UnregisteredComponentError)?;
-        for component_bitmask in &mut self.map {
-            *component_bitmask ^= bitmask;
+    pub fn delete_component_checked<T: Any>(&mut self) -> Result<()> {
+        let typeid = TypeId::of::<T>();
+        let id = self.component_ids.remove(&typeid).ok_or(ComponentError::UnregisteredComponentError)?;
+        self.unique.remove(&typeid);
+        self.main_thread.remove(&typeid);
+        self.ungroup(id);
+        self.generation += 1;
+        let bitmask = 1 << id;
+
+        for index in 0..self.map.len() {
+            if self.map[index] & bitmask != 0 {
+                self.set_mask(index, self.map[index] & !bitmask);
+            }
         }
+        // dropped after the masks are updated, so that the removals of watched components are recorded
+        self.components[id] = None;
         Ok(())
     }
 
-    pub fn delete_entity_by_id(&mut self, index: usize) -> eyre::Result<()> {
+    /**
+    Removes a component from an entity using the entity's index, and returns the owned component data.
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Sword(u8);
+
+    let mut ents = Entities::default();
+
+    ents.create_entity().insert(Sword(3));
+    ents.create_entity();
+
+    // move the sword over to the second entity
+    let sword = ents.take_component::<Sword>(0).unwrap();
+    ents.insert_component_into_entity_by_id(sword, 1);
+
+    assert!(ents.take_component::<Sword>(0).is_err());
+    assert_eq!(ents.take_component::<Sword>(1).unwrap(), Sword(3));
+    ```
+
+    Returns an error if the component isn't registered, if the entity doesn't have the component, or if it's component is
+    [Shared] with other entities or handles.
+     */
+    pub fn take_component<T: Any>(&mut self, index: usize) -> Result<T> {
+        let mask = self.get_bitmask(&TypeId::of::<T>()).ok_or(ComponentError::UnregisteredComponentError)?;
         let len = self.map.len();
-        *self.map.get_mut(index).ok_or(ComponentError::IndexOutOfBoundsError { expected: len, found: index })? = 0;
+        let entity_mask = *self.map.get(index).ok_or(ComponentError::IndexOutOfBoundsError { expected: len, found: index })?;
+        if entity_mask & mask == 0 {
+            return Err(ComponentError::NonexistentComponentDataError.into());
+        }
+        if self.typed_column::<T>().is_some_and(|components| components.is_shared(index)) {
+            return Err(ComponentError::SharedComponentError { type_name: std::any::type_name::<T>(), entity: index }.into());
+        }
 
-        Ok(())
+        self.set_mask(index, entity_mask & !mask);
+        let component = self.typed_column_mut::<T>()
+            .and_then(|components| components.take(index))
+            .ok_or(ComponentError::NonexistentComponentDataError)?;
+        Ok(component)
     }
 
     /**
-    Convenience function to get the bitmask of a given TypeId. 
-    
-    Returns None if the component requested isn't registered.
+    Removes an entity from the ECS, returning a [DespawnedEntity] that holds all of it's components.
+
+    Panics if the index is out of bounds.
      */
-    pub fn get_bitmask(&self, typeid: &TypeId) -> Option<u128> {
-        self.bit_masks.get(typeid).copied()
+    pub fn despawn(&mut self, index: usize) -> DespawnedEntity {
+        self.despawn_checked(index).unwrap()
     }
-}
 
-// Trait implementations
-impl std::fmt::Display for Entities {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:#?}")
-    }
-}
+    /**
+    Removes an entity from the ECS, returning a [DespawnedEntity] that holds all of it's components.
 
-#[derive(thiserror::Error, Debug)]
-enum ComponentError {
-    #[error("Attempt to add component to nothing.")]
-    NonexistentEntity,
-    #[error("This error should never happen. (Failed to fill fields of newly generated component on the fly)")]
-    AutomaticRegistrationError,
-    #[error("Attempt to make use of unregistered component.")]
-    UnregisteredComponentError,
-    #[error("Index out of bounds when accessing entity id. (Expected range from 0..{expected}, got {found}).")]
-    IndexOutOfBoundsError { expected: usize, found: usize },
-    #[error("Attempted to get component data that does not exist. Error in bitmask probably?")]
-    NonexistentComponentDataError,
-}
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(5));
+
+    let mut despawned = ents.despawn_checked(0).unwrap();
+    assert_eq!(despawned.take::<Health>().unwrap().0, 5);
+
+    // the slot is free again
+    assert_eq!(ents.despawn_checked(0).unwrap().len(), 0);
+    assert!(ents.despawn_checked(1).is_err());
+    ```
+
+    Returns an error if the index is out of bounds.
+     */
+    pub fn despawn_checked(&mut self, index: usize) -> Result<DespawnedEntity> {
+        let len = self.map.len();
+        let entity_mask = *self.map.get(index).ok_or(ComponentError::IndexOutOfBoundsError { expected: len, found: index })?;
+
+        // the mask is cleared first, so that the journal can still record the components
+        self.set_mask(index, 0);
+        let mut taken = HashMap::new();
+        for (typeid, id) in &self.component_ids {
+            if entity_mask & (1 << id) == 0 {
+                continue;
+            }
+            if let Some(component) = self.components[*id].as_mut().and_then(|c| c.remove(index)) {
+                taken.insert(*typeid, component);
+            }
+        }
+
+        Ok(DespawnedEntity::new(index, taken))
+    }
+
+    /**
+    Despawns every living entity for which 'keep' returns false, dropping their components, and returns
+    the number of entities despawned. Every entity is checked before any of them is despawned.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Height(f32);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Height(3.0));
+    ents.create_entity().insert(Height(-120.0));
+
+    let despawned = ents.retain(|entity| entity.get_component::<Height>().map(|h| h.0 > -100.0).unwrap_or(true));
+    assert_eq!(despawned, 1);
+    assert!(!ents.is_alive(1));
+    ```
+     */
+    pub fn retain(&mut self, mut keep: impl FnMut(&QueryEntity) -> bool) -> usize {
+        let doomed = (0..self.map.len())
+            .filter(|index| self.is_alive(*index) && !keep(&QueryEntity::new(*index, self)))
+            .collect::<Vec<Entity>>();
+        for index in &doomed {
+            // the indices come from the map, so they can't be out of bounds
+            drop(self.despawn_checked(*index));
+        }
+        doomed.len()
+    }
+
+    /**
+    Moves an entity and all of it's components into another ECS, and returns the id of the new entity there.
+    The entity is despawned from this ECS, and the component types it has are registered in 'target' if needed.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut loading = Entities::default();
+    let mut live = Entities::default();
+    live.create_entity().insert(Health(1));
+    loading.create_entity().insert(Health(8));
+
+    let moved = loading.transfer_entity(0, &mut live).unwrap();
+    assert_eq!(moved, 1);
+    assert!(!loading.is_alive(0));
+    assert!(live.has_component::<Health>(1));
+    ```
+
+    Returns an error, without moving anything, if the entity has no components, or if it has a unique
+    component that an entity of 'target' already has.
+     */
+    pub fn transfer_entity(&mut self, entity: Entity, target: &mut Entities) -> Result<Entity> {
+        if !self.is_alive(entity) {
+            return Err(ComponentError::NonexistentEntity.into());
+        }
+        // checked first, so that nothing is moved if one of the components can't be
+        self.check_transfer(entity, target)?;
+        let entity_mask = self.map[entity];
+        let ids = self.component_ids.values().copied()
+            .filter(|id| entity_mask & (1 << id) != 0)
+            .collect::<Vec<ComponentId>>();
+
+        let moved = target.create_entity().current_entity();
+        // the mask is cleared first, so that the journal can still record the components
+        self.set_mask(entity, 0);
+        for id in ids {
+            if let Some(column) = self.components[id].as_mut() {
+                column.move_to(entity, target, moved)?;
+            }
+        }
+        Ok(moved)
+    }
+
+    // returns an error if the entity has a unique component that an entity of 'target' already has
+    pub(crate) fn check_transfer(&self, entity: Entity, target: &Entities) -> Result<()> {
+        let entity_mask = self.map.get(entity).copied().unwrap_or(0);
+        for id in self.component_ids.values().filter(|id| entity_mask & (1 << *id) != 0) {
+            let column = self.column_by_id(*id).ok_or(ComponentError::UnregisteredComponentError)?;
+            target.check_unique(&column.component_type(), column.component_name(), usize::MAX)?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_entity_by_id(&mut self, index: usize) -> Result<()> {
+        let len = self.map.len();
+        if index >= len {
+            return Err(ComponentError::IndexOutOfBoundsError { expected: len, found: index }.into());
+        }
+        let mask = self.map[index];
+        self.set_mask(index, 0);
+        self.drop_components(index, mask);
+
+        Ok(())
+    }
+
+    // drops the components in 'mask' of the entity at 'index', once they were removed from it's mask
+    fn drop_components(&mut self, index: usize, mut mask: u128) {
+        while mask != 0 {
+            let id = mask.trailing_zeros() as usize;
+            if let Some(column) = self.components.get_mut(id).and_then(Option::as_mut) {
+                drop(column.remove(index));
+            }
+            mask &= mask - 1;
+        }
+    }
+
+    /**
+    Returns mutable references to the component T of several different entities at once.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(i32);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(10));
+    ents.create_entity().insert(Health(10));
+
+    {
+        // entity 0 drains 3 health from entity 1
+        let [mut attacker, mut target] = ents.get_components_mut::<Health, 2>(&[0, 1]).unwrap();
+        target.0 -= 3;
+        attacker.0 += 3;
+    }
+
+    let healths = ents.get_components_mut::<Health, 2>(&[0, 1]).unwrap().map(|hp| hp.0);
+    assert_eq!(healths, [13, 7]);
+
+    // the same entity can't be borrowed twice
+    assert!(ents.get_components_mut::<Health, 2>(&[1, 1]).is_err());
+    ```
+
+    Returns an error if an id appears more than once, if one of the entities doesn't have the component,
+    or if one of the components is already borrowed.
+     */
+    pub fn get_components_mut<T: Any, const N: usize>(&self, ids: &[usize; N]) -> Result<[RefMut<'_, T>; N]> {
+        for (i, id) in ids.iter().enumerate() {
+            if ids[..i].contains(id) {
+                return Err(ComponentError::DuplicateIdError(*id).into());
+            }
+        }
+
+        let components = self.typed_column::<T>().ok_or(ComponentError::UnregisteredComponentError)?;
+
+        let mut borrows = Vec::with_capacity(N);
+        for &id in ids {
+            if !self.has_component::<T>(id) {
+                return Err(ComponentError::NonexistentComponentDataError.into());
+            }
+            let component = components.get_typed(id).ok_or(ComponentError::NonexistentComponentDataError)?;
+            borrows.push(try_borrow_mut(component, std::any::type_name::<T>(), id)?);
+        }
+
+        Ok(borrows.try_into().unwrap_or_else(|_| unreachable!("Collected the wrong number of components")))
+    }
+
+    /**
+    Borrows the component T of the entity 'id', returning an error instead of panicking if it is already
+    borrowed mutably.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(i32);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(10));
+
+    let health = ents.try_get_component_mut::<Health>(0).unwrap();
+    let error = ents.try_get_component::<Health>(0).map(|_| ()).unwrap_err();
+    assert!(matches!(error, Error::Component(ComponentError::ComponentAlreadyBorrowedError { entity: 0, .. })));
+    assert!(error.to_string().contains("Health"));
+
+    drop(health);
+    assert_eq!(ents.try_get_component::<Health>(0).unwrap().0, 10);
+    ```
+
+    Returns an error if the entity doesn't have the component, or if it is already borrowed mutably.
+     */
+    pub fn try_get_component<T: Any>(&self, id: Entity) -> Result<Ref<'_, T>> {
+        try_borrow(self.typed_component::<T>(id)?, std::any::type_name::<T>(), id)
+    }
+
+    /**
+    Mutably borrows the component T of the entity 'id', see [Entities::try_get_component()].
+
+    Returns an error if the entity doesn't have the component, or if it is already borrowed.
+     */
+    pub fn try_get_component_mut<T: Any>(&self, id: Entity) -> Result<RefMut<'_, T>> {
+        let component = try_borrow_mut(self.typed_component::<T>(id)?, std::any::type_name::<T>(), id)?;
+        self.mark_changed::<T>(id);
+        Ok(component)
+    }
+
+    /**
+    Borrows the components T of every entity as a single slice, along with the entity owning each of them, to hand
+    them to SIMD code or upload them to the GPU in one go. The components are in no particular order, and only
+    the ones owned by an entity are part of the slice, not the [Shared] ones.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Position(f32);
+    struct Velocity(f32);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Position(1.0));
+    ents.create_entity().insert(Velocity(2.0));
+    ents.create_entity().insert(Position(3.0));
+
+    let (positions, entities) = ents.column::<Position>().unwrap();
+    let total = positions.iter().map(|position| position.0).sum::<f32>();
+    assert_eq!(total, 4.0);
+    assert_eq!(positions.len(), entities.len());
+    assert!(entities.contains(&2) && !entities.contains(&1));
+
+    // no component can be borrowed mutably while the column is borrowed
+    assert!(ents.try_get_component_mut::<Position>(0).is_err());
+    assert_eq!(ents.try_get_component::<Position>(2).unwrap().0, 3.0);
+    ```
+
+    The whole column is checked for components borrowed mutably, so this takes as long as the number of components.
+    Returns an error if the component isn't registered, if one of the components is already borrowed mutably,
+    or if the component is zero sized, since zero sized components aren't stored anywhere.
+     */
+    pub fn column<T: Any>(&self) -> Result<(Ref<'_, [T]>, &[Entity])> {
+        self.typed_column::<T>().ok_or(ComponentError::UnregisteredComponentError)?.borrow_all()
+    }
+
+    /**
+    Mutably borrows the components T of every entity as a single slice, along with the entity owning each of them.
+    Every component is marked as changed, see [Entities::column()].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Position(f32);
+
+    let mut ents = Entities::default();
+    for i in 0..4 {
+        ents.create_entity().insert(Position(i as f32));
+    }
+
+    let (mut positions, _) = ents.column_mut::<Position>().unwrap();
+    positions.iter_mut().for_each(|position| position.0 *= 2.0);
+    assert!(ents.column::<Position>().is_err());
+    drop(positions);
+
+    assert_eq!(ents.try_get_component::<Position>(3).unwrap().0, 6.0);
+    ```
+
+    Returns an error if the component isn't registered, if one of the components is already borrowed,
+    or if the component is zero sized.
+     */
+    pub fn column_mut<T: Any>(&self) -> Result<(RefMut<'_, [T]>, &[Entity])> {
+        let column = self.typed_column::<T>().ok_or(ComponentError::UnregisteredComponentError)?;
+        let (components, entities) = column.borrow_all_mut()?;
+        for entity in entities {
+            column.mark_changed(*entity, self.tick);
+        }
+        Ok((components, entities))
+    }
+
+    // the cell of the component T of the entity 'id', if it has one
+    fn typed_component<T: Any>(&self, id: Entity) -> Result<ComponentCell<'_, T>> {
+        let components = self.typed_column::<T>().ok_or(ComponentError::UnregisteredComponentError)?;
+        if !self.has_component::<T>(id) {
+            return Err(ComponentError::NonexistentComponentDataError.into());
+        }
+        Ok(components.get_typed(id).ok_or(ComponentError::NonexistentComponentDataError)?)
+    }
+
+    /**
+    Deletes every entity and drops all of their components, component types stay registered
+    and keep their bitmasks.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(1));
+    ents.create_entity().insert(Health(2));
+
+    ents.clear();
+
+    assert_eq!(Query::new(&ents).auto::<Health>().len(), 0);
+    assert_eq!(ents.create_entity().current_entity(), 0);
+    ```
+     */
+    pub fn clear(&mut self) {
+        if self.is_recording() {
+            self.record_clear();
+        }
+        self.columns_mut().for_each(|column| column.clear());
+        self.map.clear();
+        self.entity_count = 0;
+        self.insert_cursor = 0;
+        self.archetypes = Archetypes::default();
+        self.clear_groups();
+        self.events.clear();
+        self.storage_events.clear();
+        self.changes.clear();
+        self.names.get_mut().clear();
+        self.despawned.clear();
+        self.generation += 1;
+    }
+
+    /**
+    Reserves capacity for at least 'additional' more entities in the map and in the vector of 
+    every registered component, so that spawning them doesn't reallocate over and over.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut ents = Entities::default();
+    ents.register_component::<Health>();
+    ents.reserve(10_000);
+
+    for _ in 0..10_000 {
+        ents.create_entity().insert(Health(3));
+    }
+    ```
+
+    Component types registered afterwards don't get the reserved capacity.
+     */
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+        self.columns_mut().for_each(|column| column.reserve(additional));
+    }
+
+    /**
+    Shrinks the capacity of the map and of the vector of every registered component as much as possible,
+    for example once a level is done loading.
+     */
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+        self.columns_mut().for_each(|column| column.shrink_to_fit());
+    }
+
+    /**
+    Returns the type name of a registered component, recorded when it was registered.
+
+    ```
+    use sceller::prelude::*;
+    use std::any::TypeId;
+
+    struct Health(u8);
+
+    let mut ents = Entities::default();
+    ents.register_component::<Health>();
+
+    assert!(ents.component_name(&TypeId::of::<Health>()).unwrap().ends_with("Health"));
+    assert_eq!(ents.component_name(&TypeId::of::<u8>()), None);
+    ```
+     */
+    pub fn component_name(&self, typeid: &TypeId) -> Option<&'static str> {
+        self.column_of(typeid).map(|column| column.component_name())
+    }
+
+    /**
+    Returns a human readable summary of the entities, with the number of entities having each component type,
+    followed by every living entity along with the names of it's components, and it's [Name] if it has one.
+    Type names are shortened by removing their module paths.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Enemy;
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(3)).insert(Name::new("goblin"));
+    ents.create_entity().insert(Health(3)).insert(Enemy);
+
+    let dump = ents.debug_dump();
+    assert!(dump.contains("Health  2"));
+    assert!(dump.contains("0 \"goblin\"  Health, Name"));
+    assert!(dump.contains("1  Enemy, Health"));
+    ```
+     */
+    pub fn debug_dump(&self) -> String {
+        let mut components = self.components.iter()
+            .enumerate()
+            .filter_map(|(id, column)| column.as_ref().map(|column| (id, short_type_name(column.component_name()))))
+            .collect::<Vec<(ComponentId, String)>>();
+        components.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+        let width = components.iter().map(|(_, name)| name.len()).max().unwrap_or(0);
+        let mut dump = String::from("Components\n");
+        for (id, name) in &components {
+            let count = self.count_matching(1 << id);
+            dump += &format!("  {name:width$}  {count}\n");
+        }
+
+        dump += "\nEntities\n";
+        for (entity, mask) in self.iter_ids() {
+            let names = components.iter()
+                .filter(|(id, _)| mask & 1 << id != 0)
+                .map(|(_, name)| name.as_str())
+                .collect::<Vec<&str>>();
+            let label = self.has_component::<Name>(entity)
+                .then(|| self.typed_column::<Name>().and_then(|names| names.get_typed(entity)))
+                .flatten()
+                .map(|name| format!(" {:?}", name.borrow().as_str()))
+                .unwrap_or_default();
+            dump += &format!("  {entity}{label}  {}\n", names.join(", "));
+        }
+        dump
+    }
+
+    /**
+    Returns an iterator over the id and bitmask of every living entity, that is every entity with at least
+    one component, in ascending order of id.
+
+    ```
+    use sceller::prelude::*;
+    use std::any::TypeId;
+
+    struct Health(u8);
+    struct Enemy;
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(3));
+    ents.create_entity().insert(Enemy);
+    ents.create_entity().insert(Health(1)).insert(Enemy);
+    ents.delete_entity_by_id(1).unwrap();
+
+    let ids = ents.iter_ids().map(|(entity, _)| entity).collect::<Vec<Entity>>();
+    assert_eq!(ids, vec![0, 2]);
+
+    let enemy = ents.get_bitmask(&TypeId::of::<Enemy>()).unwrap();
+    assert_eq!(ents.iter_ids().filter(|(_, mask)| mask & enemy != 0).count(), 1);
+    ```
+     */
+    pub fn iter_ids(&self) -> impl Iterator<Item = (Entity, u128)> + '_ {
+        self.map.iter().copied().enumerate().filter(|(_, mask)| *mask != 0)
+    }
+
+    /**
+    Returns the TypeIds of the components of the entity at 'index', in the order of their [ComponentId]s.
+    Returns an empty vector if the entity doesn't exist.
+     */
+    pub fn component_types(&self, index: usize) -> Vec<TypeId> {
+        let mask = self.map.get(index).copied().unwrap_or(0);
+        self.components.iter()
+            .enumerate()
+            .filter(|(id, _)| mask & 1 << id != 0)
+            .filter_map(|(_, column)| column.as_ref().map(|column| column.component_type()))
+            .collect()
+    }
+
+    /**
+    Returns true if the entity at 'index' exists and has at least one component.
+     */
+    pub fn is_alive(&self, index: usize) -> bool {
+        self.map.get(index).map(|mask| *mask != 0).unwrap_or(false)
+    }
+
+    /**
+    Returns the number of living entities, that is the entities with at least one component.
+    Vacant slots, left by despawned entities or created without components, aren't counted.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(3));
+    ents.create_entity().insert(Health(1));
+    ents.create_entity();
+    ents.delete_entity_by_id(0).unwrap();
+
+    assert_eq!(ents.len(), 1);
+    assert_eq!(ents.capacity(), 3);
+    assert_eq!(ents.vacant(), 2);
+    ```
+     */
+    pub fn len(&self) -> usize {
+        self.archetypes.count_matching(0)
+    }
+
+    /// Returns true if there isn't any living entity.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of entity slots, living or vacant, which is one more than the highest entity id.
+    pub fn capacity(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns the number of vacant entity slots, which are reused by the next entities created.
+    pub fn vacant(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    /**
+    Returns true if the entity at 'index' has a component of type T.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Speed(u8);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(3));
+
+    assert!(ents.has_component::<Health>(0));
+    assert!(!ents.has_component::<Speed>(0));
+    assert!(!ents.has_component::<Health>(1));
+    ```
+     */
+    pub fn has_component<T: Any>(&self, index: usize) -> bool {
+        match (self.get_bitmask(&TypeId::of::<T>()), self.map.get(index)) {
+            (Some(mask), Some(entity_mask)) => entity_mask & mask != 0,
+            _ => false,
+        }
+    }
+
+    // every change to an entity's bitmask goes through here, so that it's archetype stays up to date
+    fn set_mask(&mut self, index: usize, mask: u128) {
+        if !self.journaled.is_empty() {
+            let lost = self.map[index] & !mask;
+            for id in (0..u128::BITS as usize).filter(|id| lost & 1 << id != 0) {
+                self.record_change(index, id);
+            }
+        }
+
+        let old = std::mem::replace(&mut self.map[index], mask);
+        if old != mask {
+            self.mark_inserted(index, mask & !old);
+            self.archetypes.moved(index, old, mask);
+            if !self.groups.is_empty() {
+                self.regroup(index, old, mask);
+            }
+            self.generation += 1;
+            if !self.watched.is_empty() {
+                self.record_events(index, old, mask);
+            }
+            if self.observed {
+                self.record_storage_events(index, old, mask);
+            }
+            if self.is_recording() {
+                self.record_mask(index, old, mask);
+            }
+            if mask == 0 && self.tracks_despawns {
+                self.despawned.push(index);
+            }
+            if !self.names.get_mut().is_empty() {
+                if let Some(id) = self.component_ids.get(&TypeId::of::<Name>()) {
+                    if old & !mask & 1 << id != 0 {
+                        self.names.get_mut().remove(index);
+                    }
+                }
+            }
+        }
+    }
+
+    // marks the components in 'gained' as inserted into 'entity' during the current tick
+    fn mark_inserted(&self, entity: Entity, mut gained: u128) {
+        while gained != 0 {
+            if let Some(column) = self.column_by_id(gained.trailing_zeros() as usize) {
+                column.mark_inserted(entity, self.tick);
+            }
+            gained &= gained - 1;
+        }
+    }
+
+    pub(crate) fn names(&self) -> &RefCell<NameIndex> {
+        &self.names
+    }
+
+    // records every watched component that 'entity' gained or lost when it's mask went from 'old' to 'new'
+    fn record_events(&mut self, entity: Entity, old: u128, new: u128) {
+        let changed = old ^ new;
+        for id in (0..u128::BITS as usize).filter(|id| changed & 1 << id != 0) {
+            if let Some(type_id) = self.column_by_id(id).map(|column| column.component_type()) {
+                if self.watched.contains(&type_id) {
+                    self.events.push(ComponentEvent { type_id, entity, added: new & 1 << id != 0 });
+                }
+            }
+        }
+    }
+
+    // records the spawn or despawn of 'entity' and every component it gained or lost when it's mask went from 'old' to 'new'
+    fn record_storage_events(&mut self, entity: Entity, old: u128, new: u128) {
+        if old == 0 {
+            self.storage_events.push(StorageEvent::Spawned(EntitySpawned { entity }));
+        }
+        let changed = old ^ new;
+        for id in (0..u128::BITS as usize).filter(|id| changed & 1 << id != 0) {
+            let Some(column) = self.column_by_id(id) else { continue };
+            let (type_id, type_name) = (column.component_type(), column.component_name());
+            self.storage_events.push(match new & 1 << id != 0 {
+                true => StorageEvent::Inserted(ComponentInserted { entity, type_id, type_name }),
+                false => StorageEvent::Removed(ComponentRemoved { entity, type_id, type_name }),
+            });
+        }
+        if new == 0 {
+            self.storage_events.push(StorageEvent::Despawned(EntityDespawned { entity }));
+        }
+    }
+
+    // records the value the component 'id' of 'entity' has before it changes, if it's type is journaled
+    fn record_change(&mut self, entity: Entity, id: ComponentId) {
+        let Some(column) = self.column_by_id(id) else { return };
+        let type_id = column.component_type();
+        let Some(clone) = self.journaled.get(&type_id) else { return };
+
+        let value = self.has_component_id(id, entity)
+            .then(|| column.get(entity))
+            .flatten()
+            .map(|component| clone(&*component.borrow()));
+        self.changes.push(ComponentChange { entity, type_id, value });
+    }
+
+    // starts recording the values components of the type 'type_id' had before they changed
+    pub(crate) fn journal(&mut self, type_id: TypeId, clone: CloneFn) {
+        self.journaled.insert(type_id, clone);
+    }
+
+    // takes every recorded change, oldest first
+    pub(crate) fn take_changes(&mut self) -> Vec<ComponentChange> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /*
+        replaces the component of type 'type_id' of 'entity' with 'value', inserting or removing it, and 
+        returns the component it had before. Used to undo changes recorded by the journal, if the type was 
+        unregistered since then nothing happens and 'value' is returned as is.
+    */
+    pub(crate) fn swap_component(&mut self, entity: Entity, type_id: TypeId, value: Option<Box<dyn Any>>) -> Option<Box<dyn Any>> {
+        let Some(id) = self.component_id(&type_id) else { return value };
+        if entity >= self.map.len() {
+            return value;
+        }
+
+        let mask = self.map[entity];
+        match value {
+            Some(value) => {
+                let name = value.downcast_ref::<Name>().map(|name| name.to_string());
+                // replaced in place, so that an entity of a group keeps it's place in the column
+                let old = self.components[id].as_mut().and_then(|column| {
+                    let old = column.put(entity, value);
+                    column.mark_changed(entity, self.tick);
+                    old
+                }).filter(|_| mask & 1 << id != 0);
+                if let Some(name) = name {
+                    self.names.get_mut().insert(entity, &name);
+                }
+                if mask & 1 << id != 0 && self.is_recording() {
+                    self.record_insert(entity, id);
+                }
+                self.set_mask(entity, mask | 1 << id);
+                old
+            },
+            None => {
+                // the mask is cleared first, so that the entity leaves it's groups before it's component is moved
+                self.set_mask(entity, mask & !(1 << id));
+                self.components[id].as_mut().and_then(|column| column.remove(entity)).filter(|_| mask & 1 << id != 0)
+            },
+        }
+    }
+
+    // starts recording when components of the type 'type_id' are added to or removed from entities
+    pub(crate) fn watch(&mut self, type_id: TypeId) {
+        self.watched.insert(type_id);
+    }
+
+    // takes every recorded event, oldest first
+    pub(crate) fn take_events(&mut self) -> Vec<ComponentEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    // starts logging the entities whose component of the type 'type_id' is written
+    pub(crate) fn log_writes(&mut self, type_id: TypeId) {
+        self.logged.insert(type_id);
+        if let Some(id) = self.component_id(&type_id) {
+            if let Some(column) = self.components[id].as_mut() {
+                column.log_writes();
+            }
+        }
+    }
+
+    // takes the entities whose component of the type 'type_id' was written, in ascending order
+    pub(crate) fn take_writes(&self, type_id: TypeId) -> Vec<Entity> {
+        self.column_of(&type_id).map(|column| column.take_writes()).unwrap_or_default()
+    }
+
+    // the entities whose component of the type 'type_id' was written, without taking them
+    pub(crate) fn writes(&self, type_id: TypeId) -> Vec<Entity> {
+        self.column_of(&type_id).map(|column| column.writes()).unwrap_or_default()
+    }
+
+    // starts recording the entities that are despawned
+    pub(crate) fn track_despawns(&mut self) {
+        self.tracks_despawns = true;
+    }
+
+    // takes every entity despawned since they were last taken, oldest first
+    pub(crate) fn take_despawned(&mut self) -> Vec<Entity> {
+        std::mem::take(&mut self.despawned)
+    }
+
+    // starts recording the structural changes for the observers of the World
+    pub(crate) fn observe(&mut self) {
+        self.observed = true;
+    }
+
+    // takes every recorded structural change, oldest first
+    pub(crate) fn take_storage_events(&mut self) -> Vec<StorageEvent> {
+        std::mem::take(&mut self.storage_events)
+    }
+
+    /**
+    Returns the current tick, which is advanced every time the World runs it's [Schedule](crate::schedule::Schedule),
+    see [World::tick()](crate::world::World::tick). Components that change are marked with the tick during which
+    they changed, see [Changed].
+     */
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub(crate) fn set_tick(&mut self, tick: u64) {
+        self.tick = tick;
+    }
+
+    // marks the component T of 'entity' as changed during the current tick
+    pub(crate) fn mark_changed<T: Any>(&self, entity: Entity) {
+        if let Some(column) = self.column_of(&TypeId::of::<T>()) {
+            column.mark_changed(entity, self.tick);
+        }
+    }
+
+    // marks the component 'id' of 'entity' as changed during the current tick, without hashing anything
+    pub(crate) fn mark_changed_id(&self, id: ComponentId, entity: Entity) {
+        if let Some(column) = self.column_by_id(id) {
+            column.mark_changed(entity, self.tick);
+        }
+    }
+
+    // whether the component 'id' of 'entity' changed during the tick before the current one
+    pub(crate) fn changed_last_tick(&self, id: ComponentId, entity: Entity) -> bool {
+        self.tick > 0 && self.column_by_id(id).is_some_and(|column| column.changed_during(entity, self.tick - 1))
+    }
+
+    /**
+    Returns the last tick during which the component T of 'entity' changed, that is was inserted, replaced
+    or borrowed mutably. Returns None if the entity doesn't have the component.
+     */
+    pub fn changed_tick<T: Any>(&self, entity: Entity) -> Option<u64> {
+        if !self.has_component::<T>(entity) {
+            return None;
+        }
+        self.column_of(&TypeId::of::<T>())?.changed_tick(entity)
+    }
+
+    /**
+    Returns a counter that changes every time an entity gains or loses components, is despawned,
+    or a component type is registered or deleted. Used by [CachedQuery] to only look for matching
+    entities again after such a change.
+     */
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /**
+    Returns the ids of every entity that has all of the components in 'mask', in ascending order.
+     */
+    pub(crate) fn matching_entities(&self, mask: u128) -> Vec<Entity> {
+        self.archetypes.matching(mask)
+    }
+
+    /**
+    Calls 'f' with the id of every entity that has all of the components in 'mask', without collecting them,
+    so the ids aren't in any particular order.
+     */
+    pub(crate) fn for_each_matching(&self, mask: u128, f: impl FnMut(Entity)) {
+        self.archetypes.for_each_matching(mask, f)
+    }
+
+    /**
+    Returns the number of entities that have all of the components in 'mask'.
+     */
+    pub(crate) fn count_matching(&self, mask: u128) -> usize {
+        self.archetypes.count_matching(mask)
+    }
+
+    /**
+    Returns the number of archetypes, that is the number of distinct combinations of 
+    components among the entities in the ECS.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Speed(u8);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(1));
+    ents.create_entity().insert(Health(2));
+    ents.create_entity().insert(Health(3)).insert(Speed(1));
+
+    assert_eq!(ents.archetype_count(), 2);
+    ```
+     */
+    pub fn archetype_count(&self) -> usize {
+        self.archetypes.len()
+    }
+
+    /**
+    Returns the number of registered component types.
+     */
+    pub fn component_type_count(&self) -> usize {
+        self.component_ids.len()
+    }
+
+    // every registered column, in no particular order
+    fn columns_mut(&mut self) -> impl Iterator<Item = &mut Box<dyn ComponentColumn>> {
+        self.components.iter_mut().flatten()
+    }
+
+    /*
+        borrows the columns of several components mutably at once, in the same order as 'ids',
+        panics if a component is asked for twice since it can't be borrowed mutably twice
+    */
+    #[cfg(feature = "rayon")]
+    pub(crate) fn columns_by_ids_mut(&mut self, ids: &[ComponentId]) -> Vec<&mut dyn Any> {
+        let mut columns = self.components.iter_mut()
+            .map(|column| column.as_deref_mut())
+            .collect::<Vec<Option<&mut dyn ComponentColumn>>>();
+
+        ids.iter()
+            .map(|id| columns[*id].take()
+                .expect("A component can only appear once in a parallel query")
+                .as_any_mut())
+            .collect()
+    }
+
+    // the type erased column of the component 'id', without hashing anything
+    pub(crate) fn column_by_id(&self, id: ComponentId) -> Option<&dyn ComponentColumn> {
+        self.components.get(id)?.as_deref()
+    }
+
+    // the type erased column of the component type 'typeid'
+    pub(crate) fn column_of(&self, typeid: &TypeId) -> Option<&dyn ComponentColumn> {
+        self.column_by_id(self.component_id(typeid)?)
+    }
+
+    // the column storing components of type T, downcast from it's type erased form
+    pub(crate) fn typed_column<T: Any>(&self) -> Option<&Column<T>> {
+        let id = self.component_id(&TypeId::of::<T>())?;
+        self.column_by_id(id)?.as_any().downcast_ref::<Column<T>>()
+    }
+
+    pub(crate) fn typed_column_mut<T: Any>(&mut self) -> Option<&mut Column<T>> {
+        let id = self.component_id(&TypeId::of::<T>())?;
+        self.components.get_mut(id)?.as_mut()?.as_any_mut().downcast_mut::<Column<T>>()
+    }
+
+    // whether the entity at 'index' has the component 'id' in it's bitmask
+    pub(crate) fn has_component_id(&self, id: ComponentId, index: usize) -> bool {
+        self.map.get(index).map(|mask| mask & 1 << id != 0).unwrap_or(false)
+    }
+
+    // the type erased component 'id' at 'index', if there is one, without hashing anything
+    pub(crate) fn component_at_id(&self, id: ComponentId, index: usize) -> Option<ComponentCell<'_>> {
+        self.column_by_id(id)?.get(index)
+    }
+
+    /**
+    Returns the [ComponentId] of a given TypeId, which is assigned when the component is registered.
+    Looking components up by id instead of by TypeId avoids hashing the TypeId every time.
+
+    ```
+    use sceller::prelude::*;
+    use std::any::TypeId;
+
+    struct Health(u8);
+    struct Speed(u8);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(1)).insert(Speed(2));
+
+    assert_eq!(ents.component_id(&TypeId::of::<Speed>()), Some(1));
+    assert_eq!(ents.get_bitmask(&TypeId::of::<Speed>()), Some(0b10));
+    ```
+    
+    Returns None if the component requested isn't registered.
+     */
+    pub fn component_id(&self, typeid: &TypeId) -> Option<ComponentId> {
+        self.component_ids.get(typeid).copied()
+    }
+
+    /**
+    Convenience function to get the bitmask of a given TypeId. 
+    
+    Returns None if the component requested isn't registered.
+     */
+    pub fn get_bitmask(&self, typeid: &TypeId) -> Option<u128> {
+        self.component_id(typeid).map(|id| 1 << id)
+    }
+}
+
+// Trait implementations
+impl std::fmt::Display for Entities {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:#?}")
+    }
+}
+
+// shortens a type name by removing the module path of every type in it, 
+// e.g: "alloc::vec::Vec<my_game::Health>" becomes "Vec<Health>"
+pub(crate) fn short_type_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            short += segment.rsplit("::").next().unwrap_or_default();
+            segment.clear();
+            short.push(c);
+        }
+    }
+    short += segment.rsplit("::").next().unwrap_or_default();
+    short
+}
+
+#[derive(thiserror::Error, Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum ComponentError {
+    #[error("Attempt to add component to nothing.")]
+    NonexistentEntity,
+    #[error("This error should never happen. (Failed to fill fields of newly generated component on the fly)")]
+    AutomaticRegistrationError,
+    #[error("Attempt to make use of unregistered component.")]
+    UnregisteredComponentError,
+    #[error("Index out of bounds when accessing entity id. (Expected range from 0..{expected}, got {found}).")]
+    IndexOutOfBoundsError { expected: usize, found: usize },
+    #[error("Attempted to get component data that does not exist. Error in bitmask probably?")]
+    NonexistentComponentDataError,
+    #[error("Attempted to borrow a component that is already borrowed elsewhere.")]
+    BorrowedComponentError,
+    #[error("Attempted to borrow the components of entity {0} more than once.")]
+    DuplicateIdError(usize),
+    #[error("The component {0} is already part of another group.")]
+    GroupedComponentError(&'static str),
+    #[error("Attempted to borrow the component {type_name} of entity {entity}, which is already borrowed elsewhere.")]
+    ComponentAlreadyBorrowedError { type_name: &'static str, entity: Entity },
+    #[error("Attempted to insert the default value of a component that wasn't registered with one.")]
+    NoDefaultError,
+    #[error("Attempted to add the unique component {name} to an entity, but entity {holder} already has it.")]
+    DuplicateUniqueComponentError { name: &'static str, holder: Entity },
+    #[error("The zero sized component {0} takes no memory, and can't be inserted as a shared component.")]
+    SharedTagError(&'static str),
+    #[error("The component {type_name} of entity {entity} is shared with other entities or handles, and can't be taken.")]
+    SharedComponentError { type_name: &'static str, entity: Entity },
+    #[error("The component {0} is part of a group, whose components are stored in order and can't be shared.")]
+    SharedGroupedComponentError(&'static str),
+    #[error("Attempted to borrow the column of {0}, but one of it's components is already borrowed elsewhere.")]
+    ColumnAlreadyBorrowedError(&'static str),
+    #[error("The zero sized component {0} isn't stored anywhere, so it's column can't be borrowed as a slice.")]
+    TagColumnError(&'static str),
+}
 
 #[cfg(test)]
 mod tests {
@@ -502,7 +2025,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn new_entities_fill_deleted_spots() -> eyre::Result<()> {
+    fn new_entities_fill_deleted_spots() -> Result<()> {
         let mut ents = Entities::default();
 
         ents.create_entity()
@@ -520,12 +2043,10 @@ mod tests {
 
         assert_eq!(ents.map[0], 1);
 
-        let hp = ents.components.get(&TypeId::of::<Health>()).unwrap()[0]
-            .as_ref()
+        let hp = ents.typed_column::<Health>().unwrap()
+            .get_typed(0)
             .unwrap()
             .borrow();
-        let hp = hp.downcast_ref::<Health>()
-            .unwrap();
 
         assert_eq!(hp.0, 20);
 
@@ -533,7 +2054,7 @@ mod tests {
     }
 
     #[test]
-    fn delete_entities_by_id() -> eyre::Result<()> {
+    fn delete_entities_by_id() -> Result<()> {
         let mut ents = Entities::default();
 
         ents.create_entity()
@@ -558,7 +2079,7 @@ mod tests {
         ents.register_component::<Health>();
         ents.register_component::<Id>();
 
-        let hp_component = ents.components.get(&TypeId::of::<Health>()).unwrap();
+        let hp_component = ents.column_of(&TypeId::of::<Health>()).unwrap();
 
         assert_eq!(hp_component.len(), 0);
         dbg!(ents);
@@ -570,12 +2091,38 @@ mod tests {
         ents.register_component::<Health>();
         ents.register_component::<Id>();
 
-        let hp_component = ents.bit_masks.get(&TypeId::of::<Health>()).unwrap();
+        let hp_component = ents.get_bitmask(&TypeId::of::<Health>()).unwrap();
 
-        assert_eq!(*hp_component, 1);
+        assert_eq!(hp_component, 1);
         dbg!(ents);
     }
 
+    #[test]
+    fn component_ids_index_columns() -> Result<()> {
+        let mut ents = Entities::default();
+        ents.create_entity().insert_checked(Health(1))?;
+        ents.create_entity().insert_checked(Health(2))?;
+
+        // registering after entities exist gives the column a slot for each of them
+        ents.register_component::<Id>();
+        ents.insert_component_into_entity_by_id_checked(Id(String::from("late")), 1)?;
+
+        // registering again keeps the existing id and components
+        ents.register_component::<Health>();
+
+        let health = ents.component_id(&TypeId::of::<Health>()).unwrap();
+        let id = ents.component_id(&TypeId::of::<Id>()).unwrap();
+        assert_eq!((health, id), (0, 1));
+        assert_eq!(ents.map, vec![1, 3]);
+
+        let hp = ents.component_at_id(health, 1).unwrap().borrow();
+        assert_eq!(hp.downcast_ref::<Health>().unwrap().0, 2);
+        assert!(ents.component_at_id(id, 0).is_none());
+        assert!(ents.component_at_id(2, 0).is_none());
+
+        Ok(())
+    }
+
     #[test]
     fn create_entity() {
         let mut ents = Entities::default();
@@ -583,12 +2130,12 @@ mod tests {
         ents.register_component::<Id>();
 
         ents.create_entity();
-        let hp = ents.components.get(&TypeId::of::<Health>()).unwrap();
-        let speed = ents.components.get(&TypeId::of::<Id>()).unwrap();
+        let hp = ents.column_of(&TypeId::of::<Health>()).unwrap();
+        let speed = ents.column_of(&TypeId::of::<Id>()).unwrap();
 
         assert!(hp.len() == speed.len() && hp.len() == 1);
-        assert!(speed[0].is_none());
-        assert!(hp[0].is_none());
+        assert!(speed.get(0).is_none());
+        assert!(hp.get(0).is_none());
 
         dbg!(ents.components);
     }
@@ -606,16 +2153,16 @@ mod tests {
             .insert(Health(50))
             .insert(Id(String::from("hey")));
 
-        let health1 = &ents.components.get(&TypeId::of::<Health>()).unwrap()[0];
-        let wrapped_health = health1.as_ref().unwrap();
+        let health1 = ents.column_of(&TypeId::of::<Health>()).unwrap();
+        let wrapped_health = health1.get(0).unwrap();
         let borrowed_health = wrapped_health.borrow();
         let hp = borrowed_health.downcast_ref::<Health>().unwrap();
 
         assert_eq!(hp.0, 100);
         dbg!(hp);
 
-        let hp = ents.components.get(&TypeId::of::<Health>()).unwrap();
-        let speed = ents.components.get(&TypeId::of::<Unique>()).unwrap();
+        let hp = ents.column_of(&TypeId::of::<Health>()).unwrap();
+        let speed = ents.column_of(&TypeId::of::<Unique>()).unwrap();
 
         assert!(hp.len() == speed.len() && hp.len() == ents.entity_count);
         // assert!(speed[0].is_none());
@@ -648,6 +2195,68 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn insert_bundle() -> Result<()> {
+        let mut ents = Entities::default();
+
+        ents.create_entity()
+            .insert_bundle_checked((Health(100), Id(String::from("hi"))))?;
+
+        ents.create_entity()
+            .insert_bundle_checked((Unique, Health(50)))?;
+
+        assert_eq!(ents.map[0], 3);
+        assert_eq!(ents.map[1], 5);
+        assert_eq!(ents.column_of(&TypeId::of::<Unique>()).unwrap().len(), 2);
+
+        let hp = ents.typed_column::<Health>().unwrap()
+            .get_typed(1)
+            .unwrap()
+            .borrow();
+        assert_eq!(hp.0, 50);
+
+        Ok(())
+    }
+
+    #[test]
+    fn spawn_batch_fills_vacant_slots() -> Result<()> {
+        let mut ents = Entities::default();
+
+        ents.create_entity().insert_checked(Health(1))?;
+        ents.create_entity().insert_checked(Health(2))?;
+        ents.create_entity().insert_checked(Health(3))?;
+        ents.delete_entity_by_id(1)?;
+
+        let ids = ents.spawn_batch_checked((10..13).map(|hp| (Health(hp), Unique)))?;
+
+        assert_eq!(ids, vec![1, 3, 4]);
+        assert_eq!(ents.map, vec![1, 3, 1, 3, 3]);
+        assert_eq!(ents.entity_count, 5);
+        assert_eq!(ents.column_of(&TypeId::of::<Unique>()).unwrap().len(), 5);
+        assert_eq!(ents.count_matching(2), 3);
+        assert_eq!(ents.current_entity(), 4);
+
+        let hp = ents.typed_column::<Health>().unwrap().get_typed(1).unwrap().borrow();
+        assert_eq!(hp.0, 10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reserve_and_shrink() -> Result<()> {
+        let mut ents = Entities::default();
+        ents.create_entity().insert_checked(Health(1))?;
+
+        ents.reserve(100);
+        assert!(ents.map.capacity() >= 101);
+
+        ents.shrink_to_fit();
+        assert!(ents.map.capacity() < 101);
+        assert_eq!(ents.typed_column::<Health>().unwrap().get_typed(0).unwrap().borrow().0, 1);
+
+        Ok(())
+    }
+
     #[test]
     fn delete_component_by_ent_id() -> Result<()> {
         let mut ents = Entities::default();
@@ -668,7 +2277,7 @@ mod tests {
     }
 
     #[test] 
-    fn add_component_by_ent_id() -> eyre::Result<()> {
+    fn add_component_by_ent_id() -> Result<()> {
         let mut ents = Entities::default();
 
         ents.create_entity()
@@ -689,7 +2298,7 @@ mod tests {
     }
 
     #[test]
-    fn remove_component() -> eyre::Result<()> {
+    fn remove_component() -> Result<()> {
         let mut ents = Entities::default();
 
         ents.create_entity()
@@ -710,8 +2319,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn remove_component_only_clears_owners() -> Result<()> {
+        let mut ents = Entities::default();
+
+        ents.create_entity()
+            .insert_checked(Health(100))?
+            .insert_checked(Id(String::from("hi")))?;
+
+        ents.create_entity()
+            .insert_checked(Id(String::from("hey")))?;
+
+        ents.delete_component_checked::<Health>()?;
+
+        // the second entity never had a 'Health' so it's mask is left alone
+        assert_eq!(ents.map, vec![2, 2]);
+        assert!(ents.typed_column::<Health>().is_none());
+        assert!(ents.delete_component_checked::<Health>().is_err());
+        assert_eq!(ents.map, vec![2, 2]);
+
+        // the freed bit is reused without clashing with 'Id'
+        ents.create_entity().insert_checked(Unique)?;
+        assert_eq!(ents.get_bitmask(&TypeId::of::<Unique>()), Some(1));
+        assert_eq!(ents.map, vec![2, 2, 1]);
+        assert_eq!(ents.count_matching(2), 2);
+
+        Ok(())
+    }
+
     #[test] 
-    fn double_delete_fix() -> eyre::Result<()> {
+    fn double_delete_fix() -> Result<()> {
         let mut ents = Entities::default();
 
         ents.create_entity()
@@ -734,8 +2371,112 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn take_component() -> Result<()> {
+        let mut ents = Entities::default();
+
+        ents.create_entity()
+            .insert_checked(Health(100))?
+            .insert_checked(Id(String::from("hi")))?;
+
+        let health = ents.take_component::<Health>(0)?;
+        assert_eq!(health.0, 100);
+
+        // only the 'Id' component is left, and the slot is cleared
+        assert_eq!(ents.map[0], 2);
+        assert!(ents.typed_column::<Health>().unwrap().get_typed(0).is_none());
+
+        assert!(ents.take_component::<Health>(0).is_err());
+        assert!(ents.take_component::<Unique>(0).is_err());
+        assert!(ents.take_component::<Id>(4).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn despawn_returns_components() -> Result<()> {
+        let mut ents = Entities::default();
+
+        ents.create_entity()
+            .insert_checked(Health(100))?
+            .insert_checked(Id(String::from("hi")))?;
+        ents.create_entity()
+            .insert_checked(Health(50))?;
+
+        let mut despawned = ents.despawn_checked(0)?;
+        assert_eq!(despawned.len(), 2);
+        assert_eq!(despawned.take::<Health>()?.0, 100);
+        assert!(despawned.take::<Health>().is_err());
+        assert!(!despawned.has::<Unique>());
+
+        assert_eq!(ents.map[0], 0);
+        assert!(ents.typed_column::<Id>().unwrap().get_typed(0).is_none());
+
+        // the despawned slot gets reused
+        ents.create_entity().insert_checked(Health(10))?;
+        assert_eq!(ents.current_entity(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_components_mut() -> Result<()> {
+        let mut ents = Entities::default();
+
+        ents.create_entity().insert_checked(Health(100))?;
+        ents.create_entity().insert_checked(Id(String::from("no health")))?;
+        ents.create_entity().insert_checked(Health(50))?;
+
+        {
+            let [mut a, mut b] = ents.get_components_mut::<Health, 2>(&[2, 0])?;
+            std::mem::swap(&mut a.0, &mut b.0);
+
+            // already borrowed above
+            assert!(ents.get_components_mut::<Health, 1>(&[0]).is_err());
+        }
+
+        let [a, b] = ents.get_components_mut::<Health, 2>(&[0, 2])?;
+        assert_eq!((a.0, b.0), (50, 100));
+        drop((a, b));
+
+        assert!(ents.get_components_mut::<Health, 2>(&[0, 1]).is_err());
+        assert!(ents.get_components_mut::<Health, 3>(&[0, 2, 0]).is_err());
+        assert!(ents.get_components_mut::<Unique, 1>(&[0]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn archetypes_follow_masks() -> Result<()> {
+        let mut ents = Entities::default();
+
+        ents.create_entity().insert_checked(Health(1))?.insert_checked(Unique)?;
+        ents.create_entity().insert_checked(Health(2))?;
+        ents.create_entity().insert_checked(Id(String::from("three")))?.insert_checked(Health(3))?;
+        ents.create_entity().insert_checked(Unique)?;
+
+        ents.delete_component_by_entity_id_checked::<Unique>(0)?;
+        ents.take_component::<Health>(2)?;
+        ents.despawn_checked(1)?;
+        ents.create_entity().insert_checked(Unique)?.insert_checked(Health(4))?;
+
+        // the archetypes give the same answer as scanning the map
+        for mask in [1, 2, 3, 4, 5, 6, 7] {
+            let scanned = ents.map.iter().enumerate()
+                .filter(|(_, entity_mask)| **entity_mask != 0 && *entity_mask & mask == mask)
+                .map(|(index, _)| index)
+                .collect::<Vec<usize>>();
+            assert_eq!(ents.matching_entities(mask), scanned);
+            assert_eq!(ents.count_matching(mask), scanned.len());
+        }
+        assert_eq!(ents.archetype_count(), 4);
+
+        Ok(())
+    }
+
     #[derive(Debug)]
     struct Health(u16);
+    #[allow(dead_code)]
     struct Id(String);
 
     struct Unique;
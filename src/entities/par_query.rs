@@ -0,0 +1,235 @@
+//! # Parallel Queries
+//!
+//! Enabled with the `rayon` feature, this module allows iterating over the results of a query on the
+//! [rayon](https://docs.rs/rayon) thread pool with [World::par_iter()] and [World::par_for_each()].
+//!
+//! Components are stored in RefCells, which can't be shared between threads, so unlike an [FnQuery](super::FnQuery)
+//! a parallel query needs exclusive access to the World. Every matching component is borrowed up front,
+//! then the results are split into chunks that are processed on different threads.
+//!
+//! A parallel query can only read components that are Sync and write components that are Send, which is checked
+//! when it compiles, so other components never leave the thread owning the World. Components that are Send or
+//! Sync but must still stay on that thread, like handles to GPU resources, are registered with
+//! [World::register_main_thread()]: [World::par_for_each()] then runs on the calling thread, and
+//! [World::par_iter()] returns an error. Filtering on them with [With<T>](super::With) doesn't access them.
+
+use std::any::Any;
+
+use rayon::prelude::*;
+
+use super::{column::Column, Changed, Entity, FnQueryContainedTupleType, QueryError, With};
+use crate::{world::World, error::Result};
+
+/**
+A trait implemented by the types a parallel query can contain, '&T' for components that are
+only read, which must be Sync, '&mut T' for components that are written to, which must be Send, 
+and [With<T>](super::With) or [Changed<T>](super::Changed) for components that are only filtered on.
+ */
+pub trait ParQueryContainedIndividualType<'a> {
+    type Item: Send;
+
+    // borrows the component of every entity in 'ids' from the column of this type
+    fn fetch_many(column: &'a mut dyn Any, ids: &[Entity]) -> Vec<Self::Item>;
+}
+
+impl<'a, T: Any + Sync> ParQueryContainedIndividualType<'a> for &T {
+    type Item = &'a T;
+
+    fn fetch_many(column: &'a mut dyn Any, ids: &[Entity]) -> Vec<Self::Item> {
+        column.downcast_mut::<Column<T>>().unwrap()
+            .get_many_mut(ids)
+            .into_iter()
+            .map(|component| &*component)
+            .collect()
+    }
+}
+
+impl<'a, T: Any + Send> ParQueryContainedIndividualType<'a> for &mut T {
+    type Item = &'a mut T;
+
+    fn fetch_many(column: &'a mut dyn Any, ids: &[Entity]) -> Vec<Self::Item> {
+        column.downcast_mut::<Column<T>>().unwrap().get_many_mut(ids)
+    }
+}
+
+impl<'a, T: Any> ParQueryContainedIndividualType<'a> for With<T> {
+    type Item = ();
+
+    fn fetch_many(_column: &'a mut dyn Any, ids: &[Entity]) -> Vec<Self::Item> {
+        vec![(); ids.len()]
+    }
+}
+
+impl<'a, T: Any> ParQueryContainedIndividualType<'a> for Changed<T> {
+    type Item = ();
+
+    fn fetch_many(_column: &'a mut dyn Any, ids: &[Entity]) -> Vec<Self::Item> {
+        vec![(); ids.len()]
+    }
+}
+
+/**
+A trait that abstracts over whether the type contained in a parallel query is a tuple and of what size.
+ */
+pub trait ParQueryContainedTupleType<'a> {
+    type Item: Send;
+
+    // borrows every element of the tuple for every entity in 'ids', 'columns' being in the same order as the tuple
+    fn fetch_all(columns: Vec<&'a mut dyn Any>, ids: &[Entity]) -> Vec<Self::Item>;
+}
+
+impl<'a, T> ParQueryContainedTupleType<'a> for T
+where T: ParQueryContainedIndividualType<'a>
+{
+    type Item = T::Item;
+
+    fn fetch_all(columns: Vec<&'a mut dyn Any>, ids: &[Entity]) -> Vec<Self::Item> {
+        T::fetch_many(columns.into_iter().next().unwrap(), ids)
+    }
+}
+
+macro_rules! impl_par_query_tuple {
+    ($($t:ident $results:ident),+) => {
+        impl<'a, $($t),+> ParQueryContainedTupleType<'a> for ($($t,)+)
+        where
+            $($t: ParQueryContainedIndividualType<'a>,)+
+        {
+            type Item = ($($t::Item,)+);
+
+            fn fetch_all(columns: Vec<&'a mut dyn Any>, ids: &[Entity]) -> Vec<Self::Item> {
+                let mut columns = columns.into_iter();
+                $(let mut $results = $t::fetch_many(columns.next().unwrap(), ids).into_iter();)+
+                ids.iter()
+                    .map(|_| ($($results.next().unwrap(),)+))
+                    .collect()
+            }
+        }
+    };
+}
+
+impl_par_query_tuple!(T1 r1, T2 r2);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3, T4 r4);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3, T4 r4, T5 r5);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3, T4 r4, T5 r5, T6 r6);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3, T4 r4, T5 r5, T6 r6, T7 r7);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3, T4 r4, T5 r5, T6 r6, T7 r7, T8 r8);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3, T4 r4, T5 r5, T6 r6, T7 r7, T8 r8, T9 r9);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3, T4 r4, T5 r5, T6 r6, T7 r7, T8 r8, T9 r9, T10 r10);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3, T4 r4, T5 r5, T6 r6, T7 r7, T8 r8, T9 r9, T10 r10, T11 r11);
+impl_par_query_tuple!(T1 r1, T2 r2, T3 r3, T4 r4, T5 r5, T6 r6, T7 r7, T8 r8, T9 r9, T10 r10, T11 r11, T12 r12);
+
+// Parallel queries
+impl World {
+    /**
+    Runs a query for one or more component types and returns a parallel iterator over the results,
+    which yields '&T' for '&T' and '&mut T' for '&mut T'.
+
+    Takes the same types as [FnQuery](struct.FnQuery.html), but since every result is borrowed before
+    the iterator is returned, the World has to be borrowed mutably. A component type can only appear once.
+
+    ```
+    use sceller::prelude::*;
+    use rayon::prelude::*;
+
+    struct Position(f32);
+    struct Velocity(f32);
+
+    let mut world = World::new();
+    for i in 0..100 {
+        world.spawn().insert(Position(0.0)).insert(Velocity(i as f32));
+    }
+
+    let fastest = world.par_iter::<&Velocity>()
+        .map(|vel| vel.0)
+        .reduce(|| 0.0, f32::max);
+    assert_eq!(fastest, 99.0);
+    ```
+
+    Panics if the query contains a component registered with [World::register_main_thread()],
+    see [World::par_iter_checked()].
+     */
+    pub fn par_iter<'a, T>(&'a mut self) -> rayon::vec::IntoIter<<T as ParQueryContainedTupleType<'a>>::Item>
+    where
+        T: for<'b> FnQueryContainedTupleType<'b> + ParQueryContainedTupleType<'a>
+    {
+        self.par_iter_checked::<T>().unwrap()
+    }
+
+    /**
+    Same as [World::par_iter()], but returns an error naming the component instead of panicking if the query
+    contains a component registered with [World::register_main_thread()].
+     */
+    pub fn par_iter_checked<'a, T>(&'a mut self) -> Result<rayon::vec::IntoIter<<T as ParQueryContainedTupleType<'a>>::Item>>
+    where
+        T: for<'b> FnQueryContainedTupleType<'b> + ParQueryContainedTupleType<'a>
+    {
+        if let Some(name) = self.main_thread_component::<T>() {
+            return Err(QueryError::MainThreadComponentError(name).into());
+        }
+        Ok(self.par_fetch::<T>().into_par_iter())
+    }
+
+    /**
+    Runs 'f' on every result of a query, in parallel on the rayon thread pool. See [World::par_iter()].
+
+    If the query contains a component registered with [World::register_main_thread()], 'f' runs on every result
+    one after the other on the calling thread instead.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Position(f32);
+    struct Velocity(f32);
+
+    let mut world = World::new();
+    for i in 0..100 {
+        world.spawn().insert(Position(0.0)).insert(Velocity(i as f32));
+    }
+
+    world.par_for_each::<(&mut Position, &Velocity), _>(|(pos, vel)| {
+        pos.0 += vel.0 * 0.5;
+    });
+
+    let pos = world.query_typed::<&Position>().last().unwrap();
+    assert_eq!(pos.0, 49.5);
+    ```
+     */
+    pub fn par_for_each<'a, T, F>(&'a mut self, f: F)
+    where
+        T: for<'b> FnQueryContainedTupleType<'b> + ParQueryContainedTupleType<'a>,
+        F: Fn(<T as ParQueryContainedTupleType<'a>>::Item) + Send + Sync,
+    {
+        if self.main_thread_component::<T>().is_some() {
+            self.par_fetch::<T>().into_iter().for_each(f);
+        } else {
+            self.par_fetch::<T>().into_par_iter().for_each(f);
+        }
+    }
+
+    // the name of the first component accessed by the query that was registered to stay on the main thread
+    fn main_thread_component<T>(&self) -> Option<&'static str>
+    where T: for<'b> FnQueryContainedTupleType<'b>
+    {
+        T::access().into_iter()
+            .find(|access| self.entities.is_main_thread_id(&access.type_id))
+            .map(|access| access.name)
+    }
+
+    // borrows every result of the query, marking the components borrowed mutably as changed
+    fn par_fetch<'a, T>(&'a mut self) -> Vec<<T as ParQueryContainedTupleType<'a>>::Item>
+    where
+        T: for<'b> FnQueryContainedTupleType<'b> + ParQueryContainedTupleType<'a>
+    {
+        let components = T::component_ids(&self.entities);
+        let ids = T::ids(&self.entities, &components);
+        // the components borrowed mutably are marked as changed before their columns are borrowed
+        for access in T::access().iter().filter(|access| access.mutable) {
+            if let Some(component) = self.entities.component_id(&access.type_id) {
+                ids.iter().for_each(|id| self.entities.mark_changed_id(component, *id));
+            }
+        }
+        let columns = self.entities.columns_by_ids_mut(&components);
+        T::fetch_all(columns, &ids)
+    }
+}
@@ -7,22 +7,54 @@
 use super::*;
 use super::auto_query::{AutoQuery, AutoQueryMut};
 use super::query_entity::*;
+use crate::commands::CommandQueue;
 
 //
 // ideas: turn Query result into tuple of Vec<> of all different types
 //
 
+/**
+A lazy iterator over the [QueryEntity]s matching a [Query], in ascending order of id, returned by
+[Query::iter_entities()].
+ */
+#[derive(Debug)]
+pub struct QueryEntityIter<'a> {
+    entities: &'a Entities,
+    commands: Option<&'a CommandQueue>,
+    map: u128,
+    // the id of the next entity to look at
+    next: Entity,
+}
+
+impl<'a> Iterator for QueryEntityIter<'a> {
+    type Item = QueryEntity<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (offset, _) = self.entities.map.get(self.next..)?.iter().enumerate()
+            .find(|(_, mask)| *mask & self.map == self.map)?;
+        let id = self.next + offset;
+        self.next = id + 1;
+        Some(QueryEntity { id, entities: self.entities, commands: self.commands })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.entities.map.len().saturating_sub(self.next)))
+    }
+}
+
 #[derive(Debug)]
 /**
 A struct used to interface with the ECS.
 
 Contains a map of components included and a reference to the Entites struct, as well
-as a vector of the type_ids contained in the query for ease of use.
+as a vector of the [ComponentId]s contained in the query, so that running it doesn't hash any TypeId.
  */
 pub struct Query<'a> {
     map: u128,
     pub(super) entities: &'a Entities,
-    type_ids: Vec<TypeId>,
+    component_ids: Vec<ComponentId>,
+    // the queue the QueryEntities returned by the query push their structural changes into
+    commands: Option<&'a CommandQueue>,
 }
 
 impl<'a> Query<'a> {
@@ -32,7 +64,15 @@ impl<'a> Query<'a> {
     Takes an immutable reference to an entites struct.
      */
     pub fn new(entities: &'a Entities) -> Self {
-        Self { map: 0, entities, type_ids: Vec::new() }
+        Self { map: 0, entities, component_ids: Vec::new(), commands: None }
+    }
+
+    /**
+    Creates a new Query whose [QueryEntity]s queue their structural changes into 'commands',
+    see [QueryEntity::insert()]. This is how [World::query()](struct.World.html#method.query) creates it's queries.
+     */
+    pub fn with_commands(entities: &'a Entities, commands: &'a CommandQueue) -> Self {
+        Self { commands: Some(commands), ..Self::new(entities) }
     }
 
     /**
@@ -105,11 +145,11 @@ impl<'a> Query<'a> {
     
     ```
      */
-    pub fn with_component_checked<T: Any>(&mut self) -> eyre::Result<&mut Self> {
+    pub fn with_component_checked<T: Any>(&mut self) -> Result<&mut Self> {
         let typeid = TypeId::of::<T>();
-        if let Some(bitmask) = self.entities.get_bitmask(&typeid) {
-            self.map |= bitmask;
-            self.type_ids.push(typeid);
+        if let Some(id) = self.entities.component_id(&typeid) {
+            self.map |= 1 << id;
+            self.component_ids.push(id);
         } else {
             return Err(QueryError::UnregisteredComponentError.into())
         }
@@ -119,7 +159,8 @@ impl<'a> Query<'a> {
 
     /**
     Executes and returns the result of a query in the form of a vector of vectors 
-    of [ComponentType](types.ComponentType.html).
+    of [ComponentType](types.ComponentType.html), in the order the components were added to the query.
+    See [Query::run_named()] to look the components up by type instead.
 
     ```
     use sceller::prelude::*;
@@ -162,32 +203,72 @@ impl<'a> Query<'a> {
     assert_eq!(second2.0, 'b');
     ```
      */
-    pub fn run(&mut self) -> Vec<Vec<ComponentType>> {
+    pub fn run(&mut self) -> Vec<Vec<ComponentType<'a>>> {
         // signifies that we have no valid components to query
         if self.map == 0 {
             return vec![]
         }
 
-        let indexes = self.entities.map.iter().enumerate().filter_map(|(index, map)| {
-            if map & self.map == self.map {
-                Some(index)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<usize>>();
-
-        self.type_ids.iter().map(|typeid| {
-            let components = self.entities.components.get(typeid).unwrap();
-            let mut query_components = Vec::new();
-            for index in &indexes {
-                query_components.push(components[*index].clone());
-            }
-            query_components.into_iter().flatten().collect::<Vec<_>>()
+        let indexes = self.entities.matching_entities(self.map);
+
+        let entities = self.entities;
+        self.component_ids.iter().map(|id| {
+            indexes.iter()
+                .filter_map(|index| entities.component_at_id(*id, *index))
+                .collect::<Vec<_>>()
         })
         .collect::<Vec<Vec<ComponentType>>>()
     }
 
+    /**
+    Executes the [Query] and returns a [QueryResult], which stores the components of the matching entities by type,
+    so that reordering the calls to [Query::with_component()] doesn't change which component is read.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+    struct Armour(u32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(10)).insert(Armour(4));
+    world.spawn().insert(Health(5)).insert(Armour(1));
+
+    let result = world.query()
+        .with_component::<Armour>()
+        .with_component::<Health>()
+        .run_named();
+
+    let first = result.column::<Health>()[0].borrow();
+    assert_eq!(first.downcast_ref::<Health>().unwrap().0, 10);
+
+    let total = result.iter::<Health>().zip(result.iter::<Armour>())
+        .map(|(health, armour)| health.0 + armour.0)
+        .sum::<u32>();
+    assert_eq!(total, 20);
+    ```
+
+    An empty query returns an empty result.
+     */
+    pub fn run_named(&self) -> QueryResult<'a> {
+        if self.map == 0 {
+            return QueryResult::new(self.entities, Vec::new(), HashMap::new());
+        }
+
+        let ids = self.entities.matching_entities(self.map);
+
+        let entities = self.entities;
+        let columns = self.component_ids.iter().map(|id| {
+            let components = ids.iter()
+                .filter_map(|index| entities.component_at_id(*id, *index))
+                .collect::<Vec<_>>();
+            (*id, components)
+        })
+        .collect::<HashMap<ComponentId, Vec<ComponentType>>>();
+
+        QueryResult::new(entities, ids, columns)
+    }
+
     /**
     Executes the [Query] and returns the result in the form of a vector or [QueryEntity]s. 
 
@@ -221,20 +302,46 @@ impl<'a> Query<'a> {
     Essentially provides a more user-friendly way of making queries, remains non-destructive of the 
     [Entities] object passed in.
      */
-    pub fn run_entity(&self) -> eyre::Result<Vec<QueryEntity>> {
+    pub fn run_entity(&self) -> Result<Vec<QueryEntity<'a>>> {
         // signifies that we have no valid components to query
         if self.map == 0 {
             return Err(QueryError::UnregisteredComponentError.into());
         }
 
-        Ok(self.entities.map.iter().enumerate().filter_map(|(index, map)| {
-            if map & self.map == self.map {
-                Some(QueryEntity::new(index, self.entities))
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<QueryEntity>>())
+        Ok(self.iter_entities()?.collect::<Vec<QueryEntity>>())
+    }
+
+    /**
+    Same as [Query::run_entity()], but returns an iterator finding the matching entities one at a time,
+    so that searches stopping at the first match don't look at every other entity.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Enemy;
+    struct Position(i32);
+
+    let mut ents = Entities::default();
+    for x in [40, 3, -7, 12] {
+        ents.create_entity().insert(Enemy).insert(Position(x));
+    }
+
+    let mut query = Query::new(&ents);
+    let in_range = query.with_component_checked::<Enemy>().unwrap()
+        .iter_entities().unwrap()
+        .find(|enemy| enemy.get_component::<Position>().unwrap().0.abs() < 10);
+
+    assert_eq!(in_range.unwrap().id, 1);
+    ```
+
+    Returns an error if no components were added to the query.
+     */
+    pub fn iter_entities(&self) -> Result<QueryEntityIter<'a>> {
+        if self.map == 0 {
+            return Err(QueryError::UnregisteredComponentError.into());
+        }
+
+        Ok(QueryEntityIter { entities: self.entities, commands: self.commands, map: self.map, next: 0 })
     }
 
     /**
@@ -296,8 +403,8 @@ impl<'a> Query<'a> {
     For more info on the implementation, check the source or the documentation for
     [super::auto_query].
      */
-    pub fn auto<T: Any>(&self) -> AutoQuery<T> {
-        AutoQuery::new(&self.entities)
+    pub fn auto<T: Any>(&self) -> AutoQuery<'a, T> {
+        AutoQuery::new(self.entities)
     }
     
     /**
@@ -358,9 +465,35 @@ impl<'a> Query<'a> {
 
     For more info on the implementation, check the source or the documentation for
     [super::auto_query].
+
+    Panics if another mutable auto query of T is still alive, see [Query::auto_mut_checked()].
+     */
+    pub fn auto_mut<T: Any>(&self) -> AutoQueryMut<'a, T> {
+        AutoQueryMut::new(self.entities)
+    }
+
+    /**
+    Same as [Query::auto_mut()], but returns an error naming the component instead of panicking if another
+    mutable auto query of T, or one of it's iterators, is still alive.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(3));
+
+    let query = world.query();
+    let healths = query.auto_mut_checked::<Health>().unwrap().into_iter();
+    assert!(query.auto_mut_checked::<Health>().is_err());
+
+    drop(healths);
+    query.auto_mut_checked::<Health>().unwrap().nth(0).unwrap().0 = 4;
+    ```
      */
-    pub fn auto_mut<T: Any>(&self) -> AutoQueryMut<T> {
-        AutoQueryMut::new(&self.entities)
+    pub fn auto_mut_checked<T: Any>(&self) -> Result<AutoQueryMut<'a, T>> {
+        AutoQueryMut::new_checked(self.entities)
     }
 
     /**
@@ -389,20 +522,13 @@ impl<'a> Query<'a> {
     assert_eq!(indexes.len(), *&query1[0].len());
     ```
     
-    All this function does in essence is loop over the inner 'map' of the entities, which 
-    stores their respective bitmasks, and do the & product of it and the Query object's bitmask map.
+    All this function does in essence is look for the archetypes of the entities whose bitmask 
+    contains the Query object's bitmask map, and collect the indexes of their entities.
     
     It pushes these indexes into a vector and then places this into 'buf'.
      */
     pub fn read_indexes_to_buf(&mut self, buf: &mut Vec<usize>) -> &mut Self {
-        *buf = self.entities.map.iter().enumerate().filter_map(|(index, map)| {
-            if map & self.map == self.map {
-                Some(index)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<usize>>();
+        *buf = self.entities.matching_entities(self.map);
         self
     }
 }
@@ -420,6 +546,20 @@ pub enum QueryError {
     UnregisteredComponentError,
     #[error("QueryEntity contains out of bounds components.")]
     OutOfBoundsIdError,
+    #[error("Entity {0} doesn't have every component of the query.")]
+    NonMatchingEntityError(Entity),
+    #[error("Attempted to queue a change to entity {0} from a query without a command queue, create the query with World::query().")]
+    NoCommandQueueError(Entity),
+    #[error("Expected exactly one entity with the component {name}, found {count}.")]
+    SingleEntityError { name: &'static str, count: usize },
+    #[error("Attempted to create a mutable auto query of {0} while another one is still alive.")]
+    AliasedAutoQueryError(&'static str),
+    #[error("No group is made of exactly the components of the query {0:?}, register one with World::register_group().")]
+    UngroupedQueryError(Vec<&'static str>),
+    #[error("The query result has no column of the component {0}, add it to the query with Query::with_component().")]
+    MissingColumnError(&'static str),
+    #[error("The component {0} must stay on the main thread and can't be sent to the thread pool, use World::par_for_each() instead.")]
+    MainThreadComponentError(&'static str),
 }
 
 #[cfg(test)]
@@ -449,7 +589,7 @@ mod tests {
     }
 
     #[test]
-    fn query_for_entity_mut() -> eyre::Result<()> {
+    fn query_for_entity_mut() -> Result<()> {
         let mut ents = Entities::default();
 
         // add in a dummy entity
@@ -474,7 +614,7 @@ mod tests {
     }
 
     #[test]
-    fn query_for_entity_ref() -> eyre::Result<()> {
+    fn query_for_entity_ref() -> Result<()> {
         let mut ents = Entities::default();
 
         // add in a dummy entity
@@ -498,7 +638,7 @@ mod tests {
     }
 
     #[test]
-    fn query_mask_updating() -> eyre::Result<()> {
+    fn query_mask_updating() -> Result<()> {
         let ents = init_entities()?;
 
         let mut query = Query::new(&ents);
@@ -506,14 +646,14 @@ mod tests {
             .with_component_checked::<Component2>()?;
 
         assert_eq!(query.map, 3);
-        assert_eq!(TypeId::of::<Component1>(), query.type_ids[0]);
-        assert_eq!(TypeId::of::<Component2>(), query.type_ids[1]);
+        assert_eq!(ents.component_id(&TypeId::of::<Component1>()), Some(query.component_ids[0]));
+        assert_eq!(ents.component_id(&TypeId::of::<Component2>()), Some(query.component_ids[1]));
 
         Ok(())
     }
 
     #[test]
-    fn run_query() -> eyre::Result<()> {
+    fn run_query() -> Result<()> {
         let ents = init_entities()?;
 
         let mut indexes = Vec::new();
@@ -550,7 +690,7 @@ mod tests {
         Ok(())
     }
 
-    fn init_entities() -> eyre::Result<Entities> {
+    fn init_entities() -> Result<Entities> {
         let mut ents = Entities::default();
 
         // add in a dummy entity
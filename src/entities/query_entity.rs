@@ -4,7 +4,9 @@
 
 use std::{any::{Any, TypeId}, cell::{Ref, RefMut}};
 
-use super::{Entities, ComponentError, query::QueryError};
+use super::{Entities, ComponentError, column, query::QueryError};
+use crate::commands::{CommandQueue, Commands};
+use crate::error::Result;
 
 
 /**
@@ -15,7 +17,8 @@ it can iterate over the entities relevent to it's query.
  */
 pub struct QueryEntity<'a> {
     pub id: usize,
-    entities: &'a Entities, 
+    pub(super) entities: &'a Entities, 
+    pub(super) commands: Option<&'a CommandQueue>,
 }
 
 impl<'a> QueryEntity<'a> {
@@ -32,7 +35,28 @@ impl<'a> QueryEntity<'a> {
     ```
      */
     pub fn new(index: usize, entities: &'a Entities) -> Self {
-        Self { id: index, entities }
+        Self { id: index, entities, commands: None }
+    }
+
+    /**
+    Returns true if this entity has a component of type T, checked using the entity's bitmask.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Shield(u8);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert(Health(3));
+
+    let entity = QueryEntity::new(0, &ents);
+    assert!(entity.has_component::<Health>());
+    assert!(!entity.has_component::<Shield>());
+    ```
+     */
+    pub fn has_component<T: Any>(&self) -> bool {
+        self.entities.has_component::<T>(self.id)
     }
 
     /**
@@ -66,16 +90,17 @@ impl<'a> QueryEntity<'a> {
     }
     ```
      */
-    pub fn get_component<T: Any>(&self) -> eyre::Result<Ref<T>> {
+    pub fn get_component<T: Any>(&self) -> Result<Ref<'_, T>> {
         let typeid = TypeId::of::<T>();
-        let components = self.entities.components.get(&typeid).ok_or(ComponentError::UnregisteredComponentError)?;
+        let components = self.entities.column_of(&typeid).ok_or(ComponentError::UnregisteredComponentError)?;
 
+        if self.id >= components.len() {
+            return Err(QueryError::OutOfBoundsIdError.into());
+        }
         let component = components.get(self.id)
-            .ok_or(QueryError::OutOfBoundsIdError)?
-            .as_ref()
             .ok_or(ComponentError::NonexistentComponentDataError)?;
 
-        let borrow = component.borrow();
+        let borrow = column::try_borrow(component, std::any::type_name::<T>(), self.id)?;
 
         Ok(
             Ref::map(borrow, |any| {
@@ -115,16 +140,18 @@ impl<'a> QueryEntity<'a> {
     }
     ```
      */
-    pub fn get_component_mut<T: Any>(&self) -> eyre::Result<RefMut<T>> {
+    pub fn get_component_mut<T: Any>(&self) -> Result<RefMut<'_, T>> {
         let typeid = TypeId::of::<T>();
-        let components = self.entities.components.get(&typeid).ok_or(ComponentError::UnregisteredComponentError)?;
+        let components = self.entities.column_of(&typeid).ok_or(ComponentError::UnregisteredComponentError)?;
 
+        if self.id >= components.len() {
+            return Err(QueryError::OutOfBoundsIdError.into());
+        }
         let component = components.get(self.id)
-            .ok_or(QueryError::OutOfBoundsIdError)?
-            .as_ref()
             .ok_or(ComponentError::NonexistentComponentDataError)?;
 
-        let borrow = component.borrow_mut();
+        let borrow = column::try_borrow_mut(component, std::any::type_name::<T>(), self.id)?;
+        components.mark_changed(self.id, self.entities.tick());
 
         Ok(
             RefMut::map(borrow, |any| {
@@ -132,4 +159,61 @@ impl<'a> QueryEntity<'a> {
             })
         )
     }
-}
\ No newline at end of file
+
+    /**
+    Queues the insertion of a component into this entity. Like [Commands](struct.Commands.html), the change 
+    is only applied once the World applies it's commands, at the end of the system or stage, or with 
+    [World::apply_commands()](struct.World.html#method.apply_commands), so the query can keep being used in the meantime.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(i32);
+    struct Dead;
+    struct Corpse;
+
+    let mut world = World::new();
+    world.spawn().insert(Health(0));
+    world.spawn().insert(Health(8));
+
+    for entity in world.query().with_component_checked::<Health>().unwrap().run_entity().unwrap() {
+        if entity.get_component::<Health>().unwrap().0 <= 0 {
+            entity.remove::<Health>().unwrap();
+            entity.insert(Dead).unwrap();
+        }
+    }
+    world.apply_commands().unwrap();
+
+    assert!(world.entity_has::<Dead>(0));
+    assert!(!world.entity_has::<Health>(0));
+    assert!(world.entity_has::<Health>(1));
+    ```
+
+    Returns an error if the QueryEntity wasn't created by a query of the World, since there is nowhere to queue the change.
+     */
+    pub fn insert<T: Any>(&self, data: T) -> Result<()> {
+        self.commands()?.insert(self.id, data);
+        Ok(())
+    }
+
+    /**
+    Queues the removal of a component from this entity, see [QueryEntity::insert()].
+     */
+    pub fn remove<T: Any>(&self) -> Result<()> {
+        self.commands()?.remove::<T>(self.id);
+        Ok(())
+    }
+
+    /**
+    Queues the deletion of this entity, see [QueryEntity::insert()].
+     */
+    pub fn despawn(&self) -> Result<()> {
+        self.commands()?.despawn(self.id);
+        Ok(())
+    }
+
+    fn commands(&self) -> Result<Commands<'a>> {
+        let queue = self.commands.ok_or(QueryError::NoCommandQueueError(self.id))?;
+        Ok(Commands::with_entities(queue, self.entities))
+    }
+}
@@ -0,0 +1,139 @@
+//! # Query Result
+//!
+//! The query result module defines [QueryResult], returned by [Query::run_named()](super::Query::run_named).
+//! Unlike the vector of vectors returned by [Query::run()](super::Query::run), it stores the components of the
+//! query by type, so that reading them doesn't depend on the order the components were added to the query in.
+
+use std::{any::{Any, TypeId}, cell::{Ref, RefMut}, collections::HashMap};
+
+use super::{column, ComponentId, ComponentType, Entities, Entity, QueryError};
+use crate::error::Result;
+
+/**
+The result of a [Query](super::Query) run with [Query::run_named()](super::Query::run_named), which stores the
+components of every matching entity by type.
+
+```
+use sceller::prelude::*;
+
+struct Health(u32);
+struct Speed(f32);
+
+let mut world = World::new();
+world.spawn().insert(Health(10)).insert(Speed(2.0));
+world.spawn().insert(Health(3));
+world.spawn().insert(Health(7)).insert(Speed(0.5));
+
+let result = world.query()
+    .with_component::<Speed>()
+    .with_component::<Health>()
+    .run_named();
+
+assert_eq!(result.entities(), &[0, 2]);
+assert_eq!(result.column::<Health>().len(), 2);
+
+for mut health in result.iter_mut::<Health>() {
+    health.0 += 1;
+}
+let healths = result.iter::<Health>().map(|health| health.0).collect::<Vec<u32>>();
+assert_eq!(healths, vec![11, 8]);
+```
+ */
+#[derive(Debug)]
+pub struct QueryResult<'a> {
+    entities: &'a Entities,
+    ids: Vec<Entity>,
+    // the components of every type of the query, in the order of 'ids'
+    columns: HashMap<ComponentId, Vec<ComponentType<'a>>>,
+}
+
+impl<'a> QueryResult<'a> {
+    pub(super) fn new(entities: &'a Entities, ids: Vec<Entity>, columns: HashMap<ComponentId, Vec<ComponentType<'a>>>) -> Self {
+        Self { entities, ids, columns }
+    }
+
+    /// Returns the ids of the entities matching the query, in the same order as the components of every column.
+    pub fn entities(&self) -> &[Entity] {
+        &self.ids
+    }
+
+    /// Returns the number of entities matching the query.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns true if no entity matched the query.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns true if the component T was part of the query.
+    pub fn contains<T: Any>(&self) -> bool {
+        self.component_id::<T>().is_some()
+    }
+
+    /**
+    Returns the components of type T of every matching entity, in the order of [QueryResult::entities()].
+
+    Panics if T wasn't part of the query, see [QueryResult::column_checked()].
+     */
+    pub fn column<T: Any>(&self) -> &[ComponentType<'a>] {
+        self.column_checked::<T>().unwrap()
+    }
+
+    /**
+    Same as [QueryResult::column()], but returns an error naming the component if it wasn't part of the query.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+    struct Speed(f32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(10)).insert(Speed(2.0));
+
+    let result = world.query().with_component::<Health>().run_named();
+    assert!(result.column_checked::<Health>().is_ok());
+    assert!(result.column_checked::<Speed>().is_err());
+    ```
+     */
+    pub fn column_checked<T: Any>(&self) -> Result<&[ComponentType<'a>]> {
+        self.component_id::<T>()
+            .and_then(|id| self.columns.get(&id))
+            .map(|components| components.as_slice())
+            .ok_or_else(|| QueryError::MissingColumnError(std::any::type_name::<T>()).into())
+    }
+
+    /**
+    Returns an iterator borrowing the components of type T of every matching entity.
+
+    Panics if T wasn't part of the query, or when reaching a component that is borrowed mutably.
+     */
+    pub fn iter<T: Any>(&self) -> impl Iterator<Item = Ref<'a, T>> + '_ {
+        self.column::<T>().iter().zip(&self.ids).map(|(component, id)| {
+            Ref::map(column::borrow(*component, std::any::type_name::<T>(), *id), |any| any.downcast_ref::<T>().unwrap())
+        })
+    }
+
+    /**
+    Returns an iterator mutably borrowing the components of type T of every matching entity, which are marked as
+    changed during the current tick as they are borrowed.
+
+    Panics if T wasn't part of the query, or when reaching a component that is already borrowed.
+     */
+    pub fn iter_mut<T: Any>(&self) -> impl Iterator<Item = RefMut<'a, T>> + '_ {
+        let component_id = self.component_id::<T>();
+        self.column::<T>().iter().zip(&self.ids).map(move |(component, id)| {
+            let component = column::borrow_mut(*component, std::any::type_name::<T>(), *id);
+            // the column exists, since 'column' didn't panic
+            self.entities.mark_changed_id(component_id.unwrap(), *id);
+            RefMut::map(component, |any| any.downcast_mut::<T>().unwrap())
+        })
+    }
+
+    // the id of T, if it is part of the query
+    fn component_id<T: Any>(&self) -> Option<ComponentId> {
+        self.entities.component_id(&TypeId::of::<T>()).filter(|id| self.columns.contains_key(id))
+    }
+}
@@ -0,0 +1,131 @@
+use crate::recording::{Mutation, MutationLog, RecordedComponent, Recorder};
+use crate::error::Result;
+
+use super::{ComponentId, Entities, Entity};
+
+impl Entities {
+    pub(crate) fn recorder(&self) -> &Recorder {
+        &self.recorder
+    }
+
+    pub(crate) fn recorder_mut(&mut self) -> &mut Recorder {
+        &mut self.recorder
+    }
+
+    // starts a log holding the entities that already exist, unless one was started already
+    pub(crate) fn start_recording(&mut self) {
+        if self.recorder.log.is_none() {
+            let mut log = MutationLog::default();
+            self.record_state(&mut log);
+            self.recorder.log = Some(log);
+        }
+    }
+
+    // records a spawn for every living entity, followed by it's recorded components
+    fn record_state(&self, log: &mut MutationLog) {
+        for (entity, mask) in self.iter_ids() {
+            log.push(Mutation::Spawn(entity));
+            for id in (0..u128::BITS as usize).filter(|id| mask & 1 << id != 0) {
+                if let Some(component) = self.recorded_component(entity, id) {
+                    log.push(Mutation::Insert { entity, component });
+                }
+            }
+        }
+    }
+
+    // a copy of the component 'id' of 'entity', if it's type is recorded
+    fn recorded_component(&self, entity: Entity, id: ComponentId) -> Option<RecordedComponent> {
+        let column = self.column_by_id(id)?;
+        let type_id = column.component_type();
+        let replay = *self.recorder.types.get(&type_id)?;
+        let clone = self.types.get(&type_id)?.clone?;
+        let value = clone(&*column.get(entity)?.borrow());
+        Some(RecordedComponent::new(type_id, column.component_name(), value, clone, replay))
+    }
+
+    pub(crate) fn is_recording(&self) -> bool {
+        self.recorder.log.is_some()
+    }
+
+    fn record(&mut self, mutation: Mutation) {
+        if let Some(log) = self.recorder.log.as_mut() {
+            log.push(mutation);
+        }
+    }
+
+    // records the component 'id' stored in 'entity', when it replaces one the entity already had
+    pub(super) fn record_insert(&mut self, entity: Entity, id: ComponentId) {
+        if let Some(component) = self.recorded_component(entity, id) {
+            self.record(Mutation::Insert { entity, component });
+        }
+    }
+
+    // records the changes made to 'entity' when it's mask went from 'old' to 'new'
+    pub(super) fn record_mask(&mut self, entity: Entity, old: u128, new: u128) {
+        if new == 0 {
+            self.record(Mutation::Despawn(entity));
+            return;
+        }
+        if old == 0 {
+            self.record(Mutation::Spawn(entity));
+        }
+
+        let changed = old ^ new;
+        for id in (0..u128::BITS as usize).filter(|id| changed & 1 << id != 0) {
+            if new & 1 << id != 0 {
+                self.record_insert(entity, id);
+                continue;
+            }
+            let Some(column) = self.column_by_id(id) else { continue };
+            let (type_id, name) = (column.component_type(), column.component_name());
+            if self.recorder.types.contains_key(&type_id) {
+                self.record(Mutation::Remove { entity, type_id, name });
+            }
+        }
+    }
+
+    // records the despawn of every living entity, before they are all cleared
+    pub(super) fn record_clear(&mut self) {
+        let living = self.iter_ids().map(|(entity, _)| entity).collect::<Vec<Entity>>();
+        for entity in living {
+            self.record(Mutation::Despawn(entity));
+        }
+    }
+
+    /*
+        moves the recorder of 'other' into these entities, which replace them, recording the despawn of the
+        entities of 'other' and the spawn of these ones
+    */
+    pub(crate) fn take_recorder_from(&mut self, other: &mut Entities) {
+        other.record_clear();
+        self.recorder = std::mem::take(&mut other.recorder);
+        if let Some(mut log) = self.recorder.log.take() {
+            self.record_state(&mut log);
+            self.recorder.log = Some(log);
+        }
+    }
+
+    // applies a mutation of a log, without running hooks
+    pub(crate) fn apply_mutation(&mut self, mutation: &Mutation) -> Result<()> {
+        match mutation {
+            Mutation::Spawn(entity) => {
+                self.create_entity_at(*entity);
+            },
+            Mutation::Insert { entity, component } => {
+                if *entity >= self.map.len() {
+                    self.create_entity_at(*entity);
+                }
+                component.replay(self, *entity)?;
+            },
+            Mutation::Remove { entity, type_id, .. } => {
+                self.swap_component(*entity, *type_id, None);
+            },
+            Mutation::Despawn(entity) => {
+                if self.map.get(*entity).is_some_and(|mask| *mask != 0) {
+                    self.despawn_checked(*entity)?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+//! # Shared components
+//!
+//! A [Shared] handle stores a single component value that any number of entities can have, like the sprite of
+//! thousands of identical tiles. The column of the component then only stores a pointer to the value for each of
+//! them, and every query, [Entities::try_get_component()] or [QueryEntity](super::QueryEntity) finds the shared value as
+//! if the entity owned it.
+//!
+//! Mutating a shared component through any of the entities changes it for all of them.
+
+use std::{any::Any, cell::{Ref, RefCell, RefMut}, rc::Rc};
+
+use super::{column::Slot, ComponentError, Entities, Entity};
+use crate::{error::Result, world::World};
+
+/**
+A handle to a component value shared by many entities, inserted with [Entities::insert_shared()] or
+[World::insert_shared()]. Cloning the handle is cheap, and never clones the value.
+
+The shared components of a despawned entity are taken back as Shared handles with
+[DespawnedEntity::take_shared()](super::DespawnedEntity::take_shared), and [World::take_component()] fails unless the entity is the last holder of the value.
+ */
+pub struct Shared<T>(Rc<RefCell<T>>);
+
+impl<T> Shared<T> {
+    /// Creates a handle to a new shared value.
+    pub fn new(value: T) -> Self {
+        Self(Rc::new(RefCell::new(value)))
+    }
+
+    /// Borrows the shared value. Panics if it is borrowed mutably, by a query for instance.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.0.borrow()
+    }
+
+    /// Borrows the shared value mutably, changing it for every entity sharing it. Panics if it is already borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
+        self.0.borrow_mut()
+    }
+
+    /// Returns true if both handles point to the same value.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Returns the number of handles and entities holding the value, including this handle.
+    pub fn holders(&self) -> usize {
+        Rc::strong_count(&self.0)
+    }
+
+    /// Returns the shared value if this handle is it's last holder, or gives the handle back otherwise.
+    pub fn try_into_inner(self) -> std::result::Result<T, Self> {
+        Rc::try_unwrap(self.0).map(RefCell::into_inner).map_err(Self)
+    }
+
+    pub(crate) fn from_rc(value: Rc<RefCell<T>>) -> Self {
+        Self(value)
+    }
+
+    pub(crate) fn into_rc(self) -> Rc<RefCell<T>> {
+        self.0
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Shared").field(&self.0).finish()
+    }
+}
+
+impl Entities {
+    /**
+    Gives the entity at 'map_index' the component value of a [Shared] handle, replacing the component it had.
+    Panics if the component can't be inserted, see [Entities::insert_shared_checked()].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Sprite(&'static str);
+
+    let mut ents = Entities::default();
+    let grass = Shared::new(Sprite("grass.png"));
+    for tile in 0..3 {
+        ents.create_entity();
+        ents.insert_shared(tile, &grass);
+    }
+
+    // the value is stored once, but found on every tile
+    assert_eq!(grass.holders(), 4);
+    grass.borrow_mut().0 = "snow.png";
+    for tile in 0..3 {
+        assert_eq!(ents.try_get_component::<Sprite>(tile).unwrap().0, "snow.png");
+    }
+    ```
+     */
+    pub fn insert_shared<T: Any>(&mut self, map_index: Entity, shared: &Shared<T>) {
+        self.insert_shared_checked(map_index, shared).unwrap()
+    }
+
+    /**
+    Same as [Entities::insert_shared()], but returns an error instead of panicking if the entity doesn't exist,
+    if the component is unique and another entity has it, if it is zero sized, since zero sized components take
+    no memory anyway, or if it is part of a [group](Entities::register_group), whose components are stored in order.
+     */
+    pub fn insert_shared_checked<T: Any>(&mut self, map_index: Entity, shared: &Shared<T>) -> Result<()> {
+        if std::mem::size_of::<T>() == 0 {
+            return Err(ComponentError::SharedTagError(std::any::type_name::<T>()).into());
+        }
+        let bitmask = self.register_if_new::<T>()?;
+        if self.is_grouped(bitmask) {
+            return Err(ComponentError::SharedGroupedComponentError(std::any::type_name::<T>()).into());
+        }
+        self.store_slot(Slot::Shared(shared.0.clone()), map_index)?;
+        self.set_mask(map_index, self.map[map_index] | bitmask);
+        Ok(())
+    }
+}
+
+// Shared stuff
+impl World {
+    /**
+    Gives an entity the component value of a [Shared] handle, replacing the component it had.
+    Panics if the component can't be inserted.
+
+    See [Entities::insert_shared()] for more information.
+     */
+    pub fn insert_shared<T: Any>(&mut self, entity: Entity, shared: &Shared<T>) {
+        self.insert_shared_checked(entity, shared).unwrap()
+    }
+
+    /**
+    Gives an entity the component value of a [Shared] handle, replacing the component it had.
+
+    See [Entities::insert_shared_checked()] for more information.
+     */
+    pub fn insert_shared_checked<T: Any>(&mut self, entity: Entity, shared: &Shared<T>) -> Result<()> {
+        self.step(|world| world.entities.insert_shared_checked(entity, shared))
+    }
+}
@@ -0,0 +1,250 @@
+use std::any::{Any, TypeId};
+
+use super::{short_type_name, CloneFn, Entities};
+#[cfg(feature = "inspector")]
+use super::{ComponentCell, Entity};
+#[cfg(feature = "serde")]
+use crate::error::Result;
+
+/// Serializes a component into a RON string without knowing it's type, see [TypeRegistration].
+#[cfg(feature = "serde")]
+pub type SerializeFn = fn(&dyn Any) -> Result<String>;
+/// Deserializes a component from a RON string without knowing it's type, see [TypeRegistration].
+#[cfg(feature = "serde")]
+pub type DeserializeFn = fn(&str) -> Result<Box<dyn Any>>;
+/// Converts a component into a JSON value without knowing it's type, see [TypeRegistration].
+#[cfg(feature = "inspector")]
+pub type ToJsonFn = fn(&dyn Any) -> Result<serde_json::Value>;
+/// Overwrites a component with one deserialized from a JSON value without knowing it's type, see [TypeRegistration].
+#[cfg(feature = "inspector")]
+pub type FromJsonFn = fn(&mut dyn Any, serde_json::Value) -> Result<()>;
+/// Inserts a component deserialized from a JSON value into an entity without knowing it's type, see [TypeRegistration].
+#[cfg(feature = "scripting")]
+pub type InsertJsonFn = fn(&mut Entities, Entity, serde_json::Value) -> Result<()>;
+
+/**
+What the ECS knows about a registered component type: it's name and TypeId, along with the functions
+cloning and (de)serializing values of the type without knowing it, if they were registered.
+ */
+#[derive(Debug, Clone)]
+pub struct TypeRegistration {
+    pub name: &'static str,
+    pub type_id: TypeId,
+    /// Clones a value of the type, set by [TypeRegistry::register_clone()].
+    pub clone: Option<CloneFn>,
+    /// Serializes a value of the type into a RON string, set by [TypeRegistry::register_serde()].
+    #[cfg(feature = "serde")]
+    pub serialize: Option<SerializeFn>,
+    /// Deserializes a value of the type from a RON string, set by [TypeRegistry::register_serde()].
+    #[cfg(feature = "serde")]
+    pub deserialize: Option<DeserializeFn>,
+    /// Converts a value of the type into JSON, set by [TypeRegistry::register_serde()].
+    #[cfg(feature = "inspector")]
+    pub to_json: Option<ToJsonFn>,
+    /// Overwrites a value of the type with one deserialized from JSON, set by [TypeRegistry::register_serde()].
+    #[cfg(feature = "inspector")]
+    pub from_json: Option<FromJsonFn>,
+    /// Inserts a value of the type deserialized from JSON into an entity, set by [TypeRegistry::register_serde()].
+    #[cfg(feature = "scripting")]
+    pub insert_json: Option<InsertJsonFn>,
+}
+
+impl TypeRegistration {
+    fn new<T: Any>() -> Self {
+        Self {
+            name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            clone: None,
+            #[cfg(feature = "serde")]
+            serialize: None,
+            #[cfg(feature = "serde")]
+            deserialize: None,
+            #[cfg(feature = "inspector")]
+            to_json: None,
+            #[cfg(feature = "inspector")]
+            from_json: None,
+            #[cfg(feature = "scripting")]
+            insert_json: None,
+        }
+    }
+
+    /// Returns the name of the type without the paths of it's modules, e.g. 'Vec<Health>'.
+    pub fn short_name(&self) -> String {
+        short_type_name(self.name)
+    }
+}
+
+/**
+Stores a [TypeRegistration] for every component type registered in an [Entities], whether it was
+registered by hand or when it was first inserted. The functions of a type are added to it's registration by
+[World::journal_component()](../struct.World.html#method.journal_component) and
+[World::register_serde_component()](../struct.World.html#method.register_serde_component), or directly
+through [World::type_registry_mut()](../struct.World.html#method.type_registry_mut).
+
+```
+use sceller::prelude::*;
+use std::any::TypeId;
+
+#[derive(Clone, PartialEq, Debug)]
+struct Health(u32);
+
+let mut world = World::new();
+world.spawn().insert(Health(3));
+world.type_registry_mut().register_clone::<Health>();
+
+let registration = world.type_registry().get_by_name("Health").unwrap();
+assert_eq!(registration.type_id, TypeId::of::<Health>());
+
+let health = world.get::<&Health>(0).unwrap();
+let clone = (registration.clone.unwrap())(&*health);
+assert_eq!(clone.downcast_ref::<Health>(), Some(&Health(3)));
+```
+ */
+#[derive(Debug, Clone, Default)]
+pub struct TypeRegistry {
+    types: Vec<TypeRegistration>,
+}
+
+impl TypeRegistry {
+    /// Registers the name and TypeId of T. Registering the same type twice does nothing.
+    pub fn register<T: Any>(&mut self) -> &mut TypeRegistration {
+        let type_id = TypeId::of::<T>();
+        match self.types.iter().position(|registration| registration.type_id == type_id) {
+            Some(index) => &mut self.types[index],
+            None => {
+                self.types.push(TypeRegistration::new::<T>());
+                self.types.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Registers T along with the function cloning it's values.
+    pub fn register_clone<T: Any + Clone>(&mut self) {
+        self.register::<T>().clone = Some(clone_value::<T>);
+    }
+
+    /// Registers T along with the functions serializing and deserializing it's values.
+    #[cfg(feature = "serde")]
+    pub fn register_serde<T>(&mut self)
+    where
+        T: Any + serde::Serialize + serde::de::DeserializeOwned
+    {
+        let registration = self.register::<T>();
+        registration.serialize = Some(serialize_value::<T>);
+        registration.deserialize = Some(deserialize_value::<T>);
+        #[cfg(feature = "inspector")]
+        {
+            registration.to_json = Some(value_to_json::<T>);
+            registration.from_json = Some(value_from_json::<T>);
+        }
+        #[cfg(feature = "scripting")]
+        {
+            registration.insert_json = Some(insert_from_json::<T>);
+        }
+    }
+
+    /// Returns the registration of the type with this TypeId.
+    pub fn get(&self, type_id: &TypeId) -> Option<&TypeRegistration> {
+        self.types.iter().find(|registration| registration.type_id == *type_id)
+    }
+
+    /// Returns the registration of a type from it's name, or from the same name without it's module paths.
+    pub fn get_by_name(&self, name: &str) -> Option<&TypeRegistration> {
+        self.types.iter().find(|registration| registration.name == name)
+            .or_else(|| self.types.iter().find(|registration| registration.short_name() == name))
+    }
+
+    /// Returns the name of the type with this TypeId, if it was registered.
+    pub fn name_of(&self, type_id: &TypeId) -> Option<&'static str> {
+        self.get(type_id).map(|registration| registration.name)
+    }
+
+    /// Returns true if the type with this TypeId was registered.
+    pub fn contains(&self, type_id: &TypeId) -> bool {
+        self.get(type_id).is_some()
+    }
+
+    /// Returns an iterator over the registration of every type, in the order they were registered.
+    pub fn iter(&self) -> std::slice::Iter<'_, TypeRegistration> {
+        self.types.iter()
+    }
+
+    /// Returns the number of registered types.
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Returns true if no type was registered.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+}
+
+fn clone_value<T: Any + Clone>(value: &dyn Any) -> Box<dyn Any> {
+    Box::new(value.downcast_ref::<T>().expect("value given to the clone function of another type").clone())
+}
+
+#[cfg(feature = "serde")]
+fn serialize_value<T: Any + serde::Serialize>(value: &dyn Any) -> Result<String> {
+    let value = value.downcast_ref::<T>().expect("value given to the serialize function of another type");
+    Ok(ron::to_string(value)?)
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_value<T: Any + serde::de::DeserializeOwned>(value: &str) -> Result<Box<dyn Any>> {
+    Ok(Box::new(ron::from_str::<T>(value)?))
+}
+
+#[cfg(feature = "inspector")]
+fn value_to_json<T: Any + serde::Serialize>(value: &dyn Any) -> Result<serde_json::Value> {
+    let value = value.downcast_ref::<T>().expect("value given to the JSON function of another type");
+    Ok(serde_json::to_value(value)?)
+}
+
+#[cfg(feature = "inspector")]
+fn value_from_json<T: Any + serde::de::DeserializeOwned>(value: &mut dyn Any, json: serde_json::Value) -> Result<()> {
+    let value = value.downcast_mut::<T>().expect("value given to the JSON function of another type");
+    *value = serde_json::from_value(json)?;
+    Ok(())
+}
+
+#[cfg(feature = "scripting")]
+fn insert_from_json<T: Any + serde::de::DeserializeOwned>(entities: &mut Entities, entity: Entity, json: serde_json::Value) -> Result<()> {
+    let value = serde_json::from_value::<T>(json)?;
+    entities.insert_component_into_entity_by_id_checked(value, entity).map(|_| ())
+}
+
+impl Entities {
+    /// Returns the registration of every component type registered in the ECS.
+    pub fn type_registry(&self) -> &TypeRegistry {
+        &self.types
+    }
+
+    /// Returns the registration of every component type, to add functions to them.
+    pub fn type_registry_mut(&mut self) -> &mut TypeRegistry {
+        &mut self.types
+    }
+
+    // moves the registrations of 'other' into these entities, keeping the types only registered here
+    pub(crate) fn take_types_from(&mut self, other: &mut Entities) {
+        let mut types = std::mem::take(&mut other.types);
+        for registration in self.types.types.drain(..) {
+            if !types.contains(&registration.type_id) {
+                types.types.push(registration);
+            }
+        }
+        self.types = types;
+    }
+
+    // the TypeId, name and value of every component of an entity, in the order of their ids
+    #[cfg(feature = "inspector")]
+    pub(crate) fn components_of(&self, entity: Entity) -> Vec<(TypeId, &'static str, ComponentCell<'_>)> {
+        (0..self.components.len())
+            .filter(|id| self.has_component_id(*id, entity))
+            .filter_map(|id| {
+                let column = self.column_by_id(id)?;
+                Some((column.component_type(), column.component_name(), column.get(entity)?))
+            })
+            .collect()
+    }
+}
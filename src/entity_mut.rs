@@ -0,0 +1,90 @@
+//! # Entity Mut
+//!
+//! The entity mut module adds [EntityMut], a mutable handle to a single entity of a World returned by
+//! [World::entity_mut()], to change it's components without repeating it's id in every call.
+//! Unlike the [Commands](crate::commands::Commands), the changes are applied right away, and the
+//! components replaced or removed are given back.
+
+use std::any::Any;
+
+use crate::{world::World, entities::{ComponentError, DespawnedEntity, Entity}};
+use crate::error::Result;
+
+/**
+A mutable handle to a living entity of a World.
+
+```
+use sceller::prelude::*;
+
+#[derive(Debug, PartialEq)]
+struct Weapon(&'static str);
+
+let mut world = World::new();
+let knight = world.spawn().insert(Weapon("sword")).current_entity();
+
+let mut entity = world.entity_mut(knight);
+let old = entity.insert(Weapon("axe"));
+assert_eq!(old, Some(Weapon("sword")));
+assert_eq!(entity.remove::<Weapon>(), Some(Weapon("axe")));
+assert!(!entity.has::<Weapon>());
+```
+ */
+pub struct EntityMut<'w> {
+    world: &'w mut World,
+    id: Entity,
+}
+
+impl<'w> EntityMut<'w> {
+    /// Returns the id of the entity.
+    pub fn id(&self) -> Entity {
+        self.id
+    }
+
+    /// Returns true if the entity has a component of type T.
+    pub fn has<T: Any>(&self) -> bool {
+        self.world.entity_has::<T>(self.id)
+    }
+
+    /// Inserts a component into the entity, and returns the component it replaced if it already had one.
+    pub fn insert<T: Any>(&mut self, data: T) -> Option<T> {
+        self.insert_checked(data).unwrap()
+    }
+
+    /// Same as [EntityMut::insert()], but returns an error instead of panicking if the component can't be inserted.
+    pub fn insert_checked<T: Any>(&mut self, data: T) -> Result<Option<T>> {
+        self.world.insert_component_into_entity_checked(data, self.id)
+    }
+
+    /// Removes the component T from the entity and returns it, or None if the entity doesn't have one.
+    pub fn remove<T: Any>(&mut self) -> Option<T> {
+        match self.has::<T>() {
+            true => self.world.take_component::<T>(self.id).ok(),
+            false => None,
+        }
+    }
+
+    /// Despawns the entity and returns it's components.
+    pub fn despawn(self) -> DespawnedEntity {
+        self.world.despawn(self.id)
+    }
+}
+
+// EntityMut stuff
+impl World {
+    /**
+    Returns an [EntityMut] for the entity 'id'. Panics if the entity isn't alive.
+     */
+    pub fn entity_mut(&mut self, id: Entity) -> EntityMut<'_> {
+        self.entity_mut_checked(id).unwrap()
+    }
+
+    /**
+    Returns an [EntityMut] for the entity 'id', or an error if the entity isn't alive, see [World::contains()].
+     */
+    pub fn entity_mut_checked(&mut self, id: Entity) -> Result<EntityMut<'_>> {
+        if !self.contains(id) {
+            return Err(ComponentError::NonexistentEntity.into());
+        }
+        Ok(EntityMut { world: self, id })
+    }
+}
@@ -0,0 +1,140 @@
+//! # Error
+//!
+//! The error module contains [Error], the error type returned by every fallible function in the crate,
+//! which wraps the error enum of the module it came from so that specific failures can be matched on.
+//!
+//! It implements std::error::Error, so it can be converted into any other error type that
+//! accepts one, such as eyre's Report or anyhow's Error, with the '?' operator. Where those crates
+//! aren't available, like in a browser, [Error::report()] and [ErrorReport] format an error along with
+//! the errors that caused it.
+
+use std::fmt::Display;
+
+use crate::{
+    assets::AssetError,
+    entities::{ComponentError, QueryError},
+    hierarchy::HierarchyError,
+    resources::ResourcesError,
+    schedule::ScheduleError,
+    state::StateError,
+    system::SystemError,
+};
+
+/**
+A Result whose error type defaults to [Error].
+ */
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/**
+The error type of the crate, wrapping the error of every module.
+
+```
+use sceller::prelude::*;
+
+struct Health(u8);
+impl Resource for Health {}
+
+let world = World::new();
+
+let error = world.get_resource::<Health>().err().unwrap();
+assert!(matches!(error, Error::Resources(ResourcesError::NonexistentResourceError)));
+```
+ */
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Component(#[from] ComponentError),
+    #[error(transparent)]
+    Query(#[from] QueryError),
+    #[error(transparent)]
+    Resources(#[from] ResourcesError),
+    #[error(transparent)]
+    System(#[from] SystemError),
+    #[error(transparent)]
+    Schedule(#[from] ScheduleError),
+    #[error(transparent)]
+    State(#[from] StateError),
+    #[error(transparent)]
+    Hierarchy(#[from] HierarchyError),
+    #[error(transparent)]
+    Asset(#[from] AssetError),
+    #[cfg(feature = "serde")]
+    #[error(transparent)]
+    Serialization(#[from] crate::serialization::SerializationError),
+    #[cfg(feature = "scripting")]
+    #[error(transparent)]
+    Script(#[from] crate::scripting::ScriptError),
+}
+
+impl Error {
+    /**
+    Returns a [Display]able report of the error, followed by every error that caused it, see [ErrorReport].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    impl Resource for Health {}
+
+    let world = World::new();
+
+    let error = world.get_resource::<Health>().err().unwrap();
+    assert_eq!(error.report().to_string(), error.to_string());
+    ```
+     */
+    pub fn report(&self) -> ErrorReport<'_> {
+        ErrorReport::new(self)
+    }
+}
+
+/**
+Formats an error with every error that caused it, one per line, without depending on an error reporting
+crate. It works with any std::error::Error, like the errors given to the
+[error handler](crate::error_handler) of a World.
+
+```
+use sceller::prelude::*;
+
+#[derive(Debug)]
+struct ParseError;
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid level file")
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&ResourcesError::NonexistentResourceError)
+    }
+}
+
+let report = ErrorReport::new(&ParseError).to_string();
+assert_eq!(report.lines().count(), 2);
+assert!(report.starts_with("Invalid level file\n  caused by: "));
+```
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorReport<'a> {
+    error: &'a dyn std::error::Error,
+}
+
+impl<'a> ErrorReport<'a> {
+    /// Creates a report of 'error' and it's sources.
+    pub fn new(error: &'a dyn std::error::Error) -> Self {
+        Self { error }
+    }
+}
+
+impl Display for ErrorReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)?;
+        let mut source = self.error.source();
+        while let Some(error) = source {
+            write!(f, "\n  caused by: {}", error)?;
+            source = error.source();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+//! # Error Handler
+//!
+//! The error handler module allows systems to return a `Result<(), E>`, for any error type that can be
+//! boxed into a std::error::Error, like the crate's [Error](crate::Error), eyre's Report or anyhow's Error.
+//! This lets systems use the '?' operator instead of unwrapping every fallible call.
+//!
+//! The errors returned by systems are given to the World's error handler, wherever the system is run from:
+//! [World::run_system()](struct.World.html#method.run_system), the schedule, or the systems of a state.
+//! The handler is set with [World::set_error_handler()], and defaults to [panic_on_error()].
+
+use std::cell::RefMut;
+
+use crate::{world::World, resources::Resource};
+
+/**
+A function called with every error returned by a system, see [World::set_error_handler()].
+ */
+pub type ErrorHandler = fn(&World, SystemFailure);
+
+/**
+An error returned by a system, along with the name of the system that returned it.
+ */
+#[derive(Debug)]
+pub struct SystemFailure {
+    pub system: &'static str,
+    pub error: Box<dyn std::error::Error>,
+}
+
+impl std::fmt::Display for SystemFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "System {} failed: {}", self.system, self.error)
+    }
+}
+
+/**
+A resource storing the errors returned by systems when the error handler is [collect_errors()].
+ */
+#[derive(Debug, Default)]
+pub struct SystemErrors(pub Vec<SystemFailure>);
+
+impl Resource for SystemErrors {}
+
+/// The default error handler, which panics with the error and the name of the system that returned it.
+pub fn panic_on_error(_world: &World, failure: SystemFailure) {
+    panic!("{}", failure);
+}
+
+/// An error handler which prints the error and the name of the system that returned it to stderr.
+pub fn log_error(_world: &World, failure: SystemFailure) {
+    eprintln!("{}", failure);
+}
+
+/**
+An error handler which pushes the errors into the [SystemErrors] resource, so they can be handled later
+by another system. Falls back to [log_error()] if the World has no SystemErrors resource.
+ */
+pub fn collect_errors(world: &World, failure: SystemFailure) {
+    match world.get_resource_mut::<SystemErrors>() {
+        Ok(mut errors) => errors.0.push(failure),
+        Err(_) => log_error(world, failure),
+    }
+}
+
+// Error handler stuff
+impl World {
+    /**
+    Sets the function called with the errors returned by systems, replacing [panic_on_error()].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Level(u32);
+    impl Resource for Level {}
+
+    fn load_level(level: Res<Level>) -> eyre::Result<()> {
+        if level.0 > 3 {
+            eyre::bail!("level {} doesn't exist", level.0);
+        }
+        Ok(())
+    }
+
+    let mut world = World::new();
+    world.insert_resource(Level(5));
+    world.init_resource::<SystemErrors>();
+    world.set_error_handler(collect_errors);
+
+    world.run_system(load_level);
+
+    let errors = world.get_resource::<SystemErrors>().unwrap();
+    assert_eq!(errors.0[0].error.to_string(), "level 5 doesn't exist");
+    assert!(errors.0[0].system.contains("load_level"));
+    ```
+     */
+    pub fn set_error_handler(&mut self, handler: ErrorHandler) -> &mut Self {
+        self.error_handler = Some(handler);
+        self
+    }
+
+    /// Returns the function called with the errors returned by systems.
+    pub fn error_handler(&self) -> ErrorHandler {
+        self.error_handler.unwrap_or(panic_on_error)
+    }
+
+    /// Gives an error returned by a system to the World's error handler.
+    pub fn handle_system_error(&self, system: &'static str, error: Box<dyn std::error::Error>) {
+        (self.error_handler())(self, SystemFailure { system, error })
+    }
+
+    /// Returns the errors collected by [collect_errors()], or None if there is no [SystemErrors] resource.
+    pub fn system_errors(&self) -> Option<RefMut<'_, SystemErrors>> {
+        self.get_resource_mut::<SystemErrors>().ok()
+    }
+}
@@ -0,0 +1,327 @@
+//! # Hierarchy
+//!
+//! The hierarchy module adds the [Parent] and [Children] components, which link entities together
+//! into trees. They are managed by [World::set_parent()](struct.World.html#method.set_parent) and
+//! [World::despawn_recursive()](struct.World.html#method.despawn_recursive) so that both sides of
+//! a link always agree, and should not be inserted by hand.
+//!
+//! [Parent] is a [relationship](crate::relationship) of every World, so despawning an entity any other way
+//! also removes it from the children of it's parent, and removes the Parent of it's children, which become
+//! roots of their own trees, rather than leaving it's id behind for the next entity to take it.
+//!
+//! The [propagate()] system walks these trees from the roots down, to compute a value for every entity
+//! from the value of it's parent, such as the global transforms of entities from their local transforms.
+
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+
+use crate::{world::World, entities::{Entity, QueryEntity}, commands::Commands, system::{Access, AccessKind, IntoSystem, ParamAccess}};
+use crate::error::Result;
+
+/// A component storing the parent of an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parent(pub Entity);
+
+/// A component storing the children of an entity, in the order they were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Children(Vec<Entity>);
+
+impl Children {
+    /// Returns an iterator over the ids of the children.
+    pub fn iter(&self) -> std::slice::Iter<'_, Entity> {
+        self.0.iter()
+    }
+
+    /// Returns the number of children.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no children.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns true if 'entity' is one of the children.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.0.contains(&entity)
+    }
+
+    // replaces the id of every child, used when the entities are spawned from a scene or merged into another World
+    pub(crate) fn map_ids(&mut self, f: impl Fn(Entity) -> Entity) {
+        self.0.iter_mut().for_each(|child| *child = f(*child));
+    }
+}
+
+// Hierarchy stuff
+impl World {
+    /**
+    Makes 'child' a child of 'parent', removing it from the children of it's previous parent if it had one.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Name(&'static str);
+
+    let mut world = World::new();
+    let ship = world.spawn().insert(Name("Ship")).current_entity();
+    let cannon = world.spawn().insert(Name("Cannon")).current_entity();
+    let barrel = world.spawn().insert(Name("Barrel")).current_entity();
+
+    world.set_parent(cannon, ship);
+    world.set_parent(barrel, cannon);
+
+    assert_eq!(world.parent(barrel), Some(cannon));
+    assert_eq!(world.descendants(ship), vec![cannon, barrel]);
+
+    world.despawn_recursive(ship);
+    assert_eq!(world.query().auto::<Name>().len(), 0);
+    ```
+
+    Panics if either entity doesn't exist, or if 'parent' is 'child' or one of it's descendants.
+     */
+    pub fn set_parent(&mut self, child: Entity, parent: Entity) -> &mut Self {
+        self.set_parent_checked(child, parent).unwrap()
+    }
+
+    /**
+    Makes 'child' a child of 'parent', removing it from the children of it's previous parent if it had one.
+
+    Returns an error if either entity doesn't exist, or if 'parent' is 'child' or one of it's descendants.
+     */
+    pub fn set_parent_checked(&mut self, child: Entity, parent: Entity) -> Result<&mut Self> {
+        for entity in [child, parent] {
+            if !self.entities.is_alive(entity) {
+                return Err(HierarchyError::NonexistentEntityError(entity).into());
+            }
+        }
+        if child == parent || self.descendants(child).contains(&parent) {
+            return Err(HierarchyError::CyclicHierarchyError { child, parent }.into());
+        }
+
+        self.step(|world| -> Result<()> {
+            world.remove_parent_checked(child)?;
+
+            let mut children = world.entities.take_component::<Children>(parent).unwrap_or_default();
+            children.0.push(child);
+            world.entities.insert_component_into_entity_by_id_checked(children, parent)?;
+            world.entities.insert_component_into_entity_by_id_checked(Parent(parent), child).map(|_| ())
+        })?;
+
+        Ok(self)
+    }
+
+    /**
+    Removes the parent of 'child', making it the root of it's own hierarchy. Does nothing
+    if the entity has no parent.
+
+    Panics if the entity doesn't exist.
+     */
+    pub fn remove_parent(&mut self, child: Entity) -> &mut Self {
+        self.remove_parent_checked(child).unwrap()
+    }
+
+    /**
+    Removes the parent of 'child', making it the root of it's own hierarchy. Does nothing
+    if the entity has no parent.
+
+    Returns an error if the entity doesn't exist.
+     */
+    pub fn remove_parent_checked(&mut self, child: Entity) -> Result<&mut Self> {
+        if !self.entities.is_alive(child) {
+            return Err(HierarchyError::NonexistentEntityError(child).into());
+        }
+
+        self.step(|world| {
+            if let Ok(Parent(parent)) = world.entities.take_component::<Parent>(child) {
+                remove_child(world, child, parent);
+            }
+        });
+
+        Ok(self)
+    }
+
+    /**
+    Returns the parent of an entity, if it has one.
+     */
+    pub fn parent(&self, child: Entity) -> Option<Entity> {
+        if !self.entities.has_component::<Parent>(child) {
+            return None;
+        }
+        QueryEntity::new(child, &self.entities).get_component::<Parent>().ok().map(|parent| parent.0)
+    }
+
+    /**
+    Returns the ids of the children of an entity. Only the entities whose [Parent] is still 'parent' are returned,
+    in case [Children] was changed by hand.
+     */
+    pub fn children(&self, parent: Entity) -> Vec<Entity> {
+        if !self.entities.has_component::<Children>(parent) {
+            return Vec::new();
+        }
+        QueryEntity::new(parent, &self.entities).get_component::<Children>()
+            .map(|children| children.iter().copied().filter(|child| self.parent(*child) == Some(parent)).collect())
+            .unwrap_or_default()
+    }
+
+    /**
+    Returns the ids of every descendant of an entity (children, grandchildren...), depth first and
+    in the order the children were added. The entity itself isn't included, and neither are the ids
+    of dead entities or of entities whose [Parent] isn't the entity they are listed under.
+     */
+    pub fn descendants(&self, entity: Entity) -> Vec<Entity> {
+        let mut descendants = Vec::new();
+        let mut stack = self.children(entity);
+        stack.reverse();
+
+        while let Some(next) = stack.pop() {
+            descendants.push(next);
+            stack.extend(self.children(next).into_iter().rev());
+        }
+
+        descendants
+    }
+
+    /**
+    Despawns an entity along with all of it's descendants, and removes it from the children of it's parent.
+
+    Panics if the entity doesn't exist.
+     */
+    pub fn despawn_recursive(&mut self, entity: Entity) {
+        self.despawn_recursive_checked(entity).unwrap()
+    }
+
+    /**
+    Despawns an entity along with all of it's descendants, and removes it from the children of it's parent.
+
+    Returns an error if the entity doesn't exist.
+     */
+    pub fn despawn_recursive_checked(&mut self, entity: Entity) -> Result<()> {
+        self.step(|world| {
+            world.remove_parent_checked(entity)?;
+
+            let descendants = world.descendants(entity);
+            world.entities.despawn_checked(entity)?;
+            for descendant in descendants {
+                world.entities.despawn_checked(descendant)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+// removes 'child' from the children of 'parent', once 'parent' is no longer it's Parent
+pub(crate) fn remove_child(world: &mut World, child: Entity, parent: Entity) {
+    if !world.get::<&Children>(parent).is_ok_and(|children| children.contains(child)) {
+        return;
+    }
+    if let Ok(mut children) = world.entities.take_component::<Children>(parent) {
+        children.0.retain(|&c| c != child);
+        if !children.is_empty() {
+            // the parent was just found holding it's children, so they can be put back
+            let _ = world.entities.insert_component_into_entity_by_id_checked(children, parent);
+        }
+    }
+}
+
+/**
+Returns a system computing the component 'G' of every entity with the component 'L', by walking the
+hierarchy from the roots down. 'combine' is given the 'G' computed for the parent of the entity, or None
+for roots, along with the entity's own 'L'. 'G' is inserted into the entities which don't have it yet.
+
+Entities whose parent doesn't have an 'L' are roots of their own tree, as are their children.
+
+```
+use sceller::prelude::*;
+
+struct LocalTransform(i32);
+struct GlobalTransform(i32);
+
+fn combine(parent: Option<&GlobalTransform>, local: &LocalTransform) -> GlobalTransform {
+    GlobalTransform(parent.map_or(0, |parent| parent.0) + local.0)
+}
+
+let mut world = World::new();
+let ship = world.spawn().insert(LocalTransform(10)).current_entity();
+let cannon = world.spawn().insert(LocalTransform(2)).current_entity();
+let barrel = world.spawn().insert(LocalTransform(1)).current_entity();
+world.set_parent(cannon, ship);
+world.set_parent(barrel, cannon);
+
+world.add_system(propagate(combine));
+world.run_schedule();
+
+assert_eq!(world.get::<&GlobalTransform>(barrel).unwrap().0, 13);
+```
+ */
+pub fn propagate<L: Any, G: Any>(combine: fn(Option<&G>, &L) -> G) -> Propagate<L, G> {
+    Propagate { combine, phantom: PhantomData }
+}
+
+/**
+The system returned by [propagate()].
+ */
+pub struct Propagate<L, G> {
+    combine: fn(Option<&G>, &L) -> G,
+    phantom: PhantomData<fn() -> L>,
+}
+
+impl<L: Any, G: Any> Propagate<L, G> {
+    // computes the value of an entity then of it's descendants, and writes it once they are done with it
+    fn visit(&self, world: &World, entity: Entity, parent: Option<&G>) {
+        let global = match world.get::<&L>(entity) {
+            Ok(local) => (self.combine)(parent, &local),
+            Err(_) => return,
+        };
+        for child in world.children(entity) {
+            if world.entities.has_component::<L>(child) {
+                self.visit(world, child, Some(&global));
+            }
+        }
+
+        if world.entities.has_component::<G>(entity) {
+            if let Ok(mut current) = world.get::<&mut G>(entity) {
+                *current = global;
+                return;
+            }
+        }
+        Commands::new(&world.commands).insert(entity, global);
+    }
+}
+
+impl<L: Any, G: Any> IntoSystem<Propagate<L, G>> for Propagate<L, G> {
+    fn run(&self, world: &World) {
+        let Some(mask) = world.entities.get_bitmask(&TypeId::of::<L>()) else {
+            return;
+        };
+        let roots = world.entities.matching_entities(mask).into_iter()
+            .filter(|entity| world.parent(*entity).is_none_or(|parent| !world.entities.has_component::<L>(parent)));
+        for root in roots {
+            self.visit(world, root, None);
+        }
+    }
+
+    fn access(&self) -> Vec<ParamAccess> {
+        let access = |type_id, name, mutable| Access { kind: AccessKind::Component, type_id, name, mutable };
+        vec![ParamAccess {
+            param: std::any::type_name::<Self>(),
+            access: vec![
+                access(TypeId::of::<L>(), std::any::type_name::<L>(), false),
+                access(TypeId::of::<G>(), std::any::type_name::<G>(), true),
+                access(TypeId::of::<Parent>(), std::any::type_name::<Parent>(), false),
+                access(TypeId::of::<Children>(), std::any::type_name::<Children>(), false),
+            ],
+        }]
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum HierarchyError {
+    #[error("Attempt to use an entity that doesn't exist in a hierarchy: {0}.")]
+    NonexistentEntityError(Entity),
+    #[error("Attempt to make entity {parent} the parent of entity {child}, which would create a cycle.")]
+    CyclicHierarchyError { child: Entity, parent: Entity },
+}
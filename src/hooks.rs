@@ -0,0 +1,135 @@
+//! # Hooks
+//!
+//! The hooks module allows running callbacks when a component of a given type is added to an entity,
+//! with [World::on_add()](struct.World.html#method.on_add), or removed from one, with 
+//! [World::on_remove()](struct.World.html#method.on_remove). This is useful to keep something outside of 
+//! the ECS, like the bodies of a physics engine, in sync with the entities.
+//!
+//! Hooks run once the change has been made, so an on_remove hook can no longer access the removed component.
+//! Changes made through the [Entities] returned by [World::spawn()](struct.World.html#method.spawn)
+//! can't run hooks right away, their hooks run the next time the World is changed through one of it's
+//! own methods, or when [World::run_hooks()](struct.World.html#method.run_hooks) is called.
+
+use std::{any::{Any, TypeId}, collections::HashMap, rc::Rc};
+
+use crate::{world::World, entities::{Entities, Entity}};
+
+type Hook = Rc<dyn Fn(&mut World, Entity)>;
+
+// the hooks of every component type, stored in the World
+#[derive(Default)]
+pub(crate) struct ComponentHooks {
+    on_add: HashMap<TypeId, Vec<Hook>>,
+    on_remove: HashMap<TypeId, Vec<Hook>>,
+}
+
+impl std::fmt::Debug for ComponentHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComponentHooks")
+            .field("on_add", &self.on_add.values().map(Vec::len).sum::<usize>())
+            .field("on_remove", &self.on_remove.values().map(Vec::len).sum::<usize>())
+            .finish()
+    }
+}
+
+impl World {
+    /**
+    Adds a hook that is run with the id of an entity every time it gains a component of type T.
+    Replacing a component an entity already has does not run it.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Collider(f32);
+    struct Bodies(Vec<Entity>);
+    impl Resource for Bodies {}
+
+    let mut world = World::new();
+    world.insert_resource(Bodies(Vec::new()));
+
+    world.on_add::<Collider>(|world, entity| {
+        world.get_resource_mut::<Bodies>().unwrap().0.push(entity);
+    });
+    world.on_remove::<Collider>(|world, entity| {
+        world.get_resource_mut::<Bodies>().unwrap().0.retain(|body| *body != entity);
+    });
+
+    world.spawn().insert(Collider(1.0));
+    world.spawn().insert(Collider(2.0));
+    world.run_hooks();
+    assert_eq!(world.get_resource::<Bodies>().unwrap().0, vec![0, 1]);
+
+    world.despawn(0);
+    assert_eq!(world.get_resource::<Bodies>().unwrap().0, vec![1]);
+    ```
+     */
+    pub fn on_add<T: Any>(&mut self, hook: impl Fn(&mut World, Entity) + 'static) -> &mut Self {
+        self.hooks.on_add.entry(TypeId::of::<T>()).or_default().push(Rc::new(hook));
+        self.entities.watch(TypeId::of::<T>());
+        self
+    }
+
+    /**
+    Adds a hook that is run with the id of an entity every time it loses a component of type T, 
+    either because the component was removed or taken, the entity was despawned, or the component type
+    was unregistered. See [World::on_add()](struct.World.html#method.on_add).
+
+    Note: the hook is run after the component has been removed. Clearing the World's entities 
+    with [World::clear_entities()](struct.World.html#method.clear_entities) does not run it.
+     */
+    pub fn on_remove<T: Any>(&mut self, hook: impl Fn(&mut World, Entity) + 'static) -> &mut Self {
+        self.hooks.on_remove.entry(TypeId::of::<T>()).or_default().push(Rc::new(hook));
+        self.entities.watch(TypeId::of::<T>());
+        self
+    }
+
+    /**
+    Runs the hooks of every component added or removed since hooks were last run, in the order
+    the changes were made, then the [observers](crate::observer) of those changes. Changes made by the hooks
+    and observers themselves also run their hooks and observers.
+
+    This is done automatically by the methods of the World that change entities, so it is only needed
+    after changing entities through [World::spawn()](struct.World.html#method.spawn). It also ends the
+    current step of the [journal](journal/index.html).
+     */
+    pub fn run_hooks(&mut self) {
+        // the changes made by the hooks are part of the change that ran them
+        self.nested_step(|world| loop {
+            let cleaned = world.update_relationships();
+            let events = world.entities.take_events();
+            let storage_events = world.entities.take_storage_events();
+            if !cleaned && events.is_empty() && storage_events.is_empty() {
+                break;
+            }
+
+            for event in events {
+                let hooks = if event.added { &world.hooks.on_add } else { &world.hooks.on_remove };
+                let hooks = hooks.get(&event.type_id).cloned().unwrap_or_default();
+                for hook in hooks {
+                    hook(world, event.entity);
+                }
+            }
+            world.notify_observers(storage_events);
+        });
+        self.commit_journal();
+    }
+
+    // replaces the World's entities, keeping the component types that have hooks watched, the changes observed and the tick,
+    // the holders of relationships are indexed again from the new entities
+    pub(crate) fn replace_entities(&mut self, mut entities: Entities) {
+        entities.set_tick(self.entities.tick());
+        for type_id in self.hooks.on_add.keys().chain(self.hooks.on_remove.keys()) {
+            entities.watch(*type_id);
+        }
+        if !self.observers.is_empty() {
+            entities.observe();
+        }
+        self.reset_journal(&mut entities);
+        entities.take_traits_from(&mut self.entities);
+        entities.take_types_from(&mut self.entities);
+        entities.take_recorder_from(&mut self.entities);
+        self.entities = entities;
+        self.query_caches.clear();
+        self.relationships.reindex();
+    }
+}
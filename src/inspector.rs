@@ -0,0 +1,85 @@
+//! # Inspector
+//!
+//! The inspector module adds [World::inspect()](../struct.World.html#method.inspect), which exports the
+//! components of an entity as JSON, so that they can be shown by debugging tools outside of the program.
+//!
+//! Components are converted with the functions stored in the [TypeRegistry](crate::entities::TypeRegistry),
+//! which are registered along with the serialization functions by
+//! [World::register_serde_component()](../struct.World.html#method.register_serde_component).
+//! Components of other types are still listed, with a null value.
+
+use serde_json::{json, Value};
+
+use crate::{world::World, entities::{try_borrow, ComponentError, Entity}};
+use crate::error::Result;
+
+// Inspector stuff
+impl World {
+    /**
+    Returns the components of an entity as JSON, in the form:
+
+    ```json
+    {
+        "entity": 0,
+        "components": [
+            { "type": "my_game::Health", "name": "Health", "value": [10] },
+            { "type": "my_game::Mesh", "name": "Mesh", "value": null }
+        ]
+    }
+    ```
+
+    where 'value' is null for the types that weren't registered with
+    [register_serde_component()](struct.World.html#method.register_serde_component).
+
+    ```
+    use sceller::prelude::*;
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Health(u32);
+    struct Mesh;
+
+    let mut world = World::new();
+    world.register_serde_component::<Health>();
+    let player = world.spawn().insert(Health(10)).insert(Mesh).current_entity();
+
+    let inspected = world.inspect(player);
+    assert_eq!(inspected["components"][0]["name"], "Health");
+    assert_eq!(inspected["components"][0]["value"], 10);
+    assert!(inspected["components"][1]["value"].is_null());
+    ```
+
+    Panics if the entity doesn't exist, or if one of it's components is mutably borrowed.
+     */
+    pub fn inspect(&self, entity: Entity) -> Value {
+        self.inspect_checked(entity).unwrap()
+    }
+
+    /**
+    Returns the components of an entity as JSON, see [inspect()](struct.World.html#method.inspect).
+
+    Returns an error if the entity doesn't exist, if one of it's components is mutably borrowed, or if
+    a component fails to be converted.
+     */
+    pub fn inspect_checked(&self, entity: Entity) -> Result<Value> {
+        if !self.entities.is_alive(entity) {
+            return Err(ComponentError::NonexistentEntity.into());
+        }
+
+        let mut components = Vec::new();
+        for (type_id, name, component) in self.entities.components_of(entity) {
+            let component = try_borrow(component, name, entity)?;
+            let value = match self.type_registry().get(&type_id).and_then(|registration| registration.to_json) {
+                Some(to_json) => to_json(&*component)?,
+                None => Value::Null,
+            };
+            components.push(json!({
+                "type": name,
+                "name": crate::entities::short_type_name(name),
+                "value": value,
+            }));
+        }
+
+        Ok(json!({ "entity": entity, "components": components }))
+    }
+}
@@ -0,0 +1,231 @@
+//! # Journal
+//!
+//! The journal module allows undoing and redoing the structural changes made to a World, with
+//! [World::undo()](struct.World.html#method.undo) and [World::redo()](struct.World.html#method.redo).
+//!
+//! Only the component and resource types registered with [World::journal_component()](struct.World.html#method.journal_component)
+//! and [World::journal_resource()](struct.World.html#method.journal_resource) are recorded, and they have to be Clone since
+//! the journal keeps a copy of their value every time they are inserted, replaced or removed. Changing a component or a
+//! resource in place, through a [RefMut](std::cell::RefMut), isn't recorded.
+//!
+//! Every call to a method of the World that changes entities or resources is one step of the journal, along with the
+//! changes made by the hooks it runs. The components inserted through the [Entities] returned by
+//! [World::spawn()](struct.World.html#method.spawn) are one step, which ends at the next call to one of those methods.
+//! Undoing and redoing a step doesn't run hooks, since the changes made by the hooks are part of the step.
+
+use std::{any::{Any, TypeId}, cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::{
+    entities::{CloneFn, ComponentChange, Entities},
+    resources::{Resource, Resources},
+    world::World,
+};
+
+type ResourceCloneFn = fn(&Resources) -> Option<Rc<dyn Any>>;
+
+// a component or resource along with the value it had before it changed, or after it when the change was undone
+#[derive(Debug)]
+enum Change {
+    Component(ComponentChange),
+    Resource { type_id: TypeId, name: &'static str, value: Option<Rc<dyn Any>> },
+}
+
+// the steps that can be undone and redone, stored in the World
+#[derive(Debug, Default)]
+pub(crate) struct Journal {
+    components: HashMap<TypeId, CloneFn>,
+    resources: HashMap<TypeId, ResourceCloneFn>,
+    undo: Vec<Vec<Change>>,
+    redo: Vec<Vec<Change>>,
+    // the resource changes of the current step
+    pending: Vec<Change>,
+    // the number of steps being run, only the outermost one is added to the journal
+    depth: usize,
+}
+
+impl Journal {
+    fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.pending.clear();
+    }
+}
+
+fn clone_component<T: Any + Clone>(component: &dyn Any) -> Box<dyn Any> {
+    Box::new(component.downcast_ref::<T>().unwrap().clone())
+}
+
+fn clone_resource<T: Any + Clone>(resources: &Resources) -> Option<Rc<dyn Any>> {
+    let resource = resources.get_ref::<T>().ok()?;
+    Some(Rc::new(RefCell::new(resource.clone())))
+}
+
+impl World {
+    /**
+    Starts recording the components of type T in the journal, so that inserting, replacing and removing them can be undone.
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Clone)]
+    struct Health(u8);
+
+    let mut world = World::new();
+    world.journal_component::<Health>();
+
+    world.spawn().insert(Health(3));
+    world.insert_component_into_entity(Health(1), 0);
+    world.despawn(0);
+    assert!(!world.entity_has::<Health>(0));
+
+    world.undo();
+    assert_eq!(world.get::<&Health>(0).unwrap().0, 1);
+    world.undo();
+    assert_eq!(world.get::<&Health>(0).unwrap().0, 3);
+    world.undo();
+    assert!(!world.entity_has::<Health>(0));
+
+    world.redo();
+    assert_eq!(world.get::<&Health>(0).unwrap().0, 3);
+    ```
+     */
+    pub fn journal_component<T: Any + Clone>(&mut self) -> &mut Self {
+        self.journal.components.insert(TypeId::of::<T>(), clone_component::<T>);
+        self.entities.journal(TypeId::of::<T>(), clone_component::<T>);
+        self.entities.type_registry_mut().register_clone::<T>();
+        self
+    }
+
+    /**
+    Starts recording the resources of type T in the journal, so that inserting, replacing and deleting them can be undone.
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Clone)]
+    struct Level(u32);
+    impl Resource for Level {}
+
+    let mut world = World::new();
+    world.journal_resource::<Level>();
+
+    world.insert_resource(Level(1));
+    world.insert_resource(Level(2));
+    world.delete_resource::<Level>().unwrap();
+
+    world.undo();
+    assert_eq!(world.get_resource::<Level>().unwrap().0, 2);
+    world.undo();
+    world.undo();
+    assert!(world.get_resource::<Level>().is_err());
+    assert!(!world.undo());
+    ```
+     */
+    pub fn journal_resource<T: Resource + Clone>(&mut self) -> &mut Self {
+        self.journal.resources.insert(TypeId::of::<T>(), clone_resource::<T>);
+        self
+    }
+
+    /**
+    Undoes the last step recorded in the journal. Returns false if there was nothing to undo.
+     */
+    pub fn undo(&mut self) -> bool {
+        // the components inserted after a spawn are a step of their own
+        self.run_hooks();
+        let Some(mut step) = self.journal.undo.pop() else { return false };
+
+        for change in step.iter_mut().rev() {
+            self.swap_change(change);
+        }
+        self.journal.redo.push(step);
+        true
+    }
+
+    /**
+    Redoes the last step undone with [World::undo()], as long as nothing was changed since then.
+    Returns false if there was nothing to redo.
+     */
+    pub fn redo(&mut self) -> bool {
+        self.run_hooks();
+        let Some(mut step) = self.journal.redo.pop() else { return false };
+
+        for change in step.iter_mut() {
+            self.swap_change(change);
+        }
+        self.journal.undo.push(step);
+        true
+    }
+
+    /**
+    Forgets every step of the journal. The types registered keep being recorded.
+     */
+    pub fn clear_journal(&mut self) {
+        self.entities.take_changes();
+        self.journal.clear();
+    }
+
+    // puts the value of a change back, keeping the value it replaces in it's place
+    fn swap_change(&mut self, change: &mut Change) {
+        match change {
+            Change::Component(change) => {
+                change.value = self.entities.swap_component(change.entity, change.type_id, change.value.take());
+                // the journal, the hooks and the observers mustn't see the changes made to undo a step
+                self.entities.take_changes();
+                self.entities.take_events();
+                self.entities.take_storage_events();
+            },
+            Change::Resource { type_id, name, value } => {
+                *value = self.resources.swap_raw(*type_id, name, value.take());
+            },
+        }
+    }
+
+    /*
+        runs 'f' as a single step of the journal, the steps run by 'f' being part of it. Used by every method
+        of the World that changes entities or resources, which also runs the hooks of their changes
+    */
+    pub(crate) fn step<R>(&mut self, f: impl FnOnce(&mut World) -> R) -> R {
+        self.run_hooks();
+        let result = self.nested_step(f);
+        self.run_hooks();
+        result
+    }
+
+    // runs 'f' as part of the current step, without ending it
+    pub(crate) fn nested_step<R>(&mut self, f: impl FnOnce(&mut World) -> R) -> R {
+        self.journal.depth += 1;
+        let result = f(self);
+        self.journal.depth -= 1;
+        result
+    }
+
+    // records the value the resource T has before it changes, if it's type is journaled
+    pub(crate) fn record_resource<T: Any>(&mut self) {
+        if let Some(clone) = self.journal.resources.get(&TypeId::of::<T>()) {
+            let value = clone(&self.resources);
+            self.journal.pending.push(Change::Resource { type_id: TypeId::of::<T>(), name: std::any::type_name::<T>(), value });
+        }
+    }
+
+    // ends the current step, adding it to the journal unless it is empty or nested in another step
+    pub(crate) fn commit_journal(&mut self) {
+        if self.journal.depth > 0 {
+            return;
+        }
+        let changes = self.entities.take_changes();
+        self.journal.pending.extend(changes.into_iter().map(Change::Component));
+        if !self.journal.pending.is_empty() {
+            let step = std::mem::take(&mut self.journal.pending);
+            self.journal.undo.push(step);
+            self.journal.redo.clear();
+        }
+    }
+
+    // makes entities that will replace the World's ones record the same component types, and forgets the journal's steps
+    pub(crate) fn reset_journal(&mut self, entities: &mut Entities) {
+        for (type_id, clone) in &self.journal.components {
+            entities.journal(*type_id, *clone);
+        }
+        self.journal.clear();
+    }
+}
@@ -19,19 +19,85 @@
 
 pub mod resources;
 pub mod world;
+pub mod component;
 pub mod entities;
 pub mod system;
+pub mod commands;
+pub mod entity_mut;
+pub mod schedule;
+pub mod condition;
+pub mod ordering;
+pub mod pipe;
+pub mod error_handler;
+pub mod state;
+pub mod hierarchy;
+pub mod hooks;
+pub mod observer;
+pub mod relationship;
+pub mod name;
+pub mod time;
+pub mod tick;
+pub mod diagnostics;
+pub mod sub_world;
+pub mod app;
+pub mod journal;
+pub mod rollback;
+pub mod recording;
+pub mod merge;
+pub mod assets;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub mod bench;
+pub mod error;
+#[cfg(feature = "serde")]
+pub mod serialization;
+#[cfg(feature = "serde")]
+pub mod scene;
+#[cfg(feature = "serde")]
+pub mod replication;
+#[cfg(feature = "inspector")]
+pub mod inspector;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+#[cfg(feature = "egui")]
+pub mod egui_inspector;
 
 pub mod prelude {
     pub use super::resources::*;
     pub use super::world::*;
+    pub use super::component::*;
     pub use super::entities::*;
     pub use super::system::*;
+    pub use super::commands::*;
+    pub use super::entity_mut::*;
+    pub use super::schedule::*;
+    pub use super::condition::*;
+    pub use super::ordering::*;
+    pub use super::pipe::*;
+    pub use super::error_handler::*;
+    pub use super::state::*;
+    pub use super::hierarchy::*;
+    pub use super::observer::*;
+    pub use super::relationship::*;
+    pub use super::name::*;
+    pub use super::time::*;
+    pub use super::tick::*;
+    pub use super::diagnostics::*;
+    pub use super::sub_world::*;
+    pub use super::app::*;
+    pub use super::rollback::*;
+    pub use super::recording::*;
+    pub use super::merge::*;
+    pub use super::assets::*;
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub use super::bench::*;
+    pub use super::error::*;
+    pub use super::query_trait;
 
     pub use std::cell::{Ref, RefMut};
-    pub use eyre::Result;
 }
 
+pub use error::{Error, Result};
+
 #[cfg(test)]
 mod tests {
     #[test]
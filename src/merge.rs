@@ -0,0 +1,116 @@
+//! # Merging
+//!
+//! The merge module adds [World::merge()], which moves every entity and resource of another World into this one.
+//! This allows building parts of a World separately, like the chunks of a level loaded in the background,
+//! and adding them to the live World once they are complete.
+//!
+//! The entities are given new ids in the World they are merged into, and the components of the
+//! [hierarchy](crate::hierarchy) are updated to point to them. Other components storing the ids of entities, like
+//! [relationships](crate::relationship), aren't updated, but the map of ids returned by the merge can be used to do so.
+//! Resources that both Worlds have are resolved according to a [MergePolicy].
+
+use std::collections::HashMap;
+
+use crate::{world::World, entities::Entity, hierarchy::{Children, Parent}, resources::ResourcesError};
+use crate::error::Result;
+
+/**
+What happens to a resource that both Worlds have when one is merged into the other, see [World::merge()].
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MergePolicy {
+    /// The resource of the World merged into is kept, and the other one is dropped.
+    #[default]
+    KeepExisting,
+    /// The resource of the World merged into is replaced by the other one.
+    Replace,
+    /// The merge fails, without moving anything.
+    Fail,
+}
+
+// Merge stuff
+impl World {
+    /**
+    Moves every entity and resource of 'other' into this World, and returns the new id of every entity moved,
+    keyed by the id it had in 'other'. Panics if the merge fails, see [World::merge_checked()].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Mesh(&'static str);
+    struct Seed(u64);
+    impl Resource for Seed {}
+
+    let mut world = World::new();
+    world.insert_resource(Seed(1));
+    world.spawn().insert(Name::new("player"));
+
+    // a chunk of the level, built separately
+    let mut chunk = World::new();
+    chunk.insert_resource(Seed(2));
+    let tree = chunk.spawn().insert(Mesh("tree.obj")).current_entity();
+    let leaf = chunk.spawn().insert(Mesh("leaf.obj")).current_entity();
+    chunk.set_parent(leaf, tree);
+
+    let moved = world.merge(chunk, MergePolicy::KeepExisting);
+    let (tree, leaf) = (moved[&tree], moved[&leaf]);
+    assert_eq!(world.get::<&Mesh>(tree).unwrap().0, "tree.obj");
+    assert_eq!(world.parent(leaf), Some(tree));
+    assert_eq!(world.get_resource::<Seed>().unwrap().0, 1);
+    ```
+     */
+    pub fn merge(&mut self, other: World, policy: MergePolicy) -> HashMap<Entity, Entity> {
+        self.merge_checked(other, policy).unwrap()
+    }
+
+    /**
+    Same as [World::merge()], but returns an error instead of panicking if both Worlds have the same resource
+    with [MergePolicy::Fail], or if an entity of 'other' has a unique component that an entity of this World
+    already has. Nothing is moved in both cases.
+
+    The hooks of this World are run for the components moved, but the systems, hooks and pending commands of
+    'other' are dropped along with it.
+     */
+    pub fn merge_checked(&mut self, mut other: World, policy: MergePolicy) -> Result<HashMap<Entity, Entity>> {
+        let resources = other.resources.drain();
+        if policy == MergePolicy::Fail {
+            if let Some((type_id, name, _)) = resources.iter().find(|(type_id, _, _)| self.resources.name_of(type_id).is_some()) {
+                let name = self.resources.name_of(type_id).unwrap_or(name);
+                return Err(ResourcesError::ConflictingResourceError(name).into());
+            }
+        }
+        let entities = other.entities.iter_ids().map(|(entity, _)| entity).collect::<Vec<Entity>>();
+        for entity in &entities {
+            other.entities.check_transfer(*entity, &self.entities)?;
+        }
+
+        let moved = self.step(|world| {
+            let mut moved = HashMap::with_capacity(entities.len());
+            for entity in entities {
+                moved.insert(entity, other.entities.transfer_entity(entity, &mut world.entities)?);
+            }
+            world.map_hierarchy(&moved);
+            Ok::<_, crate::Error>(moved)
+        })?;
+
+        for (type_id, name, value) in resources {
+            if policy == MergePolicy::Replace || self.resources.name_of(&type_id).is_none() {
+                self.resources.swap_raw(type_id, name, Some(value));
+            }
+        }
+        Ok(moved)
+    }
+
+    // points the hierarchy components of the entities moved to the new ids of their parent and children
+    fn map_hierarchy(&mut self, moved: &HashMap<Entity, Entity>) {
+        let map = |entity: Entity| moved.get(&entity).copied().unwrap_or(entity);
+        for entity in moved.values() {
+            if let Ok(mut parent) = self.entities.try_get_component_mut::<Parent>(*entity) {
+                parent.0 = map(parent.0);
+            }
+            if let Ok(mut children) = self.entities.try_get_component_mut::<Children>(*entity) {
+                children.map_ids(map);
+            }
+        }
+    }
+}
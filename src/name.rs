@@ -0,0 +1,181 @@
+//! # Names
+//!
+//! The name module adds the [Name] component, which gives an entity a human readable name that it
+//! can be found by with [World::find_by_name()](struct.World.html#method.find_by_name), for example
+//! when debugging or scripting. The names are indexed as they are inserted and removed, and the names
+//! changed in place are indexed again by the next lookup, so finding an entity doesn't look through
+//! every entity.
+
+use std::{any::TypeId, cell::{BorrowError, Ref}, collections::{BTreeSet, HashMap}};
+
+use crate::{world::World, entities::{Entities, Entity}};
+
+/**
+A component storing the name of an entity.
+
+To rename an entity, insert a new Name into it or change it in place, the names borrowed mutably
+are indexed again the next time an entity is looked up by name.
+
+```
+use sceller::prelude::*;
+
+let mut world = World::new();
+world.spawn().insert(Name::new("player"));
+world.spawn().insert(Name::new("goblin"));
+
+assert_eq!(world.find_by_name("goblin"), Some(1));
+assert_eq!(world.find_by_name("dragon"), None);
+
+world.insert_component_into_entity(Name::new("boss"), 1);
+assert_eq!(world.find_by_name("goblin"), None);
+assert_eq!(world.find_by_name("boss"), Some(1));
+
+*world.get::<&mut Name>(1).unwrap() = Name::new("corpse");
+assert_eq!(world.find_by_name("boss"), None);
+assert_eq!(world.find_by_name("corpse"), Some(1));
+```
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Name(String);
+
+impl Name {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    /// Returns the name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::ops::Deref for Name {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Name {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<&str> for Name {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for Name {
+    fn from(name: String) -> Self {
+        Self(name)
+    }
+}
+
+// the entities having each name, kept up to date by Entities as names are inserted and removed
+#[derive(Debug, Default)]
+pub(crate) struct NameIndex {
+    by_name: HashMap<String, BTreeSet<Entity>>,
+    by_entity: HashMap<Entity, String>,
+    // the entities whose name was written in place but was still borrowed mutably when it was to be indexed again
+    pending: BTreeSet<Entity>,
+}
+
+impl NameIndex {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.by_entity.is_empty()
+    }
+
+    pub(crate) fn insert(&mut self, entity: Entity, name: &str) {
+        self.remove(entity);
+        self.by_name.entry(name.to_owned()).or_default().insert(entity);
+        self.by_entity.insert(entity, name.to_owned());
+    }
+
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        if let Some(name) = self.by_entity.remove(&entity) {
+            if let Some(entities) = self.by_name.get_mut(&name) {
+                entities.remove(&entity);
+                if entities.is_empty() {
+                    self.by_name.remove(&name);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.by_name.clear();
+        self.by_entity.clear();
+        self.pending.clear();
+    }
+
+    // the entities indexed under 'name', in ascending order
+    pub(crate) fn get(&self, name: &str) -> impl Iterator<Item = Entity> + '_ {
+        self.by_name.get(name).into_iter().flatten().copied()
+    }
+}
+
+impl Entities {
+    /**
+    Returns the entity whose [Name] is 'name', or the one with the lowest id if several entities have that name.
+
+    See [World::find_by_name()](struct.World.html#method.find_by_name).
+     */
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        let mut index = self.names().borrow_mut();
+        // the names written in place since the last lookup are indexed again, unless they are still borrowed mutably
+        let mut written = std::mem::take(&mut index.pending);
+        written.extend(self.take_writes(TypeId::of::<Name>()));
+        for entity in written {
+            match self.stored_name(entity) {
+                Ok(Some(stored)) => index.insert(entity, &stored),
+                Ok(None) => index.remove(entity),
+                Err(_) => {
+                    index.pending.insert(entity);
+                },
+            }
+        }
+
+        // only trust the index if the entity still has that name, a name borrowed mutably is never a match
+        let found = index.get(name).find(|entity| {
+            self.stored_name(*entity).is_ok_and(|stored| stored.is_some_and(|stored| stored.as_str() == name))
+        });
+        found
+    }
+
+    // the Name of 'entity', if it has one, or an error if it is borrowed mutably
+    fn stored_name(&self, entity: Entity) -> Result<Option<Ref<'_, Name>>, BorrowError> {
+        if !self.has_component::<Name>(entity) {
+            return Ok(None);
+        }
+        self.typed_column::<Name>()
+            .and_then(|names| names.get_typed(entity))
+            .map(|stored| stored.try_borrow())
+            .transpose()
+    }
+}
+
+impl World {
+    /**
+    Returns the entity whose [Name] is 'name', or the one with the lowest id if several entities have that name.
+
+    ```
+    use sceller::prelude::*;
+
+    let mut world = World::new();
+    let camera = world.spawn_batch([(Name::new("camera"),)])[0];
+
+    assert_eq!(world.find_by_name("camera"), Some(camera));
+
+    world.delete_component_from_ent::<Name>(camera);
+    assert_eq!(world.find_by_name("camera"), None);
+    ```
+     */
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        self.entities.find_by_name(name)
+    }
+}
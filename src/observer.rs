@@ -0,0 +1,192 @@
+//! # Observers
+//!
+//! The observer module allows running callbacks on the structural changes of the World's entities with
+//! [World::observe()]: an entity being spawned ([EntitySpawned]) or despawned ([EntityDespawned]), or a component
+//! of any type being inserted into ([ComponentInserted]) or removed from ([ComponentRemoved]) an entity.
+//! Every change is also a [StorageEvent], which can be observed to see all of them.
+//!
+//! Unlike systems reading the events of a frame, like [AssetEvents](crate::assets::AssetEvents), observers run as
+//! soon as the method of the World making the change returns, before anything else can look at the World. This
+//! makes them useful to keep invariants that must hold in the middle of a frame. Like [hooks](crate::hooks),
+//! changes made through the [Entities](crate::entities::Entities) returned by [World::spawn()] are only observed
+//! the next time the World is changed through one of it's own methods, or when [World::run_hooks()] is called.
+
+use std::{any::{Any, TypeId}, rc::Rc};
+
+use crate::{world::World, entities::Entity};
+
+type Observer = Rc<dyn Fn(&StorageEvent, &mut World)>;
+
+/// An entity being spawned, which happens when it gets it's first component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntitySpawned {
+    pub entity: Entity,
+}
+
+/// An entity being despawned, which happens when it loses it's last component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntityDespawned {
+    pub entity: Entity,
+}
+
+/// A component being inserted into an entity that didn't have one of it's type. Replacing a component isn't an insertion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentInserted {
+    pub entity: Entity,
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+}
+
+impl ComponentInserted {
+    /// Returns true if the component inserted is of type T.
+    pub fn is<T: Any>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+}
+
+/// A component being removed from an entity, because it was removed, taken, or the entity was despawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentRemoved {
+    pub entity: Entity,
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+}
+
+impl ComponentRemoved {
+    /// Returns true if the component removed is of type T.
+    pub fn is<T: Any>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+}
+
+/**
+Any structural change of the World's entities. A despawned entity loses every component before it is despawned,
+and a spawned entity is spawned before it gains it's first components.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageEvent {
+    Spawned(EntitySpawned),
+    Despawned(EntityDespawned),
+    Inserted(ComponentInserted),
+    Removed(ComponentRemoved),
+}
+
+impl StorageEvent {
+    /// Returns the entity that changed.
+    pub fn entity(&self) -> Entity {
+        match self {
+            Self::Spawned(event) => event.entity,
+            Self::Despawned(event) => event.entity,
+            Self::Inserted(event) => event.entity,
+            Self::Removed(event) => event.entity,
+        }
+    }
+}
+
+/**
+A trait implemented by the events that can be observed with [World::observe()], which picks the
+changes of the World's entities that are of this event.
+ */
+pub trait ObserverEvent: Any + Sized {
+    /// Returns the event if 'event' is one, None otherwise.
+    fn from_storage_event(event: &StorageEvent) -> Option<Self>;
+}
+
+impl ObserverEvent for StorageEvent {
+    fn from_storage_event(event: &StorageEvent) -> Option<Self> {
+        Some(*event)
+    }
+}
+
+macro_rules! impl_observer_event {
+    ($($event:ident $variant:ident),+) => {
+        $(
+            impl ObserverEvent for $event {
+                fn from_storage_event(event: &StorageEvent) -> Option<Self> {
+                    match event {
+                        StorageEvent::$variant(event) => Some(*event),
+                        _ => None,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_observer_event!(EntitySpawned Spawned, EntityDespawned Despawned, ComponentInserted Inserted, ComponentRemoved Removed);
+
+// the observers of the World, in the order they were added
+#[derive(Default)]
+pub(crate) struct Observers {
+    observers: Vec<Observer>,
+}
+
+impl Observers {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Observers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Observers").field("len", &self.observers.len()).finish()
+    }
+}
+
+// Observer stuff
+impl World {
+    /**
+    Adds an observer that is run with every event of type E, right after the change is made and with mutable access
+    to the World, see the [observer module](crate::observer).
+
+    ```
+    use sceller::prelude::*;
+
+    struct Player;
+    struct GameOver(bool);
+    impl Resource for GameOver {}
+
+    let mut world = World::new();
+    world.insert_resource(GameOver(false));
+    world.observe(|event: &ComponentRemoved, world| {
+        if event.is::<Player>() {
+            world.get_resource_mut::<GameOver>().unwrap().0 = true;
+        }
+    });
+
+    let player = world.spawn().insert(Player).current_entity();
+    world.spawn().insert(7_u32);
+    world.despawn(1);
+    assert!(!world.get_resource::<GameOver>().unwrap().0);
+
+    // the game is over as soon as the player is gone, before any system runs
+    world.despawn(player);
+    assert!(world.get_resource::<GameOver>().unwrap().0);
+    ```
+
+    Changes made by the observer are observed too, once it returns. Clearing the World's entities with
+    [World::clear_entities()] isn't observed.
+     */
+    pub fn observe<E: ObserverEvent>(&mut self, observer: impl Fn(&E, &mut World) + 'static) -> &mut Self {
+        self.observers.observers.push(Rc::new(move |event, world| {
+            if let Some(event) = E::from_storage_event(event) {
+                observer(&event, world);
+            }
+        }));
+        self.entities.observe();
+        self
+    }
+
+    // runs every observer with each event, in the order the changes were made
+    pub(crate) fn notify_observers(&mut self, events: Vec<StorageEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        let observers = self.observers.observers.clone();
+        for event in &events {
+            for observer in &observers {
+                observer(event, self);
+            }
+        }
+    }
+}
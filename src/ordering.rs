@@ -0,0 +1,200 @@
+//! # System Ordering
+//!
+//! The ordering module allows ordering the systems of a stage with [IntoOrderedSystem::before()] and
+//! [IntoOrderedSystem::after()], instead of relying on the order they were added in. A system can be
+//! ordered relative to another system, or to every system of a named set which systems join with
+//! [IntoOrderedSystem::in_set()].
+//!
+//! The systems of each stage are sorted every time a system is added to it, keeping the order they were
+//! added in wherever it doesn't break a constraint. Constraints only apply between systems of the same stage,
+//! labels that don't match any system of the stage are ignored.
+
+use std::marker::PhantomData;
+
+use crate::{world::World, system::{BoxedSystem, IntoSystem, ParamAccess}};
+use crate::error::Result;
+
+/**
+A label referring to one or more systems, used in the ordering constraints of a system.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SystemLabel {
+    /// A single system, by the type name of the function it was created from.
+    System(&'static str),
+    /// Every system that joined the named set with [IntoOrderedSystem::in_set()].
+    Set(&'static str),
+}
+
+/**
+A trait implemented by anything that can be turned into a [SystemLabel], that is systems,
+which refer to themselves, and strings, which are the names of sets.
+ */
+pub trait IntoSystemLabel<Marker> {
+    fn label(&self) -> SystemLabel;
+}
+
+impl IntoSystemLabel<()> for &'static str {
+    fn label(&self) -> SystemLabel {
+        SystemLabel::Set(self)
+    }
+}
+
+impl<F, T> IntoSystemLabel<T> for F
+where F: IntoSystem<T>
+{
+    fn label(&self) -> SystemLabel {
+        SystemLabel::System(self.name())
+    }
+}
+
+/**
+The sets a system is part of, and the systems it has to run before and after.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemOrdering {
+    pub sets: Vec<&'static str>,
+    pub before: Vec<SystemLabel>,
+    pub after: Vec<SystemLabel>,
+}
+
+impl SystemOrdering {
+    fn merge(&mut self, other: SystemOrdering) {
+        self.sets.extend(other.sets);
+        self.before.extend(other.before);
+        self.after.extend(other.after);
+    }
+}
+
+/**
+A system along with ordering constraints, created with the methods of [IntoOrderedSystem].
+ */
+pub struct OrderedSystem<F, T> {
+    system: F,
+    ordering: SystemOrdering,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<F, T> IntoSystem<(T, SystemOrdering)> for OrderedSystem<F, T>
+where F: IntoSystem<T>
+{
+    fn run(&self, world: &World) {
+        self.system.run(world)
+    }
+
+    fn validate(&self, world: &World) -> Result<()> {
+        self.system.validate(world)
+    }
+
+    fn access(&self) -> Vec<ParamAccess> {
+        self.system.access()
+    }
+
+    fn name(&self) -> &'static str {
+        self.system.name()
+    }
+
+    fn ordering(&self) -> SystemOrdering {
+        let mut ordering = self.system.ordering();
+        ordering.merge(self.ordering.clone());
+        ordering
+    }
+}
+
+/**
+Adds [before()](IntoOrderedSystem::before), [after()](IntoOrderedSystem::after) and [in_set()](IntoOrderedSystem::in_set) to every system.
+
+```
+use sceller::prelude::*;
+
+struct Log(Vec<&'static str>);
+impl Resource for Log {}
+
+fn input(mut log: ResMut<Log>) { log.0.push("input"); }
+fn movement(mut log: ResMut<Log>) { log.0.push("movement"); }
+fn gravity(mut log: ResMut<Log>) { log.0.push("gravity"); }
+fn collision(mut log: ResMut<Log>) { log.0.push("collision"); }
+
+let mut world = World::new();
+world.insert_resource(Log(Vec::new()));
+
+world.add_system(collision.after("physics"));
+world.add_system(movement.in_set("physics").after(input));
+world.add_system(gravity.in_set("physics").before(movement));
+world.add_system(input);
+
+world.run_schedule();
+assert_eq!(world.get_resource::<Log>().unwrap().0, vec!["gravity", "input", "movement", "collision"]);
+```
+ */
+pub trait IntoOrderedSystem<T>: IntoSystem<T> + Sized {
+    /// Makes the system run before the systems matching 'label' in the same stage.
+    fn before<M>(self, label: impl IntoSystemLabel<M>) -> OrderedSystem<Self, T> {
+        let before = vec![label.label()];
+        OrderedSystem { system: self, ordering: SystemOrdering { before, ..Default::default() }, phantom: PhantomData }
+    }
+
+    /// Makes the system run after the systems matching 'label' in the same stage.
+    fn after<M>(self, label: impl IntoSystemLabel<M>) -> OrderedSystem<Self, T> {
+        let after = vec![label.label()];
+        OrderedSystem { system: self, ordering: SystemOrdering { after, ..Default::default() }, phantom: PhantomData }
+    }
+
+    /// Adds the system to the set named 'set', so that other systems can be ordered relative to the whole set.
+    fn in_set(self, set: &'static str) -> OrderedSystem<Self, T> {
+        OrderedSystem { system: self, ordering: SystemOrdering { sets: vec![set], ..Default::default() }, phantom: PhantomData }
+    }
+}
+
+impl<F, T> IntoOrderedSystem<T> for F
+where F: IntoSystem<T>
+{}
+
+fn matches(system: &BoxedSystem, label: &SystemLabel) -> bool {
+    match label {
+        SystemLabel::System(name) => system.name() == *name,
+        SystemLabel::Set(set) => system.ordering().sets.contains(set),
+    }
+}
+
+/*
+    Sorts systems so that every one of them runs after the ones it has to run after, picking the system
+    added first whenever more than one can be run. Returns the indices of the systems in the order they
+    should run in, or the names of the systems forming a cycle if there isn't one.
+*/
+pub(crate) fn sort_systems(systems: &[BoxedSystem]) -> Result<Vec<usize>, Vec<&'static str>> {
+    let count = systems.len();
+    let mut edges = vec![Vec::new(); count];
+    let mut incoming = vec![0; count];
+
+    for (first, system) in systems.iter().enumerate() {
+        for (second, other) in systems.iter().enumerate().filter(|(second, _)| *second != first) {
+            let before = system.ordering().before.iter().any(|label| matches(other, label));
+            let after = other.ordering().after.iter().any(|label| matches(system, label));
+            if before || after {
+                edges[first].push(second);
+                incoming[second] += 1;
+            }
+        }
+    }
+
+    let mut sorted = vec![false; count];
+    let mut order = Vec::with_capacity(count);
+    while let Some(next) = (0..count).find(|index| !sorted[*index] && incoming[*index] == 0) {
+        sorted[next] = true;
+        order.push(next);
+        for to in &edges[next] {
+            incoming[*to] -= 1;
+        }
+    }
+
+    if order.len() == count {
+        return Ok(order);
+    }
+
+    // the systems left all come after a cycle, removing the ones that don't lead back to it leaves the cycle
+    let mut left = (0..count).filter(|index| !sorted[*index]).collect::<Vec<usize>>();
+    while let Some(position) = left.iter().position(|index| !edges[*index].iter().any(|to| left.contains(to))) {
+        left.remove(position);
+    }
+    Err(left.into_iter().map(|index| systems[index].name()).collect())
+}
@@ -0,0 +1,230 @@
+//! # System Piping
+//!
+//! The pipe module allows systems to return values, and to pass them on to another system
+//! with [IntoPipeSystem::pipe()]. The receiving system takes the value as it's first parameter,
+//! wrapped in [In]. Once every value is consumed, the piped systems can be added to a schedule
+//! like any other system.
+//!
+//! This is mostly useful for systems that can fail, which can return a [Result](crate::Result) and
+//! leave handling the error to the system they are piped into.
+
+use std::marker::PhantomData;
+
+use crate::{world::World, system::{check_conflicts, IntoSystem, ParamAccess, SystemParams}};
+use crate::error::Result;
+
+/**
+The value a system receives from the system piped into it, which must be it's first parameter.
+
+```
+use sceller::prelude::*;
+
+struct Health(u32);
+struct Damage(u32);
+
+fn compute_damage(damage: FnQuery<&Damage>) -> u32 {
+    damage.iter().map(|damage| damage.0).sum()
+}
+
+fn apply_damage(In(damage): In<u32>, health: FnQuery<&mut Health>) {
+    for mut health in health {
+        health.0 = health.0.saturating_sub(damage);
+    }
+}
+
+let mut world = World::new();
+world.spawn().insert(Health(10));
+world.spawn().insert(Damage(3));
+world.spawn().insert(Damage(4));
+
+world.run_system(compute_damage.pipe(apply_damage));
+assert_eq!(world.get::<&Health>(0).unwrap().0, 3);
+```
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct In<T>(pub T);
+
+/**
+A trait implemented for any function that can be part of a pipe, that is any function taking
+up to eleven [SystemParams], optionally preceded by an [In] parameter, and returning any value.
+ */
+pub trait IntoPipeSystem<Input, Output, Arguments> {
+    /// Runs the system with the value piped into it, returning it's output.
+    fn run_with(&self, input: Input, world: &World) -> Output;
+
+    /// Returns an error naming the first parameter of this system that cannot be fetched from the world,
+    /// or the first two parameters whose accesses conflict.
+    fn validate(&self, world: &World) -> Result<()>;
+
+    /// Returns the components and resources borrowed by each parameter of this system.
+    fn access(&self) -> Vec<ParamAccess>;
+
+    /// Returns the name of the system, which is the type name of the function it was created from.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /**
+    Pipes the output of this system into 'next', which receives it as it's [In] parameter.
+    The two systems are run one after the other, so their parameters don't conflict with each other.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Level(u32);
+    impl Resource for Level {}
+
+    fn load_level(level: Res<Level>) -> Result<()> {
+        if level.0 > 3 {
+            return Err(ResourcesError::NonexistentResourceError.into());
+        }
+        Ok(())
+    }
+
+    fn count_errors(In(result): In<Result<()>>, mut level: ResMut<Level>) {
+        if result.is_err() {
+            level.0 = 0;
+        }
+    }
+
+    let mut world = World::new();
+    world.insert_resource(Level(5));
+    world.add_system(load_level.pipe(count_errors));
+
+    world.run_schedule();
+    assert_eq!(world.get_resource::<Level>().unwrap().0, 0);
+    ```
+     */
+    fn pipe<B, BOutput, BArguments>(self, next: B) -> PipeSystem<Self, B, Output, (Arguments, BArguments)>
+    where
+        Self: Sized,
+        B: IntoPipeSystem<Output, BOutput, BArguments>,
+    {
+        PipeSystem { first: self, second: next, phantom: PhantomData }
+    }
+}
+
+/**
+Two systems run one after the other, the output of the first being piped into the second.
+Created with [IntoPipeSystem::pipe()].
+ */
+pub struct PipeSystem<A, B, Middle, Arguments> {
+    first: A,
+    second: B,
+    phantom: PhantomData<fn() -> (Middle, Arguments)>,
+}
+
+impl<A, B, Input, Middle, Output, AArguments, BArguments> IntoPipeSystem<Input, Output, (AArguments, BArguments)>
+    for PipeSystem<A, B, Middle, (AArguments, BArguments)>
+where
+    A: IntoPipeSystem<Input, Middle, AArguments>,
+    B: IntoPipeSystem<Middle, Output, BArguments>,
+{
+    fn run_with(&self, input: Input, world: &World) -> Output {
+        let middle = self.first.run_with(input, world);
+        self.second.run_with(middle, world)
+    }
+
+    fn validate(&self, world: &World) -> Result<()> {
+        self.first.validate(world)?;
+        self.second.validate(world)
+    }
+
+    fn access(&self) -> Vec<ParamAccess> {
+        let mut access = self.first.access();
+        access.extend(self.second.access());
+        access
+    }
+
+    fn name(&self) -> &'static str {
+        self.first.name()
+    }
+}
+
+impl<A, B, Middle, AArguments, BArguments> IntoSystem<(Middle, AArguments, BArguments)> for PipeSystem<A, B, Middle, (AArguments, BArguments)>
+where
+    A: IntoPipeSystem<(), Middle, AArguments>,
+    B: IntoPipeSystem<Middle, (), BArguments>,
+{
+    fn run(&self, world: &World) {
+        self.run_with((), world)
+    }
+
+    fn validate(&self, world: &World) -> Result<()> {
+        IntoPipeSystem::validate(self, world)
+    }
+
+    fn access(&self) -> Vec<ParamAccess> {
+        IntoPipeSystem::access(self)
+    }
+
+    // only checks the accesses of each system on their own, since they don't run at the same time
+    fn check_access(&self) -> Result<()> {
+        check_conflicts(&self.first.access())?;
+        check_conflicts(&self.second.access())
+    }
+
+    fn name(&self) -> &'static str {
+        IntoPipeSystem::name(self)
+    }
+}
+
+// implements IntoPipeSystem for any function taking the given SystemParams, with and without an In parameter
+macro_rules! impl_into_pipe_system {
+    ($($param:ident),*) => {
+        impl<F, Output, $($param),*> IntoPipeSystem<(), Output, (Output, ($($param,)*))> for F
+        where
+            $($param: SystemParams,)*
+            F: Fn($($param),*) -> Output + for<'a> Fn($($param::Item<'a>),*) -> Output,
+        {
+            #[allow(unused_variables)]
+            fn run_with(&self, _input: (), world: &World) -> Output {
+                (self)($($param::get(world)),*)
+            }
+
+            #[allow(unused_variables)]
+            fn validate(&self, world: &World) -> Result<()> {
+                $($param::validate(world)?;)*
+                check_conflicts(&IntoPipeSystem::access(self))
+            }
+
+            fn access(&self) -> Vec<ParamAccess> {
+                vec![$(ParamAccess { param: std::any::type_name::<$param>(), access: $param::access() }),*]
+            }
+        }
+
+        impl<F, Input, Output, $($param),*> IntoPipeSystem<Input, Output, (In<Input>, Output, ($($param,)*))> for F
+        where
+            $($param: SystemParams,)*
+            F: Fn(In<Input>, $($param),*) -> Output + for<'a> Fn(In<Input>, $($param::Item<'a>),*) -> Output,
+        {
+            #[allow(unused_variables)]
+            fn run_with(&self, input: Input, world: &World) -> Output {
+                (self)(In(input), $($param::get(world)),*)
+            }
+
+            #[allow(unused_variables)]
+            fn validate(&self, world: &World) -> Result<()> {
+                $($param::validate(world)?;)*
+                check_conflicts(&IntoPipeSystem::access(self))
+            }
+
+            fn access(&self) -> Vec<ParamAccess> {
+                vec![$(ParamAccess { param: std::any::type_name::<$param>(), access: $param::access() }),*]
+            }
+        }
+    };
+}
+
+impl_into_pipe_system!();
+impl_into_pipe_system!(T1);
+impl_into_pipe_system!(T1, T2);
+impl_into_pipe_system!(T1, T2, T3);
+impl_into_pipe_system!(T1, T2, T3, T4);
+impl_into_pipe_system!(T1, T2, T3, T4, T5);
+impl_into_pipe_system!(T1, T2, T3, T4, T5, T6);
+impl_into_pipe_system!(T1, T2, T3, T4, T5, T6, T7);
+impl_into_pipe_system!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_into_pipe_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_into_pipe_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_into_pipe_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
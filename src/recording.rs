@@ -0,0 +1,231 @@
+//! # Recording
+//!
+//! The recording module keeps an append-only [MutationLog] of the structural changes made to the entities of a World,
+//! started with [World::enable_recording()]. Replaying the log on an empty World with [World::replay()] rebuilds the
+//! entities as they were when it was taken, which allows attaching the log to a bug report and running the exact same
+//! changes again while debugging.
+//!
+//! Every spawn and despawn is recorded, but the components are only recorded if their type was registered with
+//! [World::record_component()], since the log keeps a copy of their value every time they are inserted or replaced.
+//! Like the [journal](crate::journal), changing a component in place through a [RefMut](std::cell::RefMut) isn't
+//! recorded, and neither are resources.
+
+use std::{any::{Any, TypeId}, collections::HashMap, rc::Rc};
+
+use crate::{world::World, entities::{CloneFn, Entities, Entity}};
+use crate::error::Result;
+
+// inserts a recorded component into an entity, registering it's type if needed
+pub(crate) type ReplayFn = fn(&mut Entities, Entity, Box<dyn Any>) -> Result<()>;
+
+fn replay_component<T: Any>(entities: &mut Entities, entity: Entity, value: Box<dyn Any>) -> Result<()> {
+    let value = value.downcast::<T>().expect("component given to the replay function of another type");
+    entities.insert_component_into_entity_by_id_checked(*value, entity).map(|_| ())
+}
+
+/**
+The value of a component when it was inserted into an entity, kept by a [MutationLog].
+ */
+#[derive(Clone)]
+pub struct RecordedComponent {
+    type_id: TypeId,
+    name: &'static str,
+    // never changed, so that cloning a log only clones pointers to the values
+    value: Rc<dyn Any>,
+    clone: CloneFn,
+    replay: ReplayFn,
+}
+
+impl RecordedComponent {
+    pub(crate) fn new(type_id: TypeId, name: &'static str, value: Box<dyn Any>, clone: CloneFn, replay: ReplayFn) -> Self {
+        Self { type_id, name, value: Rc::from(value), clone, replay }
+    }
+
+    /// Returns the name of the component's type.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the TypeId of the component's type.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Returns the value of the component, or None if it isn't of type T.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref::<T>()
+    }
+
+    // inserts a copy of the value into 'entity'
+    pub(crate) fn replay(&self, entities: &mut Entities, entity: Entity) -> Result<()> {
+        (self.replay)(entities, entity, (self.clone)(&*self.value))
+    }
+}
+
+impl std::fmt::Debug for RecordedComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordedComponent").field("name", &self.name).finish_non_exhaustive()
+    }
+}
+
+/**
+A structural change made to the entities of a World, see [MutationLog].
+ */
+#[derive(Debug, Clone)]
+pub enum Mutation {
+    /// The entity got it's first component.
+    Spawn(Entity),
+    /// A component of a recorded type was inserted into the entity, or replaced the one it had.
+    Insert { entity: Entity, component: RecordedComponent },
+    /// A component of a recorded type was removed from the entity, which still has other components.
+    Remove { entity: Entity, type_id: TypeId, name: &'static str },
+    /// The entity lost all of it's components.
+    Despawn(Entity),
+}
+
+impl Mutation {
+    /// Returns the entity changed.
+    pub fn entity(&self) -> Entity {
+        match self {
+            Self::Spawn(entity) | Self::Despawn(entity) => *entity,
+            Self::Insert { entity, .. } | Self::Remove { entity, .. } => *entity,
+        }
+    }
+}
+
+/**
+The changes made to the entities of a World since [World::enable_recording()] was called, oldest first.
+The log starts with the entities that already existed at that point, so that replaying it on an empty World
+gives back the same entities.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct MutationLog {
+    mutations: Vec<Mutation>,
+}
+
+impl MutationLog {
+    /// Returns an iterator over the mutations, oldest first.
+    pub fn iter(&self) -> std::slice::Iter<'_, Mutation> {
+        self.mutations.iter()
+    }
+
+    /// Returns the number of mutations in the log.
+    pub fn len(&self) -> usize {
+        self.mutations.len()
+    }
+
+    /// Returns true if nothing was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.mutations.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, mutation: Mutation) {
+        self.mutations.push(mutation);
+    }
+}
+
+impl<'a> IntoIterator for &'a MutationLog {
+    type Item = &'a Mutation;
+    type IntoIter = std::slice::Iter<'a, Mutation>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// the component types recorded, and the log of the entities while they are recording
+#[derive(Debug, Default)]
+pub(crate) struct Recorder {
+    pub(crate) types: HashMap<TypeId, ReplayFn>,
+    pub(crate) log: Option<MutationLog>,
+}
+
+// Recording stuff
+impl World {
+    /**
+    Registers the component T so that it's value is part of the [MutationLog] every time it is inserted or replaced.
+     */
+    pub fn record_component<T: Any + Clone>(&mut self) -> &mut Self {
+        self.entities.type_registry_mut().register_clone::<T>();
+        self.entities.recorder_mut().types.insert(TypeId::of::<T>(), replay_component::<T>);
+        self
+    }
+
+    /**
+    Starts recording the changes made to the entities into a [MutationLog], beginning with the entities
+    that already exist. Does nothing if the World is already recording.
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Health(u8);
+    #[derive(Clone, Debug, PartialEq)]
+    struct Poisoned;
+
+    let mut world = World::new();
+    world.record_component::<Health>().record_component::<Poisoned>();
+    world.spawn().insert(Health(10));
+    world.enable_recording();
+
+    world.spawn().insert(Health(7)).insert(Poisoned);
+    world.insert_component_into_entity(Health(4), 1);
+    world.delete_component_from_ent::<Poisoned>(1);
+    world.despawn(0);
+
+    let log = world.disable_recording().unwrap();
+    assert!(matches!(log.iter().last(), Some(Mutation::Despawn(0))));
+
+    // the log can be replayed to get the same entities back
+    let mut replayed = World::new();
+    replayed.replay(&log);
+    assert!(!replayed.contains(0));
+    assert_eq!(*replayed.get::<&Health>(1).unwrap(), Health(4));
+    assert!(!replayed.entity_has::<Poisoned>(1));
+    ```
+     */
+    pub fn enable_recording(&mut self) -> &mut Self {
+        self.entities.start_recording();
+        self
+    }
+
+    /**
+    Stops recording, and returns the [MutationLog] recorded since [World::enable_recording()] was called,
+    or None if the World wasn't recording.
+     */
+    pub fn disable_recording(&mut self) -> Option<MutationLog> {
+        self.entities.recorder_mut().log.take()
+    }
+
+    /// Returns true if the changes made to the entities are being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.entities.recorder().log.is_some()
+    }
+
+    /// Returns the [MutationLog] recorded so far, or None if the World isn't recording.
+    pub fn mutation_log(&self) -> Option<&MutationLog> {
+        self.entities.recorder().log.as_ref()
+    }
+
+    /**
+    Applies every mutation of the log to the entities, in order. The changes made by hooks were recorded along
+    with the changes that ran them, so hooks aren't run again, but replaying the log is a single step of the
+    [journal](crate::journal). Panics if a mutation can't be applied, see [World::replay_checked()].
+     */
+    pub fn replay(&mut self, log: &MutationLog) {
+        self.replay_checked(log).unwrap()
+    }
+
+    /**
+    Same as [World::replay()], but returns an error if a component can't be inserted, which can happen when a
+    unique component is replayed on a World that isn't empty. The mutations before it are still applied.
+     */
+    pub fn replay_checked(&mut self, log: &MutationLog) -> Result<()> {
+        self.step(|world| {
+            let result = log.iter().try_for_each(|mutation| world.entities.apply_mutation(mutation));
+            world.entities.take_events();
+            world.entities.take_storage_events();
+            result
+        })
+    }
+}
@@ -0,0 +1,401 @@
+//! # Relationships
+//!
+//! The relationship module allows declaring that a component type links the entity holding it to another
+//! entity, it's target, with [World::register_relationship()]. When the target is despawned, the holder is
+//! either despawned along with it or loses the component, depending on the [RelationshipPolicy], so that
+//! no component is left pointing at a dead entity, or at another entity reusing it's id.
+//!
+//! The [Relation] component is a ready made relationship, registered with [World::register_relation()], whose
+//! target can also be cleared instead, which leaves the holder with a broken relation it can check for rather
+//! than a stale id. The entities whose relationships target an entity are found with [World::relations_to()].
+//!
+//! The holders of every relationship are indexed by their target, from the components written since the index was
+//! last updated, so that despawning an entity only visits the entities holding a relationship to it. This is done
+//! every time the World is changed through one of it's own methods, along with the [hooks](crate::hooks), so changes
+//! made through the [Entities](crate::entities::Entities) returned by [World::spawn()](struct.World.html#method.spawn)
+//! are only cleaned up the next time that happens, or when [World::run_hooks()](struct.World.html#method.run_hooks) is called.
+
+use std::{any::{Any, TypeId}, collections::{BTreeSet, HashMap}, marker::PhantomData, rc::Rc};
+
+use crate::{world::World, entities::Entity, hierarchy::Parent};
+
+/**
+What happens to the entities holding a relationship component when their target is despawned.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelationshipPolicy {
+    /// The holder is despawned along with it's target.
+    Despawn,
+    /// The relationship component is removed from the holder.
+    Remove,
+    /// The target of the [Relation] is cleared, and the holder keeps it. Other relationship components are removed,
+    /// like with [RelationshipPolicy::Remove].
+    Clear,
+}
+
+/**
+A component linking the entity holding it to a target entity, where T tells apart the different kinds of links,
+registered with [World::register_relation()]. Unlike an id stored in a component of it's own, the target can't
+outlive the entity it points to: depending on the [RelationshipPolicy], the holder is despawned, loses the relation,
+or keeps it with it's target cleared.
+
+```
+use sceller::prelude::*;
+
+struct Likes;
+
+let mut world = World::new();
+world.register_relation::<Likes>(RelationshipPolicy::Clear);
+
+let cake = world.spawn().insert(Name::new("cake")).current_entity();
+let alice = world.spawn().insert(Relation::<Likes>::new(cake)).current_entity();
+world.run_hooks();
+assert_eq!(world.get::<&Relation<Likes>>(alice).unwrap().target(), Some(cake));
+
+// the cake is eaten, and alice doesn't like the next entity to get it's id by mistake
+world.despawn(cake);
+let relation = *world.get::<&Relation<Likes>>(alice).unwrap();
+assert!(relation.is_broken());
+assert_eq!(relation.target(), None);
+```
+ */
+pub struct Relation<T> {
+    target: Option<Entity>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Relation<T> {
+    /// Creates a relation to 'target'.
+    pub fn new(target: Entity) -> Self {
+        Self { target: Some(target), marker: PhantomData }
+    }
+
+    /// Returns the target of the relation, or None if it was cleared when it's target was despawned.
+    pub fn target(&self) -> Option<Entity> {
+        self.target
+    }
+
+    /// Returns true if the target was cleared when it was despawned.
+    pub fn is_broken(&self) -> bool {
+        self.target.is_none()
+    }
+
+    /// Points the relation to another target, which also repairs a broken relation.
+    pub fn set_target(&mut self, target: Entity) {
+        self.target = Some(target);
+    }
+}
+
+impl<T> Clone for Relation<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Relation<T> {}
+
+impl<T> PartialEq for Relation<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.target == other.target
+    }
+}
+
+impl<T> Eq for Relation<T> {}
+
+impl<T> std::hash::Hash for Relation<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.target.hash(state)
+    }
+}
+
+impl<T> std::fmt::Debug for Relation<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple(&format!("Relation<{}>", crate::entities::short_type_name(std::any::type_name::<T>())))
+            .field(&self.target)
+            .finish()
+    }
+}
+
+/**
+An entity whose relationship component targets another entity, returned by [World::relations_to()].
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RelationTo {
+    pub holder: Entity,
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+}
+
+impl RelationTo {
+    /// Returns true if the relationship component is of type R.
+    pub fn is<R: Any>(&self) -> bool {
+        self.type_id == TypeId::of::<R>()
+    }
+}
+
+// the target of the relationship component of a holder, None if it has none or it's target was cleared
+type TargetFn = Rc<dyn Fn(&World, Entity) -> Option<Entity>>;
+
+// applies the policy of a relationship to a holder whose target was despawned
+type PolicyFn = Rc<dyn Fn(&mut World, Entity)>;
+
+// called with a holder and the living entity it stopped targeting, because it lost the component, was despawned or retargeted
+type UnlinkFn = fn(&mut World, Entity, Entity);
+
+// the relationship component types registered in the World
+pub(crate) struct Relationships {
+    relationships: Vec<Relationship>,
+    // false until the holders of every relationship are indexed, and again once the entities they were indexed from are replaced
+    indexed: bool,
+}
+
+struct Relationship {
+    type_id: TypeId,
+    name: &'static str,
+    target: TargetFn,
+    policy: PolicyFn,
+    unlink: Option<UnlinkFn>,
+    // the target every holder was indexed under, and the holders indexed under every target
+    targets: HashMap<Entity, Entity>,
+    indexed: HashMap<Entity, BTreeSet<Entity>>,
+}
+
+impl Relationship {
+    // R linking it's holder to the entity returned by 'target', which can be cleared by 'clear' if R allows it
+    fn new<R: Any>(target: impl Fn(&R) -> Option<Entity> + Copy + 'static, clear: Option<fn(&mut R)>, policy: RelationshipPolicy) -> Self {
+        let read: TargetFn = Rc::new(move |world: &World, holder: Entity| {
+            world.get::<&R>(holder).ok().and_then(|component| target(&component))
+        });
+
+        let apply: PolicyFn = Rc::new(move |world: &mut World, holder: Entity| {
+            // a holder can't fail to be despawned, lose R or be borrowed, since it was just found holding it
+            let _ = match (policy, clear) {
+                (RelationshipPolicy::Despawn, _) => world.entities.despawn_checked(holder).map(|_| ()),
+                (RelationshipPolicy::Clear, Some(clear)) => world.entities.try_get_component_mut::<R>(holder)
+                    .map(|mut component| clear(&mut component)),
+                _ => world.entities.delete_component_by_entity_id_checked::<R>(holder),
+            };
+        });
+
+        Self {
+            type_id: TypeId::of::<R>(),
+            name: std::any::type_name::<R>(),
+            target: read,
+            policy: apply,
+            unlink: None,
+            targets: HashMap::new(),
+            indexed: HashMap::new(),
+        }
+    }
+
+    // indexes 'holder' under 'target', or under nothing if it has none, and returns the target it was indexed under
+    fn link(&mut self, holder: Entity, target: Option<Entity>) -> Option<Entity> {
+        let old = match target {
+            Some(target) => self.targets.insert(holder, target),
+            None => self.targets.remove(&holder),
+        };
+        if old == target {
+            return old;
+        }
+        if let Some(old) = old {
+            if let Some(holders) = self.indexed.get_mut(&old) {
+                holders.remove(&holder);
+                if holders.is_empty() {
+                    self.indexed.remove(&old);
+                }
+            }
+        }
+        if let Some(target) = target {
+            self.indexed.entry(target).or_default().insert(holder);
+        }
+        old
+    }
+}
+
+impl Default for Relationships {
+    fn default() -> Self {
+        // the Parent of an entity is a relationship of every World, so that despawning either side of a link updates the other
+        let mut parent = Relationship::new(|parent: &Parent| Some(parent.0), None, RelationshipPolicy::Remove);
+        parent.unlink = Some(crate::hierarchy::remove_child);
+        Self { relationships: vec![parent], indexed: false }
+    }
+}
+
+impl std::fmt::Debug for Relationships {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.relationships.iter().map(|relationship| relationship.name)).finish()
+    }
+}
+
+impl Relationships {
+    // makes the index be rebuilt from every holder the next time it is updated, once the World's entities were replaced
+    pub(crate) fn reindex(&mut self) {
+        self.indexed = false;
+    }
+}
+
+// Relationship stuff
+impl World {
+    /**
+    Declares that the component R links the entity holding it to the entity returned by 'target',
+    and what to do with the holder when the target is despawned. Registering R again replaces it's policy.
+
+    ```
+    use sceller::prelude::*;
+
+    struct TargetOf(Entity);
+    struct Follows(Entity);
+
+    let mut world = World::new();
+    world.register_relationship(|target: &TargetOf| target.0, RelationshipPolicy::Despawn);
+    world.register_relationship(|follows: &Follows| follows.0, RelationshipPolicy::Remove);
+
+    let enemy = world.spawn().insert(Name::new("enemy")).current_entity();
+    let marker = world.spawn().insert(Name::new("marker")).insert(TargetOf(enemy)).current_entity();
+    let camera = world.spawn().insert(Name::new("camera")).insert(Follows(enemy)).current_entity();
+    world.run_hooks();
+
+    world.despawn(enemy);
+    assert!(world.get::<&Name>(marker).is_err());
+    assert!(world.get::<&Name>(camera).is_ok());
+    assert!(world.get::<&Follows>(camera).is_err());
+    ```
+     */
+    pub fn register_relationship<R: Any>(&mut self, target: fn(&R) -> Entity, policy: RelationshipPolicy) -> &mut Self {
+        self.add_relationship(move |component: &R| Some(target(component)), None, policy)
+    }
+
+    /**
+    Registers the [Relation] of kind T, and what to do with the holder when the target is despawned.
+    Registering it again replaces it's policy, see [World::register_relationship()].
+     */
+    pub fn register_relation<T: 'static>(&mut self, policy: RelationshipPolicy) -> &mut Self {
+        self.add_relationship(Relation::<T>::target, Some(|relation: &mut Relation<T>| relation.target = None), policy)
+    }
+
+    /**
+    Returns the entities whose registered relationship components target 'entity', in the order the relationships
+    were registered in, then in ascending order of holder. The children of the entity are among them, since the
+    [Parent](crate::hierarchy::Parent) of an entity is a relationship of every World. The holders are looked up in the index of every relationship,
+    along with the components written since it was last updated, rather than by going through every holder.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Likes;
+    struct Follows(Entity);
+
+    let mut world = World::new();
+    world.register_relation::<Likes>(RelationshipPolicy::Remove);
+    world.register_relationship(|follows: &Follows| follows.0, RelationshipPolicy::Remove);
+
+    let player = world.spawn().insert(Name::new("player")).current_entity();
+    let fan = world.spawn().insert(Relation::<Likes>::new(player)).current_entity();
+    let camera = world.spawn().insert(Follows(player)).current_entity();
+
+    let holders = world.relations_to(player);
+    assert_eq!(holders.iter().map(|relation| relation.holder).collect::<Vec<Entity>>(), vec![fan, camera]);
+    assert!(holders[0].is::<Relation<Likes>>());
+    assert!(world.relations_to(fan).is_empty());
+    ```
+     */
+    pub fn relations_to(&self, entity: Entity) -> Vec<RelationTo> {
+        self.relationships.relationships.iter()
+            .flat_map(|relationship| {
+                // the holders whose component was written since the index was updated may target the entity as well
+                let holders = match self.relationships.indexed {
+                    true => relationship.indexed.get(&entity).into_iter().flatten().copied()
+                        .chain(self.entities.writes(relationship.type_id))
+                        .collect::<BTreeSet<Entity>>(),
+                    false => self.entities.get_bitmask(&relationship.type_id)
+                        .map(|mask| self.entities.matching_entities(mask).into_iter().collect())
+                        .unwrap_or_default(),
+                };
+                holders.into_iter()
+                    .filter(|holder| (relationship.target)(self, *holder) == Some(entity))
+                    .map(|holder| RelationTo { holder, type_id: relationship.type_id, type_name: relationship.name })
+            })
+            .collect()
+    }
+
+    // registers R as linking it's holder to the entity returned by 'target', which can be cleared by 'clear' if R allows it
+    fn add_relationship<R: Any>(&mut self, target: impl Fn(&R) -> Option<Entity> + Copy + 'static, clear: Option<fn(&mut R)>, policy: RelationshipPolicy) -> &mut Self {
+        let mut relationship = Relationship::new(target, clear, policy);
+        let type_id = relationship.type_id;
+        // registering R again keeps what happens when a holder stops targeting an entity, like a child leaving it's parent
+        if let Some(old) = self.relationships.relationships.iter().find(|relationship| relationship.type_id == type_id) {
+            relationship.unlink = old.unlink;
+        }
+        self.relationships.relationships.retain(|relationship| relationship.type_id != type_id);
+        self.relationships.relationships.push(relationship);
+        // the holders that already exist are indexed along with the others
+        self.relationships.reindex();
+        self
+    }
+
+    /*
+        updates the index of the holders of every relationship with the components written since it was last updated,
+        then applies the policy of the relationships whose target was despawned since then, or is already dead. Returns
+        true if a policy was applied, which can leave the relationships targeting the holders dead as well
+    */
+    pub(crate) fn update_relationships(&mut self) -> bool {
+        if self.relationships.relationships.is_empty() {
+            return false;
+        }
+        let rebuild = !self.relationships.indexed;
+        if rebuild {
+            self.entities.track_despawns();
+            self.relationships.indexed = true;
+        }
+        let despawned = self.entities.take_despawned().into_iter().collect::<BTreeSet<Entity>>();
+
+        let mut relationships = std::mem::take(&mut self.relationships.relationships);
+        let mut stale = Vec::new();
+        for (index, relationship) in relationships.iter_mut().enumerate() {
+            let written = if rebuild {
+                relationship.targets.clear();
+                relationship.indexed.clear();
+                self.entities.log_writes(relationship.type_id);
+                self.entities.take_writes(relationship.type_id);
+                self.entities.get_bitmask(&relationship.type_id)
+                    .map(|mask| self.entities.matching_entities(mask))
+                    .unwrap_or_default()
+            } else {
+                self.entities.take_writes(relationship.type_id)
+            };
+            let written = written.into_iter().collect::<BTreeSet<Entity>>();
+
+            // the holders indexed under a despawned entity were linked to it before it was despawned, even if it's id was taken since
+            let mut holders = despawned.iter()
+                .filter_map(|target| relationship.indexed.get(target))
+                .flatten()
+                .filter(|holder| !written.contains(holder))
+                .copied()
+                .collect::<BTreeSet<Entity>>();
+
+            for holder in written.union(&despawned) {
+                let target = (relationship.target)(self, *holder);
+                let old = relationship.link(*holder, target);
+                if let (Some(unlink), Some(old)) = (relationship.unlink, old) {
+                    if Some(old) != target && self.entities.is_alive(old) {
+                        unlink(self, *holder, old);
+                    }
+                }
+                if target.is_some_and(|target| !self.entities.is_alive(target)) {
+                    holders.insert(*holder);
+                }
+            }
+            stale.extend(holders.into_iter().map(|holder| (index, holder)));
+        }
+
+        for (index, holder) in &stale {
+            let relationship = &mut relationships[*index];
+            if (relationship.target)(self, *holder).is_some() {
+                (relationship.policy)(self, *holder);
+            }
+            relationship.link(*holder, None);
+        }
+        self.relationships.relationships = relationships;
+        !stale.is_empty()
+    }
+}
@@ -0,0 +1,256 @@
+//! # Replication
+//!
+//! Only available with the 'serde' feature.
+//!
+//! The replication module keeps another World, usually on the other end of a socket, up to date with the components
+//! registered with [World::register_replicated_component()]. [World::drain_replication_messages()] compares the
+//! replicated components of every entity with the ones sent the last time it was called, and returns a
+//! [ReplicationMessage] for every entity that was spawned, changed or despawned since then, which can be serialized
+//! and sent as is. The receiving World applies them with [World::apply_replication_message()], spawning it's own
+//! entity for every entity of the sender.
+//!
+//! Component values are sent as RON strings keyed by the [std::any::type_name] of their type, so both sides should
+//! be built from the same version of the program. Components holding the ids of other entities aren't remapped.
+
+use std::{any::Any, collections::BTreeMap};
+
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+
+use crate::{world::World, entities::Entity, serialization::SerializationError};
+use crate::error::Result;
+
+/**
+The changes made to one entity's replicated components, returned by [World::drain_replication_messages()].
+Components are keyed by their type name, and their values are RON strings.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplicationMessage {
+    /// The entity got it's first replicated component.
+    Spawn { entity: Entity, components: BTreeMap<String, String> },
+    /// Some of the entity's replicated components were inserted, changed or removed.
+    Update { entity: Entity, changed: BTreeMap<String, String>, removed: Vec<String> },
+    /// The entity was despawned, or lost all of it's replicated components.
+    Despawn { entity: Entity },
+}
+
+impl ReplicationMessage {
+    /// Returns the id of the entity on the side that sent the message.
+    pub fn entity(&self) -> Entity {
+        match self {
+            Self::Spawn { entity, .. } | Self::Update { entity, .. } | Self::Despawn { entity } => *entity,
+        }
+    }
+}
+
+struct ReplicatedFns {
+    name: &'static str,
+    save: fn(&World, Entity) -> Result<Option<String>>,
+    load: fn(&mut World, Entity, &str) -> Result<()>,
+    remove: fn(&mut World, Entity) -> Result<()>,
+}
+
+// the replicated component types, and what was sent and received so far
+#[derive(Default)]
+pub(crate) struct Replication {
+    types: Vec<ReplicatedFns>,
+    // the replicated components of every entity in the last messages drained
+    sent: BTreeMap<Entity, BTreeMap<String, String>>,
+    // the entity spawned here for every entity of the sender
+    remote: BTreeMap<Entity, Entity>,
+}
+
+impl std::fmt::Debug for Replication {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Replication")
+            .field("types", &self.types.iter().map(|fns| fns.name).collect::<Vec<_>>())
+            .field("sent", &self.sent.len())
+            .field("remote", &self.remote)
+            .finish()
+    }
+}
+
+impl Replication {
+    // forgets the entity spawned here for every entity of the sender, once they were cleared
+    pub(crate) fn forget_remote_entities(&mut self) {
+        self.remote.clear();
+    }
+}
+
+fn save_component<T: Any + Serialize>(world: &World, entity: Entity) -> Result<Option<String>> {
+    match world.get::<&T>(entity) {
+        Ok(component) => Ok(Some(ron::to_string(&*component)?)),
+        Err(_) => Ok(None),
+    }
+}
+
+fn load_component<T: Any + DeserializeOwned>(world: &mut World, entity: Entity, value: &str) -> Result<()> {
+    let data: T = ron::from_str(value)?;
+    world.insert_component_into_entity_checked(data, entity).map(|_| ())
+}
+
+fn remove_component<T: Any>(world: &mut World, entity: Entity) -> Result<()> {
+    match world.entity_has::<T>(entity) {
+        true => world.delete_component_from_ent_checked::<T>(entity),
+        false => Ok(()),
+    }
+}
+
+// Replication stuff
+impl World {
+    /**
+    Registers a component type so that it's changes are part of the [ReplicationMessage]s of the World, and so that
+    the World can apply the ones it receives. Registering the same type twice does nothing.
+     */
+    pub fn register_replicated_component<T>(&mut self) -> &mut Self
+    where
+        T: Any + Serialize + DeserializeOwned
+    {
+        let name = std::any::type_name::<T>();
+        if !self.replication.types.iter().any(|fns| fns.name == name) {
+            self.replication.types.push(ReplicatedFns {
+                name,
+                save: save_component::<T>,
+                load: load_component::<T>,
+                remove: remove_component::<T>,
+            });
+        }
+        self
+    }
+
+    /**
+    Returns a [ReplicationMessage] for every entity whose replicated components changed since the last call,
+    in ascending order of entity id, the despawned entities first. Panics if a component can't be serialized.
+
+    ```
+    use sceller::prelude::*;
+    use sceller::replication::ReplicationMessage;
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Position(i32, i32);
+
+    let mut server = World::new();
+    let mut client = World::new();
+    server.register_replicated_component::<Position>();
+    client.register_replicated_component::<Position>();
+
+    server.spawn().insert(Position(0, 0));
+    for message in server.drain_replication_messages() {
+        client.apply_replication_message(&message);
+    }
+
+    server.get::<&mut Position>(0).unwrap().0 = 3;
+    let messages = server.drain_replication_messages();
+    assert!(matches!(&messages[..], [ReplicationMessage::Update { entity: 0, .. }]));
+
+    // the messages can be sent over a socket as RON, or any other format serde supports
+    let sent = ron::to_string(&messages).unwrap();
+    for message in ron::from_str::<Vec<ReplicationMessage>>(&sent).unwrap() {
+        client.apply_replication_message(&message);
+    }
+    assert_eq!(*client.get::<&Position>(0).unwrap(), Position(3, 0));
+    assert!(server.drain_replication_messages().is_empty());
+    ```
+     */
+    pub fn drain_replication_messages(&mut self) -> Vec<ReplicationMessage> {
+        self.drain_replication_messages_checked().unwrap()
+    }
+
+    /**
+    Same as [World::drain_replication_messages()], but returns an error if a component can't be serialized,
+    in which case nothing is considered sent.
+     */
+    pub fn drain_replication_messages_checked(&mut self) -> Result<Vec<ReplicationMessage>> {
+        let mut current = BTreeMap::new();
+        for (entity, _) in self.entities.iter_ids() {
+            let mut components = BTreeMap::new();
+            for fns in &self.replication.types {
+                if let Some(value) = (fns.save)(self, entity)? {
+                    components.insert(fns.name.to_owned(), value);
+                }
+            }
+            if !components.is_empty() {
+                current.insert(entity, components);
+            }
+        }
+
+        let sent = &self.replication.sent;
+        let mut messages = sent.keys()
+            .filter(|entity| !current.contains_key(entity))
+            .map(|entity| ReplicationMessage::Despawn { entity: *entity })
+            .collect::<Vec<ReplicationMessage>>();
+
+        for (entity, components) in &current {
+            let Some(old) = sent.get(entity) else {
+                messages.push(ReplicationMessage::Spawn { entity: *entity, components: components.clone() });
+                continue;
+            };
+
+            let changed = components.iter()
+                .filter(|(name, value)| old.get(*name) != Some(value))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect::<BTreeMap<String, String>>();
+            let removed = old.keys()
+                .filter(|name| !components.contains_key(*name))
+                .cloned()
+                .collect::<Vec<String>>();
+            if !changed.is_empty() || !removed.is_empty() {
+                messages.push(ReplicationMessage::Update { entity: *entity, changed, removed });
+            }
+        }
+
+        self.replication.sent = current;
+        Ok(messages)
+    }
+
+    /**
+    Applies a message drained from another World, spawning, changing or despawning the entity replicating the
+    sender's entity. Panics if the message can't be applied, see [World::apply_replication_message_checked()].
+     */
+    pub fn apply_replication_message(&mut self, message: &ReplicationMessage) {
+        self.apply_replication_message_checked(message).unwrap()
+    }
+
+    /**
+    Same as [World::apply_replication_message()], but returns an error if the message contains a type that isn't
+    registered here, a value that can't be deserialized, or changes an entity that was never spawned here.
+     */
+    pub fn apply_replication_message_checked(&mut self, message: &ReplicationMessage) -> Result<()> {
+        let remote = message.entity();
+        match message {
+            ReplicationMessage::Spawn { components, .. } => {
+                let local = match self.replication.remote.get(&remote) {
+                    Some(local) => *local,
+                    None => self.spawn().current_entity(),
+                };
+                self.replication.remote.insert(remote, local);
+                for (name, value) in components {
+                    (self.replicated_fns(name)?.load)(self, local, value)?;
+                }
+            },
+            ReplicationMessage::Update { changed, removed, .. } => {
+                let local = *self.replication.remote.get(&remote).ok_or(SerializationError::UnknownRemoteEntityError(remote))?;
+                for (name, value) in changed {
+                    (self.replicated_fns(name)?.load)(self, local, value)?;
+                }
+                for name in removed {
+                    (self.replicated_fns(name)?.remove)(self, local)?;
+                }
+            },
+            ReplicationMessage::Despawn { .. } => {
+                let local = self.replication.remote.remove(&remote).ok_or(SerializationError::UnknownRemoteEntityError(remote))?;
+                if self.contains(local) {
+                    self.despawn_checked(local)?;
+                }
+            },
+        }
+        Ok(())
+    }
+
+    // the functions of a replicated component type from it's name
+    fn replicated_fns(&self, name: &str) -> Result<&ReplicatedFns> {
+        self.replication.types.iter()
+            .find(|fns| fns.name == name)
+            .ok_or_else(|| SerializationError::UnregisteredTypeError(name.to_owned()).into())
+    }
+}
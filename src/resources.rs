@@ -5,6 +5,34 @@
 //! anywhere at any time. Importantly, there can only be ONE of a given resource.
 
 use std::{any::{Any, TypeId}, collections::HashMap, rc::Rc, cell::{RefCell, Ref, RefMut}};
+use crate::error::Result;
+
+/**
+A marker trait for the types stored as resources of a [World](crate::world::World), required by
+[World::insert_resource()](crate::world::World::insert_resource), [Res](crate::system::Res) and the
+other resource functions of the World, so that using a component type as a resource by mistake
+fails to compile instead of failing to find it at runtime.
+
+It is implemented with `#[derive(Resource)]` when the `derive` feature is enabled, or by hand:
+
+```
+use sceller::prelude::*;
+
+struct Score(u32);
+impl Resource for Score {}
+
+let mut world = World::new();
+world.insert_resource(Score(0));
+world.run_system(|mut score: ResMut<Score>| score.0 += 10);
+assert_eq!(world.get_resource::<Score>().unwrap().0, 10);
+```
+
+The [Resources] storage itself takes any type, with or without the trait.
+ */
+pub trait Resource: Any {}
+
+#[cfg(feature = "derive")]
+pub use sceller_derive::Resource;
 
 #[derive(Default, Debug)]
 /**
@@ -14,7 +42,10 @@ A struct storing a hashmap of type id and value pairs. It is used as a resource
 the ecs.
  */
 pub struct Resources {
-    values: HashMap<TypeId, Rc<RefCell<dyn Any>>>
+    // every value is an Rc<RefCell<T>>, T being the type of the resource
+    values: HashMap<TypeId, Rc<dyn Any>>,
+    // the type name of every resource, recorded when it is added
+    names: HashMap<TypeId, &'static str>,
 }
 
 impl Resources {
@@ -53,6 +84,7 @@ impl Resources {
      */
     pub fn add<T: Any>(&mut self, res: T) {
         self.values.insert(TypeId::of::<T>(), Rc::new(RefCell::new(res)));
+        self.names.insert(TypeId::of::<T>(), std::any::type_name::<T>());
     }
 
     /**
@@ -73,13 +105,11 @@ impl Resources {
     assert_eq!(extracted_health.0, 42.0);
     ```
      */
-    pub fn get_ref<T: Any>(&self) -> eyre::Result<Ref<T>> {
+    pub fn get_ref<T: Any>(&self) -> Result<Ref<'_, T>> {
         let type_id = TypeId::of::<T>();
         if let Some(data) = self.values.get(&type_id) {
-            let rf = data.as_ref();
-            let borrow = rf.borrow();
-            // borrow.downcast_ref::<T>().ok_or(ResourcesError::NonexistentResourceError.into())
-            Ok(std::cell::Ref::map(borrow, |any| any.downcast_ref::<T>().unwrap()))
+            let rf = data.as_ref().downcast_ref::<RefCell<T>>().unwrap();
+            Ok(rf.borrow())
         } else {
             Err(ResourcesError::NonexistentResourceError.into())
         }
@@ -107,16 +137,58 @@ impl Resources {
     assert_eq!(hp.0, 42);
     ```
      */
-    pub fn get_mut<T: Any>(&self) -> eyre::Result<RefMut<T>> {
+    pub fn get_mut<T: Any>(&self) -> Result<RefMut<'_, T>> {
         if let Some(data) = self.values.get(&TypeId::of::<T>()) {
-            let rf = data.as_ref();
-            let borrow = rf.borrow_mut();
-            Ok(RefMut::map(borrow, |any| any.downcast_mut::<T>().unwrap()))
+            let rf = data.as_ref().downcast_ref::<RefCell<T>>().unwrap();
+            Ok(rf.borrow_mut())
         } else {
             Err(ResourcesError::NonexistentResourceError.into())
         }
     }
 
+    /**
+    Returns true if a resource of the given type exists, without borrowing it.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(i32);
+
+    let mut resources = Resources::new();
+    assert!(!resources.contains::<Health>());
+
+    resources.add(Health(123));
+    assert!(resources.contains::<Health>());
+    ```
+     */
+    pub fn contains<T: Any>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    /**
+    Returns a mutable reference to the resource of the given type, inserting the value returned by 'f'
+    first if it doesn't exist yet. 'f' is only called when the resource is missing.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Cache(Vec<u32>);
+
+    let mut resources = Resources::new();
+
+    resources.get_resource_or_insert_with(|| Cache(vec![1])).0.push(2);
+    resources.get_resource_or_insert_with(|| Cache(vec![])).0.push(3);
+
+    assert_eq!(resources.get_ref::<Cache>().unwrap().0, vec![1, 2, 3]);
+    ```
+     */
+    pub fn get_resource_or_insert_with<T: Any>(&mut self, f: impl FnOnce() -> T) -> RefMut<'_, T> {
+        if !self.contains::<T>() {
+            self.add(f());
+        }
+        self.get_mut::<T>().unwrap()
+    }
+
     /**
     Attempts to delete and return a resource. 
     
@@ -166,29 +238,104 @@ impl Resources {
     assert!(!res.is_ok());
     ```
      */
-    pub fn delete<T: Any>(&mut self) -> eyre::Result<T> {
-        if let Some(data) = self.values.remove(&TypeId::of::<T>())
-        {
-            Ok(
-                RefCell::into_inner(Rc::try_unwrap(downcast_t::<T>(data)).unwrap_or_else(|_| panic!("When removing resource it somehow failed to have the correct type, causing a segfault. bad, very bad")))
-            )
-        } else {
-            Err(ResourcesError::NonexistentResourceError.into())
+    pub fn delete<T: Any>(&mut self) -> Result<T> {
+        let type_id = TypeId::of::<T>();
+        let data = self.values.remove(&type_id).ok_or(ResourcesError::NonexistentResourceError)?;
+
+        let rc = data.downcast::<RefCell<T>>()
+            .unwrap_or_else(|_| panic!("Resource stored under the type id of {} has a different type", std::any::type_name::<T>()));
+
+        match Rc::try_unwrap(rc) {
+            Ok(cell) => {
+                self.names.remove(&type_id);
+                Ok(cell.into_inner())
+            }
+            Err(rc) => {
+                // still shared elsewhere, put it back so that nothing is lost
+                self.values.insert(type_id, rc);
+                Err(ResourcesError::BorrowedResourceError.into())
+            }
         }
     }
-}
 
-fn downcast_t<T: Any>(
-  rc: Rc<RefCell<dyn Any>>,
-) -> Rc<RefCell<T>> {
-    unsafe {
-        fn _sanity_check(rc: Rc<RefCell<impl Any>>) 
-        -> Rc<RefCell<dyn Any>> 
-        {
-            rc
+    /**
+    Returns the number of resources stored.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut resources = Resources::new();
+    assert_eq!(resources.len(), 0);
+
+    resources.add(Health(1));
+    resources.add(5u32);
+    assert_eq!(resources.len(), 2);
+    ```
+     */
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /**
+    Returns true if no resources are stored.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /**
+    Returns the type names of every resource stored, in alphabetical order.
+
+    ```
+    use sceller::prelude::*;
+
+    let mut resources = Resources::new();
+    resources.add(5u32);
+    resources.add(String::from("hi"));
+
+    assert_eq!(resources.type_names(), vec!["alloc::string::String", "u32"]);
+    ```
+     */
+    pub fn type_names(&self) -> Vec<&'static str> {
+        let mut names = self.names.values().copied().collect::<Vec<&'static str>>();
+        names.sort_unstable();
+        names
+    }
+
+    // the RefCell holding the resource of the type 'type_id', if it exists
+    pub(crate) fn raw(&self, type_id: &TypeId) -> Option<&dyn Any> {
+        self.values.get(type_id).map(|value| value.as_ref())
+    }
+
+    // removes every resource, with it's TypeId and type name
+    pub(crate) fn drain(&mut self) -> Vec<(TypeId, &'static str, Rc<dyn Any>)> {
+        self.values.drain()
+            .map(|(type_id, value)| (type_id, self.names.remove(&type_id).unwrap_or_default(), value))
+            .collect()
+    }
+
+    // the type name of the resource stored under 'type_id', if it exists
+    pub(crate) fn name_of(&self, type_id: &TypeId) -> Option<&'static str> {
+        self.names.get(type_id).copied()
+    }
+
+    /**
+    Replaces the type erased resource stored under 'type_id' with 'value', adding or removing it, 
+    and returns the resource it had before. 'value' must be an Rc<RefCell<T>>, T being the type of the resource.
+     */
+    pub(crate) fn swap_raw(&mut self, type_id: TypeId, name: &'static str, value: Option<Rc<dyn Any>>) -> Option<Rc<dyn Any>> {
+        match value {
+            Some(value) => {
+                self.names.insert(type_id, name);
+                self.values.insert(type_id, value)
+            },
+            None => {
+                self.names.remove(&type_id);
+                self.values.remove(&type_id)
+            },
         }
-        
-        Rc::from_raw(Rc::into_raw(rc) as *const RefCell<T>)
     }
 }
 
@@ -196,6 +343,12 @@ fn downcast_t<T: Any>(
 pub enum ResourcesError {
     #[error("Attempt to access non existent resource.")]
     NonexistentResourceError,
+    #[error("Attempt to remove a resource that is still borrowed elsewhere.")]
+    BorrowedResourceError,
+    #[error("Both Worlds being merged have the resource {0}.")]
+    ConflictingResourceError(&'static str),
+    #[error("The resource {0} was inserted while World::resource_scope() had taken it out, it was replaced by the scoped one.")]
+    ScopedResourceInsertedError(&'static str),
 }
 
 // Trait implementations
@@ -217,7 +370,7 @@ mod tests {
         resources.add(thing);
 
         let retreived_thing = resources.values.get(&TypeId::of::<Thing>()).unwrap();
-        let thing2 = std::cell::Ref::map(retreived_thing.as_ref().borrow(), |any| any.downcast_ref::<Thing>().unwrap());
+        let thing2 = retreived_thing.as_ref().downcast_ref::<RefCell<Thing>>().unwrap().borrow();
         assert_eq!(thing2.0, 12);
     }
 
@@ -246,7 +399,7 @@ mod tests {
     }
 
     #[test]
-    fn delete_resource() -> eyre::Result<()> {
+    fn delete_resource() -> Result<()> {
         let mut resources = init_resources();
 
         resources.delete::<Thing>()?;
@@ -255,6 +408,18 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn delete_shared_resource() {
+        let mut resources = init_resources();
+
+        let shared = Rc::clone(resources.values.get(&TypeId::of::<Thing>()).unwrap());
+        assert!(resources.delete::<Thing>().is_err());
+        assert_eq!(resources.get_ref::<Thing>().unwrap().0, 10);
+
+        drop(shared);
+        assert_eq!(resources.delete::<Thing>().unwrap(), Thing(10));
+    }
+
     fn init_resources() -> Resources {
         let mut res = Resources::new();
 
@@ -0,0 +1,165 @@
+//! # Rollback
+//!
+//! The rollback module allows saving the state of a World into a [RollbackSnapshot] and going back to it later
+//! with [World::rollback()], which is what rollback netcode does every time a late input arrives: the game goes back
+//! to the last confirmed frame, and simulates the frames since then again with the corrected inputs.
+//!
+//! Unlike the snapshots of the `serde` feature, nothing is serialized: the components whose type has a clone
+//! function in the [TypeRegistry](crate::entities::TypeRegistry), registered with
+//! [World::register_rollback_component()] or [World::journal_component()](struct.World.html#method.journal_component),
+//! are cloned, along with the resources registered with [World::register_rollback_resource()].
+//! The values stored in a snapshot are never changed, so cloning a snapshot only clones pointers to them.
+
+use std::{any::{Any, TypeId}, cell::RefCell, rc::Rc};
+
+use crate::{world::World, entities::Entity, resources::Resource};
+
+// clones the RefCell<T> stored by the Resources for a resource of type T
+type ResourceCloneFn = fn(&dyn Any) -> Rc<dyn Any>;
+
+fn clone_resource<T: Any + Clone>(resource: &dyn Any) -> Rc<dyn Any> {
+    let resource = resource.downcast_ref::<RefCell<T>>().expect("resource given to the clone function of another type");
+    Rc::new(RefCell::new(resource.borrow().clone()))
+}
+
+// a component or resource saved in a snapshot along with it's type
+type SavedValue = (TypeId, Rc<dyn Any>);
+
+// the resource types registered for rollback
+#[derive(Debug, Default)]
+pub(crate) struct RollbackResources {
+    resources: Vec<(TypeId, &'static str, ResourceCloneFn)>,
+}
+
+/**
+The state of a World at one point in time, created with [World::rollback_snapshot()].
+ */
+#[derive(Debug, Clone, Default)]
+pub struct RollbackSnapshot {
+    // every living entity, with it's components that can be cloned
+    entities: Vec<(Entity, Vec<SavedValue>)>,
+    // the registered resources that existed, RefCells as stored by the Resources
+    resources: Vec<SavedValue>,
+}
+
+impl RollbackSnapshot {
+    /// Returns the ids of the entities saved in the snapshot.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter().map(|(entity, _)| *entity)
+    }
+
+    /// Returns the number of components saved in the snapshot.
+    pub fn component_count(&self) -> usize {
+        self.entities.iter().map(|(_, components)| components.len()).sum()
+    }
+}
+
+// Rollback stuff
+impl World {
+    /**
+    Registers the component T so that it is saved in [RollbackSnapshot]s.
+     */
+    pub fn register_rollback_component<T: Any + Clone>(&mut self) -> &mut Self {
+        self.entities.type_registry_mut().register_clone::<T>();
+        self
+    }
+
+    /**
+    Registers the resource T so that it is saved in [RollbackSnapshot]s.
+     */
+    pub fn register_rollback_resource<T: Resource + Clone>(&mut self) -> &mut Self {
+        let type_id = TypeId::of::<T>();
+        if !self.rollback.resources.iter().any(|(registered, _, _)| *registered == type_id) {
+            self.rollback.resources.push((type_id, std::any::type_name::<T>(), clone_resource::<T>));
+        }
+        self
+    }
+
+    /**
+    Saves every living entity, with it's components registered for rollback, and the resources registered
+    for rollback into a [RollbackSnapshot].
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Clone)]
+    struct Position(i32);
+    #[derive(Clone)]
+    struct Frame(u32);
+    impl Resource for Frame {}
+
+    let mut world = World::new();
+    world.register_rollback_component::<Position>();
+    world.register_rollback_resource::<Frame>();
+    world.spawn().insert(Position(0));
+    world.insert_resource(Frame(0));
+
+    let confirmed = world.rollback_snapshot();
+    for _ in 0..3 {
+        world.get::<&mut Position>(0).unwrap().0 += 2;
+        world.get_resource_mut::<Frame>().unwrap().0 += 1;
+    }
+    world.spawn().insert(Position(100));
+
+    world.rollback(&confirmed);
+    assert_eq!(world.get::<&Position>(0).unwrap().0, 0);
+    assert_eq!(world.get_resource::<Frame>().unwrap().0, 0);
+    assert!(!world.contains(1));
+    ```
+     */
+    pub fn rollback_snapshot(&self) -> RollbackSnapshot {
+        let registry = self.entities.type_registry();
+        let entities = self.entities.iter_ids()
+            .map(|(entity, _)| {
+                let components = self.entities.component_types(entity).into_iter()
+                    .filter_map(|type_id| {
+                        let clone = registry.get(&type_id)?.clone?;
+                        let component = self.entities.component_at_id(self.entities.component_id(&type_id)?, entity)?;
+                        let value: Rc<dyn Any> = Rc::from(clone(&*component.borrow()));
+                        Some((type_id, value))
+                    })
+                    .collect();
+                (entity, components)
+            })
+            .collect();
+
+        let resources = self.rollback.resources.iter()
+            .filter_map(|(type_id, _, clone)| Some((*type_id, clone(self.resources.raw(type_id)?))))
+            .collect();
+
+        RollbackSnapshot { entities, resources }
+    }
+
+    /**
+    Puts the World back in the state saved in the snapshot. The entities that didn't exist when it was taken
+    are despawned, and the entities that did get the components they had back, at the same ids, without the
+    components that weren't registered for rollback. The resources registered for rollback that didn't exist
+    are deleted, and the other resources are left untouched.
+
+    Hooks aren't run for the components changed by a rollback, and the steps of the [journal](crate::journal) are forgotten.
+     */
+    pub fn rollback(&mut self, snapshot: &RollbackSnapshot) {
+        self.run_hooks();
+        self.entities.clear();
+        for (entity, components) in &snapshot.entities {
+            self.entities.create_entity_at(*entity);
+            for (type_id, value) in components {
+                let Some(clone) = self.entities.type_registry().get(type_id).and_then(|registration| registration.clone) else {
+                    continue;
+                };
+                self.entities.swap_component(*entity, *type_id, Some(clone(&**value)));
+            }
+        }
+        self.entities.take_events();
+        self.entities.take_storage_events();
+        self.relationships.reindex();
+        self.clear_journal();
+
+        for (type_id, name, clone) in &self.rollback.resources {
+            let value = snapshot.resources.iter()
+                .find(|(saved, _)| saved == type_id)
+                .map(|(_, value)| clone(&**value));
+            self.resources.swap_raw(*type_id, name, value);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! # Scene
+//!
+//! Only available with the 'serde' feature.
+//!
+//! The scene module adds [Scene], a group of entities described as data, which can be loaded from
+//! [RON](https://github.com/ron-rs/ron) (or anything else serde can read) and spawned into a World any number
+//! of times with [World::spawn_scene()], for example to build levels or prefabs outside of the code.
+//!
+//! Entities in a scene have ids that are local to the scene. When it is spawned every entity gets a new id in
+//! the World, and the components that refer to other entities, such as [Parent] and [Children], are updated
+//! to point to the new ids. Those components have to implement [MapEntities] and be registered with
+//! [World::register_serde_component_with_entities()].
+
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+    entities::Entity,
+    hierarchy::{Children, Parent},
+    serialization::{EntitySnapshot, WorldSnapshot},
+    world::World,
+};
+use crate::error::Result;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/**
+A group of entities and their components, which can be spawned into a World with [World::spawn_scene()].
+
+Like in a [WorldSnapshot], every component value is a RON string keyed by the name of it's type,
+which can be written without it's module path.
+
+```
+use sceller::prelude::*;
+use sceller::scene::Scene;
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize)]
+struct Health(u8);
+
+let scene = Scene::from_ron(r#"(
+    entities: [
+        (id: 0, components: { "Health": "(10)" }),
+        (id: 1, components: { "Health": "(25)", "sceller::name::Name": "(\"boss\")" }),
+    ],
+)"#).unwrap();
+
+let mut world = World::new();
+world.register_serde_component::<Health>();
+world.register_serde_component::<Name>();
+
+world.spawn_scene(&scene).unwrap();
+world.spawn_scene(&scene).unwrap();
+
+assert_eq!(world.query_typed::<&Health>().count(), 4);
+assert_eq!(world.get::<&Health>(world.find_by_name("boss").unwrap()).unwrap().0, 25);
+```
+ */
+pub struct Scene {
+    pub entities: Vec<EntitySnapshot>,
+}
+
+impl Scene {
+    /**
+    Reads a scene from a [RON](https://github.com/ron-rs/ron) string.
+     */
+    pub fn from_ron(ron: &str) -> Result<Self> {
+        Ok(ron::from_str(ron)?)
+    }
+
+    /**
+    Writes the scene into a [RON](https://github.com/ron-rs/ron) string.
+     */
+    pub fn to_ron(&self) -> Result<String> {
+        Ok(ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)
+    }
+}
+
+// the entities of a snapshot, the ids of the World becoming local to the scene
+impl From<WorldSnapshot> for Scene {
+    fn from(snapshot: WorldSnapshot) -> Self {
+        Self { entities: snapshot.entities }
+    }
+}
+
+/**
+The ids the entities of a [Scene] were given when it was spawned, returned by [World::spawn_scene()].
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntityMap {
+    map: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    /// Returns the id in the World of the entity with the id 'entity' in the scene, if it was spawned.
+    pub fn get(&self, entity: Entity) -> Option<Entity> {
+        self.map.get(&entity).copied()
+    }
+
+    /// Same as [EntityMap::get()], but returns 'entity' itself if it isn't part of the scene.
+    pub fn map(&self, entity: Entity) -> Entity {
+        self.get(entity).unwrap_or(entity)
+    }
+
+    /// Returns the number of entities that were spawned.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if no entities were spawned.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the (scene id, World id) pairs, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.map.iter().map(|(from, to)| (*from, *to))
+    }
+
+    pub(crate) fn insert(&mut self, from: Entity, to: Entity) {
+        self.map.insert(from, to);
+    }
+}
+
+/**
+A trait implemented by components that store the ids of other entities, so that they can be
+updated when a [Scene] is spawned.
+
+```
+use sceller::prelude::*;
+use sceller::scene::{EntityMap, MapEntities};
+
+struct Target(Entity);
+
+impl MapEntities for Target {
+    fn map_entities(&mut self, map: &EntityMap) {
+        self.0 = map.map(self.0);
+    }
+}
+```
+ */
+pub trait MapEntities {
+    fn map_entities(&mut self, map: &EntityMap);
+}
+
+impl MapEntities for Parent {
+    fn map_entities(&mut self, map: &EntityMap) {
+        self.0 = map.map(self.0);
+    }
+}
+
+impl MapEntities for Children {
+    fn map_entities(&mut self, map: &EntityMap) {
+        self.map_ids(|child| map.map(child));
+    }
+}
+
+// Scene stuff
+impl World {
+    /**
+    Registers a component type that refers to other entities so that it is included in snapshots of the World,
+    and so that the entities it refers to are remapped when it is spawned as part of a [Scene].
+     */
+    pub fn register_serde_component_with_entities<T>(&mut self)
+    where
+        T: std::any::Any + Serialize + serde::de::DeserializeOwned + MapEntities
+    {
+        self.serde_registry.register_component_with_entities::<T>();
+        self.entities.type_registry_mut().register_serde::<T>();
+    }
+
+    /**
+    Spawns every entity of a [Scene] as a new entity, then updates the components implementing [MapEntities]
+    so that they point to the new entities. Returns the ids the entities of the scene were given.
+
+    ```
+    use sceller::prelude::*;
+    use sceller::scene::Scene;
+
+    let scene = Scene::from_ron(r#"(
+        entities: [
+            (id: 4, components: { "Children": "([7])" }),
+            (id: 7, components: { "Parent": "(4)" }),
+        ],
+    )"#).unwrap();
+
+    let mut world = World::new();
+    world.register_serde_component_with_entities::<Parent>();
+    world.register_serde_component_with_entities::<Children>();
+    world.spawn().insert(Name::new("existing"));
+
+    let spawned = world.spawn_scene(&scene).unwrap();
+    let (parent, child) = (spawned.get(4).unwrap(), spawned.get(7).unwrap());
+    assert_eq!((parent, child), (1, 2));
+    assert_eq!(world.parent(child), Some(parent));
+    assert_eq!(world.children(parent), vec![child]);
+    ```
+
+    Entities without any components are not spawned. References to entities that aren't part of the scene are left as they are.
+    Returns an error if the scene contains a component type that isn't registered, which is checked before anything is spawned,
+    or if a component fails to deserialize, in which case the entities spawned before it are kept.
+     */
+    pub fn spawn_scene(&mut self, scene: &Scene) -> Result<EntityMap> {
+        for entity in &scene.entities {
+            for name in entity.components.keys() {
+                self.serde_registry.component_fns(name)?;
+            }
+        }
+
+        self.step(|world| {
+            let mut map = EntityMap::default();
+            let result = world.spawn_scene_entities(scene, &mut map);
+
+            for entity in scene.entities.iter().filter(|entity| map.get(entity.id).is_some()) {
+                for name in entity.components.keys() {
+                    if let Some(map_entities) = world.serde_registry.component_fns(name)?.map_entities {
+                        map_entities(&world.entities, map.map(entity.id), &map);
+                    }
+                }
+            }
+            result.map(|_| map)
+        })
+    }
+
+    // spawns the entities of the scene one by one, since an entity's slot is only taken once it has a component
+    fn spawn_scene_entities(&mut self, scene: &Scene, map: &mut EntityMap) -> Result<()> {
+        for entity in scene.entities.iter().filter(|entity| !entity.components.is_empty()) {
+            let index = self.entities.create_entity().current_entity();
+            map.insert(entity.id, index);
+            for (name, value) in &entity.components {
+                (self.serde_registry.component_fns(name)?.load)(&mut self.entities, value, index)?;
+            }
+        }
+        Ok(())
+    }
+}
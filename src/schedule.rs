@@ -0,0 +1,448 @@
+//! # Schedule
+//!
+//! The schedule module contains [Schedule], which stores systems in an ordered list of [Stage]s
+//! so that they can all be run once per frame with a single call to
+//! [World::run_schedule()](struct.World.html#method.run_schedule).
+
+use crate::{world::World, system::{BoxedSystem, IntoSystem, SystemMeta}, ordering::sort_systems};
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/**
+A label for a stage of a [Schedule].
+
+Every Schedule starts with the PreUpdate, FixedUpdate, Update and PostUpdate stages (in that order),
+custom stages can be added using [Stage::Custom] and a name.
+ */
+pub enum Stage {
+    /**
+    A stage run only once, before the first frame. It isn't part of the stages of the schedule,
+    so other stages can't be placed around it.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    fn spawn_level(mut commands: Commands) {
+        commands.spawn().insert(Health(3));
+    }
+
+    let mut world = World::new();
+    world.add_startup_system(spawn_level);
+
+    world.run_schedule();
+    world.run_schedule();
+    assert_eq!(world.query_typed::<&Health>().count(), 1);
+    ```
+     */
+    Startup,
+    PreUpdate,
+    /**
+    A stage run once for every fixed step of time accumulated in the [Time](struct.Time.html) resource, 
+    which can be any number of times per frame, including zero.
+
+    ```
+    use sceller::prelude::*;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct Ticks(u32);
+    impl Resource for Ticks {}
+
+    fn tick(mut ticks: ResMut<Ticks>) {
+        ticks.0 += 1;
+    }
+
+    let mut world = World::new();
+    world.init_resource::<Ticks>();
+    world.add_system_to_stage(Stage::FixedUpdate, tick);
+
+    world.update_time();
+    world.get_resource_mut::<Time>().unwrap().set_fixed_step(Duration::from_secs(1));
+    world.get_resource_mut::<Time>().unwrap().advance(Duration::from_millis(2500));
+
+    world.run_schedule();
+    assert_eq!(world.get_resource::<Ticks>().unwrap().0, 2);
+    ```
+     */
+    FixedUpdate,
+    Update,
+    PostUpdate,
+    Custom(&'static str),
+}
+
+#[derive(Debug)]
+/**
+A group of systems that are run one after the other, in the order they were added.
+ */
+pub struct SystemStage {
+    label: Stage,
+    systems: Vec<BoxedSystem>,
+}
+
+impl SystemStage {
+    /// Creates a new empty stage with the given label.
+    pub fn new(label: Stage) -> Self {
+        Self { label, systems: Vec::new() }
+    }
+
+    /// Returns the label of this stage.
+    pub fn label(&self) -> Stage {
+        self.label
+    }
+
+    /// Returns the systems in this stage, in the order they will be run.
+    pub fn systems(&self) -> &[BoxedSystem] {
+        &self.systems
+    }
+
+    /// Returns what each system of this stage reads and writes, in the order they will be run.
+    pub fn system_metas(&self) -> Vec<SystemMeta> {
+        self.systems.iter()
+            .map(|system| SystemMeta { stage: Some(self.label), ..system.meta() })
+            .collect()
+    }
+
+    /**
+    Adds a system to the stage, then sorts the systems again so that the [ordering](struct.SystemOrdering.html)
+    of each system is respected. Returns an error, and doesn't add the system, if it's ordering creates a cycle.
+     */
+    pub fn add_system(&mut self, system: BoxedSystem) -> Result<()> {
+        self.systems.push(system);
+        match sort_systems(&self.systems) {
+            Ok(order) => {
+                let mut systems = std::mem::take(&mut self.systems).into_iter().map(Some).collect::<Vec<_>>();
+                self.systems = order.into_iter().map(|index| systems[index].take().unwrap()).collect();
+                Ok(())
+            },
+            Err(cycle) => {
+                self.systems.pop();
+                Err(ScheduleError::OrderingCycleError(cycle).into())
+            },
+        }
+    }
+
+    /**
+    Runs every system in the stage, then applies the [Commands](struct.Commands.html)
+    they queued. Returns the error of the first command that fails to apply.
+
+    The [Stage::FixedUpdate] stage is run once for every fixed step of time accumulated instead.
+     */
+    pub fn run(&self, world: &mut World) -> Result<()> {
+        if self.label == Stage::FixedUpdate {
+            while world.expend_fixed_step() {
+                self.run_once(world)?;
+            }
+            return Ok(());
+        }
+        self.run_once(world)
+    }
+
+    fn run_once(&self, world: &mut World) -> Result<()> {
+        for system in &self.systems {
+            world.run_instrumented(system);
+        }
+        world.apply_commands()
+    }
+}
+
+/**
+Stores systems sorted into stages.
+
+```
+use sceller::prelude::*;
+
+struct Frames(u32);
+impl Resource for Frames {}
+
+fn count_frames(mut frames: ResMut<Frames>) {
+    frames.0 += 1;
+}
+
+fn check_frames(frames: Res<Frames>) {
+    assert!(frames.0 > 0);
+}
+
+let mut world = World::new();
+world.insert_resource(Frames(0));
+
+world.add_system_to_stage(Stage::PostUpdate, check_frames);
+world.add_system_to_stage(Stage::PreUpdate, count_frames);
+
+world.run_schedule();
+world.run_schedule();
+
+assert_eq!(world.get_resource::<Frames>().unwrap().0, 2);
+```
+
+Commands queued by the systems are applied at the end of each stage, so that systems
+in a later stage can see the changes made by systems in an earlier one.
+ */
+#[derive(Debug)]
+pub struct Schedule {
+    startup: SystemStage,
+    stages: Vec<SystemStage>,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            startup: SystemStage::new(Stage::Startup),
+            stages: vec![
+                SystemStage::new(Stage::PreUpdate),
+                SystemStage::new(Stage::FixedUpdate),
+                SystemStage::new(Stage::Update),
+                SystemStage::new(Stage::PostUpdate),
+            ],
+        }
+    }
+}
+
+impl Schedule {
+    /**
+    Creates a new Schedule with the PreUpdate, FixedUpdate, Update and PostUpdate stages.
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    Creates a new Schedule with no stages at all.
+     */
+    pub fn empty() -> Self {
+        Self { startup: SystemStage::new(Stage::Startup), stages: Vec::new() }
+    }
+
+    /// Returns the [Stage::Startup] stage of this schedule.
+    pub fn startup_stage(&self) -> &SystemStage {
+        &self.startup
+    }
+
+    /// Returns the stages of this schedule, in the order they will be run.
+    pub fn stages(&self) -> &[SystemStage] {
+        &self.stages
+    }
+
+    /// Returns what each system of the schedule reads and writes, starting with the startup systems,
+    /// in the order they will be run.
+    pub fn system_metas(&self) -> Vec<SystemMeta> {
+        std::iter::once(&self.startup)
+            .chain(&self.stages)
+            .flat_map(SystemStage::system_metas)
+            .collect()
+    }
+
+    fn stage_index(&self, label: Stage) -> Result<usize> {
+        self.stages.iter().position(|stage| stage.label == label)
+            .ok_or_else(|| ScheduleError::NonexistentStageError(label).into())
+    }
+
+    fn check_unique(&self, label: Stage) -> Result<()> {
+        if self.stages.iter().any(|stage| stage.label == label) {
+            return Err(ScheduleError::DuplicateStageError(label).into());
+        }
+        Ok(())
+    }
+
+    /**
+    Adds a new stage at the end of the schedule. Panics if the stage already exists.
+     */
+    pub fn add_stage(&mut self, label: Stage) -> &mut Self {
+        self.add_stage_checked(label).unwrap()
+    }
+
+    /**
+    Adds a new stage at the end of the schedule. Returns an error if the stage already exists.
+     */
+    pub fn add_stage_checked(&mut self, label: Stage) -> Result<&mut Self> {
+        self.check_unique(label)?;
+        self.stages.push(SystemStage::new(label));
+        Ok(self)
+    }
+
+    /**
+    Adds a new stage that runs right before 'target'. Panics if 'target' doesn't exist
+    or if the new stage already exists.
+
+    ```
+    use sceller::prelude::*;
+
+    let mut schedule = Schedule::new();
+    schedule.add_stage_before(Stage::Update, Stage::Custom("Physics"));
+
+    let labels = schedule.stages().iter().map(|s| s.label()).collect::<Vec<Stage>>();
+    assert_eq!(labels, vec![Stage::PreUpdate, Stage::FixedUpdate, Stage::Custom("Physics"), Stage::Update, Stage::PostUpdate]);
+    ```
+     */
+    pub fn add_stage_before(&mut self, target: Stage, label: Stage) -> &mut Self {
+        self.add_stage_before_checked(target, label).unwrap()
+    }
+
+    /**
+    Adds a new stage that runs right before 'target'. Returns an error if 'target' doesn't exist
+    or if the new stage already exists.
+     */
+    pub fn add_stage_before_checked(&mut self, target: Stage, label: Stage) -> Result<&mut Self> {
+        self.check_unique(label)?;
+        let index = self.stage_index(target)?;
+        self.stages.insert(index, SystemStage::new(label));
+        Ok(self)
+    }
+
+    /**
+    Adds a new stage that runs right after 'target'. Panics if 'target' doesn't exist
+    or if the new stage already exists.
+     */
+    pub fn add_stage_after(&mut self, target: Stage, label: Stage) -> &mut Self {
+        self.add_stage_after_checked(target, label).unwrap()
+    }
+
+    /**
+    Adds a new stage that runs right after 'target'. Returns an error if 'target' doesn't exist
+    or if the new stage already exists.
+     */
+    pub fn add_stage_after_checked(&mut self, target: Stage, label: Stage) -> Result<&mut Self> {
+        self.check_unique(label)?;
+        let index = self.stage_index(target)?;
+        self.stages.insert(index + 1, SystemStage::new(label));
+        Ok(self)
+    }
+
+    /**
+    Adds a system to the [Stage::Update] stage. Panics if the schedule has no Update stage.
+     */
+    pub fn add_system<F, T>(&mut self, system: F) -> &mut Self
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        self.add_system_to_stage(Stage::Update, system)
+    }
+
+    /**
+    Adds a system to the given stage. Panics if the stage doesn't exist, or if the
+    ordering of the system creates a cycle.
+     */
+    pub fn add_system_to_stage<F, T>(&mut self, stage: Stage, system: F) -> &mut Self
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        self.add_system_to_stage_checked(stage, system).unwrap()
+    }
+
+    /**
+    Adds a system to the given stage. Returns an error if the stage doesn't exist, or if the
+    ordering of the system creates a cycle, see [IntoOrderedSystem](trait.IntoOrderedSystem.html).
+
+    ```
+    use sceller::prelude::*;
+
+    fn movement(_commands: Commands) {}
+    fn collision(_commands: Commands) {}
+
+    let mut schedule = Schedule::new();
+    schedule.add_system(movement.before(collision));
+
+    let error = schedule.add_system_to_stage_checked(Stage::Update, collision.before(movement)).err().unwrap();
+    assert!(matches!(error, Error::Schedule(ScheduleError::OrderingCycleError(_))));
+    // the system creating the cycle isn't added
+    assert_eq!(schedule.stages()[2].systems().len(), 1);
+    ```
+     */
+    pub fn add_system_to_stage_checked<F, T>(&mut self, stage: Stage, system: F) -> Result<&mut Self>
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        let stage = match stage {
+            Stage::Startup => &mut self.startup,
+            _ => {
+                let index = self.stage_index(stage)?;
+                &mut self.stages[index]
+            },
+        };
+        stage.add_system(BoxedSystem::new(system))?;
+        Ok(self)
+    }
+
+    /**
+    Adds a system to the [Stage::Startup] stage, which is only run before the first frame.
+    Panics if the ordering of the system creates a cycle.
+     */
+    pub fn add_startup_system<F, T>(&mut self, system: F) -> &mut Self
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        self.add_system_to_stage(Stage::Startup, system)
+    }
+
+    /**
+    Runs the [Stage::Startup] stage on the World. This is done by [World::run_schedule()](struct.World.html#method.run_schedule)
+    the first time it is called.
+
+    Returns the error of the first queued command that fails to apply.
+     */
+    pub fn run_startup(&self, world: &mut World) -> Result<()> {
+        self.startup.run(world)
+    }
+
+    /**
+    Runs every stage of the schedule in order on the World, except for the [Stage::Startup] stage.
+    The [tick](crate::tick) of the World is advanced before the first stage runs.
+
+    Returns the error of the first queued command that fails to apply.
+     */
+    pub fn run(&self, world: &mut World) -> Result<()> {
+        world.advance_tick();
+        for stage in &self.stages {
+            stage.run(world)?;
+        }
+        Ok(())
+    }
+
+    /**
+    Runs the schedule 'steps' times in a row on the World, advancing it's tick once per run, see [Schedule::run()].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Position(i32);
+
+    fn walk(query: FnQuery<&mut Position>) {
+        for mut pos in query {
+            pos.0 += 1;
+        }
+    }
+
+    let mut world = World::new();
+    world.spawn().insert(Position(0));
+    let mut schedule = Schedule::new();
+    schedule.add_system(walk);
+
+    schedule.run_n(&mut world, 10).unwrap();
+    assert_eq!(world.get::<&Position>(0).unwrap().0, 10);
+    assert_eq!(world.tick(), 10);
+    ```
+
+    Stops at the first run that returns an error, and returns it.
+     */
+    pub fn run_n(&self, world: &mut World, steps: u64) -> Result<()> {
+        for _ in 0..steps {
+            self.run(world)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ScheduleError {
+    #[error("Attempt to use a stage that doesn't exist in the schedule: {0:?}.")]
+    NonexistentStageError(Stage),
+    #[error("Attempt to add a stage that already exists in the schedule: {0:?}.")]
+    DuplicateStageError(Stage),
+    #[error("Attempt to add a system whose ordering creates a cycle between the systems {0:?}.")]
+    OrderingCycleError(Vec<&'static str>),
+}
@@ -0,0 +1,202 @@
+//! # Scripting
+//!
+//! Only available with the 'scripting' feature.
+//!
+//! The scripting module adds [ScriptWorld], a handle to a shared World whose methods only take entity ids,
+//! component names and JSON values, so that they can be registered as functions of a scripting language like
+//! Rhai or Lua. The handle is Clone and 'static, as both `rhai::Engine::register_fn()` and mlua's `UserData`
+//! require, and JSON values are converted from and into the values of the script by `rhai::serde` and
+//! mlua's `LuaSerdeExt`.
+//!
+//! Scripts can only see the component types registered with
+//! [World::register_serde_component()](../struct.World.html#method.register_serde_component), which are found by
+//! their full or short name in the [TypeRegistry](crate::entities::TypeRegistry), e.g. "my_game::Health" or "Health".
+
+use std::{cell::{RefCell, RefMut, Ref}, rc::Rc};
+
+use serde_json::Value;
+
+use crate::{world::World, entities::{try_borrow, ComponentError, Entity, TypeRegistration}};
+use crate::error::Result;
+
+/**
+A clonable handle to a World for scripts, see the [module documentation](crate::scripting).
+
+```
+use sceller::prelude::*;
+use sceller::scripting::ScriptWorld;
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+
+#[derive(Serialize, Deserialize)]
+struct Health(u32);
+#[derive(Serialize, Deserialize)]
+struct Position { x: f32, y: f32 }
+
+let mut world = World::new();
+world.register_serde_component::<Health>();
+world.register_serde_component::<Position>();
+
+let script = ScriptWorld::new(world);
+let player = script.spawn(&json!({ "Health": 10, "Position": { "x": 0.0, "y": 1.0 } })).unwrap();
+script.set(player, "Health", json!(7)).unwrap();
+assert_eq!(script.get(player, "Health").unwrap(), json!(7));
+assert_eq!(script.query(&["Health", "Position"]).unwrap(), vec![player]);
+
+script.remove(player, "Position").unwrap();
+assert!(!script.has(player, "Position").unwrap());
+assert!(script.get(player, "Mana").is_err());
+
+// the World is still available to the rest of the program
+assert_eq!(script.world().get::<&Health>(player).unwrap().0, 7);
+```
+ */
+#[derive(Debug, Clone)]
+pub struct ScriptWorld {
+    world: Rc<RefCell<World>>,
+}
+
+impl ScriptWorld {
+    /// Creates a handle owning the World.
+    pub fn new(world: World) -> Self {
+        Self::from_shared(Rc::new(RefCell::new(world)))
+    }
+
+    /// Creates a handle to a World shared with the rest of the program.
+    pub fn from_shared(world: Rc<RefCell<World>>) -> Self {
+        Self { world }
+    }
+
+    /// Returns the World shared by the handle.
+    pub fn shared(&self) -> Rc<RefCell<World>> {
+        self.world.clone()
+    }
+
+    /// Borrows the World. Panics if a script is changing it.
+    pub fn world(&self) -> Ref<'_, World> {
+        self.world.borrow()
+    }
+
+    /// Borrows the World mutably. Panics if it is already borrowed.
+    pub fn world_mut(&self) -> RefMut<'_, World> {
+        self.world.borrow_mut()
+    }
+
+    fn borrow(&self) -> Result<Ref<'_, World>> {
+        self.world.try_borrow().map_err(|_| ScriptError::WorldBorrowedError.into())
+    }
+
+    fn borrow_mut(&self) -> Result<RefMut<'_, World>> {
+        self.world.try_borrow_mut().map_err(|_| ScriptError::WorldBorrowedError.into())
+    }
+
+    /**
+    Spawns an entity with the components of a JSON object whose keys are component names, and returns it's id.
+    Nothing is spawned if one of the components can't be inserted.
+     */
+    pub fn spawn(&self, components: &Value) -> Result<Entity> {
+        let components = components.as_object().ok_or(ScriptError::NotAnObjectError)?;
+        let mut world = self.borrow_mut()?;
+        let entity = world.entities.create_entity().current_entity();
+        for (name, value) in components {
+            if let Err(error) = insert_json(&mut world, entity, name, value.clone()) {
+                world.despawn_checked(entity)?;
+                return Err(error);
+            }
+        }
+        Ok(entity)
+    }
+
+    /// Despawns an entity, see [World::despawn_checked()](../struct.World.html#method.despawn_checked).
+    pub fn despawn(&self, entity: Entity) -> Result<()> {
+        self.borrow_mut()?.despawn_checked(entity).map(|_| ())
+    }
+
+    /// Returns true if the entity has the component 'name'.
+    pub fn has(&self, entity: Entity, name: &str) -> Result<bool> {
+        let world = self.borrow()?;
+        let type_id = registration(&world, name)?.type_id;
+        Ok(world.entities.component_id(&type_id).is_some_and(|id| world.entities.has_component_id(id, entity)))
+    }
+
+    /// Returns the component 'name' of the entity as JSON, or an error if the entity doesn't have it.
+    pub fn get(&self, entity: Entity, name: &str) -> Result<Value> {
+        let world = self.borrow()?;
+        let registration = registration(&world, name)?;
+        let to_json = registration.to_json.ok_or_else(|| ScriptError::NotReflectableError(name.to_owned()))?;
+        let component = world.entities.component_id(&registration.type_id)
+            .filter(|id| world.entities.has_component_id(*id, entity))
+            .and_then(|id| world.entities.component_at_id(id, entity))
+            .ok_or(ComponentError::NonexistentComponentDataError)?;
+        let component = try_borrow(component, registration.name, entity)?;
+        to_json(&*component)
+    }
+
+    /**
+    Inserts the component 'name' deserialized from JSON into the entity, replacing the one it had,
+    and running the hooks of the component type.
+     */
+    pub fn set(&self, entity: Entity, name: &str, value: Value) -> Result<()> {
+        let mut world = self.borrow_mut()?;
+        if !world.contains(entity) {
+            return Err(ComponentError::NonexistentEntity.into());
+        }
+        insert_json(&mut world, entity, name, value)
+    }
+
+    /// Removes the component 'name' from the entity, if it has one.
+    pub fn remove(&self, entity: Entity, name: &str) -> Result<()> {
+        let mut world = self.borrow_mut()?;
+        let type_id = registration(&world, name)?.type_id;
+        world.step(|world| world.entities.swap_component(entity, type_id, None));
+        Ok(())
+    }
+
+    /// Returns the entities that have every component in 'names', in ascending order.
+    pub fn query(&self, names: &[&str]) -> Result<Vec<Entity>> {
+        let world = self.borrow()?;
+        let mut mask = 0;
+        for name in names {
+            let type_id = registration(&world, name)?.type_id;
+            match world.entities.component_id(&type_id) {
+                Some(id) => mask |= 1 << id,
+                // no entity ever had the component
+                None => return Ok(Vec::new()),
+            }
+        }
+        Ok(world.entities.matching_entities(mask))
+    }
+
+    /// Returns the names of the component types scripts can use.
+    pub fn component_names(&self) -> Result<Vec<&'static str>> {
+        let world = self.borrow()?;
+        Ok(world.type_registry().iter()
+            .filter(|registration| registration.to_json.is_some())
+            .map(|registration| registration.name)
+            .collect())
+    }
+}
+
+// the registration of the component type called 'name'
+fn registration(world: &World, name: &str) -> Result<TypeRegistration> {
+    world.type_registry().get_by_name(name)
+        .cloned()
+        .ok_or_else(|| ScriptError::UnknownComponentError(name.to_owned()).into())
+}
+
+fn insert_json(world: &mut World, entity: Entity, name: &str, value: Value) -> Result<()> {
+    let insert_json = registration(world, name)?.insert_json.ok_or_else(|| ScriptError::NotReflectableError(name.to_owned()))?;
+    world.step(|world| insert_json(&mut world.entities, entity, value))
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ScriptError {
+    #[error("No component type is registered with the name {0}.")]
+    UnknownComponentError(String),
+    #[error("The component type {0} wasn't registered with register_serde_component().")]
+    NotReflectableError(String),
+    #[error("Attempt to spawn an entity from a JSON value that isn't an object.")]
+    NotAnObjectError,
+    #[error("Attempt to use the World of a script while it is already borrowed.")]
+    WorldBorrowedError,
+}
@@ -0,0 +1,352 @@
+//! # Serialization
+//!
+//! Only available with the 'serde' feature.
+//!
+//! The serialization module allows saving the World's entities, components and resources into
+//! a [WorldSnapshot] and writing it out as [RON](https://github.com/ron-rs/ron), then loading it back.
+//! Since the ECS only ever knows components as 'dyn Any', every type that should be saved has to be
+//! registered in the World's [SerdeRegistry] first, which stores the functions used to (de)serialize it.
+//!
+//! Each component and resource value is stored as it's own RON string inside of the snapshot, this way
+//! any type serde can handle (including enums) survives the trip.
+
+use std::{any::Any, collections::{BTreeMap, BTreeSet}};
+
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+
+use crate::{entities::{short_type_name, Entities, Query, QueryEntity}, resources::Resources};
+use crate::scene::{EntityMap, MapEntities};
+use crate::error::Result;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/**
+A serializable copy of the registered components and resources of a World.
+ */
+pub struct WorldSnapshot {
+    pub entities: Vec<EntitySnapshot>,
+    pub resources: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/**
+The registered components of a single entity, keyed by the name they were registered with.
+ */
+pub struct EntitySnapshot {
+    pub id: usize,
+    pub components: BTreeMap<String, String>,
+}
+
+impl WorldSnapshot {
+    /**
+    Compares two snapshots, returning what changed to go from 'self' to 'other'. Entities are matched by their
+    ids and components by their serialized values, so an entity despawned and replaced by another one in the same
+    slot shows up as changed components.
+
+    See [World::diff()](../struct.World.html#method.diff) for an example.
+     */
+    pub fn diff(&self, other: &WorldSnapshot) -> WorldDiff {
+        let before = self.entities.iter().map(|entity| (entity.id, entity)).collect::<BTreeMap<usize, &EntitySnapshot>>();
+        let after = other.entities.iter().map(|entity| (entity.id, entity)).collect::<BTreeMap<usize, &EntitySnapshot>>();
+
+        let mut diff = WorldDiff {
+            added: after.keys().filter(|id| !before.contains_key(id)).copied().collect(),
+            removed: before.keys().filter(|id| !after.contains_key(id)).copied().collect(),
+            changed: Vec::new(),
+        };
+
+        for (id, old) in &before {
+            let Some(new) = after.get(id) else { continue };
+
+            let names = old.components.keys().chain(new.components.keys()).collect::<BTreeSet<&String>>();
+            for name in names {
+                let (old_value, new_value) = (old.components.get(name), new.components.get(name));
+                if old_value != new_value {
+                    diff.changed.push(ComponentDiff {
+                        entity: *id,
+                        component: name.clone(),
+                        before: old_value.cloned(),
+                        after: new_value.cloned(),
+                    });
+                }
+            }
+        }
+        diff
+    }
+}
+
+/**
+The differences between two [WorldSnapshot]s, created by [WorldSnapshot::diff()] or
+[World::diff()](../struct.World.html#method.diff).
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorldDiff {
+    /// Entities that only exist in the newer snapshot, in ascending order.
+    pub added: Vec<usize>,
+    /// Entities that only exist in the older snapshot, in ascending order.
+    pub removed: Vec<usize>,
+    /// Components that were inserted, deleted or changed on entities that exist in both snapshots.
+    pub changed: Vec<ComponentDiff>,
+}
+
+impl WorldDiff {
+    /// Returns true if nothing changed between the two snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/**
+A single component that changed between two snapshots, 'before' is None if it was inserted
+and 'after' is None if it was deleted. Values are RON strings.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentDiff {
+    pub entity: usize,
+    pub component: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+pub(crate) struct ComponentFns {
+    pub(crate) name: &'static str,
+    save: fn(&Entities, &mut BTreeMap<usize, EntitySnapshot>) -> Result<()>,
+    pub(crate) load: fn(&mut Entities, &str, usize) -> Result<()>,
+    // only set for components registered with register_component_with_entities
+    pub(crate) map_entities: Option<fn(&Entities, usize, &EntityMap)>,
+}
+
+struct ResourceFns {
+    name: &'static str,
+    save: fn(&Resources) -> Result<Option<String>>,
+    load: fn(&mut Resources, &str) -> Result<()>,
+}
+
+#[derive(Default)]
+/**
+Stores the functions used to serialize and deserialize every component and resource type
+registered with [World::register_serde_component()](struct.World.html#method.register_serde_component)
+and [World::register_serde_resource()](struct.World.html#method.register_serde_resource).
+
+Types are identified by their [std::any::type_name], so snapshots should be loaded by the same
+version of a program that saved them.
+ */
+pub struct SerdeRegistry {
+    components: Vec<ComponentFns>,
+    resources: Vec<ResourceFns>,
+}
+
+impl SerdeRegistry {
+    /**
+    Registers a component type as serializable. Registering the same type twice does nothing.
+     */
+    pub fn register_component<T>(&mut self)
+    where
+        T: Any + Serialize + DeserializeOwned
+    {
+        let name = std::any::type_name::<T>();
+        if self.components.iter().any(|fns| fns.name == name) {
+            return;
+        }
+
+        self.components.push(ComponentFns {
+            name,
+            save: save_component::<T>,
+            load: load_component::<T>,
+            map_entities: None,
+        });
+    }
+
+    /**
+    Registers a component type that refers to other entities as serializable, so that the entities it refers to
+    are remapped when it is spawned as part of a [Scene](../scene/struct.Scene.html).
+    If the type was already registered without it's entities, they will now be remapped.
+     */
+    pub fn register_component_with_entities<T>(&mut self)
+    where
+        T: Any + Serialize + DeserializeOwned + MapEntities
+    {
+        self.register_component::<T>();
+        let name = std::any::type_name::<T>();
+        if let Some(fns) = self.components.iter_mut().find(|fns| fns.name == name) {
+            fns.map_entities = Some(map_component::<T>);
+        }
+    }
+
+    /**
+    Registers a resource type as serializable. Registering the same type twice does nothing.
+     */
+    pub fn register_resource<T>(&mut self)
+    where
+        T: Any + Serialize + DeserializeOwned
+    {
+        let name = std::any::type_name::<T>();
+        if self.resources.iter().any(|fns| fns.name == name) {
+            return;
+        }
+
+        self.resources.push(ResourceFns {
+            name,
+            save: save_resource::<T>,
+            load: load_resource::<T>,
+        });
+    }
+
+    /**
+    Finds the functions of a registered component from the name it was registered with,
+    or from the same name without it's module path, which is easier to write by hand.
+     */
+    pub(crate) fn component_fns(&self, name: &str) -> Result<&ComponentFns> {
+        self.components.iter().find(|fns| fns.name == name)
+            .or_else(|| self.components.iter().find(|fns| short_type_name(fns.name) == name))
+            .ok_or_else(|| SerializationError::UnregisteredTypeError(name.to_owned()).into())
+    }
+
+    /**
+    Creates a [WorldSnapshot] of every registered component and resource.
+     */
+    pub fn snapshot(&self, entities: &Entities, resources: &Resources) -> Result<WorldSnapshot> {
+        let mut snapshots = BTreeMap::new();
+        for fns in &self.components {
+            (fns.save)(entities, &mut snapshots)?;
+        }
+
+        let mut saved_resources = BTreeMap::new();
+        for fns in &self.resources {
+            if let Some(value) = (fns.save)(resources)? {
+                saved_resources.insert(fns.name.to_owned(), value);
+            }
+        }
+
+        Ok(WorldSnapshot {
+            entities: snapshots.into_values().collect(),
+            resources: saved_resources,
+        })
+    }
+
+    /**
+    Inserts the entities of a [WorldSnapshot] into 'entities' at their original indexes, and
+    the resources of the snapshot into 'resources'.
+
+    Returns an error if the snapshot contains a type that isn't registered.
+     */
+    pub fn restore(&self, snapshot: &WorldSnapshot, entities: &mut Entities, resources: &mut Resources) -> Result<()> {
+        for entity in &snapshot.entities {
+            entities.create_entity_at(entity.id);
+            for (name, value) in &entity.components {
+                let fns = self.components.iter().find(|fns| fns.name == name)
+                    .ok_or_else(|| SerializationError::UnregisteredTypeError(name.clone()))?;
+                (fns.load)(entities, value, entity.id)?;
+            }
+        }
+
+        for (name, value) in &snapshot.resources {
+            let fns = self.resources.iter().find(|fns| fns.name == name)
+                .ok_or_else(|| SerializationError::UnregisteredTypeError(name.clone()))?;
+            (fns.load)(resources, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for SerdeRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SerdeRegistry")
+            .field("components", &self.components.iter().map(|fns| fns.name).collect::<Vec<_>>())
+            .field("resources", &self.resources.iter().map(|fns| fns.name).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn save_component<T>(entities: &Entities, snapshots: &mut BTreeMap<usize, EntitySnapshot>) -> Result<()>
+where
+    T: Any + Serialize
+{
+    let mut indexes = Vec::new();
+    let mut query = Query::new(entities);
+    if query.with_component_checked::<T>().is_err() {
+        // the component was never inserted into anything, so there is nothing to save
+        return Ok(());
+    }
+    query.read_indexes_to_buf(&mut indexes);
+
+    for index in indexes {
+        let entity = QueryEntity::new(index, entities);
+        let value = ron::to_string(&*entity.get_component::<T>()?)?;
+
+        snapshots.entry(index)
+            .or_insert_with(|| EntitySnapshot { id: index, components: BTreeMap::new() })
+            .components.insert(std::any::type_name::<T>().to_owned(), value);
+    }
+    Ok(())
+}
+
+fn load_component<T>(entities: &mut Entities, value: &str, index: usize) -> Result<()>
+where
+    T: Any + DeserializeOwned
+{
+    let data: T = ron::from_str(value)?;
+    entities.insert_component_into_entity_by_id_checked(data, index).map(|_| ())
+}
+
+fn map_component<T>(entities: &Entities, index: usize, map: &EntityMap)
+where
+    T: Any + MapEntities
+{
+    if let Ok([mut component]) = entities.get_components_mut::<T, 1>(&[index]) {
+        component.map_entities(map);
+    }
+}
+
+fn save_resource<T>(resources: &Resources) -> Result<Option<String>>
+where
+    T: Any + Serialize
+{
+    match resources.get_ref::<T>() {
+        Ok(resource) => Ok(Some(ron::to_string(&*resource)?)),
+        Err(_) => Ok(None),
+    }
+}
+
+fn load_resource<T>(resources: &mut Resources, value: &str) -> Result<()>
+where
+    T: Any + DeserializeOwned
+{
+    let data: T = ron::from_str(value)?;
+    resources.add(data);
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SerializationError {
+    #[error("Attempt to load a type that was not registered for serialization: {0}.")]
+    UnregisteredTypeError(String),
+    #[error("Attempt to replicate the changes of an entity that was never spawned here: {0}.")]
+    UnknownRemoteEntityError(usize),
+    #[error(transparent)]
+    RonError(#[from] ron::Error),
+    #[error(transparent)]
+    RonParseError(#[from] ron::error::SpannedError),
+    #[cfg(feature = "inspector")]
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+}
+
+impl From<ron::Error> for crate::Error {
+    fn from(error: ron::Error) -> Self {
+        SerializationError::from(error).into()
+    }
+}
+
+impl From<ron::error::SpannedError> for crate::Error {
+    fn from(error: ron::error::SpannedError) -> Self {
+        SerializationError::from(error).into()
+    }
+}
+
+#[cfg(feature = "inspector")]
+impl From<serde_json::Error> for crate::Error {
+    fn from(error: serde_json::Error) -> Self {
+        SerializationError::from(error).into()
+    }
+}
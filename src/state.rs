@@ -0,0 +1,349 @@
+//! # States
+//!
+//! The state module allows structuring a game into states (menu, playing, paused...) using the
+//! [States] resource. Systems can be added to run when a state is entered with [OnEnter], when it
+//! is exited with [OnExit], or every frame while it is active with [OnUpdate].
+//!
+//! State changes are queued with [States::set()] and only applied at the start of the next call to
+//! [World::run_schedule()](struct.World.html#method.run_schedule), so that every system in a frame
+//! sees the same state.
+//!
+//! Resources only needed while a state is active, like the caches of a level, can be inserted with
+//! [World::insert_state_resource()](struct.World.html#method.insert_state_resource) so that they are removed when
+//! the World exits that state.
+
+use std::{any::{Any, TypeId}, collections::HashMap, hash::Hash};
+
+use crate::{world::World, resources::Resource, system::{BoxedSystem, IntoSystem}, condition::IntoConditionalSystem};
+use crate::error::Result;
+
+/**
+A resource that stores the current state of type T, and the state it will change to next.
+
+Added to the World with [World::add_state()](struct.World.html#method.add_state).
+ */
+#[derive(Debug)]
+pub struct States<T> {
+    current: T,
+    next: Option<T>,
+    entered: bool,
+}
+
+impl<T: StateType> Resource for States<T> {}
+
+impl<T> States<T> {
+    fn new(initial: T) -> Self {
+        Self { current: initial, next: None, entered: false }
+    }
+
+    /// Returns the current state.
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+
+    /// Returns the state that will be entered at the start of the next frame, if any.
+    pub fn next(&self) -> Option<&T> {
+        self.next.as_ref()
+    }
+
+    /**
+    Queues a change to 'next'. The change is applied at the start of the next call
+    to [World::run_schedule()](struct.World.html#method.run_schedule), setting the same state
+    more than once in a frame only keeps the last one.
+     */
+    pub fn set(&mut self, next: T) {
+        self.next = Some(next);
+    }
+}
+
+/// Runs systems when the World enters the contained state.
+pub struct OnEnter<T>(pub T);
+/// Runs systems when the World exits the contained state.
+pub struct OnExit<T>(pub T);
+/// Runs systems every frame, in the [Stage::Update](enum.Stage.html) stage, while the World is in the contained state.
+pub struct OnUpdate<T>(pub T);
+
+/**
+A trait implemented by [OnEnter], [OnExit] and [OnUpdate], used to add systems to a state with
+[World::add_state_system()](struct.World.html#method.add_state_system).
+ */
+pub trait StateSchedule<T> {
+    fn add_system(self, world: &mut World, system: BoxedSystem) -> Result<()>;
+}
+
+impl<T: StateType> StateSchedule<T> for OnEnter<T> {
+    fn add_system(self, world: &mut World, system: BoxedSystem) -> Result<()> {
+        let mut schedules = world.get_resource_mut::<StateSchedules<T>>().map_err(|_| StateError::NonexistentStateError)?;
+        schedules.on_enter.entry(self.0).or_default().push(system);
+        Ok(())
+    }
+}
+
+impl<T: StateType> StateSchedule<T> for OnExit<T> {
+    fn add_system(self, world: &mut World, system: BoxedSystem) -> Result<()> {
+        let mut schedules = world.get_resource_mut::<StateSchedules<T>>().map_err(|_| StateError::NonexistentStateError)?;
+        schedules.on_exit.entry(self.0).or_default().push(system);
+        Ok(())
+    }
+}
+
+impl<T: StateType> StateSchedule<T> for OnUpdate<T> {
+    fn add_system(self, world: &mut World, system: BoxedSystem) -> Result<()> {
+        if world.get_resource::<States<T>>().is_err() {
+            return Err(StateError::NonexistentStateError.into());
+        }
+        world.add_system(system.run_if(in_state(self.0)));
+        Ok(())
+    }
+}
+
+/// Shorthand for the bounds every state type needs.
+pub trait StateType: Any + Clone + Eq + Hash {}
+
+impl<T> StateType for T
+where T: Any + Clone + Eq + Hash
+{}
+
+/**
+A condition that returns true if the World is currently in 'state'.
+
+See [IntoConditionalSystem::run_if()](trait.IntoConditionalSystem.html#method.run_if).
+ */
+pub fn in_state<T: StateType>(state: T) -> impl Fn(&World) -> bool {
+    move |world: &World| world.get_resource::<States<T>>()
+        .map(|states| states.current == state)
+        .unwrap_or(false)
+}
+
+// removes a resource scoped to a state, whether it is still there or not
+type RemoveFn = fn(&mut World);
+
+// the systems to run when entering and exiting each state, stored as a resource
+struct StateSchedules<T> {
+    on_enter: HashMap<T, Vec<BoxedSystem>>,
+    on_exit: HashMap<T, Vec<BoxedSystem>>,
+}
+
+impl<T: StateType> Resource for StateSchedules<T> {}
+
+impl<T> Default for StateSchedules<T> {
+    fn default() -> Self {
+        Self { on_enter: HashMap::new(), on_exit: HashMap::new() }
+    }
+}
+
+/*
+    The resources to remove when exiting each state, stored as a resource apart from the systems
+    so that the systems of a transition can insert them while StateSchedules is taken out.
+*/
+struct StateResources<T> {
+    scoped: HashMap<T, Vec<(TypeId, RemoveFn)>>,
+}
+
+impl<T: StateType> Resource for StateResources<T> {}
+
+impl<T> Default for StateResources<T> {
+    fn default() -> Self {
+        Self { scoped: HashMap::new() }
+    }
+}
+
+fn remove_resource<R: Resource>(world: &mut World) {
+    let _ = world.delete_resource::<R>();
+}
+
+// Setup and transitions
+impl World {
+    /**
+    Adds a [States] resource starting in 'initial'. The [OnEnter] systems of the initial state
+    are run at the start of the first call to [run_schedule()](struct.World.html#method.run_schedule).
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum GameState { Menu, Playing }
+
+    struct Log(Vec<&'static str>);
+    impl Resource for Log {}
+
+    fn enter_menu(mut log: ResMut<Log>) { log.0.push("enter menu"); }
+    fn start_game(mut log: ResMut<Log>, mut state: ResMut<States<GameState>>) {
+        log.0.push("menu");
+        state.set(GameState::Playing);
+    }
+    fn exit_menu(mut log: ResMut<Log>) { log.0.push("exit menu"); }
+    fn play(mut log: ResMut<Log>) { log.0.push("playing"); }
+
+    let mut world = World::new();
+    world.insert_resource(Log(Vec::new()));
+    world.add_state(GameState::Menu);
+
+    world.add_state_system(OnEnter(GameState::Menu), enter_menu);
+    world.add_state_system(OnUpdate(GameState::Menu), start_game);
+    world.add_state_system(OnExit(GameState::Menu), exit_menu);
+    world.add_state_system(OnUpdate(GameState::Playing), play);
+
+    world.run_schedule();
+    world.run_schedule();
+
+    assert_eq!(world.get_resource::<Log>().unwrap().0, vec!["enter menu", "menu", "exit menu", "playing"]);
+    ```
+
+    Adding a state type that already exists resets it to 'initial' and removes it's systems.
+     */
+    pub fn add_state<T: StateType>(&mut self, initial: T) -> &mut Self {
+        self.insert_resource(States::new(initial));
+        self.insert_resource(StateSchedules::<T>::default());
+        self.insert_resource(StateResources::<T>::default());
+        self.add_state_transition(apply_state_transition::<T>);
+        self
+    }
+
+    /**
+    Adds a system to the [OnEnter], [OnExit] or [OnUpdate] schedule of a state.
+
+    Panics if the state type wasn't added with [add_state()](struct.World.html#method.add_state).
+     */
+    pub fn add_state_system<T, S, F, A>(&mut self, schedule: S, system: F) -> &mut Self
+    where
+        S: StateSchedule<T>,
+        F: IntoSystem<A> + 'static,
+        A: 'static,
+    {
+        self.add_state_system_checked(schedule, system).unwrap()
+    }
+
+    /**
+    Adds a system to the [OnEnter], [OnExit] or [OnUpdate] schedule of a state.
+
+    Returns an error if the state type wasn't added with [add_state()](struct.World.html#method.add_state).
+     */
+    pub fn add_state_system_checked<T, S, F, A>(&mut self, schedule: S, system: F) -> Result<&mut Self>
+    where
+        S: StateSchedule<T>,
+        F: IntoSystem<A> + 'static,
+        A: 'static,
+    {
+        schedule.add_system(self, BoxedSystem::new(system))?;
+        Ok(self)
+    }
+
+    /**
+    Inserts a resource that belongs to 'state', and is removed when the World exits it, after the [OnExit] systems
+    of the state ran so that they can still use it. The resource is inserted right away, even if the World isn't in
+    'state' yet, and replaces the resource of the same type if there is one.
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum GameState { Playing, Paused }
+
+    struct PathCache(Vec<(i32, i32)>);
+    impl Resource for PathCache {}
+
+    fn pause(mut state: ResMut<States<GameState>>) {
+        state.set(GameState::Paused);
+    }
+
+    let mut world = World::new();
+    world.add_state(GameState::Playing);
+    world.insert_state_resource(GameState::Playing, PathCache(vec![(0, 0)]));
+    world.add_state_system(OnUpdate(GameState::Playing), pause);
+
+    world.run_schedule();
+    assert!(world.get_resource::<PathCache>().is_ok());
+
+    // the pause menu doesn't keep the caches of the game around
+    world.run_schedule();
+    assert_eq!(*world.get_resource::<States<GameState>>().unwrap().current(), GameState::Paused);
+    assert!(world.get_resource::<PathCache>().is_err());
+    ```
+
+    Panics if the state type wasn't added with [add_state()](struct.World.html#method.add_state).
+     */
+    pub fn insert_state_resource<T: StateType, R: Resource>(&mut self, state: T, resource: R) -> &mut Self {
+        self.insert_state_resource_checked(state, resource).unwrap()
+    }
+
+    /**
+    Inserts a resource that is removed when the World exits 'state', see
+    [insert_state_resource()](struct.World.html#method.insert_state_resource).
+
+    Returns an error if the state type wasn't added with [add_state()](struct.World.html#method.add_state).
+     */
+    pub fn insert_state_resource_checked<T: StateType, R: Resource>(&mut self, state: T, resource: R) -> Result<&mut Self> {
+        {
+            let mut resources = self.get_resource_mut::<StateResources<T>>().map_err(|_| StateError::NonexistentStateError)?;
+            // a resource type is only scoped to one state at a time, the last one it was inserted for
+            resources.scoped.values_mut().for_each(|scoped| scoped.retain(|(type_id, _)| *type_id != TypeId::of::<R>()));
+            resources.scoped.entry(state).or_default().push((TypeId::of::<R>(), remove_resource::<R>));
+        }
+        self.insert_resource(resource);
+        Ok(self)
+    }
+}
+
+/*
+    Runs the OnExit systems of the current state and the OnEnter systems of the next one,
+    applying the commands of each. StateSchedules is taken out of the resources while the
+    systems run, so that it isn't borrowed while the World is mutated.
+*/
+fn apply_state_transition<T: StateType>(world: &mut World) -> Result<()> {
+    let (exited, entered) = {
+        let mut states = world.get_resource_mut::<States<T>>()?;
+        if !states.entered {
+            states.entered = true;
+            if let Some(next) = states.next.take() {
+                states.current = next;
+            }
+            (None, Some(states.current.clone()))
+        } else {
+            match states.next.take() {
+                Some(next) => {
+                    let previous = std::mem::replace(&mut states.current, next);
+                    (Some(previous), Some(states.current.clone()))
+                },
+                None => (None, None),
+            }
+        }
+    };
+
+    if exited.is_none() && entered.is_none() {
+        return Ok(());
+    }
+
+    let schedules = world.delete_resource::<StateSchedules<T>>()?;
+    let mut result = Ok(());
+    if let Some(exited) = exited {
+        if let Some(systems) = schedules.on_exit.get(&exited) {
+            result = run_systems(world, systems);
+        }
+        let scoped = world.get_resource_mut::<StateResources<T>>()
+            .map(|mut resources| resources.scoped.remove(&exited).unwrap_or_default())
+            .unwrap_or_default();
+        for (_, remove) in scoped {
+            remove(world);
+        }
+    }
+    if let (Ok(()), Some(systems)) = (&result, entered.and_then(|state| schedules.on_enter.get(&state))) {
+        result = run_systems(world, systems);
+    }
+    world.insert_resource(schedules);
+
+    result
+}
+
+fn run_systems(world: &mut World, systems: &[BoxedSystem]) -> Result<()> {
+    for system in systems {
+        world.run_instrumented(system);
+    }
+    world.apply_commands()
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum StateError {
+    #[error("Attempt to add a system to a state that was not added to the World.")]
+    NonexistentStateError,
+}
@@ -0,0 +1,106 @@
+//! # Sub Worlds
+//!
+//! The sub world module allows splitting the World into two [SubWorld]s with [World::split()], each only
+//! allowed to access a set of components. The two sets must not conflict, which is checked once when
+//! splitting, so both views can be queried at the same time, for example by two halves of a system that
+//! would otherwise need conflicting parameters.
+//!
+//! The World isn't Sync, so 'at the same time' means interleaved on one thread rather than from
+//! several threads.
+
+use std::marker::PhantomData;
+
+use crate::{world::World, entities::{Entity, FnQuery, FnQueryContainedTupleType}, system::{check_conflicts, Access, ParamAccess, SystemError}};
+use crate::error::Result;
+
+/**
+A view of the World only allowed to access the components in 'A', created with [World::split()].
+'A' takes the same types as [FnQuery], where '&T' allows reading T and '&mut T' allows reading and writing it.
+ */
+pub struct SubWorld<'w, A> {
+    world: &'w World,
+    access: Vec<Access>,
+    phantom: PhantomData<fn() -> A>,
+}
+
+impl<'w, A> SubWorld<'w, A> {
+    /// Returns the components this view is allowed to access.
+    pub fn access(&self) -> &[Access] {
+        &self.access
+    }
+
+    /**
+    Returns a query for 'T', or an error if 'T' accesses a component this view wasn't given,
+    or borrows mutably a component it was only allowed to read.
+     */
+    pub fn query<T>(&self) -> Result<FnQuery<'w, T>>
+    where T: FnQueryContainedTupleType<'w>
+    {
+        self.check_access::<T>()?;
+        Ok(FnQuery::new(&self.world.entities))
+    }
+
+    /**
+    Returns the components 'T' of an entity, same as [World::get()](struct.World.html#method.get),
+    or an error if 'T' accesses a component this view wasn't given.
+     */
+    pub fn get<T>(&self, entity: Entity) -> Result<T::ReturnType>
+    where T: FnQueryContainedTupleType<'w>
+    {
+        self.check_access::<T>()?;
+        self.world.query().get::<T>(entity)
+    }
+
+    fn check_access<T>(&self) -> Result<()>
+    where T: FnQueryContainedTupleType<'w>
+    {
+        let allowed = |access: &Access| self.access.iter().any(|granted| {
+            granted.kind == access.kind && granted.type_id == access.type_id && (granted.mutable || !access.mutable)
+        });
+        match T::access().into_iter().find(|access| !allowed(access)) {
+            Some(access) => Err(SystemError::SubWorldAccessError(access.name).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+// Sub world stuff
+impl World {
+    /**
+    Splits the World into two views, the first only allowed to access the components in 'A' and the
+    second the ones in 'B'. Returns an error if the two sets conflict, that is if a component is in both
+    and at least one of them borrows it mutably.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Physics(f32);
+    struct Render(f32);
+
+    let mut world = World::new();
+    world.spawn().insert(Physics(1.0)).insert(Render(0.0));
+
+    let (physics, render) = world.split::<&mut Physics, &Render>().unwrap();
+    for (mut physics, render) in physics.query::<&mut Physics>().unwrap().iter().zip(render.query::<&Render>().unwrap().iter()) {
+        physics.0 += render.0;
+    }
+
+    // the render view can't touch the physics
+    assert!(render.query::<&Physics>().is_err());
+    assert!(world.split::<&mut Physics, &Physics>().is_err());
+    ```
+     */
+    pub fn split<'w, A, B>(&'w self) -> Result<(SubWorld<'w, A>, SubWorld<'w, B>)>
+    where
+        A: FnQueryContainedTupleType<'w>,
+        B: FnQueryContainedTupleType<'w>,
+    {
+        let first = ParamAccess { param: std::any::type_name::<A>(), access: A::access() };
+        let second = ParamAccess { param: std::any::type_name::<B>(), access: B::access() };
+        check_conflicts(&[first.clone(), second.clone()])?;
+        Ok((
+            SubWorld { world: self, access: first.access, phantom: PhantomData },
+            SubWorld { world: self, access: second.access, phantom: PhantomData },
+        ))
+    }
+}
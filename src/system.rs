@@ -1,22 +1,32 @@
 use std::{
-    any::Any,
-    marker::PhantomData, cell::{Ref, RefMut}
+    any::{Any, TypeId},
+    cell::{Ref, RefMut},
+    rc::Rc,
 };
 
-use crate::resources::Resources;
+use crate::resources::{Resource, Resources};
+use crate::world::World;
+use crate::commands::Commands;
+use crate::error::Result;
+use crate::ordering::SystemOrdering;
+use crate::schedule::Stage;
 
-use super::entities::{Entities, FnQuery, FnQueryContainedTupleType};
+use super::entities::{CachedQuery, FnQuery, FnQueryContainedTupleType};
 
 /**
 A function parameter that denotes an immutable reference to a Resource. 
 It's mutable equivalent is [ResMut].
 
+The resource is borrowed once when the system is run and stays borrowed until the system returns,
+it can be used just like a reference to the resource.
+
 Usage:
 ```
 use sceller::prelude::*;
 
 #[derive(Eq, PartialEq, Debug)]
 struct ReeseOurse(usize);
+impl Resource for ReeseOurse {}
 
 let mut world = World::new();
 world.insert_resource(ReeseOurse(55usize));
@@ -24,32 +34,46 @@ world.insert_resource(ReeseOurse(55usize));
 world.run_system(get_res);
 
 fn get_res(res: Res<ReeseOurse>) {
-   assert_eq!(*res.get(), ReeseOurse(55));
+   assert_eq!(*res, ReeseOurse(55));
+   assert_eq!(res.0, 55);
 }
 
 ```
  */
 pub struct Res<'a, T> {
-	resources: &'a Resources,
-	phantom: PhantomData<&'a T>,
+	value: Ref<'a, T>,
 }
 
-impl<'a, T: Any> Res<'a, T> {
+impl<'a, T: Resource> Res<'a, T> {
 	pub fn new(resources: &'a Resources) -> Self {
-		Self {
-			resources, phantom: PhantomData
-		}
+		Self::new_checked(resources).unwrap()
+	}
+
+	/// Same as [Res::new()], but returns an error if the resource does not exist.
+	pub fn new_checked(resources: &'a Resources) -> Result<Self> {
+		resources.get_ref::<T>().map(|value| Self { value })
+	}
+
+	/// Retrieve a reference to the content of the Resource, same as dereferencing it.
+	pub fn get(&self) -> &T {
+		&self.value
 	}
+}
+
+impl<'a, T> std::ops::Deref for Res<'a, T> {
+	type Target = T;
 
-	/// Retrieve a Ref<T> to the content of the Resource
-	pub fn get(&self) -> Ref<T> {
-		self.resources.get_ref::<T>().unwrap()
+	fn deref(&self) -> &Self::Target {
+		&self.value
 	}
 }
 
 /**
 A function parameter that denotes a mutable reference to a Resource. 
-It's immutable equivalent is [ResMut].
+It's immutable equivalent is [Res].
+
+The resource is mutably borrowed once when the system is run and stays borrowed until the system returns,
+so a system cannot take a [Res] and a [ResMut] (or two [ResMut]) of the same resource.
 
 Usage:
 ```
@@ -57,165 +81,634 @@ use sceller::prelude::*;
 
 #[derive(Eq, PartialEq, Debug)]
 struct ReeseOurse(usize);
+impl Resource for ReeseOurse {}
 
 let mut world = World::new();
 world.insert_resource(ReeseOurse(55usize));
 
 world.run_system(get_res);
 
-fn get_res(res_mut: ResMut<ReeseOurse>) {
-   assert_eq!(*res_mut.get(), ReeseOurse(55));
+fn get_res(mut res_mut: ResMut<ReeseOurse>) {
+   assert_eq!(*res_mut, ReeseOurse(55));
 
-   {
-	   res_mut.get().0 = 44;
-   }
+   res_mut.0 = 44;
 
-   assert_eq!(*res_mut.get(), ReeseOurse(44));
+   assert_eq!(*res_mut, ReeseOurse(44));
 }
 
 ```
  */
 pub struct ResMut<'a, T> {
-	resources: &'a Resources,
-	phantom: PhantomData<&'a T>,
+	value: RefMut<'a, T>,
 }
 
-impl<'a, T: Any> ResMut<'a, T> {
+impl<'a, T: Resource> ResMut<'a, T> {
 	pub fn new(resources: &'a Resources) -> Self {
-		Self {
-			resources, phantom: PhantomData
+		Self::new_checked(resources).unwrap()
+	}
+
+	/// Same as [ResMut::new()], but returns an error if the resource does not exist.
+	pub fn new_checked(resources: &'a Resources) -> Result<Self> {
+		resources.get_mut::<T>().map(|value| Self { value })
+	}
+
+	/// Retrieve a mutable reference to the content of the Resource, same as dereferencing it.
+	pub fn get(&mut self) -> &mut T {
+		&mut self.value
+	}
+}
+
+impl<'a, T> std::ops::Deref for ResMut<'a, T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.value
+	}
+}
+
+impl<'a, T> std::ops::DerefMut for ResMut<'a, T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.value
+	}
+}
+
+/**
+A trait implemented by every type that can be used as a parameter of a system.
+
+'Item' is the same parameter type but borrowing the [World] for the lifetime 'a, this way
+a system like `fn sys(res: Res<Thing>)` can be handed a parameter that only lives as long as 
+the call to [World::run_system()](struct.World.html#method.run_system).
+ */
+pub trait SystemParams {
+	type Item<'a>;
+
+	fn get(world: &World) -> Self::Item<'_>;
+
+	/// Returns an error if this parameter cannot be fetched from the world, in which case
+	/// [SystemParams::get()] would panic.
+	fn validate(_world: &World) -> Result<()> {
+		Ok(())
+	}
+
+	/// Returns the components and resources this parameter borrows.
+	fn access() -> Vec<Access> {
+		Vec::new()
+	}
+}
+
+/**
+A component or resource borrowed by a system parameter, used to find the parameters of a system
+that would borrow the same value while one of them borrows it mutably, which would panic when the system is run.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Access {
+	pub kind: AccessKind,
+	pub type_id: TypeId,
+	pub name: &'static str,
+	pub mutable: bool,
+}
+
+/// Whether an [Access] is to a component or a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessKind {
+	Component,
+	Resource,
+}
+
+impl std::fmt::Display for AccessKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AccessKind::Component => write!(f, "component"),
+			AccessKind::Resource => write!(f, "resource"),
 		}
 	}
+}
+
+impl Access {
+	/// An access to the component T.
+	pub fn component<T: Any + ?Sized>(mutable: bool) -> Self {
+		Self { kind: AccessKind::Component, type_id: TypeId::of::<T>(), name: std::any::type_name::<T>(), mutable }
+	}
+
+	/// An access to the resource T.
+	pub fn resource<T: Any>(mutable: bool) -> Self {
+		Self { kind: AccessKind::Resource, type_id: TypeId::of::<T>(), name: std::any::type_name::<T>(), mutable }
+	}
 
-	/// Retrieve a RefMut<T> to the content of the Resource
-	pub fn get(&self) -> RefMut<T> {
-		self.resources.get_mut::<T>().unwrap()
+	/// Returns true if both accesses borrow the same value and at least one of them is mutable.
+	pub fn conflicts_with(&self, other: &Access) -> bool {
+		self.kind == other.kind && self.type_id == other.type_id && (self.mutable || other.mutable)
 	}
 }
 
+/// The accesses of one parameter of a system, along with the parameter's type name.
+#[derive(Debug, Clone)]
+pub struct ParamAccess {
+	pub param: &'static str,
+	pub access: Vec<Access>,
+}
 
-trait ResParamType<'a> {
-	fn get(resources: &'a Resources) -> Self where Self: Sized;
+// returns an error naming the first two accesses of 'params' that conflict
+pub(crate) fn check_conflicts(params: &[ParamAccess]) -> Result<()> {
+	let accesses = params.iter()
+		.enumerate()
+		.flat_map(|(index, param)| param.access.iter().map(move |access| (index, param.param, access)))
+		.collect::<Vec<_>>();
+
+	for (i, (first_index, first, access)) in accesses.iter().enumerate() {
+		for (second_index, second, other) in &accesses[i + 1..] {
+			if access.conflicts_with(other) {
+				return Err(SystemError::ConflictingAccessError {
+					kind: access.kind,
+					name: access.name,
+					first: format!("#{} `{}`", first_index + 1, first),
+					second: format!("#{} `{}`", second_index + 1, second),
+				}.into());
+			}
+		}
+	}
+	Ok(())
 }
 
-impl<'a, T> ResParamType<'a> for Res<'a, T>
-where T: Any
+impl<'w, T> SystemParams for FnQuery<'w, T>
+where T: 'static + for<'a> FnQueryContainedTupleType<'a>
 {
-	fn get(resources: &'a Resources) -> Self where Self: Sized {
-	    Self::new(resources)
+	type Item<'a> = FnQuery<'a, T>;
+
+	fn get(world: &World) -> Self::Item<'_> {
+	    FnQuery::new(&world.entities)
+	}
+
+	fn validate(world: &World) -> Result<()> {
+		validate_query::<T>(world)
+	}
+
+	fn access() -> Vec<Access> {
+		T::access()
 	}
 }
 
-impl<'a, T> ResParamType<'a> for ResMut<'a, T>
-where T: Any
+impl<'w, T> SystemParams for CachedQuery<'w, T>
+where T: 'static + for<'a> FnQueryContainedTupleType<'a>
 {
-	fn get(resources: &'a Resources) -> Self where Self: Sized {
-	    Self::new(resources)
+	type Item<'a> = CachedQuery<'a, T>;
+
+	fn get(world: &World) -> Self::Item<'_> {
+	    CachedQuery::new(&world.entities, &world.query_caches)
+	}
+
+	fn validate(world: &World) -> Result<()> {
+		validate_query::<T>(world)
+	}
+
+	fn access() -> Vec<Access> {
+		T::access()
 	}
 }
 
-trait SystemParams<'a> {
-	fn get(entities: &'a Entities, resources: &'a Resources) -> Self where Self: Sized;
+impl<'w, T> SystemParams for Res<'w, T>
+where T: Resource
+{
+	type Item<'a> = Res<'a, T>;
+
+	fn get(world: &World) -> Self::Item<'_> {
+	    Res::new(&world.resources)
+	}
+
+	fn validate(world: &World) -> Result<()> {
+		validate_resource::<T>(world)
+	}
+
+	fn access() -> Vec<Access> {
+		vec![Access::resource::<T>(false)]
+	}
 }
 
-impl<'a, T> SystemParams<'a> for FnQuery<'a, T>
-where T: FnQueryContainedTupleType<'a>
+impl<'w, T> SystemParams for ResMut<'w, T>
+where T: Resource
 {
-	fn get(entities: &'a Entities, _resources: &'a Resources) -> Self {
-	    Self::new(entities)
+	type Item<'a> = ResMut<'a, T>;
+
+	fn get(world: &World) -> Self::Item<'_> {
+	    ResMut::new(&world.resources)
+	}
+
+	fn validate(world: &World) -> Result<()> {
+		validate_resource::<T>(world)
+	}
+
+	fn access() -> Vec<Access> {
+		vec![Access::resource::<T>(true)]
 	}
 }
 
-impl<'a, T> SystemParams<'a> for Res<'a, T>
-where T: Any
+/**
+A [Res] that is None when the resource does not exist, instead of panicking.
+
+```
+use sceller::prelude::*;
+
+struct Score(u32);
+impl Resource for Score {}
+
+let mut world = World::new();
+
+world.run_system(|score: Option<Res<Score>>| {
+    assert!(score.is_none());
+});
+
+world.insert_resource(Score(3));
+
+world.run_system(|score: Option<Res<Score>>| {
+    assert_eq!(score.unwrap().0, 3);
+});
+```
+ */
+impl<'w, T> SystemParams for Option<Res<'w, T>>
+where T: Resource
 {
-	fn get(_entities: &'a Entities, resources: &'a Resources) -> Self {
-	    Self::new(resources)
+	type Item<'a> = Option<Res<'a, T>>;
+
+	fn get(world: &World) -> Self::Item<'_> {
+	    Res::new_checked(&world.resources).ok()
+	}
+
+	fn access() -> Vec<Access> {
+		vec![Access::resource::<T>(false)]
 	}
 }
 
-impl<'a, T> SystemParams<'a> for ResMut<'a, T>
-where T: Any
+/// A [ResMut] that is None when the resource does not exist, instead of panicking.
+impl<'w, T> SystemParams for Option<ResMut<'w, T>>
+where T: Resource
 {
-	fn get(_entities: &'a Entities, resources: &'a Resources) -> Self {
-	    Self::new(resources)
+	type Item<'a> = Option<ResMut<'a, T>>;
+
+	fn get(world: &World) -> Self::Item<'_> {
+	    ResMut::new_checked(&world.resources).ok()
+	}
+
+	fn access() -> Vec<Access> {
+		vec![Access::resource::<T>(true)]
 	}
 }
 
-pub trait IntoSystem<'a, Arguments> {
-	fn run(self, entities: &'a Entities, resources: &'a Resources);
+fn validate_resource<T: Any>(world: &World) -> Result<()> {
+	if world.resources.contains::<T>() {
+		Ok(())
+	} else {
+		Err(SystemError::MissingResourceError(std::any::type_name::<T>()).into())
+	}
 }
 
-impl<'a, F, T> IntoSystem<'a, T> for F 
-where 
-	T: SystemParams<'a>,
-	F: Fn(T)
+fn validate_query<T>(world: &World) -> Result<()>
+where T: for<'a> FnQueryContainedTupleType<'a>
 {
-	fn run(self, entities: &'a Entities, resources: &'a Resources) {
-	    (self)(T::get(entities, resources))
+	for (typeid, name) in T::type_ids().iter().zip(T::type_names()) {
+		if world.entities.get_bitmask(typeid).is_none() {
+			return Err(SystemError::UnregisteredComponentError(name).into());
+		}
 	}
+	Ok(())
 }
 
-impl<'a, F, T1, T2> IntoSystem<'a, (T1, T2)> for F 
-where 
-	T1: SystemParams<'a>,
-	T2: SystemParams<'a>,
-	F: Fn(T1, T2)
-{
-	fn run(self, entities: &'a Entities, resources: &'a Resources) {
-	    (self)(T1::get(entities, resources), T2::get(entities, resources))
+impl<'w> SystemParams for Commands<'w> {
+	type Item<'a> = Commands<'a>;
+
+	fn get(world: &World) -> Self::Item<'_> {
+	    Commands::with_entities(&world.commands, &world.entities)
 	}
 }
 
-impl<'a, F, T1, T2, T3> IntoSystem<'a, (T1, T2, T3)> for F 
-where 
-	T1: SystemParams<'a>,
-	T2: SystemParams<'a>,
-	T3: SystemParams<'a>,
-	F: Fn(T1, T2, T3)
-{
-	fn run(self, entities: &'a Entities, resources: &'a Resources) {
-	    (self)(
-	    	T1::get(entities, resources), 
-	    	T2::get(entities, resources),
-	    	T3::get(entities, resources))
+/**
+A trait implemented for any function that can be run as a system, that is any function
+taking up to twelve [SystemParams] and returning either nothing or a `Result<(), E>`, whose errors
+are given to the World's [error handler](struct.World.html#method.set_error_handler).
+ */
+pub trait IntoSystem<Arguments> {
+	fn run(&self, world: &World);
+
+	/// Returns an error naming the first parameter of this system that cannot be fetched from the world,
+	/// or the first two parameters whose accesses conflict.
+	fn validate(&self, _world: &World) -> Result<()> {
+		self.check_access()
+	}
+
+	/// Returns the components and resources borrowed by each parameter of this system.
+	fn access(&self) -> Vec<ParamAccess> {
+		Vec::new()
+	}
+
+	/// Returns an error naming the component or resource, and the two parameters, if two parameters of this system 
+	/// would borrow the same component or resource while one of them borrows it mutably.
+	///
+	/// ```
+	/// use sceller::prelude::*;
+	///
+	/// struct Position(i32);
+	/// impl Resource for Position {}
+	///
+	/// fn nested(outer: FnQuery<&mut Position>, inner: FnQuery<&Position>) {}
+	/// fn fine(outer: FnQuery<&mut Position>, res: Res<Position>) {}
+	///
+	/// assert!(nested.check_access().unwrap_err().to_string().contains("Position"));
+	/// assert!(fine.check_access().is_ok());
+	/// ```
+	fn check_access(&self) -> Result<()> {
+		check_conflicts(&self.access())
+	}
+
+	/// Returns the name of the system, which is the type name of the function it was created from.
+	fn name(&self) -> &'static str {
+		std::any::type_name::<Self>()
+	}
+
+	/// Returns the sets this system is part of and the systems it runs before and after,
+	/// see [IntoOrderedSystem](trait.IntoOrderedSystem.html).
+	fn ordering(&self) -> SystemOrdering {
+		SystemOrdering::default()
 	}
 }
 
-impl<'a, F, T1, T2, T3, T4> IntoSystem<'a, (T1, T2, T3, T4)> for F 
-where 
-	T1: SystemParams<'a>,
-	T2: SystemParams<'a>,
-	T3: SystemParams<'a>,
-	T4: SystemParams<'a>,
-	F: Fn(T1, T2, T3, T4)
-{
-	fn run(self, entities: &'a Entities, resources: &'a Resources) {
-	    (self)(
-	    	T1::get(entities, resources), 
-	    	T2::get(entities, resources),
-	    	T3::get(entities, resources),
-	    	T4::get(entities, resources),
-	    	)
-	}
-}
-
-impl<'a, F, T1, T2, T3, T4, T5> IntoSystem<'a, (T1, T2, T3, T4, T5)> for F 
-where 
-	T1: SystemParams<'a>,
-	T2: SystemParams<'a>,
-	T3: SystemParams<'a>,
-	T4: SystemParams<'a>,
-	T5: SystemParams<'a>,
-	F: Fn(T1, T2, T3, T4, T5)
-{
-	fn run(self, entities: &'a Entities, resources: &'a Resources) {
-	    (self)(
-	    	T1::get(entities, resources), 
-	    	T2::get(entities, resources),
-	    	T3::get(entities, resources),
-	    	T4::get(entities, resources),
-	    	T5::get(entities, resources),
-	    	)
-	}
-}
\ No newline at end of file
+/**
+A type erased system, used to store systems of different types together, for
+example inside of a [Schedule](struct.Schedule.html).
+
+```
+use sceller::prelude::*;
+
+struct Counter(u32);
+impl Resource for Counter {}
+
+fn count(mut counter: ResMut<Counter>) {
+    counter.0 += 1;
+}
+
+let mut world = World::new();
+world.insert_resource(Counter(0));
+
+let system = BoxedSystem::new(count);
+
+system.run(&world);
+system.run(&world);
+
+assert_eq!(world.get_resource::<Counter>().unwrap().0, 2);
+```
+ */
+pub struct BoxedSystem {
+	name: &'static str,
+	func: Box<dyn Fn(&World)>,
+	validate: ValidateFn,
+	access: Vec<ParamAccess>,
+	ordering: SystemOrdering,
+}
+
+type ValidateFn = Box<dyn Fn(&World) -> Result<()>>;
+
+impl BoxedSystem {
+	/// Boxes any function implementing [IntoSystem].
+	pub fn new<F, T>(system: F) -> Self
+	where
+		F: IntoSystem<T> + 'static,
+		T: 'static,
+	{
+		let system = Rc::new(system);
+		let validated = Rc::clone(&system);
+		Self {
+			name: system.name(),
+			access: system.access(),
+			ordering: system.ordering(),
+			func: Box::new(move |world| system.run(world)),
+			validate: Box::new(move |world| validated.validate(world)),
+		}
+	}
+
+	/// Returns the name of the function this system was created from.
+	pub fn name(&self) -> &'static str {
+		self.name
+	}
+
+	/// Returns the sets this system is part of and the systems it runs before and after.
+	pub fn ordering(&self) -> &SystemOrdering {
+		&self.ordering
+	}
+
+	/// Returns the name and accesses of the system, see [SystemMeta].
+	pub fn meta(&self) -> SystemMeta {
+		SystemMeta { name: self.name, stage: None, params: self.access.clone() }
+	}
+
+	/// Runs the system on the World.
+	///
+	/// Note: this does not apply the [Commands] the system queued, see 
+	/// [World::apply_commands()](struct.World.html#method.apply_commands).
+	pub fn run(&self, world: &World) {
+		(self.func)(world)
+	}
+}
+
+impl IntoSystem<BoxedSystem> for BoxedSystem {
+	fn run(&self, world: &World) {
+		(self.func)(world)
+	}
+
+	fn validate(&self, world: &World) -> Result<()> {
+		(self.validate)(world)
+	}
+
+	fn access(&self) -> Vec<ParamAccess> {
+		self.access.clone()
+	}
+
+	fn name(&self) -> &'static str {
+		self.name
+	}
+
+	fn ordering(&self) -> SystemOrdering {
+		self.ordering.clone()
+	}
+}
+
+/**
+What a system reads and writes, along with it's name and the stage it was added to, if it was added to a
+[Schedule](struct.Schedule.html). Created with [SystemMeta::of()] for any system, or returned for every system of
+the World by [World::system_metas()](struct.World.html#method.system_metas).
+
+```
+use sceller::prelude::*;
+
+struct Position(i32);
+struct Velocity(i32);
+struct Gravity(i32);
+impl Resource for Gravity {}
+
+fn movement(query: FnQuery<(&mut Position, &Velocity)>) {}
+fn fall(gravity: Res<Gravity>, query: FnQuery<&mut Velocity>) {}
+
+let meta = SystemMeta::of(&movement);
+assert!(meta.writes_component::<Position>());
+assert!(meta.reads_component::<Velocity>());
+assert!(!meta.writes_component::<Velocity>());
+
+let other = SystemMeta::of(&fall);
+assert!(other.reads_resource::<Gravity>());
+assert!(meta.conflicts_with(&other));
+```
+ */
+#[derive(Debug, Clone)]
+pub struct SystemMeta {
+	pub name: &'static str,
+	pub stage: Option<Stage>,
+	/// The components and resources borrowed by each parameter of the system.
+	pub params: Vec<ParamAccess>,
+}
+
+impl SystemMeta {
+	/// Returns the name and accesses of a system, which isn't part of any stage.
+	pub fn of<T>(system: &impl IntoSystem<T>) -> Self {
+		Self { name: system.name(), stage: None, params: system.access() }
+	}
+
+	/// Returns every component and resource the system borrows, mutably or not.
+	pub fn access(&self) -> impl Iterator<Item = &Access> {
+		self.params.iter().flat_map(|param| &param.access)
+	}
+
+	/// Returns the components and resources the system only borrows immutably.
+	pub fn reads(&self) -> impl Iterator<Item = &Access> {
+		self.access().filter(|access| !access.mutable)
+	}
+
+	/// Returns the components and resources the system borrows mutably.
+	pub fn writes(&self) -> impl Iterator<Item = &Access> {
+		self.access().filter(|access| access.mutable)
+	}
+
+	/// Returns true if the system borrows the component T, mutably or not.
+	pub fn reads_component<T: Any>(&self) -> bool {
+		self.has_access(AccessKind::Component, TypeId::of::<T>(), false)
+	}
+
+	/// Returns true if the system borrows the component T mutably.
+	pub fn writes_component<T: Any>(&self) -> bool {
+		self.has_access(AccessKind::Component, TypeId::of::<T>(), true)
+	}
+
+	/// Returns true if the system borrows the resource T, mutably or not.
+	pub fn reads_resource<T: Any>(&self) -> bool {
+		self.has_access(AccessKind::Resource, TypeId::of::<T>(), false)
+	}
+
+	/// Returns true if the system borrows the resource T mutably.
+	pub fn writes_resource<T: Any>(&self) -> bool {
+		self.has_access(AccessKind::Resource, TypeId::of::<T>(), true)
+	}
+
+	fn has_access(&self, kind: AccessKind, type_id: TypeId, mutable: bool) -> bool {
+		self.access().any(|access| access.kind == kind && access.type_id == type_id && (access.mutable || !mutable))
+	}
+
+	/// Returns true if both systems borrow the same component or resource while one of them borrows it mutably,
+	/// in which case they can't run at the same time.
+	pub fn conflicts_with(&self, other: &SystemMeta) -> bool {
+		self.access().any(|access| other.access().any(|other| access.conflicts_with(other)))
+	}
+}
+
+impl std::fmt::Debug for BoxedSystem {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("BoxedSystem")
+			.field("name", &self.name)
+			.finish()
+	}
+}
+
+// implements IntoSystem for any function taking the given SystemParams
+macro_rules! impl_into_system {
+	($($param:ident),+) => {
+		impl<F, $($param),+> IntoSystem<($($param,)+)> for F 
+		where 
+			$($param: SystemParams,)+
+			F: Fn($($param),+) + for<'a> Fn($($param::Item<'a>),+)
+		{
+			fn run(&self, world: &World) {
+			    (self)($($param::get(world)),+)
+			}
+
+			fn validate(&self, world: &World) -> Result<()> {
+				$($param::validate(world)?;)+
+				self.check_access()
+			}
+
+			fn access(&self) -> Vec<ParamAccess> {
+				vec![$(ParamAccess { param: std::any::type_name::<$param>(), access: $param::access() }),+]
+			}
+		}
+	};
+}
+
+impl_into_system!(T1);
+impl_into_system!(T1, T2);
+impl_into_system!(T1, T2, T3);
+impl_into_system!(T1, T2, T3, T4);
+impl_into_system!(T1, T2, T3, T4, T5);
+impl_into_system!(T1, T2, T3, T4, T5, T6);
+impl_into_system!(T1, T2, T3, T4, T5, T6, T7);
+impl_into_system!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_into_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_into_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_into_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_into_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+
+// implements IntoSystem for any function taking the given SystemParams and returning a Result,
+// whose errors are given to the World's error handler
+macro_rules! impl_into_fallible_system {
+	($($param:ident),+) => {
+		impl<F, E, $($param),+> IntoSystem<(Result<(), E>, ($($param,)+))> for F 
+		where 
+			$($param: SystemParams,)+
+			E: Into<Box<dyn std::error::Error>>,
+			F: Fn($($param),+) -> Result<(), E> + for<'a> Fn($($param::Item<'a>),+) -> Result<(), E>
+		{
+			fn run(&self, world: &World) {
+				if let Err(error) = (self)($($param::get(world)),+) {
+					world.handle_system_error(self.name(), error.into());
+				}
+			}
+
+			fn validate(&self, world: &World) -> Result<()> {
+				$($param::validate(world)?;)+
+				self.check_access()
+			}
+
+			fn access(&self) -> Vec<ParamAccess> {
+				vec![$(ParamAccess { param: std::any::type_name::<$param>(), access: $param::access() }),+]
+			}
+		}
+	};
+}
+
+impl_into_fallible_system!(T1);
+impl_into_fallible_system!(T1, T2);
+impl_into_fallible_system!(T1, T2, T3);
+impl_into_fallible_system!(T1, T2, T3, T4);
+impl_into_fallible_system!(T1, T2, T3, T4, T5);
+impl_into_fallible_system!(T1, T2, T3, T4, T5, T6);
+impl_into_fallible_system!(T1, T2, T3, T4, T5, T6, T7);
+impl_into_fallible_system!(T1, T2, T3, T4, T5, T6, T7, T8);
+impl_into_fallible_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_into_fallible_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_into_fallible_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_into_fallible_system!(T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+
+#[derive(thiserror::Error, Debug)]
+pub enum SystemError {
+	#[error("System requires the resource {0}, which does not exist.")]
+	MissingResourceError(&'static str),
+	#[error("System queries the component {0}, which was never registered.")]
+	UnregisteredComponentError(&'static str),
+	#[error("System parameters {first} and {second} both borrow the {kind} {name}, and at least one of them borrows it mutably.")]
+	ConflictingAccessError { kind: AccessKind, name: &'static str, first: String, second: String },
+	#[error("Sub world accesses the component {0}, which it wasn't given.")]
+	SubWorldAccessError(&'static str),
+}
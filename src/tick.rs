@@ -0,0 +1,152 @@
+//! # Ticks
+//!
+//! The tick module adds the [Tick] resource, a counter advanced every time the World runs it's
+//! [Schedule](crate::schedule::Schedule), and [World::run_steps()], which runs the World's schedule a precise
+//! number of times. This is what turn-based games and headless simulations need to advance the World step by step,
+//! the same way every time.
+//!
+//! Every component that is inserted, replaced or borrowed mutably is marked with the tick during which it changed,
+//! so that the systems of the next tick can only visit the components that changed with the
+//! [Changed](crate::entities::Changed) filter.
+
+use std::any::Any;
+
+use crate::{world::World, resources::Resource, entities::Entity, time::Time};
+
+/**
+A resource storing the number of times the World's [Schedule](crate::schedule::Schedule) ran, inserted
+the first time it runs. The first run is tick 1, so the changes made before it are seen during it.
+Changing the resource doesn't change the tick of the World, it is overwritten the next time the tick advances.
+
+```
+use sceller::prelude::*;
+
+struct Turns(Vec<u64>);
+impl Resource for Turns {}
+
+fn play(tick: Res<Tick>, mut turns: ResMut<Turns>) {
+    turns.0.push(tick.0);
+}
+
+let mut world = World::new();
+world.insert_resource(Turns(Vec::new()));
+world.add_system(play);
+
+world.run_steps(3);
+assert_eq!(world.get_resource::<Turns>().unwrap().0, vec![1, 2, 3]);
+assert_eq!(*world.get_resource::<Tick>().unwrap(), Tick(3));
+```
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Tick(pub u64);
+
+impl Resource for Tick {}
+
+// Tick stuff
+impl World {
+    /**
+    Returns the current tick of the World, which is 0 until it's schedule first runs. See the [tick module](crate::tick).
+     */
+    pub fn tick(&self) -> u64 {
+        self.entities.tick()
+    }
+
+    /**
+    Advances the tick of the World and updates the [Tick] resource, inserting it if it doesn't exist.
+
+    This is already done every time a [Schedule](crate::schedule::Schedule) runs, so it only needs to be called
+    when systems are run without a schedule.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(10));
+    world.spawn().insert(Health(10));
+
+    // the components inserted during tick 0 are seen as changed during tick 1
+    world.advance_tick();
+    assert_eq!(world.query_typed::<Changed<Health>>().count(), 2);
+
+    world.get::<&mut Health>(1).unwrap().0 -= 5;
+    world.advance_tick();
+    assert_eq!(world.query_typed::<Changed<Health>>().count(), 1);
+
+    world.advance_tick();
+    assert_eq!(world.query_typed::<Changed<Health>>().count(), 0);
+    ```
+     */
+    pub fn advance_tick(&mut self) {
+        let tick = self.entities.tick() + 1;
+        self.entities.set_tick(tick);
+        self.insert_resource(Tick(tick));
+    }
+
+    /**
+    Returns the last tick during which the component T of 'entity' changed, that is was inserted, replaced
+    or borrowed mutably. Returns None if the entity doesn't have the component.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut world = World::new();
+    let player = world.spawn().insert(Health(10)).current_entity();
+    world.advance_tick();
+    world.advance_tick();
+    assert_eq!(world.changed_tick::<Health>(player), Some(0));
+
+    world.get::<&mut Health>(player).unwrap().0 -= 1;
+    assert_eq!(world.changed_tick::<Health>(player), Some(2));
+    ```
+     */
+    pub fn changed_tick<T: Any>(&self, entity: Entity) -> Option<u64> {
+        self.entities.changed_tick::<T>(entity)
+    }
+
+    /**
+    Runs the World's [Schedule](crate::schedule::Schedule) 'steps' times, like calling [World::run_schedule()]
+    in a loop, except that the [Time] resource advances by exactly one fixed step per run instead of by the real
+    time passed. Every step runs the [Stage::FixedUpdate](crate::schedule::Stage::FixedUpdate) stage once and sees
+    the same delta, so the World ends up in the same state every time.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Position(f32);
+    struct Velocity(f32);
+
+    fn movement(time: Res<Time>, query: FnQuery<(&mut Position, &Velocity)>) {
+        for (mut pos, vel) in query {
+            pos.0 += vel.0 * time.delta_seconds();
+        }
+    }
+
+    let mut world = World::new();
+    world.spawn().insert(Position(0.0)).insert(Velocity(60.0));
+    world.add_system(movement);
+
+    world.run_steps(120);
+    assert_eq!(world.tick(), 120);
+    assert_eq!(world.get_resource::<Time>().unwrap().frame_count(), 120);
+    assert!((world.get::<&Position>(0).unwrap().0 - 120.0).abs() < 0.01);
+    ```
+
+    Panics if a queued command fails to apply.
+     */
+    pub fn run_steps(&mut self, steps: u64) {
+        self.init_resource::<Time>();
+        for _ in 0..steps {
+            {
+                let mut time = self.get_resource_mut::<Time>().unwrap();
+                let step = time.fixed_step();
+                time.advance(step);
+            }
+            self.begin_diagnostics_frame();
+            self.run_own_frame().unwrap();
+        }
+    }
+}
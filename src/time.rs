@@ -0,0 +1,209 @@
+//! # Time
+//!
+//! The time module adds the [Time] resource, which keeps track of the time between frames, the time
+//! elapsed since the first frame and the number of frames. It is updated at the start of every call to
+//! [World::run_schedule()](struct.World.html#method.run_schedule), or by [World::update_time()](struct.World.html#method.update_time)
+//! when the systems are run without a schedule.
+//!
+//! The real time passed is also accumulated to run the [Stage::FixedUpdate](enum.Stage.html#variant.FixedUpdate) stage
+//! once for every fixed step, which can be run any number of times per frame, so that physics and gameplay
+//! code don't depend on the framerate.
+//!
+//! On `wasm32-unknown-unknown` there is no clock to read without JavaScript, so every frame is one fixed step
+//! long, as if the World was run with [World::run_steps()](crate::world::World::run_steps).
+
+use std::time::{Duration, Instant};
+
+use crate::{world::World, resources::Resource};
+
+/// The default time between two runs of the [Stage::FixedUpdate](enum.Stage.html#variant.FixedUpdate) stage, 60 times per second.
+pub const DEFAULT_FIXED_STEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+// returns the current instant, or None on targets without a clock, where Instant::now() panics
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) fn now() -> Option<Instant> {
+    Some(Instant::now())
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) fn now() -> Option<Instant> {
+    None
+}
+
+/**
+A resource storing the time between frames, used to make systems independent of the framerate.
+
+```
+use sceller::prelude::*;
+
+struct Position(f32);
+struct Velocity(f32);
+
+fn movement(time: Res<Time>, query: FnQuery<(&mut Position, &Velocity)>) {
+    for (mut pos, vel) in query {
+        pos.0 += vel.0 * time.delta_seconds();
+    }
+}
+
+let mut world = World::new();
+world.spawn().insert(Position(0.0)).insert(Velocity(2.0));
+world.add_system(movement);
+
+world.run_schedule();
+assert_eq!(world.get_resource::<Time>().unwrap().frame_count(), 1);
+```
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Time {
+    delta: Duration,
+    elapsed: Duration,
+    frame_count: u64,
+    last_update: Option<Instant>,
+    fixed_step: Duration,
+    // the time passed that hasn't been used by a fixed step yet
+    accumulated: Duration,
+}
+
+impl Resource for Time {}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self {
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            frame_count: 0,
+            last_update: None,
+            fixed_step: DEFAULT_FIXED_STEP,
+            accumulated: Duration::ZERO,
+        }
+    }
+}
+
+impl Time {
+    /// Returns the time passed between the last two frames.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Returns the time passed between the last two frames, in seconds.
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Returns the time passed since the first frame.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the time passed since the first frame, in seconds.
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    /// Returns the number of frames since the Time was created.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Returns the time between two runs of the FixedUpdate stage.
+    pub fn fixed_step(&self) -> Duration {
+        self.fixed_step
+    }
+
+    /// Returns the time between two runs of the FixedUpdate stage, in seconds.
+    pub fn fixed_step_seconds(&self) -> f32 {
+        self.fixed_step.as_secs_f32()
+    }
+
+    /**
+    Sets the time between two runs of the FixedUpdate stage. Panics if the step is zero.
+     */
+    pub fn set_fixed_step(&mut self, step: Duration) {
+        assert!(!step.is_zero(), "Attempt to set a fixed step of zero");
+        self.fixed_step = step;
+    }
+
+    /// Returns the time accumulated towards the next run of the FixedUpdate stage.
+    pub fn accumulated(&self) -> Duration {
+        self.accumulated
+    }
+
+    /**
+    Starts a new frame, using the real time passed since the last call as the delta.
+    The delta of the first frame is zero, and on targets without a clock every delta is one fixed step.
+     */
+    pub fn update(&mut self) {
+        let Some(now) = now() else {
+            let step = self.fixed_step;
+            self.advance(step);
+            return;
+        };
+        let delta = self.last_update.map(|last| now - last).unwrap_or_default();
+        self.last_update = Some(now);
+        self.advance(delta);
+    }
+
+    /**
+    Starts a new frame, 'delta' after the last one, without looking at the real time.
+    Useful to run a World at a fixed rate, or in tests.
+
+    ```
+    use sceller::prelude::*;
+    use std::time::Duration;
+
+    let mut time = Time::default();
+    time.advance(Duration::from_millis(20));
+    time.advance(Duration::from_millis(30));
+
+    assert_eq!(time.delta(), Duration::from_millis(30));
+    assert_eq!(time.elapsed(), Duration::from_millis(50));
+    assert_eq!(time.frame_count(), 2);
+    ```
+     */
+    pub fn advance(&mut self, delta: Duration) {
+        self.delta = delta;
+        self.elapsed += delta;
+        self.accumulated += delta;
+        self.frame_count += 1;
+    }
+
+    // uses up one fixed step of the time accumulated, returns false if there isn't enough time left
+    fn expend_fixed_step(&mut self) -> bool {
+        if self.accumulated < self.fixed_step {
+            return false;
+        }
+        self.accumulated -= self.fixed_step;
+        true
+    }
+}
+
+// Time stuff
+impl World {
+    /**
+    Updates the [Time] resource with the real time passed since the last update, inserting it if it doesn't exist.
+
+    This is already done at the start of [World::run_schedule()], so it only needs to be called when systems are run
+    without the schedule.
+
+    ```
+    use sceller::prelude::*;
+
+    let mut world = World::new();
+    world.update_time();
+    world.update_time();
+
+    assert_eq!(world.get_resource::<Time>().unwrap().frame_count(), 2);
+    ```
+     */
+    pub fn update_time(&mut self) {
+        self.init_resource::<Time>();
+        self.get_resource_mut::<Time>().unwrap().update();
+    }
+
+    // uses up one fixed step of the Time resource, returns false if there is no Time or not enough time left
+    pub(crate) fn expend_fixed_step(&self) -> bool {
+        self.get_resource_mut::<Time>()
+            .map(|mut time| time.expend_fixed_step())
+            .unwrap_or(false)
+    }
+}
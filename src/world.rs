@@ -6,23 +6,52 @@
 use std::any::Any;
 
 use crate::prelude::*;
+use crate::entities::QueryCaches;
+use crate::hooks::ComponentHooks;
+use crate::observer::Observers;
+use crate::relationship::Relationships;
+use crate::journal::Journal;
+use crate::rollback::RollbackResources;
 
 #[derive(Debug, Default)]
 /**
 World contains the ECS, and is used to interact with it.
  */
 pub struct World {
-    resources: Resources,
-    entities: Entities,
+    pub(crate) resources: Resources,
+    pub(crate) entities: Entities,
+    pub(crate) commands: CommandQueue,
+    pub(crate) query_caches: QueryCaches,
+    pub(crate) hooks: ComponentHooks,
+    pub(crate) observers: Observers,
+    pub(crate) relationships: Relationships,
+    pub(crate) journal: Journal,
+    pub(crate) rollback: RollbackResources,
+    schedule: Schedule,
+    // whether the startup systems were run
+    started: bool,
+    state_transitions: Vec<fn(&mut World) -> Result<()>>,
+    // the function given the errors returned by systems, panic_on_error if None
+    pub(crate) error_handler: Option<ErrorHandler>,
+    #[cfg(feature = "serde")]
+    pub(crate) serde_registry: crate::serialization::SerdeRegistry,
+    #[cfg(feature = "serde")]
+    pub(crate) replication: crate::replication::Replication,
 }
 
 // Resource stuff
 impl World {
     /**
      Constructor function. Initialises all contained structs to their default values.
+
+     With the `auto-register` feature, also registers every type deriving [Component](component::Component).
      */
     pub fn new() -> Self {
-        Self::default()
+        #[allow(unused_mut)]
+        let mut world = Self::default();
+        #[cfg(feature = "auto-register")]
+        world.register_components();
+        world
     }
 
     /**
@@ -31,12 +60,112 @@ impl World {
      * Ensures that it is passed all of the necessary information, such as
      * requested resources, or queries. This function's implementation is
      * built on the code in the [system] module, so check out that for more info. 
+     * 
+     * Any [Commands] queued by the system are applied once it has finished running,
+     * panics if one of them fails. If the system returns an error, it is given to the World's
+     * error handler, see [set_error_handler()](struct.World.html#method.set_error_handler).
+     * 
+     * Panics before running the system if two of it's parameters conflict, see 
+     * [IntoSystem::check_access()](trait.IntoSystem.html#method.check_access).
+     */
+    pub fn run_system<F, T>(&mut self, gen: F)
+    where
+        F: IntoSystem<T>
+    {
+        gen.check_access().unwrap();
+        gen.run(self);
+        self.apply_commands().unwrap();
+    }
+
+    /**
+     * Same as [run_system()](struct.World.html#method.run_system), but instead of panicking
+     * when one of the system's parameters cannot be fetched (a [Res] of a resource that does not exist,
+     * or an [FnQuery] of a component that was never registered), or when two of them conflict, 
+     * returns an error naming them without running the system.
+     * 
+     * ```
+     * use sceller::prelude::*;
+     * 
+     * struct Score(u32);
+     * impl Resource for Score {}
+     * 
+     * let mut world = World::new();
+     * 
+     * let result = world.run_system_checked(|score: Res<Score>| println!("{}", score.0));
+     * assert!(result.unwrap_err().to_string().contains("Score"));
+     * ```
      */
-    pub fn run_system<'a, F, T: 'a>(&'a self, gen: F)
+    pub fn run_system_checked<F, T>(&mut self, gen: F) -> Result<()>
     where
-        F: IntoSystem<'a, T>
+        F: IntoSystem<T>
     {
-        gen.run(&self.entities, &self.resources)
+        gen.validate(self)?;
+        gen.run(self);
+        self.apply_commands()
+    }
+
+    /**
+     * Applies every command waiting in the World's [CommandQueue].
+     * 
+     * This is done automatically by [run_system()](struct.World.html#method.run_system), 
+     * but is useful when queueing commands by hand with [commands()](struct.World.html#method.commands).
+     * 
+     * Returns the error of the first command that fails.
+     */
+    pub fn apply_commands(&mut self) -> Result<()> {
+        let queue = std::mem::take(&mut self.commands);
+        self.step(|world| queue.apply(world))
+    }
+
+    /**
+     * Returns a [Commands] struct pushing into this World's command queue.
+     * 
+     * ```
+     * use sceller::prelude::*;
+     * 
+     * struct Thing(u8);
+     * 
+     * let mut world = World::new();
+     * world.register_component::<Thing>();
+     * 
+     * world.commands().spawn().insert(Thing(3));
+     * assert_eq!(world.query().auto::<Thing>().len(), 0);
+     * 
+     * world.apply_commands().unwrap();
+     * assert_eq!(world.query().auto::<Thing>().len(), 1);
+     * ```
+     */
+    pub fn commands(&self) -> Commands<'_> {
+        Commands::with_entities(&self.commands, &self.entities)
+    }
+
+    /**
+     * Reserves the id of a new entity and returns an [EntityCommands] queueing it's components, the entity
+     * is spawned when the commands are applied. Only needs an immutable reference, so it can be used while
+     * iterating over a query. See [Commands::spawn_reserved()](struct.Commands.html#method.spawn_reserved).
+     * 
+     * ```
+     * use sceller::prelude::*;
+     * 
+     * struct Gun(u32);
+     * struct Bullet(u32);
+     * 
+     * let mut world = World::new();
+     * world.spawn().insert(Gun(5));
+     * world.spawn().insert(Gun(8));
+     * 
+     * let mut bullets = Vec::new();
+     * for gun in world.query_typed::<&Gun>() {
+     *     bullets.push(world.spawn_deferred().insert(Bullet(gun.0)).id().unwrap());
+     * }
+     * assert_eq!(bullets, vec![2, 3]);
+     * 
+     * world.apply_commands().unwrap();
+     * assert_eq!(world.get::<&Bullet>(3).unwrap().0, 8);
+     * ```
+     */
+    pub fn spawn_deferred(&self) -> EntityCommands<'_> {
+        self.commands().spawn_reserved()
     }
 
     /**
@@ -48,6 +177,7 @@ impl World {
      
      #[derive(Eq, PartialEq, Debug)]
      struct ImportantResource(String);
+     impl Resource for ImportantResource {}
 
      {
          let mut world = World::new();
@@ -60,8 +190,38 @@ impl World {
      }
      ```
      */
-    pub fn insert_resource<T: std::any::Any>(&mut self, res: T) {
-        self.resources.add(res);
+    pub fn insert_resource<T: Resource>(&mut self, res: T) {
+        self.step(|world| {
+            world.record_resource::<T>();
+            world.resources.add(res);
+        });
+    }
+
+    /**
+     Inserts a resource created with [FromWorld], unless a resource of this type already exists.
+     Any type implementing [Default] can be used.
+
+     ```
+     use sceller::prelude::*;
+
+     #[derive(Default)]
+     struct Score(u32);
+     impl Resource for Score {}
+
+     let mut world = World::new();
+     world.init_resource::<Score>();
+     world.get_resource_mut::<Score>().unwrap().0 = 5;
+
+     // does nothing, the resource already exists
+     world.init_resource::<Score>();
+     assert_eq!(world.get_resource::<Score>().unwrap().0, 5);
+     ```
+     */
+    pub fn init_resource<T: FromWorld + Resource>(&mut self) {
+        if !self.resources.contains::<T>() {
+            let res = T::from_world(self);
+            self.insert_resource(res);
+        }
     }
 
     /**
@@ -72,6 +232,7 @@ impl World {
      use sceller::prelude::*;
      
      struct FpsCounter(u16);
+     impl Resource for FpsCounter {}
      
      let mut world = World::new();
      
@@ -81,7 +242,7 @@ impl World {
      assert_eq!(fps.0, 60);
      ``` 
      */
-    pub fn get_resource<T: Any>(&self) -> eyre::Result<Ref<T>> {
+    pub fn get_resource<T: Resource>(&self) -> Result<Ref<'_, T>> {
         self.resources.get_ref()
     }
 
@@ -93,6 +254,7 @@ impl World {
       use sceller::prelude::*;
       
       struct Thing(u8);
+      impl Resource for Thing {}
       
       let mut world = World::new();
       
@@ -105,7 +267,7 @@ impl World {
       assert_eq!(thing2.0, 12);
       ```
      */
-    pub fn get_resource_mut<T: Any>(&self) -> eyre::Result<RefMut<T>> {
+    pub fn get_resource_mut<T: Resource>(&self) -> Result<RefMut<'_, T>> {
         self.resources.get_mut::<T>()
     }
 
@@ -114,8 +276,295 @@ impl World {
       
       See the [Resources](struct.Resources.html) documentation for more information.
      */
-    pub fn delete_resource<T: Any>(&mut self) -> eyre::Result<T> {
-        self.resources.delete::<T>()
+    pub fn delete_resource<T: Resource>(&mut self) -> Result<T> {
+        self.step(|world| {
+            if world.resources.contains::<T>() {
+                world.record_resource::<T>();
+            }
+            world.resources.delete::<T>()
+        })
+    }
+
+    /**
+    Takes the resource T out of the World and calls 'f' with both of them, so that the closure can mutate the
+    resource and the rest of the World at the same time, which [World::get_resource_mut()] can't do without
+    borrowing the World twice. The resource doesn't exist for the World while it is taken out, and is put back
+    once 'f' returns, or before the panic carries on if 'f' panics, so that it is never lost.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Position(f32);
+    struct Gravity(f32);
+    impl Resource for Gravity {}
+
+    let mut world = World::new();
+    world.insert_resource(Gravity(-10.0));
+    world.spawn().insert(Position(100.0));
+
+    let moved = world.resource_scope(|world, gravity: &mut Gravity| {
+        let falling = world.spawn().insert(Position(50.0)).current_entity();
+        for mut pos in world.query_typed::<&mut Position>() {
+            pos.0 += gravity.0;
+        }
+        gravity.0 *= 2.0;
+        falling
+    });
+    assert_eq!(world.get::<&Position>(moved).unwrap().0, 40.0);
+    assert_eq!(world.get_resource::<Gravity>().unwrap().0, -20.0);
+    ```
+
+    Panics if the resource doesn't exist or is borrowed, or if 'f' inserted a resource of the same type, see
+    [World::resource_scope_checked()].
+     */
+    pub fn resource_scope<T: Resource, U>(&mut self, f: impl FnOnce(&mut World, &mut T) -> U) -> U {
+        self.resource_scope_checked(f).unwrap()
+    }
+
+    /**
+    Calls 'f' with the World and the resource T taken out of it, see [World::resource_scope()].
+
+    Returns an error if the resource doesn't exist or is borrowed, without calling 'f'. Also returns an error,
+    dropping the result of 'f', if 'f' inserted a resource of the same type: the scoped resource replaces it.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Level(u32);
+    impl Resource for Level {}
+
+    let mut world = World::new();
+    world.insert_resource(Level(1));
+
+    let result = world.resource_scope_checked(|world, level: &mut Level| {
+        level.0 += 1;
+        world.insert_resource(Level(10));
+    });
+    assert!(matches!(result, Err(Error::Resources(ResourcesError::ScopedResourceInsertedError(_)))));
+    assert_eq!(world.get_resource::<Level>().unwrap().0, 2);
+    ```
+     */
+    pub fn resource_scope_checked<T: Resource, U>(&mut self, f: impl FnOnce(&mut World, &mut T) -> U) -> Result<U> {
+        // taken out of the storage directly, since the resource is only lent to the closure
+        let mut res = self.resources.delete::<T>()?;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(self, &mut res)));
+        let inserted = self.resources.contains::<T>();
+        self.resources.add(res);
+        match result {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(_) if inserted => Err(ResourcesError::ScopedResourceInsertedError(std::any::type_name::<T>()).into()),
+            Ok(result) => Ok(result),
+        }
+    }
+}
+
+/**
+A trait for resources that can be created from the [World], used by [World::init_resource()].
+It is implemented for every type implementing [Default].
+
+```
+use sceller::prelude::*;
+
+struct Health(u32);
+
+// a cache of the total health of every entity
+struct TotalHealth(u32);
+impl Resource for TotalHealth {}
+
+impl FromWorld for TotalHealth {
+    fn from_world(world: &mut World) -> Self {
+        TotalHealth(world.query_typed::<&Health>().map(|hp| hp.0).sum())
+    }
+}
+
+let mut world = World::new();
+world.spawn().insert(Health(10));
+world.spawn().insert(Health(5));
+
+world.init_resource::<TotalHealth>();
+assert_eq!(world.get_resource::<TotalHealth>().unwrap().0, 15);
+```
+ */
+pub trait FromWorld {
+    fn from_world(world: &mut World) -> Self;
+}
+
+impl<T: Default> FromWorld for T {
+    fn from_world(_world: &mut World) -> Self {
+        T::default()
+    }
+}
+
+// Schedule stuff
+impl World {
+    /**
+    Adds a system to the [Stage::Update] stage of the World's [Schedule].
+    
+    ```
+    use sceller::prelude::*;
+    
+    struct Frames(u32);
+    impl Resource for Frames {}
+    
+    fn count_frames(mut frames: ResMut<Frames>) {
+        frames.0 += 1;
+    }
+    
+    let mut world = World::new();
+    world.insert_resource(Frames(0));
+    world.add_system(count_frames);
+    
+    world.run_schedule();
+    
+    assert_eq!(world.get_resource::<Frames>().unwrap().0, 1);
+    ```
+     */
+    pub fn add_system<F, T>(&mut self, system: F) -> &mut Self
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        self.schedule.add_system(system);
+        self
+    }
+
+    /**
+    Adds a system to the given stage of the World's [Schedule]. Panics if the stage doesn't exist.
+
+    See [Schedule::add_system_to_stage()](struct.Schedule.html#method.add_system_to_stage) for more information.
+     */
+    pub fn add_system_to_stage<F, T>(&mut self, stage: Stage, system: F) -> &mut Self
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        self.schedule.add_system_to_stage(stage, system);
+        self
+    }
+
+    /**
+    Adds a system to the [Stage::Startup] stage of the World's [Schedule], which is run once
+    at the start of the first call to [World::run_schedule()], before the state changes are applied.
+    
+    Startup systems added after the first frame aren't run, unless the World is reset with [World::clear()].
+
+    ```
+    use sceller::prelude::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum GameState { Loading, Playing }
+
+    struct Level(u32);
+    impl Resource for Level {}
+
+    fn load_level(mut commands: Commands) {
+        commands.add(|world| {
+            world.insert_resource(Level(1));
+            Ok(())
+        });
+    }
+
+    fn start(level: Res<Level>, mut state: ResMut<States<GameState>>) {
+        assert_eq!(level.0, 1);
+        state.set(GameState::Playing);
+    }
+
+    let mut world = World::new();
+    world.add_state(GameState::Loading);
+    world.add_startup_system(load_level);
+    world.add_state_system(OnEnter(GameState::Loading), start);
+
+    world.run_schedule();
+    assert_eq!(*world.get_resource::<States<GameState>>().unwrap().next().unwrap(), GameState::Playing);
+    ```
+     */
+    pub fn add_startup_system<F, T>(&mut self, system: F) -> &mut Self
+    where
+        F: IntoSystem<T> + 'static,
+        T: 'static,
+    {
+        self.schedule.add_startup_system(system);
+        self
+    }
+
+    /**
+    Returns what each system of the World's [Schedule] reads and writes, see [SystemMeta].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+
+    fn regenerate(query: FnQuery<&mut Health>) {}
+    fn draw_health(query: FnQuery<&Health>) {}
+
+    let mut world = World::new();
+    world.add_system(regenerate);
+    world.add_system_to_stage(Stage::PostUpdate, draw_health);
+
+    let metas = world.system_metas();
+    assert_eq!(metas[0].stage, Some(Stage::Update));
+    assert!(metas[0].writes_component::<Health>());
+    assert!(metas[1].name.ends_with("draw_health"));
+    assert!(metas[0].conflicts_with(&metas[1]));
+    ```
+     */
+    pub fn system_metas(&self) -> Vec<SystemMeta> {
+        self.schedule.system_metas()
+    }
+
+    /**
+    Returns a mutable reference to the World's [Schedule], used to add custom stages.
+     */
+    pub fn schedule_mut(&mut self) -> &mut Schedule {
+        &mut self.schedule
+    }
+
+    /**
+    Runs every stage of the World's [Schedule] once, applying the [Commands] queued by 
+    each stage at the end of it. This is meant to be called once per frame.
+
+    The [Time] resource is updated first, then the [Stage::Startup] stage is run if this is the first frame,
+    and any [States] changes queued during the last frame are applied before the first stage.
+    If the World has [Diagnostics], the time taken by each system is recorded into them.
+
+    Panics if a queued command fails to apply.
+     */
+    pub fn run_schedule(&mut self) {
+        self.update_time();
+        self.begin_diagnostics_frame();
+        self.run_own_frame().unwrap()
+    }
+
+    // runs a frame of the World's own schedule, which is taken out of the World while it runs
+    pub(crate) fn run_own_frame(&mut self) -> Result<()> {
+        let schedule = std::mem::take(&mut self.schedule);
+        let result = self.run_frame(&schedule);
+        self.schedule = schedule;
+        result
+    }
+
+    fn run_frame(&mut self, schedule: &Schedule) -> Result<()> {
+        if !self.started {
+            self.started = true;
+            schedule.run_startup(self)?;
+        }
+        self.apply_state_transitions()?;
+        schedule.run(self)
+    }
+
+    // registers the transition function of a state type, only once per type
+    pub(crate) fn add_state_transition(&mut self, transition: fn(&mut World) -> Result<()>) {
+        if !self.state_transitions.contains(&transition) {
+            self.state_transitions.push(transition);
+        }
+    }
+
+    fn apply_state_transitions(&mut self) -> Result<()> {
+        for transition in self.state_transitions.clone() {
+            transition(self)?;
+        }
+        Ok(())
     }
 }
 
@@ -142,108 +591,1067 @@ impl World {
     }
 
     /**
-      Creates a new entity and returns current Entities instance.
-      
-      ```
-      use sceller::prelude::*;
-      
-      struct Thing(u8);
-      
-      let mut world = World::new();
-      
-      world.spawn()
-          .insert(Thing(6));
-      ```
+    Returns the name, TypeId and reflection functions of every component type registered in the World,
+    see [TypeRegistry].
      */
-    pub fn spawn(&mut self) -> &mut Entities {
-        self.entities.create_entity()
+    pub fn type_registry(&self) -> &TypeRegistry {
+        self.entities.type_registry()
     }
 
-    /**
-    Delete a component from an entity using it's index.
+    /// Returns the registration of every component type registered in the World, to add functions to them.
+    pub fn type_registry_mut(&mut self) -> &mut TypeRegistry {
+        self.entities.type_registry_mut()
+    }
 
-    See [Entities::delete_component_from_ent_by_id()](struct.Entities.html#method.delete_component_by_entity_id) for more information.
+    /**
+    Registers a component type along with it's [Default] value, so that it can be inserted into entities
+    without constructing it by hand, see [insert_default()](struct.World.html#method.insert_default).
      */
-    pub fn delete_component_from_ent<T: Any>(&mut self, index: usize) {
-        self.entities.delete_component_by_entity_id::<T>(index)
+    pub fn register_component_with_default<T: Default + Any>(&mut self) {
+        self.entities.register_component_with_default::<T>()
     }
 
     /**
-    Delete a component from an entity using it's index and throws an error if it fails.
+    Registers a component type along with the function creating it's default value, for types that don't
+    implement [Default] or whose default value should be different in this World.
 
-    See [Entities::delete_component_from_ent_by_id_checked()](struct.Entities.html#method.delete_component_by_entity_id_checked) for more information.
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u32);
+    struct Enemy;
+
+    let mut world = World::new();
+    world.register_component_with(|| Health(100));
+
+    world.spawn().insert(Enemy);
+    world.spawn().insert(Enemy);
+    world.spawn().insert(Name::new("chest"));
+
+    // give every enemy a Health(100)
+    let enemies = world.query().with_component_checked::<Enemy>().unwrap()
+        .run_entity().unwrap()
+        .iter().map(|entity| entity.id)
+        .collect::<Vec<Entity>>();
+    world.insert_default_batch::<Health>(enemies);
+
+    assert_eq!(world.query_typed::<(&Health, &Enemy)>().map(|(health, _)| health.0).sum::<u32>(), 200);
+    ```
      */
-    pub fn delete_component_from_ent_checked<T: Any>(&mut self, index: usize) -> eyre::Result<()> {
-        self.entities.delete_component_by_entity_id_checked::<T>(index)
+    pub fn register_component_with<T: Any>(&mut self, init: impl Fn() -> T + 'static) {
+        self.entities.register_component_with(init)
     }
 
     /**
-    Inserts a component into an entity using it's index.
+    Inserts the default value of the component T into an entity, replacing the component it already has.
+    Panics if T wasn't registered with a default value, or if the entity doesn't exist.
+     */
+    pub fn insert_default<T: Any>(&mut self, entity: Entity) {
+        self.insert_default_checked::<T>(entity).unwrap()
+    }
 
-    See [Entities::insert_component_into_entity_by_id()](struct.Entities.html#method.insert_component_into_entity_by_id) for more information.
+    /**
+    Inserts the default value of the component T into an entity, replacing the component it already has.
+    Returns an error if T wasn't registered with a default value, or if the entity doesn't exist.
      */
-    pub fn insert_component_into_entity<T: Any>(&mut self, data: T, index: usize) {
-        self.entities.insert_component_into_entity_by_id(data, index);
+    pub fn insert_default_checked<T: Any>(&mut self, entity: Entity) -> Result<()> {
+        self.step(|world| world.entities.insert_default::<T>(entity))
     }
 
     /**
-    Inserts a component into an entity using it's index.
+    Inserts the default value of the component T into every entity of 'entities'. Panics if T wasn't
+    registered with a default value, or if one of the entities doesn't exist.
+     */
+    pub fn insert_default_batch<T: Any>(&mut self, entities: impl IntoIterator<Item = Entity>) {
+        self.insert_default_batch_checked::<T>(entities).unwrap()
+    }
 
-    See [Entities::insert_component_into_entity_by_id_checked()](struct.Entities.html#method.insert_component_into_entity_by_id_checked) for more information.
+    /**
+    Inserts the default value of the component T into every entity of 'entities'. Returns an error if T wasn't
+    registered with a default value, or if one of the entities doesn't exist, the entities before it keep their new component.
      */
-    pub fn insert_component_into_entity_checked<T: Any>(&mut self, data: T, index: usize) -> eyre::Result<()> {
-        self.entities.insert_component_into_entity_by_id_checked(data, index)
+    pub fn insert_default_batch_checked<T: Any>(&mut self, entities: impl IntoIterator<Item = Entity>) -> Result<()> {
+        self.step(|world| entities.into_iter().try_for_each(|entity| world.entities.insert_default::<T>(entity)))
     }
 
     /**
-    Unregisters a component from the ECS entirely.
+    Registers a component type that only one entity at a time can have, inserting it into a second entity
+    fails like any other insertion would. Panics if more than one entity already has it.
 
-    See [Entities::delete_component()](struct.Entities.html#method.delete_component) for more information.
+    ```
+    use sceller::prelude::*;
+
+    struct Player(&'static str);
+
+    let mut world = World::new();
+    world.register_unique::<Player>();
+
+    world.spawn().insert(Player("loafoflead"));
+    let error = world.spawn().insert_checked(Player("impostor")).err().unwrap();
+    assert!(matches!(error, Error::Component(ComponentError::DuplicateUniqueComponentError { holder: 0, .. })));
+
+    let player = world.single::<Player>().unwrap();
+    assert_eq!(world.get::<&Player>(player).unwrap().0, "loafoflead");
+    ```
      */
-    pub fn unregister_component<T: Any>(&mut self) {
-        self.entities.delete_component::<T>();
+    pub fn register_unique<T: Any>(&mut self) {
+        self.entities.register_unique::<T>()
     }
 
     /**
-    Unregisters a component from the ECS entirely and throws an error if it fails.
+    Same as [register_unique()](struct.World.html#method.register_unique), but returns an error
+    instead of panicking if more than one entity already has the component.
+     */
+    pub fn register_unique_checked<T: Any>(&mut self) -> Result<()> {
+        self.entities.register_unique_checked::<T>()
+    }
 
-    See [Entities::delete_component_checked()](struct.Entities.html#method.delete_component_checked) for more information.
+    /**
+    Registers a component type that must only be accessed on the thread owning the World, like a handle to a GPU
+    resource whose API is bound to one thread, even if the type is Send or Sync. Systems always run on the thread
+    of the World, so this only changes the parallel queries of the `rayon` feature: `World::par_for_each()` runs
+    on the calling thread when it's query contains the component, and `World::par_iter()` refuses to hand it to
+    the thread pool.
+
+    ```
+    use sceller::prelude::*;
+
+    struct GpuBuffer(u32);
+
+    let mut world = World::new();
+    world.register_main_thread::<GpuBuffer>();
+    world.spawn().insert(GpuBuffer(3));
+    assert!(world.is_main_thread::<GpuBuffer>());
+
+    # #[cfg(feature = "rayon")]
+    # {
+    let caller = std::thread::current().id();
+    world.par_for_each::<&GpuBuffer, _>(|_| assert_eq!(std::thread::current().id(), caller));
+    assert!(world.par_iter_checked::<&GpuBuffer>().is_err());
+    # }
+    ```
      */
-    pub fn unregister_component_checked<T: Any>(&mut self) -> eyre::Result<()> {
-        self.entities.delete_component_checked::<T>()
+    pub fn register_main_thread<T: Any>(&mut self) {
+        self.entities.register_main_thread::<T>()
     }
 
-    pub fn delete_entity(&mut self, index: usize) -> eyre::Result<()> {
-        self.entities.delete_entity_by_id(index)
+    /// Returns true if the component type was registered with [World::register_main_thread()].
+    pub fn is_main_thread<T: Any>(&self) -> bool {
+        self.entities.is_main_thread::<T>()
     }
-}
 
-// Query stuff 
-impl World {
     /**
-    Creates and returns a new query, allowing the user to query for elements in the ECS.
-    
+    Registers a group of component types, such as `(Position, Velocity)`, whose columns are kept sorted so that
+    the entities having all of them are stored first, which lets [World::query_group()] find them without comparing
+    the bitmask of every entity with the one of the query. Panics if one of the components is already part of
+    another group.
+
     ```
     use sceller::prelude::*;
-    
-    struct Thing(u8);
-    
+
+    struct Position(i32, i32);
+    struct Velocity(i32, i32);
+    struct Wall;
+
     let mut world = World::new();
-    
-    world.spawn()
-        .insert(Thing(9));
-    
-    let query = world.query().with_component_checked::<Thing>().unwrap().run();
-    
-    let borrow = query[0][0].borrow();
-    
-    assert_eq!(borrow.downcast_ref::<Thing>().unwrap().0, 9);
+    world.register_group::<(Position, Velocity)>();
+    world.spawn().insert(Position(0, 0)).insert(Wall);
+    world.spawn().insert(Position(0, 0)).insert(Velocity(2, 1));
+
+    for (mut position, velocity) in world.query_group::<(&mut Position, &Velocity)>() {
+        position.0 += velocity.0;
+        position.1 += velocity.1;
+    }
+    assert_eq!(world.get::<&Position>(1).unwrap().0, 2);
+
+    // a component can only be part of one group
+    assert!(world.register_group_checked::<(Position, Wall)>().is_err());
     ```
-    
+
+    See [Entities::register_group()](struct.Entities.html#method.register_group) for more information.
+     */
+    pub fn register_group<B: Bundle>(&mut self) {
+        self.entities.register_group::<B>()
+    }
+
+    /**
+    Same as [register_group()](struct.World.html#method.register_group), but returns an error
+    instead of panicking if one of the components is already part of another group.
+     */
+    pub fn register_group_checked<B: Bundle>(&mut self) -> Result<()> {
+        self.entities.register_group_checked::<B>()
+    }
+
+    /**
+    Returns the only entity with the component T, usually a component registered with 
+    [register_unique()](struct.World.html#method.register_unique). Returns an error if the component 
+    isn't registered, or if there isn't exactly one entity with it.
+     */
+    pub fn single<T: Any>(&self) -> Result<Entity> {
+        self.entities.single::<T>()
+    }
+
+    /**
+    Registers the component type T as implementing the trait Tr, given the functions casting a reference to T
+    into a trait object, which are usually just `|c| c`. The components of every type implementing a trait can
+    then be queried together with `&dyn Tr` or `&mut dyn Tr`, once [query_trait!](crate::query_trait) has been used on the trait.
+
+    ```
+    use sceller::prelude::*;
+
+    trait Weapon {
+        fn sharpen(&mut self);
+        fn damage(&self) -> u32;
+    }
+
+    struct Sword(u32);
+    struct Axe(u32);
+
+    impl Weapon for Sword {
+        fn sharpen(&mut self) { self.0 += 1; }
+        fn damage(&self) -> u32 { self.0 }
+    }
+    impl Weapon for Axe {
+        fn sharpen(&mut self) { self.0 += 2; }
+        fn damage(&self) -> u32 { self.0 * 2 }
+    }
+
+    query_trait!(Weapon);
+
+    let mut world = World::new();
+    world.register_trait::<dyn Weapon, Sword>(|c| c, |c| c)
+        .register_trait::<dyn Weapon, Axe>(|c| c, |c| c);
+
+    world.spawn().insert(Sword(5));
+    world.spawn().insert(Axe(3));
+
+    for mut weapon in world.query_typed::<&mut dyn Weapon>().flatten() {
+        weapon.sharpen();
+    }
+    assert_eq!(world.get::<&dyn Weapon>(1).unwrap()[0].damage(), 10);
+    ```
+     */
+    pub fn register_trait<Tr: ?Sized + 'static, T: Any>(&mut self, cast: fn(&T) -> &Tr, cast_mut: fn(&mut T) -> &mut Tr) -> &mut Self {
+        self.entities.register_trait(cast, cast_mut);
+        self
+    }
+
+    /**
+      Creates a new entity and returns current Entities instance.
+      
+      ```
+      use sceller::prelude::*;
+      
+      struct Thing(u8);
+      
+      let mut world = World::new();
+      
+      world.spawn()
+          .insert(Thing(6));
+      ```
+     */
+    pub fn spawn(&mut self) -> &mut Entities {
+        // the hooks of the previously spawned entity
+        self.run_hooks();
+        self.entities.create_entity()
+    }
+
+    /**
+    Reserves capacity for at least 'additional' more entities.
+
+    See [Entities::reserve()](struct.Entities.html#method.reserve) for more information.
+     */
+    pub fn reserve_entities(&mut self, additional: usize) {
+        self.entities.reserve(additional);
+    }
+
+    /**
+    Shrinks the capacity used to store entities as much as possible.
+
+    See [Entities::shrink_to_fit()](struct.Entities.html#method.shrink_to_fit) for more information.
+     */
+    pub fn shrink_entities_to_fit(&mut self) {
+        self.entities.shrink_to_fit();
+    }
+
+    /**
+    Spawns an entity for every [Bundle] of the iterator, and returns their ids.
+
+    See [Entities::spawn_batch()](struct.Entities.html#method.spawn_batch) for more information.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Enemy;
+
+    let mut world = World::new();
+    let enemies = world.spawn_batch((0..10).map(|_| (Health(3), Enemy)));
+
+    assert_eq!(enemies, (0..10).collect::<Vec<Entity>>());
+    ```
+
+    Panics if one of the bundles can't be inserted.
+     */
+    pub fn spawn_batch<B, I>(&mut self, bundles: I) -> Vec<Entity>
+    where
+        B: Bundle,
+        I: IntoIterator<Item = B>,
+    {
+        self.step(|world| world.entities.spawn_batch(bundles))
+    }
+
+    /**
+    Same as [spawn_batch()](struct.World.html#method.spawn_batch), but returns an error if one of the bundles
+    can't be inserted, for example if it holds a component registered with
+    [register_unique()](struct.World.html#method.register_unique) that another entity already has.
+
+    The entities spawned before the failing bundle stay in the World with all of their components, none of the
+    failing bundle's components are stored, and the ids left for the bundles after it stay vacant.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Boss;
+
+    let mut world = World::new();
+    world.register_unique::<Boss>();
+
+    let result = world.spawn_batch_checked((0..3).map(|i| (Health(i), Boss)));
+    assert!(result.is_err());
+    assert_eq!(world.get::<&Health>(0).unwrap().0, 0);
+    assert!(!world.contains(1) && !world.contains(2));
+    ```
+     */
+    pub fn spawn_batch_checked<B, I>(&mut self, bundles: I) -> Result<Vec<Entity>>
+    where
+        B: Bundle,
+        I: IntoIterator<Item = B>,
+    {
+        self.step(|world| world.entities.spawn_batch_checked(bundles))
+    }
+
+    /**
+    Delete a component from an entity using it's index.
+
+    See [Entities::delete_component_from_ent_by_id()](struct.Entities.html#method.delete_component_by_entity_id) for more information.
+     */
+    pub fn delete_component_from_ent<T: Any>(&mut self, index: usize) {
+        self.step(|world| world.entities.delete_component_by_entity_id::<T>(index));
+    }
+
+    /**
+    Delete a component from an entity using it's index and throws an error if it fails.
+
+    See [Entities::delete_component_from_ent_by_id_checked()](struct.Entities.html#method.delete_component_by_entity_id_checked) for more information.
+     */
+    pub fn delete_component_from_ent_checked<T: Any>(&mut self, index: usize) -> Result<()> {
+        self.step(|world| world.entities.delete_component_by_entity_id_checked::<T>(index))
+    }
+
+    /**
+    Removes a component from an entity using it's index, and returns it.
+
+    See [Entities::take_component()](struct.Entities.html#method.take_component) for more information.
+     */
+    pub fn take_component<T: Any>(&mut self, index: usize) -> Result<T> {
+        self.step(|world| world.entities.take_component::<T>(index))
+    }
+
+    /**
+    Returns true if the entity 'id' has a component of type T, checked using the entity's bitmask.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Shield(u8);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(3));
+
+    assert!(world.entity_has::<Health>(0));
+    assert!(!world.entity_has::<Shield>(0));
+    assert!(!world.entity_has::<Health>(1));
+    ```
+     */
+    pub fn entity_has<T: Any>(&self, id: Entity) -> bool {
+        self.entities.has_component::<T>(id)
+    }
+
+    /**
+    Returns true if the entity 'id' is alive, that is if it has at least one component, so that stored ids
+    can be checked before they are used. See [Entities::is_alive()](struct.Entities.html#method.is_alive).
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut world = World::new();
+    let entity = world.spawn().insert(Health(3)).current_entity();
+    assert!(world.contains(entity));
+
+    world.despawn(entity);
+    assert!(!world.contains(entity));
+    assert!(!world.contains(42));
+    ```
+     */
+    pub fn contains(&self, id: Entity) -> bool {
+        self.entities.is_alive(id)
+    }
+
+    /**
+    Returns the number of living entities, see [Entities::len()](struct.Entities.html#method.len).
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(3));
+    world.spawn().insert(Health(1));
+    world.despawn(0);
+
+    assert_eq!(world.entity_count(), 1);
+    assert_eq!(world.entity_capacity(), 2);
+    assert_eq!(world.vacant_entities(), 1);
+    ```
+     */
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns the number of entity slots, living or vacant, see [Entities::capacity()](struct.Entities.html#method.capacity).
+    pub fn entity_capacity(&self) -> usize {
+        self.entities.capacity()
+    }
+
+    /// Returns the number of vacant entity slots, see [Entities::vacant()](struct.Entities.html#method.vacant).
+    pub fn vacant_entities(&self) -> usize {
+        self.entities.vacant()
+    }
+
+    /**
+    Returns an iterator over every living entity along with the TypeIds of it's components, in ascending order of id,
+    without needing a component that every entity has. See [Entities::iter_ids()](struct.Entities.html#method.iter_ids)
+    to get their bitmasks instead.
+
+    ```
+    use sceller::prelude::*;
+    use std::any::TypeId;
+
+    struct Health(u8);
+    struct Wall;
+
+    let mut world = World::new();
+    world.spawn().insert(Health(3));
+    world.spawn().insert(Wall);
+
+    let entities = world.iter_entities().collect::<Vec<_>>();
+    assert_eq!(entities, vec![(0, vec![TypeId::of::<Health>()]), (1, vec![TypeId::of::<Wall>()])]);
+    ```
+     */
+    pub fn iter_entities(&self) -> impl Iterator<Item = (Entity, Vec<std::any::TypeId>)> + '_ {
+        self.entities.iter_ids().map(|(entity, _)| (entity, self.entities.component_types(entity)))
+    }
+
+    /**
+    Returns mutable references to the component T of several different entities at once.
+
+    See [Entities::get_components_mut()](struct.Entities.html#method.get_components_mut) for more information.
+     */
+    pub fn get_components_mut<T: Any, const N: usize>(&self, ids: &[Entity; N]) -> Result<[RefMut<'_, T>; N]> {
+        self.entities.get_components_mut::<T, N>(ids)
+    }
+
+    /**
+    Borrows the component T of an entity, returning an error naming the component and the entity if it is
+    already borrowed mutably, instead of panicking.
+
+    See [Entities::try_get_component()](struct.Entities.html#method.try_get_component) for more information.
+     */
+    pub fn try_get_component<T: Any>(&self, id: Entity) -> Result<Ref<'_, T>> {
+        self.entities.try_get_component::<T>(id)
+    }
+
+    /**
+    Mutably borrows the component T of an entity, returning an error naming the component and the entity if it is
+    already borrowed, instead of panicking.
+
+    See [Entities::try_get_component_mut()](struct.Entities.html#method.try_get_component_mut) for more information.
+     */
+    pub fn try_get_component_mut<T: Any>(&self, id: Entity) -> Result<RefMut<'_, T>> {
+        self.entities.try_get_component_mut::<T>(id)
+    }
+
+    /**
+    Borrows the components T of every entity as a single slice, along with the entity owning each of them,
+    for SIMD code or GPU uploads.
+
+    See [Entities::column()](struct.Entities.html#method.column) for more information.
+     */
+    pub fn column<T: Any>(&self) -> Result<(Ref<'_, [T]>, &[Entity])> {
+        self.entities.column::<T>()
+    }
+
+    /**
+    Mutably borrows the components T of every entity as a single slice, along with the entity owning each of them.
+
+    See [Entities::column_mut()](struct.Entities.html#method.column_mut) for more information.
+     */
+    pub fn column_mut<T: Any>(&self) -> Result<(RefMut<'_, [T]>, &[Entity])> {
+        self.entities.column_mut::<T>()
+    }
+
+    /**
+    Inserts a component into an entity using it's index, and returns the component it replaced if the entity already had one.
+
+    See [Entities::insert_component_into_entity_by_id()](struct.Entities.html#method.insert_component_into_entity_by_id) for more information.
+     */
+    pub fn insert_component_into_entity<T: Any>(&mut self, data: T, index: usize) -> Option<T> {
+        self.step(|world| world.entities.insert_component_into_entity_by_id(data, index))
+    }
+
+    /**
+    Inserts a component into an entity using it's index, and returns the component it replaced if the entity already had one.
+
+    See [Entities::insert_component_into_entity_by_id_checked()](struct.Entities.html#method.insert_component_into_entity_by_id_checked) for more information.
+     */
+    pub fn insert_component_into_entity_checked<T: Any>(&mut self, data: T, index: usize) -> Result<Option<T>> {
+        self.step(|world| world.entities.insert_component_into_entity_by_id_checked(data, index))
+    }
+
+    /**
+    Unregisters a component from the ECS entirely.
+
+    See [Entities::delete_component()](struct.Entities.html#method.delete_component) for more information.
+     */
+    pub fn unregister_component<T: Any>(&mut self) {
+        self.step(|world| world.entities.delete_component::<T>());
+    }
+
+    /**
+    Unregisters a component from the ECS entirely and throws an error if it fails.
+
+    See [Entities::delete_component_checked()](struct.Entities.html#method.delete_component_checked) for more information.
+     */
+    pub fn unregister_component_checked<T: Any>(&mut self) -> Result<()> {
+        self.step(|world| world.entities.delete_component_checked::<T>())
+    }
+
+    /**
+    Removes an entity from the ECS and returns it's components. Panics if the index is out of bounds.
+
+    See [DespawnedEntity](struct.DespawnedEntity.html) for more information.
+     */
+    pub fn despawn(&mut self, entity: Entity) -> DespawnedEntity {
+        self.step(|world| world.entities.despawn(entity))
+    }
+
+    /**
+    Removes an entity from the ECS and returns it's components. Returns an error if the index is out of bounds.
+
+    See [Entities::despawn_checked()](struct.Entities.html#method.despawn_checked) for more information.
+     */
+    pub fn despawn_checked(&mut self, entity: Entity) -> Result<DespawnedEntity> {
+        self.step(|world| world.entities.despawn_checked(entity))
+    }
+
+    /**
+    Moves an entity and all of it's components into another World, and returns the id of the new entity there.
+    Panics if the entity doesn't exist, or if it has a unique component that an entity of 'other' already has.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Mesh(&'static str);
+    struct Loaded;
+
+    let mut loading = World::new();
+    let mut live = World::new();
+    live.spawn().insert(Name::new("player"));
+
+    loading.spawn().insert(Mesh("tree.obj")).insert(Loaded);
+    loading.spawn().insert(Mesh("rock.obj"));
+
+    // move the finished entity into the live world
+    let moved = loading.transfer_entity(&mut live, 0);
+    assert_eq!(live.get::<&Mesh>(moved).unwrap().0, "tree.obj");
+
+    assert_eq!(loading.query_typed::<&Mesh>().count(), 1);
+    assert_eq!(live.query_typed::<(&Mesh, &Loaded)>().count(), 1);
+    ```
+
+    Ids are specific to a World, so components storing the ids of other entities, like the ones of the 
+    [hierarchy](crate::hierarchy), aren't updated and shouldn't be moved.
+     */
+    pub fn transfer_entity(&mut self, other: &mut World, entity: Entity) -> Entity {
+        self.transfer_entity_checked(other, entity).unwrap()
+    }
+
+    /**
+    Moves an entity and all of it's components into another World, and returns the id of the new entity there.
+    Returns an error, without moving anything, if the entity doesn't exist, or if it has a unique component that
+    an entity of 'other' already has.
+     */
+    pub fn transfer_entity_checked(&mut self, other: &mut World, entity: Entity) -> Result<Entity> {
+        self.step(|world| other.step(|other| world.entities.transfer_entity(entity, &mut other.entities)))
+    }
+
+    /**
+    Despawns every entity for which 'keep' returns false in one pass, and returns the number of entities despawned.
+    The entities are given as [QueryEntity]s, so any of their components can be looked at.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Position(f32);
+    struct Immortal;
+
+    let mut world = World::new();
+    world.spawn().insert(Position(10.0));
+    world.spawn().insert(Position(-200.0));
+    world.spawn().insert(Position(-500.0)).insert(Immortal);
+
+    // remove everything below the kill plane
+    let despawned = world.retain(|entity| {
+        entity.has_component::<Immortal>() || entity.get_component::<Position>().unwrap().0 > -100.0
+    });
+
+    assert_eq!(despawned, 1);
+    assert_eq!(world.query_typed::<&Position>().count(), 2);
+    ```
+     */
+    pub fn retain(&mut self, keep: impl FnMut(&QueryEntity) -> bool) -> usize {
+        self.step(|world| world.entities.retain(keep))
+    }
+
+    /**
+    Despawns every entity with a component T for which 'predicate' returns true, and returns the number
+    of entities despawned. Entities without the component are kept.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(i32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(0));
+    world.spawn().insert(Health(12));
+    world.spawn().insert(Name::new("rock"));
+
+    assert_eq!(world.despawn_where::<Health>(|health| health.0 <= 0), 1);
+    assert_eq!(world.query().auto::<Health>().len(), 1);
+    assert!(world.find_by_name("rock").is_some());
+    ```
+     */
+    pub fn despawn_where<T: Any>(&mut self, mut predicate: impl FnMut(&T) -> bool) -> usize {
+        self.retain(|entity| {
+            !entity.has_component::<T>() || !entity.get_component::<T>().map(|component| predicate(&component)).unwrap_or(false)
+        })
+    }
+
+    pub fn delete_entity(&mut self, index: usize) -> Result<()> {
+        self.step(|world| world.entities.delete_entity_by_id(index))
+    }
+
+    /**
+    Despawns every entity, dropping all of their components. Component types stay registered and
+    resources are left untouched.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Level(u32);
+    impl Resource for Level {}
+
+    let mut world = World::new();
+    world.insert_resource(Level(3));
+    world.spawn().insert(Health(10));
+
+    world.clear_entities();
+
+    assert_eq!(world.query().auto::<Health>().len(), 0);
+    assert_eq!(world.get_resource::<Level>().unwrap().0, 3);
+    ```
+     */
+    pub fn clear_entities(&mut self) {
+        self.entities.clear();
+        self.relationships.reindex();
+        self.clear_journal();
+    }
+
+    /**
+    Resets the World, removing every entity, component type, resource and queued command. 
+    States added with [add_state()](struct.World.html#method.add_state) are removed too since
+    they are stored as resources, but the systems of the World's [Schedule] are kept, and the
+    startup systems are run again by the next call to [World::run_schedule()].
+
+    What the World was told about component types is kept, since it doesn't depend on any entity: the
+    [hooks](crate::hooks), [observers](crate::observer) and [relationships](crate::relationship), the
+    types of the [TypeRegistry] and of traits, the types recorded by the journal and by rollbacks, and the
+    error handler. Hooks and observers are kept as they are, so they shouldn't hold on to the ids of the
+    entities cleared. What was registered about a component type in the entities themselves, like unique
+    components, defaults or groups, is removed along with it.
+
+    What the World knew about it's entities is forgotten: the holders of relationships and the names are
+    indexed again from the empty World, the steps of the journal are dropped, and with the `serde` feature
+    the entities spawned for the entities of another World by replication are forgotten, so the messages
+    about them are errors rather than changes to the entities that take their ids.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Level(u32);
+    impl Resource for Level {}
+
+    let mut world = World::new();
+    world.insert_resource(Level(3));
+    world.spawn().insert(Health(10));
+
+    world.clear();
+
+    assert!(world.get_resource::<Level>().is_err());
+    assert!(world.query().with_component_checked::<Health>().is_err());
+    ```
+     */
+    pub fn clear(&mut self) {
+        self.replace_entities(Entities::default());
+        #[cfg(feature = "serde")]
+        self.replication.forget_remote_entities();
+        self.resources = Resources::default();
+        self.commands = CommandQueue::default();
+        self.state_transitions.clear();
+        self.started = false;
+    }
+}
+
+// Query stuff 
+impl World {
+    /**
+    Creates and returns a new query, allowing the user to query for elements in the ECS.
+    
+    ```
+    use sceller::prelude::*;
+    
+    struct Thing(u8);
+    
+    let mut world = World::new();
+    
+    world.spawn()
+        .insert(Thing(9));
+    
+    let query = world.query().with_component_checked::<Thing>().unwrap().run();
+    
+    let borrow = query[0][0].borrow();
+    
+    assert_eq!(borrow.downcast_ref::<Thing>().unwrap().0, 9);
+    ```
+    
     Returns a new Query instance with a reference to this World's Entities inside.
      */
-    pub fn query(&self) -> Query {
-        Query::new(&self.entities)
+    pub fn query(&self) -> Query<'_> {
+        Query::with_commands(&self.entities, &self.commands)
+    }
+
+    /**
+    Runs a query for one or more component types and returns an iterator over the results, already 
+    borrowed and downcast to their concrete types.
+
+    Takes the same types as [FnQuery], so '&T' yields a [Ref] and '&mut T' yields a [RefMut].
+    
+    ```
+    use sceller::prelude::*;
+    
+    struct Health(u8);
+    struct Position(i32, i32);
+    
+    let mut world = World::new();
+    
+    world.spawn()
+        .insert(Health(9))
+        .insert(Position(0, 0));
+    
+    for (health, mut pos) in world.query_typed::<(&Health, &mut Position)>() {
+        pos.0 += health.0 as i32;
+    }
+    
+    let pos = world.query_typed::<&Position>().next().unwrap();
+    assert_eq!(pos.0, 9);
+    ```
+     */
+    pub fn query_typed<'a, T>(&'a self) -> FnQueryIterator<'a, T>
+    where
+        T: FnQueryContainedTupleType<'a> + 'a
+    {
+        FnQuery::<T>::new(&self.entities).into_iter()
+    }
+
+    /**
+    Iterates over the entities of the group made of exactly the components of the query, see [World::register_group()].
+    Panics if there is no such group, see [World::query_group_checked()].
+     */
+    pub fn query_group<'a, T>(&'a self) -> FnQueryIterator<'a, T>
+    where
+        T: FnQueryContainedTupleType<'a> + 'a
+    {
+        self.query_group_checked::<T>().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /**
+    Same as [World::query_group()], but returns an error instead of panicking if one of the components isn't
+    registered, or if no group is made of exactly the components of the query.
+
+    See [Query::group()] for more information.
+     */
+    pub fn query_group_checked<'a, T>(&'a self) -> Result<FnQueryIterator<'a, T>>
+    where
+        T: FnQueryContainedTupleType<'a> + 'a
+    {
+        self.query().group::<T>()
+    }
+
+    /**
+    Fetches the components of a single entity, taking the same types as [FnQuery].
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Position(i32, i32);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(9)).insert(Position(0, 0));
+    world.spawn().insert(Position(1, 1));
+
+    assert_eq!(world.get::<&Health>(0).unwrap().0, 9);
+    world.get::<&mut Position>(1).unwrap().0 = 5;
+    assert_eq!(world.get::<&Position>(1).unwrap().0, 5);
+
+    assert!(world.get::<(&Health, &Position)>(1).is_err());
+    ```
+
+    See [Query::get()] for more information.
+     */
+    pub fn get<'a, T>(&'a self, entity: Entity) -> Result<T::ReturnType>
+    where
+        T: FnQueryContainedTupleType<'a>
+    {
+        self.query().get::<T>(entity)
+    }
+
+    /**
+    Returns a [CachedQuery] for one or more component types, which only looks for the matching entities 
+    again if entities were spawned, despawned, or gained or lost components since it was last used.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+
+    let mut world = World::new();
+    world.spawn().insert(Health(9));
+
+    assert_eq!(world.query_cached::<&Health>().len(), 1);
+
+    world.spawn().insert(Health(3));
+    let healths = world.query_cached::<&Health>();
+    assert_eq!(healths.iter().map(|hp| hp.0).collect::<Vec<u8>>(), vec![9, 3]);
+    ```
+     */
+    pub fn query_cached<T>(&self) -> CachedQuery<'_, T>
+    where
+        T: 'static + for<'a> FnQueryContainedTupleType<'a>
+    {
+        CachedQuery::new(&self.entities, &self.query_caches)
+    }
+}
+
+// Serialization stuff
+#[cfg(feature = "serde")]
+impl World {
+    /**
+    Registers a component type so that it is included in snapshots of the World.
+
+    See the [serialization](serialization/index.html) module for more information.
+     */
+    pub fn register_serde_component<T>(&mut self)
+    where
+        T: Any + serde::Serialize + serde::de::DeserializeOwned
+    {
+        self.serde_registry.register_component::<T>();
+        self.entities.type_registry_mut().register_serde::<T>();
+    }
+
+    /**
+    Registers a resource type so that it is included in snapshots of the World.
+
+    See the [serialization](serialization/index.html) module for more information.
+     */
+    pub fn register_serde_resource<T>(&mut self)
+    where
+        T: Resource + serde::Serialize + serde::de::DeserializeOwned
+    {
+        self.serde_registry.register_resource::<T>();
+    }
+
+    /**
+    Creates a [WorldSnapshot](serialization/struct.WorldSnapshot.html) of every registered component and resource.
+     */
+    pub fn snapshot(&self) -> Result<crate::serialization::WorldSnapshot> {
+        self.serde_registry.snapshot(&self.entities, &self.resources)
+    }
+
+    /**
+    Compares the World to a snapshot taken earlier, returning the entities that were added and removed since then,
+    and the registered components that were inserted, deleted or changed. Only registered types are compared.
+
+    ```
+    use sceller::prelude::*;
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Health(u8);
+
+    let mut world = World::new();
+    world.register_serde_component::<Health>();
+    world.spawn().insert(Health(3));
+    world.spawn().insert(Health(5));
+
+    let before = world.snapshot().unwrap();
+    world.get::<&mut Health>(0).unwrap().0 = 2;
+    world.despawn(1);
+    world.spawn().insert(Health(9)).insert(Name::new("new"));
+    world.spawn().insert(Health(1));
+
+    let diff = world.diff(&before).unwrap();
+    assert_eq!(diff.added, vec![2]);
+    assert_eq!(diff.removed, Vec::<Entity>::new());
+    assert_eq!(diff.changed.len(), 2);
+    assert_eq!((diff.changed[0].entity, diff.changed[0].before.as_deref(), diff.changed[0].after.as_deref()), (0, Some("(3)"), Some("(2)")));
+    assert_eq!((diff.changed[1].entity, diff.changed[1].before.as_deref(), diff.changed[1].after.as_deref()), (1, Some("(5)"), Some("(9)")));
+    ```
+     */
+    pub fn diff(&self, snapshot: &crate::serialization::WorldSnapshot) -> Result<crate::serialization::WorldDiff> {
+        Ok(snapshot.diff(&self.snapshot()?))
+    }
+
+    /**
+    Replaces every entity in the World with the entities of the snapshot, keeping their original indexes,
+    and inserts the snapshot's resources (overwriting any resource of the same type).
+
+    Note: entities that were not part of the snapshot are deleted, along with all of their components.
+     */
+    pub fn restore_snapshot(&mut self, snapshot: &crate::serialization::WorldSnapshot) -> Result<()> {
+        let mut entities = Entities::default();
+        self.serde_registry.restore(snapshot, &mut entities, &mut self.resources)?;
+        entities.take_groups_from(&self.entities);
+        self.replace_entities(entities);
+        Ok(())
+    }
+
+    /**
+    Saves every registered component and resource of the World into a [RON](https://github.com/ron-rs/ron) string.
+
+    ```
+    use sceller::prelude::*;
+    use serde::{Serialize, Deserialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Health(u8);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Level(String);
+    impl Resource for Level {}
+
+    let mut world = World::new();
+    world.register_serde_component::<Health>();
+    world.register_serde_resource::<Level>();
+
+    world.spawn().insert(Health(3));
+    world.insert_resource(Level("Dungeon".to_owned()));
+
+    let save = world.save_to_ron().unwrap();
+
+    let mut loaded = World::new();
+    loaded.register_serde_component::<Health>();
+    loaded.register_serde_resource::<Level>();
+    loaded.load_from_ron(&save).unwrap();
+
+    assert_eq!(*loaded.query_typed::<&Health>().next().unwrap(), Health(3));
+    assert_eq!(*loaded.get_resource::<Level>().unwrap(), Level("Dungeon".to_owned()));
+    ```
+     */
+    pub fn save_to_ron(&self) -> Result<String> {
+        Ok(ron::ser::to_string_pretty(&self.snapshot()?, ron::ser::PrettyConfig::default())?)
+    }
+
+    /**
+    Loads a World saved with [save_to_ron()](struct.World.html#method.save_to_ron), replacing
+    every entity in the World. See [restore_snapshot()](struct.World.html#method.restore_snapshot).
+     */
+    pub fn load_from_ron(&mut self, ron: &str) -> Result<()> {
+        let snapshot: crate::serialization::WorldSnapshot = ron::from_str(ron)?;
+        self.restore_snapshot(&snapshot)
+    }
+}
+
+// Debug stuff
+impl World {
+    /**
+    Returns a human readable summary of the World: the number of entities, archetypes, component types and resources,
+    followed by every component type with the number of entities having it, every living entity with the names of
+    it's components, and the names of every resource. This is also what the World's Display implementation prints.
+
+    ```
+    use sceller::prelude::*;
+
+    struct Health(u8);
+    struct Enemy;
+    struct Score(u32);
+    impl Resource for Score {}
+
+    let mut world = World::new();
+    world.insert_resource(Score(0));
+    world.spawn().insert(Health(3)).insert(Name::new("player"));
+    world.spawn().insert(Health(1)).insert(Enemy);
+
+    let dump = world.debug_dump();
+    assert!(dump.starts_with("World: 2 entities, 2 archetypes, 3 component types, 1 resources"));
+    assert!(dump.contains("Health  2"));
+    assert!(dump.contains("0 \"player\"  Health, Name"));
+    assert!(dump.contains("1  Enemy, Health"));
+    assert!(dump.contains("Resources\n  Score"));
+    ```
+     */
+    pub fn debug_dump(&self) -> String {
+        let mut dump = format!(
+            "World: {} entities, {} archetypes, {} component types, {} resources\n\n",
+            self.entities.count_matching(0),
+            self.entities.archetype_count(),
+            self.entities.component_type_count(),
+            self.resources.len(),
+        );
+        dump += &self.entities.debug_dump();
+
+        dump += "\nResources\n";
+        for name in self.resources.type_names() {
+            dump += &format!("  {}\n", crate::entities::short_type_name(name));
+        }
+        dump
+    }
+
+    /**
+    Returns the memory allocated for the entities of the World, broken down by component type.
+    See [MemoryReport](struct.MemoryReport.html).
+     */
+    pub fn memory_report(&self) -> MemoryReport {
+        self.entities.memory_report()
     }
 }
 
@@ -251,6 +1659,6 @@ impl World {
 
 impl std::fmt::Display for World {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:#?}", self)
+        write!(f, "{}", self.debug_dump())
     }
 }
\ No newline at end of file
@@ -7,7 +7,9 @@ struct Health(u16);
 #[derive(Debug)]
 struct Enemy;
 
+#[allow(dead_code)]
 struct PlayerResource(String);
+impl Resource for PlayerResource {}
 
 #[test]
 fn test_fn_query() -> Result<()> {
@@ -23,9 +25,9 @@ fn test_fn_query() -> Result<()> {
 fn list_healths(hps: FnQuery<&Health>) {
     let mut iter = hps.iter();
 
-    assert_eq!(iter.next().unwrap().0, 12);
-    assert_eq!(iter.next().unwrap().0, 6);
     assert_eq!(iter.next().unwrap().0, 15);
+    assert_eq!(iter.next().unwrap().0, 6);
+    assert_eq!(iter.next().unwrap().0, 12);
 }
 
 #[test]
@@ -50,9 +52,9 @@ fn edit_healths(hps: FnQuery<&mut Health>) {
 fn list_new_healths(hps: FnQuery<&Health>) {
     let mut iter = hps.iter();
 
-    assert_eq!(iter.next().unwrap().0, 13);
-    assert_eq!(iter.next().unwrap().0, 7);
     assert_eq!(iter.next().unwrap().0, 16);
+    assert_eq!(iter.next().unwrap().0, 7);
+    assert_eq!(iter.next().unwrap().0, 13);
 }
 
 #[test]
@@ -75,16 +77,16 @@ fn list_healths_and_poses(query: FnQuery<(&Health, &Position)>) {
     let mut iter = query.iter();
 
     let (hp, pos) = iter.next().unwrap();
-    assert_eq!(*hp, Health(12));
-    assert_eq!(*pos, Position(6, 6));
+    assert_eq!(*hp, Health(15));
+    assert_eq!(*pos, Position(0, 0));
 
     let (hp, pos) = iter.next().unwrap();
     assert_eq!(*hp, Health(6));
     assert_eq!(*pos, Position(12, 10));
 
     let (hp, pos) = iter.next().unwrap();
-    assert_eq!(*hp, Health(15));
-    assert_eq!(*pos, Position(0, 0));
+    assert_eq!(*hp, Health(12));
+    assert_eq!(*pos, Position(6, 6));
 }
 
 fn one_mut_and_one_not(query: FnQuery<(&mut Health, &Position)>) {
@@ -104,16 +106,16 @@ fn make_sure(query: FnQuery<(&Health, &Position)>) {
     let mut iter = query.iter();
 
     let (hp, pos) = iter.next().unwrap();
-    assert_eq!(*hp, Health(14));
-    assert_eq!(*pos, Position(6, 3));
+    assert_eq!(*hp, Health(17));
+    assert_eq!(*pos, Position(0, 3));
 
     let (hp, pos) = iter.next().unwrap();
     assert_eq!(*hp, Health(8));
     assert_eq!(*pos, Position(12, 3));
 
     let (hp, pos) = iter.next().unwrap();
-    assert_eq!(*hp, Health(17));
-    assert_eq!(*pos, Position(0, 3));
+    assert_eq!(*hp, Health(14));
+    assert_eq!(*pos, Position(6, 3));
 }
 
 fn test_intoiter(query: FnQuery<(&Health, &Position, &mut Enemy)>) {
@@ -122,106 +124,331 @@ fn test_intoiter(query: FnQuery<(&Health, &Position, &mut Enemy)>) {
     }
 }
 
-// #[test]
-// fn test_mutable_iteration() -> Result<()> {
-//     let world = init_world()?;
+#[test]
+fn auto_querys() -> Result<()> {
+    let world = init_world()?;
+
+    let query = world.query(); let auto = query.auto::<Health>();
+
+    assert_eq!(auto.len(), 3);
+
+    let mut iter = auto.into_iter();
+
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next().unwrap().0, 15);
+    assert_eq!(iter.next_back().unwrap().0, 12);
+    assert_eq!(iter.next().unwrap().0, 6);
+    assert!(iter.next().is_none());
+
+    Ok(())
+}
+
+#[test]
+fn typed_queries() -> Result<()> {
+    let world = init_world()?;
+
+    for (mut hp, pos) in world.query_typed::<(&mut Health, &Position)>() {
+        hp.0 += pos.0 as u16;
+    }
+
+    let healths = world.query_typed::<&Health>().map(|hp| hp.0).collect::<Vec<u16>>();
+    assert_eq!(healths, vec![15, 18, 18]);
+
+    let reversed = world.query_typed::<&Health>().rev().map(|hp| hp.0).collect::<Vec<u16>>();
+    assert_eq!(reversed, vec![18, 18, 15]);
+    assert_eq!(world.query_typed::<(&Health, &Position)>().len(), 3);
+
+    let enemies = world.query_typed::<(&Enemy, &Health)>().count();
+    assert_eq!(enemies, 1);
+
+    Ok(())
+}
+
+#[test]
+fn fn_query_with_ids() -> Result<()> {
+    let mut world = init_world()?;
+
+    world.run_system(|mut commands: Commands, query: FnQuery<(&Health, &Position)>| {
+        for (entity, (hp, pos)) in query.iter_with_ids() {
+            if hp.0 < 10 {
+                assert_eq!(entity, 1);
+                assert_eq!(*pos, Position(12, 10));
+                commands.despawn(entity);
+            }
+        }
+    });
+
+    world.run_system(|healths: FnQuery<&Health>| {
+        let mut ids = healths.iter_with_ids().map(|(id, _)| id).collect::<Vec<Entity>>();
+        ids.sort();
+        assert_eq!(ids, vec![0, 2]);
+    });
 
-//     let query = world.query();
-//     query.query_fn(mutability_test);
+    Ok(())
+}
 
-//     Ok(())
-// }
+#[test]
+fn fn_query_for_each() -> Result<()> {
+    let world = init_world()?;
 
-// fn mutability_test(healths: FnQueryMut<Health>) {
-//     let copy = healths.clone();
+    world.query().query_fn(|query: FnQuery<(&mut Health, &Position)>| {
+        query.for_each_mut(|(mut hp, pos)| hp.0 += pos.0 as u16);
+    });
 
-//     for h in &healths {
-//         for mut h2 in &healths {
-//             h2.0 += h.0;
-//         }
-//     }
-// }
+    world.query().query_fn(|healths: FnQuery<&Health>| {
+        let mut seen = Vec::new();
+        healths.for_each(|hp| seen.push(hp.0));
+        seen.sort();
+        assert_eq!(seen, vec![15, 18, 18]);
+    });
 
-// #[test]
-// fn query_functions() -> Result<()> {
-//     let world = init_world()?;
+    Ok(())
+}
 
-//     let query = world.query();
-//     query.query_fn(&update_healths);
-//     query.query_fn(&update_enemies);
+#[test]
+fn lazy_query_iterators() -> Result<()> {
+    let world = init_world()?;
 
-//     query.query_fn(&update_healths_and_positions_seperately);
+    let mut healths = world.query_typed::<&mut Health>();
+    let mut first = healths.next().unwrap();
+    first.0 += 1;
 
-//     Ok(())
-// }
+    // only the entity that was reached is borrowed, the others can still be written to
+    {
+        let [mut last] = world.get_components_mut::<Health, 1>(&[2])?;
+        last.0 += 1;
+    }
+    assert!(world.get_components_mut::<Health, 1>(&[0]).is_err());
+    drop(first);
 
-// #[test]
-// fn query_functions_mut() -> Result<()> {
-//     let world = init_world()?;
+    let query = world.query();
+    let mut positions = query.auto_mut::<Position>().into_iter();
+    let _first = positions.next().unwrap();
+    assert!(world.get_components_mut::<Position, 2>(&[1, 2]).is_ok());
 
-//     let query = world.query();
+    let healths = world.query_typed::<&Health>().map(|hp| hp.0).collect::<Vec<u16>>();
+    assert_eq!(healths, vec![16, 6, 13]);
 
-//     // test(|h: &Health| { print!("hi") });
+    Ok(())
+}
 
-//     // <&Health as FnQueryType>::map_ref(&std::cell::RefCell::new(5));
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Velocity(i32, i32);
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Mass(u16);
 
-//     query.query_fn(update_healths);
-//     query.query_fn(change_healths);
-//     query.query_fn(update_healths_and_positions_seperately);
+#[test]
+fn large_tuple_queries() -> Result<()> {
+    let mut world = World::new();
 
-//     Ok(())
-// }
+    world.spawn().insert_checked(Position(0, 0))?.insert_checked(Velocity(1, 2))?.insert_checked(Mass(2))?.insert_checked(Health(5))?;
+    world.spawn().insert_checked(Position(4, 4))?.insert_checked(Velocity(-1, 0))?.insert_checked(Mass(1))?.insert_checked(Health(7))?;
 
-// fn change_healths(health: FnQuery<&Health>) {
-//     for hp in health.into_iter() {
-//         hp.0;
-//     }
-// }
+    world.run_system(|query: FnQuery<(&mut Position, &Velocity, &Mass, &Health)>| {
+        for (mut pos, vel, mass, _) in query {
+            pos.0 += vel.0 * mass.0 as i32;
+            pos.1 += vel.1 * mass.0 as i32;
+        }
+    });
 
-// // fn test<'a, T>(h: fn(T)) 
-// // where T: FnQueryType<'a>
-// // {
-// //     T::map_ref(&std::cell::RefCell::new(5));
-// // }
+    let positions = world.query_typed::<(&Position, &Health)>()
+        .map(|(pos, hp)| (*pos, hp.0))
+        .collect::<Vec<(Position, u16)>>();
+    assert_eq!(positions, vec![(Position(2, 4), 5), (Position(3, 4), 7)]);
 
-// fn update_healths(healths: FnQuery<&mut Health>) {
-//     for mut thing in &healths {
-//         println!("{:?}", thing);
-//         thing.0 += 5;
-//     }
-// }
+    Ok(())
+}
 
-// fn update_healths_and_positions_seperately(healths: FnQuery<Health>, positions: FnQuery<Position>) {
-//     for mut hp in healths.into_iter() {
-//         println!("Health at {:?}", hp);
-//         hp.0 += 12;
-//     }
+#[test]
+fn tuple_queries_stay_aligned() -> Result<()> {
+    let mut world = World::new();
 
-//     for mut pos in positions.into_iter() {
-//         pos.0 += 12;
-//         println!("is {:?}", pos);
-//     }
-// }
+    world.spawn().insert_checked(Health(1))?;
+    world.spawn().insert_checked(Position(1, 1))?;
+    world.spawn().insert_checked(Health(2))?.insert_checked(Position(2, 2))?;
+    world.spawn().insert_checked(Position(3, 3))?.insert_checked(Enemy)?;
+    world.spawn().insert_checked(Health(4))?.insert_checked(Position(4, 4))?;
 
-// fn update_enemies(enemies: FnQuery<(&mut Enemy, &Health)>) {
-//     for (mut enem, hp) in enemies.iter() {
-//         println!("Enemy: {:?}, ", hp);
-//     }
-// }
+    let results = world.query_typed::<(&Health, &Position)>()
+        .map(|(hp, pos)| (hp.0, *pos))
+        .collect::<Vec<(u16, Position)>>();
+    assert_eq!(results, vec![(2, Position(2, 2)), (4, Position(4, 4))]);
+
+    world.run_system(|query: FnQuery<(&Position, &Enemy)>| {
+        let ids = query.iter_with_ids().map(|(id, _)| id).collect::<Vec<Entity>>();
+        assert_eq!(ids, vec![3]);
+    });
+
+    Ok(())
+}
 
 #[test]
-fn auto_querys() -> Result<()> {
+fn cached_queries_follow_structural_changes() -> Result<()> {
+    let mut world = init_world()?;
+
+    let ids = |world: &World| world.query_cached::<(&Health, &Position)>()
+        .iter_with_ids()
+        .map(|(id, _)| id)
+        .collect::<Vec<Entity>>();
+    assert_eq!(ids(&world), vec![0, 1, 2]);
+
+    // changing the values of components isn't a structural change, but is still seen
+    world.run_system(|query: CachedQuery<&mut Health>| {
+        for mut hp in &query {
+            hp.0 += 1;
+        }
+    });
+    let healths = world.query_cached::<&Health>().iter().map(|hp| hp.0).collect::<Vec<u16>>();
+    assert_eq!(healths, vec![16, 7, 13]);
+
+    world.delete_component_from_ent_checked::<Position>(1)?;
+    assert_eq!(ids(&world), vec![0, 2]);
+
+    world.spawn().insert_checked(Position(1, 1))?.insert_checked(Health(1))?;
+    assert_eq!(ids(&world), vec![0, 2, 3]);
+
+    world.delete_entity(0)?;
+    assert_eq!(ids(&world), vec![2, 3]);
+
+    world.clear_entities();
+    assert!(world.query_cached::<(&Health, &Position)>().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn or_queries() -> Result<()> {
+    let mut world = World::new();
+
+    world.spawn().insert_checked(Health(1))?;
+    world.spawn().insert_checked(Position(1, 1))?;
+    world.spawn().insert_checked(Health(2))?.insert_checked(Enemy)?;
+    world.spawn().insert_checked(Enemy)?;
+
+    let results = world.query_typed::<Or<(&Health, &mut Position)>>()
+        .map(|(hp, pos)| (hp.map(|hp| hp.0), pos.map(|pos| *pos)))
+        .collect::<Vec<(Option<u16>, Option<Position>)>>();
+    assert_eq!(results, vec![(Some(1), None), (None, Some(Position(1, 1))), (Some(2), None)]);
+
+    world.delete_component_from_ent_checked::<Health>(0)?;
+    let ids = world.query_cached::<Or<(&Health, &Enemy)>>()
+        .iter_with_ids()
+        .map(|(id, _)| id)
+        .collect::<Vec<Entity>>();
+    assert_eq!(ids, vec![2, 3]);
+
+    Ok(())
+}
+
+#[test]
+fn with_filters_dont_borrow() -> Result<()> {
     let world = init_world()?;
 
-    let query = world.query(); let auto = query.auto::<Health>();
+    // the enemy is borrowed mutably, With<Enemy> must not borrow it again
+    let _enemy = world.query_typed::<&mut Enemy>().next().unwrap();
 
-    assert_eq!(auto.len(), 3);
+    let healths = world.query_typed::<(&Health, With<Enemy>)>()
+        .map(|(hp, _)| hp.0)
+        .collect::<Vec<u16>>();
+    assert_eq!(healths, vec![12]);
 
-    let mut iter = auto.into_iter();
+    world.query().query_fn(|query: FnQuery<(With<Position>, &mut Health, With<Enemy>)>| {
+        assert_eq!(query.iter().count(), 1);
+    });
 
-    assert_eq!(iter.next().unwrap().0, 12);
-    assert_eq!(iter.next().unwrap().0, 6);
-    assert_eq!(iter.next().unwrap().0, 15);
+    Ok(())
+}
+
+#[test]
+fn get_single_entity() -> Result<()> {
+    let mut world = init_world()?;
+
+    {
+        let (hp, mut pos) = world.get::<(&Health, &mut Position)>(2)?;
+        pos.0 += hp.0 as i32;
+    }
+    assert_eq!(*world.get::<&Position>(2)?, Position(18, 6));
+
+    assert!(matches!(
+        world.get::<(&Health, With<Enemy>)>(0),
+        Err(Error::Query(QueryError::NonMatchingEntityError(0)))
+    ));
+    assert!(world.get::<Or<(&Enemy, &Health)>>(1).is_ok());
+    assert!(world.get::<&Health>(7).is_err());
+
+    world.despawn(2);
+    assert!(world.get::<&Position>(2).is_err());
+
+    struct Unregistered;
+    assert!(matches!(
+        world.get::<&Unregistered>(0),
+        Err(Error::Query(QueryError::UnregisteredComponentError))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn split_world() -> Result<()> {
+    let world = init_world()?;
+
+    let (moving, healing) = world.split::<(&mut Position, &Enemy), &mut Health>()?;
+    let positions = moving.query::<(&mut Position, &Enemy)>()?;
+    let healths = healing.query::<&mut Health>()?;
+
+    // both views are borrowed at the same time
+    for ((mut pos, _), mut health) in positions.iter().zip(healths.iter()) {
+        pos.0 += 1;
+        health.0 += 1;
+    }
+    assert_eq!(*moving.get::<&Position>(2)?, Position(7, 6));
+    assert_eq!(healing.get::<&Health>(0)?.0, 16);
+
+    // each view only accesses it's own components, and only reads what it was given to read
+    assert!(moving.query::<&Health>().is_err());
+    assert!(healing.get::<&Position>(0).is_err());
+    let (reading, _) = world.split::<(&Position,), &Health>()?;
+    assert!(reading.query::<&mut Position>().is_err());
+    assert!(reading.query::<&Position>().is_ok());
+
+    assert!(world.split::<&mut Position, (&Position, &Health)>().is_err());
+    assert!(world.split::<&Position, (&Position, &Health)>().is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn named_query_results_ignore_the_order_of_components() -> Result<()> {
+    let mut world = init_world()?;
+
+    let healths = |result: &QueryResult| result.iter::<Health>().map(|health| *health).collect::<Vec<Health>>();
+    let first = world.query().with_component::<Health>().with_component::<Enemy>().run_named();
+    let second = world.query().with_component::<Enemy>().with_component::<Health>().run_named();
+    assert_eq!(first.entities(), &[2]);
+    assert_eq!(healths(&first), healths(&second));
+    assert!(!first.contains::<Position>());
+    assert!(first.column_checked::<Position>().is_err());
+    drop((first, second));
+    world.advance_tick();
+    world.advance_tick();
+    assert_eq!(world.query_typed::<Changed<Health>>().count(), 0);
+
+    let result = world.query().with_component::<Position>().with_component::<Health>().run_named();
+    assert_eq!(result.len(), 3);
+    for (mut health, pos) in result.iter_mut::<Health>().zip(result.iter::<Position>()) {
+        health.0 += pos.0 as u16;
+    }
+    assert_eq!(healths(&result), vec![Health(15), Health(18), Health(18)]);
+    drop(result);
+
+    // an empty query has no entities nor columns
+    let empty = world.query().run_named();
+    assert!(empty.is_empty());
+    assert!(!empty.contains::<Health>());
+
+    // borrowing mutably through the result is a change
+    world.advance_tick();
+    assert_eq!(world.query_typed::<Changed<Health>>().count(), 3);
 
     Ok(())
 }
@@ -236,4 +463,82 @@ fn init_world() -> Result<World> {
     world.insert_resource(PlayerResource("Loafoflead".to_owned()));
 
     Ok(world)
-}
\ No newline at end of file
+}
+
+trait Weapon {
+    fn damage(&self) -> u32;
+}
+
+struct Sword(u32);
+struct Bow(u32);
+struct Axe;
+
+impl Weapon for Sword {
+    fn damage(&self) -> u32 { self.0 }
+}
+impl Weapon for Bow {
+    fn damage(&self) -> u32 { self.0 / 2 }
+}
+impl Weapon for Axe {
+    fn damage(&self) -> u32 { 7 }
+}
+
+query_trait!(Weapon);
+
+#[test]
+fn query_trait_objects() -> Result<()> {
+    let mut world = World::new();
+    world.register_trait::<dyn Weapon, Sword>(|c| c, |c| c)
+        .register_trait::<dyn Weapon, Bow>(|c| c, |c| c);
+
+    world.spawn().insert(Bow(10));
+    world.spawn().insert(Sword(3)).insert(Bow(4));
+    world.spawn().insert(Axe);
+    world.spawn().insert(Sword(1));
+    world.delete_component_from_ent_checked::<Sword>(3)?;
+
+    let damage = |world: &World| world.query_typed::<&dyn Weapon>()
+        .map(|weapons| weapons.iter().map(|weapon| weapon.damage()).collect::<Vec<u32>>())
+        .collect::<Vec<_>>();
+    assert_eq!(damage(&world), vec![vec![5], vec![3, 2]]);
+
+    // registering a type afterwards is seen by cached queries too
+    assert_eq!(world.query_cached::<&dyn Weapon>().len(), 2);
+    world.register_trait::<dyn Weapon, Axe>(|c| c, |c| c);
+    assert_eq!(world.query_cached::<&dyn Weapon>().len(), 3);
+    assert_eq!(damage(&world)[2], vec![7]);
+
+    assert_eq!(world.get::<&dyn Weapon>(1)?.len(), 2);
+    assert!(world.get::<&mut dyn Weapon>(3).is_err());
+
+    fn conflicting(_: FnQuery<&mut dyn Weapon>, _: FnQuery<&dyn Weapon>) {}
+    assert!(conflicting.check_access().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn sorted_queries() -> Result<()> {
+    let mut world = init_world()?;
+
+    world.run_system(|healths: FnQuery<(&mut Health, &Position)>| {
+        let sorted = healths.iter_sorted_by_key(|(health, _)| health.0).collect::<Vec<_>>();
+        assert_eq!(sorted.iter().map(|(health, _)| health.0).collect::<Vec<u16>>(), vec![6, 12, 15]);
+        drop(sorted);
+
+        // results are only borrowed when they are reached, so they can be modified one at a time
+        for (i, (mut health, _)) in healths.iter_sorted_by(|(_, a), (_, b)| b.0.cmp(&a.0)).enumerate() {
+            health.0 = i as u16;
+        }
+    });
+    let healths = world.query_typed::<&Health>().map(|health| health.0).collect::<Vec<u16>>();
+    assert_eq!(healths, vec![2, 0, 1]);
+
+    // ties keep the order of the entity ids, the ids are sorted along with the results
+    let cached = world.query_cached::<&Position>();
+    let by_row = cached.iter_sorted_by_key(|pos| pos.0 == pos.1).map(|pos| *pos).collect::<Vec<Position>>();
+    assert_eq!(by_row, vec![Position(12, 10), Position(0, 0), Position(6, 6)]);
+    assert_eq!(cached.iter().len(), 3);
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use sceller::prelude::*;
+
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum GameState { Menu, Playing }
+
+struct Log(Vec<&'static str>);
+impl Resource for Log {}
+
+fn menu(mut log: ResMut<Log>, mut state: ResMut<States<GameState>>) {
+    log.0.push("menu");
+    state.set(GameState::Playing);
+}
+
+fn play(mut log: ResMut<Log>, mut commands: Commands) {
+    log.0.push("playing");
+    if log.0.len() == 3 {
+        commands.add(|world| {
+            world.insert_resource(AppExit);
+            Ok(())
+        });
+    }
+}
+
+#[test]
+fn runner_drives_the_app() {
+    let frames = Rc::new(RefCell::new(0));
+    let counted = Rc::clone(&frames);
+
+    let mut app = App::new();
+    app.insert_resource(Log(Vec::new()))
+        .add_state(GameState::Menu)
+        .add_state_system(OnUpdate(GameState::Menu), menu)
+        .add_state_system(OnUpdate(GameState::Playing), play)
+        .set_runner(move |mut app| {
+            while !app.should_exit() {
+                app.update();
+                *counted.borrow_mut() += 1;
+            }
+            assert_eq!(app.world.get_resource::<Log>().unwrap().0, vec!["menu", "playing", "playing"]);
+            assert_eq!(app.world.get_resource::<Time>().unwrap().frame_count(), 3);
+        });
+    app.run();
+
+    assert_eq!(*frames.borrow(), 3);
+    // the app was given to the runner
+    assert!(app.world().get_resource::<Log>().is_err());
+}
+
+#[test]
+fn default_runner_updates_once() {
+    let frames = Rc::new(RefCell::new(0));
+    let counted = Rc::clone(&frames);
+
+    App::new()
+        .add_system(move |_: Commands| *counted.borrow_mut() += 1)
+        .run();
+
+    assert_eq!(*frames.borrow(), 1);
+}
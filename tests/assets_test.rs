@@ -0,0 +1,79 @@
+use sceller::prelude::*;
+
+#[derive(Debug, PartialEq)]
+struct Mesh(&'static str);
+
+fn meshes(world: &World) -> std::cell::RefMut<'_, Assets<Mesh>> {
+    world.get_resource_mut::<Assets<Mesh>>().unwrap()
+}
+
+fn events(world: &World) -> Vec<AssetEvent> {
+    world.get_resource::<AssetEvents<Mesh>>().unwrap().iter().copied().collect()
+}
+
+#[test]
+fn handles_keep_assets_alive() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.add_assets::<Mesh>();
+
+    let tree = meshes(&world).insert(Mesh("tree"));
+    let rock = meshes(&world).insert(Mesh("rock"));
+    let (tree_id, rock_id) = (tree.id(), rock.id());
+    world.spawn().insert_checked(tree.clone())?;
+    world.spawn().insert_checked(tree)?;
+    world.spawn().insert_checked(rock)?;
+    assert_eq!(meshes(&world).handle_count(tree_id), 2);
+
+    world.run_schedule();
+    assert_eq!(events(&world), vec![AssetEvent::Added(tree_id), AssetEvent::Added(rock_id)]);
+
+    world.despawn(0);
+    world.despawn(2);
+    world.run_schedule();
+    assert_eq!(events(&world), vec![AssetEvent::Removed(rock_id)]);
+    assert_eq!(meshes(&world).len(), 1);
+    assert_eq!(meshes(&world).handle_count(tree_id), 1);
+
+    // a handle can be made again from the id while the asset exists
+    let tree = meshes(&world).handle(tree_id).unwrap();
+    world.despawn(1);
+    world.run_schedule();
+    assert_eq!(meshes(&world).get(&tree), Some(&Mesh("tree")));
+    assert!(events(&world).is_empty());
+
+    assert_eq!(meshes(&world).remove(&tree), Some(Mesh("tree")));
+    assert!(meshes(&world).get(&tree).is_none());
+    world.run_schedule();
+    assert_eq!(events(&world), vec![AssetEvent::Removed(tree_id)]);
+
+    Ok(())
+}
+
+#[test]
+fn loading_assets() -> eyre::Result<()> {
+    let mut assets = Assets::<Mesh>::new();
+    let error = assets.load_checked("tree.obj").unwrap_err();
+    assert!(matches!(error, Error::Asset(AssetError::MissingLoaderError(_))));
+
+    assets.set_loader(|path| match path {
+        "tree.obj" => Ok(Mesh("tree")),
+        _ => Err(format!("{} doesn't exist", path).into()),
+    });
+    let tree = assets.load_checked("tree.obj")?;
+    let again = assets.load_checked("tree.obj")?;
+    assert_eq!(again, tree);
+    assert_eq!(assets.get(&tree), Some(&Mesh("tree")));
+    assert_eq!(assets.handle_count(tree.id()), 2);
+
+    let error = assets.load_checked("rock.obj").unwrap_err();
+    assert_eq!(error.to_string(), "Failed to load the asset 'rock.obj': rock.obj doesn't exist");
+
+    // once freed, the asset is loaded again from it's path
+    let id = tree.id();
+    drop((tree, again));
+    assert_eq!(assets.free_unused(), 1);
+    let tree = assets.load_checked("tree.obj")?;
+    assert_ne!(tree.id(), id);
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use sceller::prelude::*;
+
+#[derive(Default)]
+struct Position(i32);
+#[derive(Default)]
+struct Velocity(i32);
+struct Frozen;
+
+#[test]
+fn operations_count_what_they_went_through() -> eyre::Result<()> {
+    let mut bench = WorldBench::new();
+    assert_eq!(bench.spawn_n::<(Position, Velocity)>(10).count, 10);
+    assert_eq!(bench.spawn_n_with(5, |i| (Position(i as i32), Frozen)).count, 5);
+    assert_eq!(bench.spawned(), (0..15).collect::<Vec<Entity>>());
+
+    assert_eq!(bench.iter_query::<&Position>().count, 15);
+    assert_eq!(bench.for_each_query::<(&Position, &Velocity)>().count, 10);
+    assert_eq!(bench.iter_query_with::<(&mut Position, With<Frozen>), _>(|(mut pos, _)| pos.0 += 1).count, 5);
+    assert_eq!(bench.world().get::<&Position>(14)?.0, 5);
+
+    assert_eq!(bench.insert_each(|entity| Velocity(entity as i32)).count, 15);
+    assert_eq!(bench.world().get::<&Velocity>(12)?.0, 12);
+
+    // entities despawned outside of the bench aren't counted
+    bench.world_mut().despawn(0);
+    assert_eq!(bench.despawn_all().count, 14);
+    assert!(bench.spawned().is_empty());
+    assert_eq!(bench.world().entity_count(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn measurements_report_throughput() {
+    let measurement = Measurement { count: 4, elapsed: std::time::Duration::from_millis(2) };
+    assert_eq!(measurement.per_item(), std::time::Duration::from_micros(500));
+    assert_eq!(measurement.throughput(), 2000.0);
+    assert!(measurement.to_string().starts_with("4 items in 2ms"));
+
+    assert_eq!(Measurement::default().per_item(), std::time::Duration::ZERO);
+    assert_eq!(Measurement::default().throughput(), 0.0);
+}
+
+#[test]
+fn measure_times_any_operation() {
+    let mut bench = WorldBench::from(World::new());
+    bench.world_mut().spawn_batch((0..8).map(|i| (Position(i),)));
+    assert!(bench.spawned().is_empty());
+
+    let measurement = bench.measure(8, |world| world.despawn_where::<Position>(|pos| pos.0 < 4));
+    assert_eq!(measurement.count, 8);
+    assert_eq!(bench.into_world().entity_count(), 4);
+}
@@ -0,0 +1,167 @@
+use sceller::prelude::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Position(i32, i32);
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Health(u16);
+#[derive(Debug)]
+struct Enemy;
+
+#[test]
+fn commands_are_applied_after_system() -> Result<()> {
+    let mut world = init_world()?;
+
+    world.run_system(spawn_enemy);
+
+    let query = world.query();
+    assert_eq!(query.auto::<Enemy>().len(), 2);
+    assert_eq!(query.auto::<Health>().len(), 3);
+
+    Ok(())
+}
+
+fn spawn_enemy(mut commands: Commands, enemies: FnQuery<&Enemy>) {
+    commands.spawn()
+        .insert(Position(1, 1))
+        .insert(Enemy);
+
+    // nothing is applied until the system has finished
+    assert_eq!(enemies.iter().count(), 1);
+}
+
+#[test]
+fn commands_insert_and_remove() -> Result<()> {
+    let mut world = init_world()?;
+
+    world.run_system(heal_and_strip);
+
+    let mut query = world.query();
+    let entities = query.with_component_checked::<Health>()?.run_entity()?;
+    assert_eq!(*entities[0].get_component::<Health>()?, Health(100));
+
+    // entity 1 no longer has a position
+    let mut query = world.query();
+    let ids = query.with_component_checked::<Position>()?.run_entity()?
+        .iter().map(|e| e.id).collect::<Vec<usize>>();
+    assert_eq!(ids, vec![0, 2]);
+
+    Ok(())
+}
+
+fn heal_and_strip(mut commands: Commands) {
+    commands.insert(0, Health(100));
+    commands.entity(1).remove::<Position>();
+}
+
+#[test]
+fn commands_spawn_reserved() -> Result<()> {
+    let mut world = init_world()?;
+    world.delete_entity(1)?;
+
+    world.run_system(|mut commands: Commands, enemies: FnQuery<&Position>| {
+        for pos in enemies.iter() {
+            let bullet = commands.spawn_reserved().insert(Health(1)).id().unwrap();
+            commands.entity(bullet).insert(*pos);
+            // vacant ids are left to the entities spawned normally
+            assert!(bullet >= 3);
+        }
+        assert_eq!(commands.spawn().insert(Enemy).id(), Some(5));
+    });
+
+    // the vacant id is left to the entities spawned right away
+    assert!(!world.contains(1));
+    assert!(world.get::<&Enemy>(5).is_ok());
+    assert_eq!(*world.get::<&Position>(3)?, Position(0, 0));
+    assert_eq!(*world.get::<&Position>(4)?, Position(6, 6));
+
+    Ok(())
+}
+
+#[test]
+fn queued_spawns_get_an_entity_each() -> Result<()> {
+    let mut world = init_world()?;
+    world.delete_entity(1)?;
+
+    world.run_system(|mut commands: Commands| {
+        commands.spawn().insert(Health(1));
+        commands.spawn().insert(Health(2));
+
+        let mut first = commands.spawn();
+        let mut second = commands.spawn();
+        first.insert(Position(1, 1));
+        second.insert(Position(2, 2));
+    });
+
+    let mut healths = world.query_typed::<&Health>().map(|health| health.0).collect::<Vec<u16>>();
+    healths.sort_unstable();
+    assert_eq!(healths, vec![1, 2, 12, 15]);
+    assert_eq!(world.query_typed::<&Position>().count(), 4);
+    assert_eq!(world.entity_count(), 6);
+
+    Ok(())
+}
+
+#[test]
+fn commands_despawn() -> Result<()> {
+    let mut world = init_world()?;
+
+    world.run_system(|mut commands: Commands| {
+        commands.despawn(2);
+    });
+
+    let query = world.query();
+    assert_eq!(query.auto::<Enemy>().len(), 0);
+    assert_eq!(query.auto::<Health>().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn failing_command_returns_error() -> Result<()> {
+    let mut world = init_world()?;
+
+    world.commands().despawn(40);
+
+    assert!(world.apply_commands().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn query_entities_queue_commands() -> Result<()> {
+    let mut world = init_world()?;
+
+    for entity in world.query().with_component_checked::<Health>()?.run_entity()? {
+        let health = entity.get_component::<Health>()?.0;
+        if entity.has_component::<Enemy>() {
+            entity.despawn()?;
+        } else if health < 10 {
+            entity.remove::<Position>()?;
+            entity.insert(Enemy)?;
+        }
+    }
+    // nothing changes until the commands are applied
+    assert_eq!(world.query().auto::<Enemy>().len(), 1);
+
+    world.apply_commands()?;
+    assert!(!world.entity_has::<Position>(1));
+    assert!(world.entity_has::<Enemy>(1));
+    assert_eq!(world.query().auto::<Health>().len(), 2);
+
+    // the entities of a query of Entities alone have nowhere to queue changes
+    let ents = Entities::default();
+    let error = QueryEntity::new(0, &ents).despawn().unwrap_err();
+    assert!(matches!(error, Error::Query(QueryError::NoCommandQueueError(0))));
+
+    Ok(())
+}
+
+fn init_world() -> Result<World> {
+    let mut world = World::new();
+
+    world.spawn().insert_checked(Position(0, 0))?.insert_checked(Health(15))?;
+    world.spawn().insert_checked(Position(12, 10))?.insert_checked(Health(6))?;
+    world.spawn().insert_checked(Position(6, 6))?.insert_checked(Health(12))?.insert_checked(Enemy)?;
+
+    Ok(world)
+}
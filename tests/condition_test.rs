@@ -0,0 +1,68 @@
+use sceller::prelude::*;
+
+struct Enabled(bool);
+impl Resource for Enabled {}
+struct Counter(u32);
+impl Resource for Counter {}
+struct Paused;
+impl Resource for Paused {}
+
+#[test]
+fn system_param_conditions() {
+    let mut world = World::new();
+    world.insert_resource(Counter(0));
+    world.insert_resource(Enabled(false));
+
+    world.add_system(count.run_if(is_enabled));
+
+    world.run_schedule();
+    assert_eq!(world.get_resource::<Counter>().unwrap().0, 0);
+
+    world.get_resource_mut::<Enabled>().unwrap().0 = true;
+
+    world.run_schedule();
+    world.run_schedule();
+    assert_eq!(world.get_resource::<Counter>().unwrap().0, 2);
+}
+
+fn count(mut counter: ResMut<Counter>) {
+    counter.0 += 1;
+}
+
+fn is_enabled(enabled: Res<Enabled>) -> bool {
+    enabled.0
+}
+
+#[test]
+fn chained_conditions_with_run_system() {
+    let mut world = World::new();
+    world.insert_resource(Counter(0));
+    world.insert_resource(Enabled(true));
+    world.insert_resource(Paused);
+
+    world.run_system(count.run_if(is_enabled).run_if(not_paused));
+    assert_eq!(world.get_resource::<Counter>().unwrap().0, 0);
+
+    world.delete_resource::<Paused>().unwrap();
+
+    world.run_system(count.run_if(is_enabled).run_if(not_paused));
+    assert_eq!(world.get_resource::<Counter>().unwrap().0, 1);
+}
+
+fn not_paused(world: &World) -> bool {
+    world.get_resource::<Paused>().is_err()
+}
+
+#[test]
+fn resource_exists_condition() {
+    let mut world = World::new();
+    world.insert_resource(Counter(0));
+
+    world.run_system(count.run_if(resource_exists::<Paused>()));
+    assert_eq!(world.get_resource::<Counter>().unwrap().0, 0);
+
+    world.insert_resource(Paused);
+
+    world.run_system(count.run_if(resource_exists::<Paused>()));
+    assert_eq!(world.get_resource::<Counter>().unwrap().0, 1);
+}
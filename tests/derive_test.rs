@@ -0,0 +1,81 @@
+#![cfg(feature = "derive")]
+
+use sceller::prelude::*;
+
+#[derive(Component, Debug, PartialEq)]
+struct Health(u32);
+
+#[derive(Component)]
+#[component(unique)]
+struct Player;
+
+#[derive(Component)]
+#[component(name = "Wrapper")]
+struct Wrapped<T: 'static>(T);
+
+#[test]
+fn derived_components() -> Result<()> {
+    assert_eq!(Health::NAME, "Health");
+    assert_eq!((Health::UNIQUE, Player::UNIQUE), (false, true));
+    assert_eq!(<Wrapped<u8> as Component>::NAME, "Wrapper");
+
+    let mut world = World::default();
+    Player::register(&mut world);
+    Wrapped::<u8>::register(&mut world);
+    assert!(world.type_registry().get(&std::any::TypeId::of::<Wrapped<u8>>()).is_some());
+
+    world.spawn().insert_checked(Player)?;
+    assert!(world.spawn().insert_checked(Player).is_err());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "auto-register")]
+fn derived_components_are_registered_in_new_worlds() -> Result<()> {
+    let mut world = World::new();
+    assert!(world.type_registry().get_by_name("Health").is_some());
+    assert!(world.type_registry().get_by_name("Player").is_some());
+    // generic types can't be registered without their parameters
+    assert!(world.type_registry().iter().all(|registration| !registration.name.contains("Wrapped")));
+
+    // queries work before any Health is inserted
+    assert_eq!(world.query_typed::<&Health>().count(), 0);
+    world.run_system(|healths: FnQuery<&Health>| assert_eq!(healths.iter().count(), 0));
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct Transform(i32, i32);
+#[derive(Debug, PartialEq)]
+struct Sprite(&'static str);
+
+#[derive(Bundle)]
+struct Visuals(Transform, Sprite);
+
+#[derive(Bundle)]
+struct PlayerBundle {
+    health: Health,
+    player: Player,
+    #[bundle]
+    visuals: Visuals,
+}
+
+#[test]
+fn derived_bundles() -> Result<()> {
+    let mut world = World::new();
+    let entity = world.spawn().insert_bundle_checked(PlayerBundle {
+        health: Health(10),
+        player: Player,
+        visuals: Visuals(Transform(1, 2), Sprite("player.png")),
+    })?.current_entity();
+
+    // nested bundles are flattened into their components
+    assert_eq!(*world.get::<&Health>(entity)?, Health(10));
+    assert_eq!(*world.get::<&Transform>(entity)?, Transform(1, 2));
+    assert_eq!(*world.get::<&Sprite>(entity)?, Sprite("player.png"));
+    assert!(world.get::<&Visuals>(entity).is_err());
+    assert_eq!(world.query_typed::<(&Player, &Sprite)>().count(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "egui")]
+
+use sceller::prelude::*;
+use sceller::egui_inspector::WorldInspectorWindow;
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Position { x: i32, y: i32 }
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Health(u32);
+struct Mesh;
+
+fn run_frame(inspector: &mut WorldInspectorWindow, world: &World) {
+    let ctx = egui::Context::default();
+    let _ = ctx.run(egui::RawInput::default(), |ctx| inspector.show(ctx, world));
+}
+
+#[test]
+fn filter_and_show_entities() -> Result<()> {
+    let mut world = World::new();
+    world.register_serde_component::<Position>();
+    world.register_serde_component::<Health>();
+
+    world.spawn().insert_checked(Name::new("player"))?.insert_checked(Position { x: 0, y: 0 })?.insert_checked(Health(3))?;
+    world.spawn().insert_checked(Position { x: 4, y: 2 })?.insert_checked(Mesh)?;
+    world.spawn().insert_checked(Mesh)?;
+    world.spawn().insert_checked(Health(1))?;
+    world.despawn(3);
+
+    let mut inspector = WorldInspectorWindow::new();
+    assert_eq!(inspector.entities(&world), vec![0, 1, 2]);
+    inspector.filter = "MESH".to_owned();
+    assert_eq!(inspector.entities(&world), vec![1, 2]);
+    inspector.filter = "pos".to_owned();
+    assert_eq!(inspector.entities(&world), vec![0, 1]);
+
+    // the selected entity is shown, and forgotten once it is despawned
+    inspector.selected = Some(1);
+    run_frame(&mut inspector, &world);
+    assert_eq!(inspector.selected, Some(1));
+    world.despawn(1);
+    run_frame(&mut inspector, &world);
+    assert_eq!(inspector.selected, None);
+
+    // a component borrowed elsewhere is skipped rather than panicking
+    inspector.selected = Some(0);
+    let health = world.get::<&mut Health>(0)?;
+    run_frame(&mut inspector, &world);
+    drop(health);
+
+    Ok(())
+}
+
+#[test]
+fn edit_components_through_json() -> Result<()> {
+    let mut world = World::new();
+    world.register_serde_component::<Position>();
+    let entity = world.spawn().insert_checked(Position { x: 1, y: 2 })?.current_entity();
+
+    let registration = world.type_registry().get_by_name("Position").unwrap();
+    let from_json = registration.from_json.unwrap();
+    from_json(&mut *world.get::<&mut Position>(entity)?, json!({ "x": 5, "y": -1 }))?;
+    assert_eq!(*world.get::<&Position>(entity)?, Position { x: 5, y: -1 });
+
+    // values that don't fit the type are errors, leaving the component as it was
+    assert!(from_json(&mut *world.get::<&mut Position>(entity)?, json!({ "x": "five" })).is_err());
+    assert_eq!(*world.get::<&Position>(entity)?, Position { x: 5, y: -1 });
+
+    Ok(())
+}
@@ -14,18 +14,21 @@ fn delete_entity() -> eyre::Result<()> {
 
     world.delete_entity(0)?; // delete 1st entity
 
-    let query = world.query().with_component_checked::<Location>()?.run();
+    {
+        // the query results borrow the world, so they are dropped before spawning again
+        let query = world.query().with_component_checked::<Location>()?.run();
 
-    // assert that the query is only one component
-    assert_eq!(query.len(), 1);
-    // assert that there is only 1 'Location' components in the system
-    assert_eq!(query[0].len(), 1);
+        // assert that the query is only one component
+        assert_eq!(query.len(), 1);
+        // assert that there is only 1 'Location' components in the system
+        assert_eq!(query[0].len(), 1);
 
-    let borrowed_locations = query[0][0].borrow();
-    let loc = borrowed_locations.downcast_ref::<Location>().unwrap();
+        let borrowed_locations = query[0][0].borrow();
+        let loc = borrowed_locations.downcast_ref::<Location>().unwrap();
 
-    // assert that now the first location is 1049 (the second component)
-    assert_eq!(loc.0, 1049);
+        // assert that now the first location is 1049 (the second component)
+        assert_eq!(loc.0, 1049);
+    }
 
     world.spawn().insert_checked(Location(0, 0))?;
 
@@ -55,10 +58,105 @@ fn test_auto_queries() -> eyre::Result<()> {
 
     let mut iter = auto.into_iter();
 
-    // backwards because it is reverse order from how we inserted them
-    assert_eq!(iter.next().unwrap().0, -9); // Third loc .0 = -9
-    assert_eq!(iter.next().unwrap().0, 5); // Second loc .0 = 5
+    // in the same order as we inserted them
     assert_eq!(iter.next().unwrap().0, 12); // First loc .0 = 12
+    assert_eq!(iter.next().unwrap().0, 5); // Second loc .0 = 5
+    assert_eq!(iter.next().unwrap().0, -9); // Third loc .0 = -9
+
+    Ok(())
+}
+
+#[test]
+fn auto_queries_skip_deleted() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert_checked(Location(1, 1))?.insert_checked(Size(1))?;
+    world.spawn().insert_checked(Location(2, 2))?.insert_checked(Size(2))?;
+    world.spawn().insert_checked(Location(3, 3))?.insert_checked(Size(3))?;
+
+    world.delete_entity(0)?;
+    world.delete_component_from_ent_checked::<Location>(2)?;
+
+    {
+        let query = world.query();
+        let locations = query.auto_mut::<Location>().into_iter().map(|loc| loc.0).collect::<Vec<i32>>();
+        assert_eq!(locations, vec![2]);
+    }
+
+    let query = world.query();
+    let locations = query.auto::<Location>().into_iter().map(|loc| loc.0).collect::<Vec<i32>>();
+    assert_eq!(locations, vec![2]);
+
+    let sizes = query.auto_mut::<Size>().into_iter().map(|size| size.0).collect::<Vec<i8>>();
+    assert_eq!(sizes, vec![2, 3]);
+
+    Ok(())
+}
+
+#[test]
+fn mutable_auto_queries_cant_alias() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert_checked(Location(1, 1))?.insert_checked(Size(1))?;
+
+    let query = world.query();
+    let mut locations = query.auto_mut::<Location>().into_iter();
+    let error = query.auto_mut_checked::<Location>().map(|_| ()).unwrap_err();
+    assert!(error.to_string().contains(std::any::type_name::<Location>()));
+    // other component types aren't affected
+    query.auto_mut_checked::<Size>()?.get(0)?.0 = 2;
+
+    locations.next().unwrap().0 = 5;
+    drop(locations);
+    assert_eq!(query.auto_mut_checked::<Location>()?.get(0)?.0, 5);
+
+    Ok(())
+}
+
+#[test]
+fn tag_components_take_a_bit_per_entity() -> eyre::Result<()> {
+    let mut world = World::new();
+    for i in 0..100 {
+        world.spawn().insert_checked(Size(i))?.insert_checked(Player)?;
+    }
+    world.delete_component_from_ent_checked::<Player>(3)?;
+    assert_eq!(world.take_component::<Player>(4).map(|_| ()).ok(), Some(()));
+    world.despawn(5);
+
+    let report = world.memory_report();
+    let players = report.get::<Player>().unwrap();
+    assert_eq!(players.size, 0);
+    assert_eq!(players.slots, 100);
+    assert!(players.bytes < players.slots * 16);
+
+    let query = world.query();
+    assert_eq!(query.auto::<Player>().len(), 97);
+    let players = world.query_typed::<(&Player, &Size)>().map(|(_, size)| size.0).collect::<Vec<i8>>();
+    assert_eq!(players.len(), 97);
+    assert!(!players.contains(&3) && !players.contains(&4));
+
+    // the tags of different entities are borrowed separately
+    let player = world.try_get_component_mut::<Player>(0)?;
+    let _other = world.try_get_component_mut::<Player>(1)?;
+    assert!(world.try_get_component::<Player>(0).is_err());
+    drop(player);
+    let _players = [world.try_get_component::<Player>(0)?, world.try_get_component::<Player>(2)?];
+
+    Ok(())
+}
+
+#[test]
+fn tags_of_different_entities_borrowed_mutably_at_once() -> eyre::Result<()> {
+    let mut world = World::new();
+    for _ in 0..3 {
+        world.spawn().insert_checked(Player)?;
+    }
+
+    let players = world.get_components_mut::<Player, 2>(&[0, 1])?;
+    assert!(world.try_get_component::<Player>(1).is_err());
+    drop(players);
+
+    let players = world.query_typed::<&mut Player>().collect::<Vec<RefMut<Player>>>();
+    assert_eq!(players.len(), 3);
+    assert!(world.try_get_component_mut::<Player>(2).is_err());
 
     Ok(())
 }
@@ -194,7 +292,428 @@ fn delete_component() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn find_entities_by_name() -> eyre::Result<()> {
+    let mut world = World::new();
+
+    world.spawn().insert_checked(Name::new("guard"))?;
+    world.spawn().insert_checked(Name::new("player"))?.insert_checked(Size(2))?;
+    world.spawn().insert_checked(Name::new("guard"))?;
+    assert_eq!(world.find_by_name("player"), Some(1));
+    assert_eq!(world.find_by_name("guard"), Some(0));
+
+    world.despawn(0);
+    assert_eq!(world.find_by_name("guard"), Some(2));
+
+    // the freed slot is reused, the new entity isn't named
+    world.spawn().insert_checked(Size(1))?;
+    assert_eq!(world.find_by_name("guard"), Some(2));
+
+    // a name changed in place is indexed again, and a name borrowed mutably is never a match
+    world.query_typed::<&mut Name>().last().unwrap().clone_from(&Name::new("captain"));
+    assert_eq!(world.find_by_name("guard"), None);
+    assert_eq!(world.find_by_name("captain"), Some(2));
+    let mut captain = world.get::<&mut Name>(2)?;
+    *captain = Name::new("guard");
+    assert_eq!(world.find_by_name("guard"), None);
+    drop(captain);
+    assert_eq!(world.find_by_name("guard"), Some(2));
+
+    world.unregister_component_checked::<Name>()?;
+    assert_eq!(world.find_by_name("player"), None);
+
+    // the entity that was named "guard" has no components left, so it's slot is reused
+    world.spawn().insert_checked(Name::from("player"))?;
+    assert_eq!(world.find_by_name("player"), Some(2));
+
+    world.clear_entities();
+    assert_eq!(world.find_by_name("player"), None);
+
+    Ok(())
+}
+
+#[test]
+fn unique_components() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.register_unique::<Player>();
+    assert!(world.single::<Player>().is_err());
+
+    world.spawn().insert_checked(Player)?;
+    world.spawn().insert_checked(Size(1))?;
+    assert!(world.insert_component_into_entity_checked(Player, 1).is_err());
+    assert!(world.spawn_batch_checked(vec![(Player, Size(2))]).is_err());
+    // inserting it again into the same entity replaces it
+    world.insert_component_into_entity_checked(Player, 0)?;
+    assert_eq!(world.single::<Player>()?, 0);
+
+    // once the holder loses it, another entity can have it
+    world.delete_component_from_ent::<Player>(0);
+    world.insert_component_into_entity_checked(Player, 1)?;
+    assert_eq!(world.single::<Player>()?, 1);
+
+    world.despawn(1);
+    world.spawn().insert_checked(Player)?;
+
+    // a component can't be made unique once more than one entity has it
+    world.spawn().insert_checked(Size(3))?;
+    world.spawn().insert_checked(Size(4))?;
+    assert!(world.register_unique_checked::<Size>().is_err());
+    assert!(world.single::<Size>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn retain_and_despawn_where() -> eyre::Result<()> {
+    let mut world = World::new();
+    for i in 0..10 {
+        world.spawn().insert_checked(Size(i))?.insert_checked(Location(i as i32, 0))?;
+    }
+    world.spawn().insert_checked(Player)?;
+
+    assert_eq!(world.despawn_where::<Size>(|size| size.0 % 2 == 0), 5);
+    assert_eq!((world.entity_count(), world.entity_capacity(), world.vacant_entities()), (6, 11, 5));
+    assert_eq!(world.query_typed::<&Size>().map(|size| size.0).collect::<Vec<i8>>(), vec![1, 3, 5, 7, 9]);
+    assert!(world.single::<Player>().is_ok());
+
+    // the despawned slots are reused by the next entities
+    assert_eq!(world.spawn().insert_checked(Size(0))?.current_entity(), 0);
+    assert_eq!(world.query().auto::<Size>().len(), 6);
+
+    assert_eq!(world.retain(|entity| entity.has_component::<Player>()), 6);
+    assert_eq!(world.query().auto::<Size>().len(), 0);
+    assert_eq!(world.retain(|_| true), 0);
+
+    Ok(())
+}
+
+#[test]
+fn type_registry_tracks_registered_components() -> Result<()> {
+    let mut world = World::new();
+    world.register_component::<Player>();
+    world.spawn().insert_checked(Size(3))?;
+    world.journal_component::<Score>();
+
+    let names = world.type_registry().iter().map(|registration| registration.short_name()).collect::<Vec<String>>();
+    assert_eq!(names, vec!["Player", "Size", "Score"]);
+    assert_eq!(world.type_registry().name_of(&std::any::TypeId::of::<Size>()), Some(std::any::type_name::<Size>()));
+    assert!(world.type_registry().get_by_name("Size").unwrap().clone.is_none());
+
+    // journaled types can be cloned without knowing them
+    world.spawn().insert_checked(Score(7))?;
+    let score = world.type_registry().get_by_name("Score").unwrap().clone.unwrap();
+    assert_eq!(score(&*world.get::<&Score>(1)?).downcast_ref::<Score>(), Some(&Score(7)));
+
+    Ok(())
+}
+
+#[test]
+fn borrow_conflicts_name_the_component() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert_checked(Size(1))?.insert_checked(Location(0, 0))?;
+    world.spawn().insert_checked(Size(2))?;
+
+    let size = world.try_get_component_mut::<Size>(1)?;
+    let error = world.get::<&Size>(1).map(|_| ()).unwrap_err();
+    assert!(matches!(error, Error::Component(ComponentError::ComponentAlreadyBorrowedError { entity: 1, .. })));
+    assert!(error.to_string().contains(std::any::type_name::<Size>()));
+    assert!(world.try_get_component::<Size>(1).is_err());
+    assert!(world.query().auto::<Size>().get(1).is_err());
+
+    // the other entities can still be borrowed
+    assert_eq!(world.get::<(&Size, &Location)>(0)?.0.0, 1);
+    drop(size);
+    assert_eq!(world.try_get_component::<Size>(1)?.0, 2);
+
+    Ok(())
+}
+
+struct Player;
+
+#[allow(dead_code)]
 struct Location(pub i32, pub i32);
 struct Size(pub i8);
 
-struct Unique;
\ No newline at end of file
+struct Unique;
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Score(u32);
+
+#[test]
+fn insert_default_components() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.register_component_with_default::<Score>();
+    world.register_component_with(|| Size(3));
+
+    world.spawn().insert_checked(Score(12))?;
+    world.spawn().insert_checked(Location(0, 0))?;
+
+    // the existing component is replaced
+    world.insert_default::<Score>(0);
+    world.insert_default_batch::<Size>([0, 1]);
+    assert_eq!(*world.get::<&Score>(0)?, Score(0));
+    assert_eq!(world.query_typed::<&Size>().map(|size| size.0).collect::<Vec<i8>>(), vec![3, 3]);
+
+    assert!(world.insert_default_checked::<Location>(0).is_err());
+    assert!(world.insert_default_checked::<Score>(10).is_err());
+
+    // the type can be given by it's TypeId alone
+    let mut ents = Entities::default();
+    ents.register_component_with_default::<Score>();
+    ents.create_entity().insert_checked(Location(1, 1))?;
+    assert!(ents.has_default(&std::any::TypeId::of::<Score>()));
+    ents.insert_default_by_type_id(&std::any::TypeId::of::<Score>(), 0)?;
+    assert!(ents.has_component::<Score>(0));
+
+    Ok(())
+}
+
+#[test]
+fn transfer_entities_between_worlds() -> eyre::Result<()> {
+    let mut loading = World::new();
+    let mut live = World::new();
+    live.register_unique::<Player>();
+    live.spawn().insert_checked(Player)?;
+
+    loading.spawn().insert_checked(Size(1))?.insert_checked(Name::new("tree"))?;
+    loading.spawn().insert_checked(Player)?.insert_checked(Size(2))?;
+
+    let tree = loading.transfer_entity_checked(&mut live, 0)?;
+    assert_eq!(tree, 1);
+    assert_eq!(live.get::<&Size>(tree)?.0, 1);
+    assert_eq!(live.find_by_name("tree"), Some(tree));
+    assert_eq!(loading.find_by_name("tree"), None);
+    assert!(loading.transfer_entity_checked(&mut live, 0).is_err());
+
+    // the live world already has a player, so nothing is moved
+    assert!(loading.transfer_entity_checked(&mut live, 1).is_err());
+    assert!(loading.entity_has::<Size>(1));
+    assert_eq!(live.query().auto::<Size>().len(), 1);
+
+    live.despawn(0);
+    let player = loading.transfer_entity_checked(&mut live, 1)?;
+    assert_eq!(live.single::<Player>()?, player);
+    assert!(!loading.entity_has::<Player>(1));
+
+    Ok(())
+}
+
+#[test]
+fn iterate_entity_ids() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert_checked(Location(0, 0))?.insert_checked(Size(1))?;
+    world.spawn().insert_checked(Size(2))?;
+    world.spawn().insert_checked(Name::new("empty"))?;
+    world.delete_entity(1)?;
+
+    let location = std::any::TypeId::of::<Location>();
+    let size = std::any::TypeId::of::<Size>();
+    let entities = world.iter_entities().collect::<Vec<_>>();
+    assert_eq!(entities.len(), 2);
+    assert_eq!(entities[0], (0, vec![location, size]));
+    assert_eq!(entities[1].0, 2);
+
+    let mut ents = Entities::default();
+    ents.create_entity().insert_checked(Location(0, 0))?.insert_checked(Size(1))?;
+    ents.create_entity().insert_checked(Size(2))?;
+    let masks = ents.iter_ids().collect::<Vec<(Entity, u128)>>();
+    assert_eq!(masks, vec![(0, 0b11), (1, 0b10)]);
+
+    Ok(())
+}
+
+#[test]
+fn stored_ids_can_be_validated() -> eyre::Result<()> {
+    let mut world = World::new();
+    let target = world.spawn().insert_checked(Size(1))?.current_entity();
+    let follower = world.spawn().insert_checked(Location(0, 0))?.current_entity();
+
+    world.delete_entity(target)?;
+    assert!(!world.contains(target));
+    assert!(world.contains(follower));
+
+    // an entity spawned later reuses the id
+    world.spawn().insert_checked(Size(2))?;
+    assert!(world.contains(target));
+
+    Ok(())
+}
+
+#[test]
+fn memory_report_per_component() -> eyre::Result<()> {
+    let mut world = World::new();
+    for i in 0..4 {
+        world.spawn().insert_checked(Location(i, i))?;
+    }
+    world.spawn().insert_checked(Size(1))?;
+
+    let report = world.memory_report();
+    let locations = report.get::<Location>().unwrap();
+    assert_eq!((locations.size, locations.count, locations.slots), (std::mem::size_of::<Location>(), 4, 5));
+    assert_eq!(report.get::<Size>().unwrap().count, 1);
+    assert!(report.total_bytes() >= locations.used_bytes() + report.entity_bytes);
+    assert!(report.to_string().contains("Location"));
+
+    // removed components and the components of deleted entities aren't counted
+    world.delete_component_from_ent_checked::<Location>(0)?;
+    world.delete_entity(1)?;
+    world.despawn(2);
+    let _ = world.take_component::<Location>(3)?;
+    world.delete_component_from_ent_checked::<Size>(4)?;
+    let report = world.memory_report();
+    assert_eq!(report.get::<Location>().unwrap().count, 0);
+    assert_eq!(report.get::<Size>().unwrap().count, 0);
+    assert!(report.to_string().contains("(0 x"));
+
+    Ok(())
+}
+
+#[test]
+fn lazy_query_entities() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert_checked(Size(1))?;
+    world.spawn().insert_checked(Location(0, 0))?;
+    world.spawn().insert_checked(Size(5))?.insert_checked(Location(1, 1))?;
+    world.spawn().insert_checked(Size(9))?;
+
+    let mut query = world.query();
+    let query = query.with_component_checked::<Size>()?;
+    let mut entities = query.iter_entities()?;
+    let big = entities.find(|entity| entity.get_component::<Size>().is_ok_and(|size| size.0 > 2));
+    assert_eq!(big.map(|entity| entity.id), Some(2));
+    assert_eq!(entities.next().map(|entity| entity.id), Some(3));
+    assert!(entities.next().is_none());
+
+    let ids = query.iter_entities()?.map(|entity| entity.id).collect::<Vec<Entity>>();
+    assert_eq!(ids, query.run_entity()?.iter().map(|entity| entity.id).collect::<Vec<Entity>>());
+    assert!(world.query().iter_entities().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn auto_queries_with_ids() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert_checked(Size(1))?;
+    world.spawn().insert_checked(Location(0, 0))?;
+    world.spawn().insert_checked(Size(5))?;
+
+    let query = world.query();
+    let sizes = query.auto::<Size>().iter_with_ids().map(|(entity, size)| (entity, size.0)).collect::<Vec<_>>();
+    assert_eq!(sizes, vec![(0, 1), (2, 5)]);
+
+    for (entity, mut size) in query.auto_mut::<Size>().iter_with_ids().rev() {
+        size.0 += entity as i8;
+    }
+    assert_eq!(query.auto::<Size>().nth(1).map(|size| size.0), Some(7));
+    assert_eq!(query.auto_mut::<Size>().get(0)?.0, 1);
+    assert!(query.auto::<Size>().get(1).is_err());
+    assert!(query.auto::<Size>().get(10).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn insert_returns_replaced_component() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert_checked(Size(1))?;
+
+    assert_eq!(world.insert_component_into_entity_checked(Size(2), 0)?.map(|size| size.0), Some(1));
+    assert!(world.insert_component_into_entity_checked(Location(0, 0), 0)?.is_none());
+
+    let mut entity = world.entity_mut(0);
+    assert_eq!(entity.insert(Size(3)).map(|size| size.0), Some(2));
+    assert_eq!(entity.remove::<Size>().map(|size| size.0), Some(3));
+    assert!(entity.remove::<Size>().is_none());
+    entity.despawn();
+    assert!(world.entity_mut_checked(0).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn removed_components_are_never_returned_as_replaced() -> eyre::Result<()> {
+    let mut world = World::new();
+    let entity = world.spawn().insert_checked(Size(1))?.insert_checked(Location(1, 1))?.current_entity();
+
+    world.delete_component_from_ent_checked::<Size>(entity)?;
+    assert!(world.insert_component_into_entity_checked(Size(2), entity)?.is_none());
+    assert_eq!(world.insert_component_into_entity_checked(Size(3), entity)?.map(|size| size.0), Some(2));
+
+    // a new entity reusing the id of a deleted one doesn't get it's components back
+    world.delete_entity(entity)?;
+    let reused = world.spawn().current_entity();
+    assert_eq!(reused, entity);
+    assert!(world.insert_component_into_entity_checked(Size(4), reused)?.is_none());
+    assert!(world.insert_component_into_entity_checked(Location(0, 0), reused)?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn failing_bundles_store_nothing() -> eyre::Result<()> {
+    use std::rc::Rc;
+
+    #[allow(dead_code)]
+    struct Tracked(Rc<()>);
+    struct Leader;
+
+    let mut world = World::new();
+    world.register_unique::<Leader>();
+    world.spawn().insert_checked(Leader)?;
+    let tracker = Rc::new(());
+
+    let spawned = world.spawn().insert_checked(Size(1))?;
+    let entity = spawned.current_entity();
+    assert!(spawned.insert_bundle_checked((Tracked(tracker.clone()), Size(2), Leader)).is_err());
+    assert_eq!(Rc::strong_count(&tracker), 1);
+    assert_eq!(world.get::<&Size>(entity)?.0, 1);
+    assert!(!world.entity_has::<Tracked>(entity));
+
+    // the entities spawned before the failing bundle keep their components, the others store nothing
+    let batch = vec![(Tracked(tracker.clone()), Leader), (Tracked(tracker.clone()), Leader)];
+    assert!(world.spawn_batch_checked(batch).is_err());
+    assert_eq!(Rc::strong_count(&tracker), 1);
+    assert_eq!(world.memory_report().get::<Tracked>().unwrap().count, 0);
+
+    Ok(())
+}
+
+#[test]
+fn columns_borrowed_as_slices() -> eyre::Result<()> {
+    let mut world = World::new();
+    for i in 0..5 {
+        world.spawn().insert_checked(Location(i, i))?;
+    }
+    world.despawn(1);
+    world.insert_shared(2, &Shared::new(Location(7, 7)));
+    world.spawn().insert_checked(Player)?;
+
+    {
+        let (locations, entities) = world.column::<Location>()?;
+        let mut owned = entities.iter().zip(locations.iter()).map(|(entity, location)| (*entity, location.0)).collect::<Vec<(Entity, i32)>>();
+        owned.sort();
+        assert_eq!(owned, vec![(0, 0), (3, 3), (4, 4)]);
+
+        // components can still be read one at a time, but not written
+        assert_eq!(world.try_get_component::<Location>(3)?.0, 3);
+        assert!(world.try_get_component_mut::<Location>(4).is_err());
+        assert!(world.column_mut::<Location>().is_err());
+        // the shared component isn't part of the column
+        assert!(world.try_get_component_mut::<Location>(2).is_ok());
+    }
+
+    let location = world.try_get_component_mut::<Location>(0)?;
+    assert!(world.column::<Location>().is_err());
+    drop(location);
+
+    let (mut locations, _) = world.column_mut::<Location>()?;
+    locations.iter_mut().for_each(|location| location.1 = -1);
+    assert!(world.try_get_component::<Location>(0).is_err());
+    drop(locations);
+    assert_eq!(world.try_get_component::<Location>(4)?.1, -1);
+
+    let error = world.column::<Player>().map(|_| ()).unwrap_err();
+    assert!(matches!(error, Error::Component(ComponentError::TagColumnError(_))));
+
+    Ok(())
+}
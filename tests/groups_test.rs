@@ -0,0 +1,117 @@
+use sceller::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Position(i32, i32);
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Velocity(i32, i32);
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Mass(u8);
+
+fn sorted<'a>(components: impl Iterator<Item = (std::cell::Ref<'a, Position>, std::cell::Ref<'a, Velocity>)>) -> Vec<(Position, Velocity)> {
+    let mut components = components.map(|(position, velocity)| (*position, *velocity)).collect::<Vec<(Position, Velocity)>>();
+    components.sort_by_key(|(position, _)| position.0);
+    components
+}
+
+// the components of the group, in the same order as the ones of a regular query
+fn grouped(world: &World) -> Vec<(Position, Velocity)> {
+    sorted(world.query_group::<(&Position, &Velocity)>())
+}
+
+fn queried(world: &World) -> Vec<(Position, Velocity)> {
+    sorted(world.query_typed::<(&Position, &Velocity)>())
+}
+
+#[test]
+fn groups_follow_structural_changes() -> eyre::Result<()> {
+    let mut world = World::new();
+    for i in 0..6 {
+        world.spawn().insert_checked(Position(i, 0))?.insert_checked(Mass(i as u8))?;
+    }
+    world.register_group::<(Position, Velocity)>();
+    assert_eq!(world.query_group::<(&Position, &Velocity)>().len(), 0);
+
+    for entity in [4, 1, 3] {
+        world.insert_component_into_entity_checked(Velocity(entity as i32, 1), entity)?;
+    }
+    assert_eq!(grouped(&world), queried(&world));
+    assert_eq!(world.query_group::<(&Velocity, &Position)>().len(), 3);
+
+    world.delete_component_from_ent::<Velocity>(1);
+    world.despawn(3);
+    world.spawn().insert_checked(Position(10, 0))?.insert_checked(Velocity(2, 2))?;
+    world.insert_component_into_entity_checked(Velocity(0, 5), 0)?;
+    assert_eq!(grouped(&world), queried(&world));
+
+    // every other way of reaching the components still finds the right entity
+    for (mut position, velocity) in world.query_group::<(&mut Position, &Velocity)>() {
+        position.1 += velocity.1;
+    }
+    assert_eq!(*world.get::<&Position>(0)?, Position(0, 5));
+    assert_eq!(*world.get::<&Position>(4)?, Position(4, 1));
+    assert_eq!(*world.get::<&Position>(5)?, Position(5, 0));
+    assert_eq!(world.get::<&Mass>(4)?.0, 4);
+    assert_eq!(world.query().auto::<Position>().into_iter().map(|position| position.0).collect::<Vec<i32>>(), vec![0, 1, 2, 10, 4, 5]);
+
+    Ok(())
+}
+
+#[test]
+fn group_errors() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.register_group::<(Position, Velocity)>();
+    world.spawn().insert_checked(Position(0, 0))?.insert_checked(Velocity(1, 1))?.insert_checked(Mass(1))?;
+
+    // registering the same group again does nothing
+    world.register_group_checked::<(Position, Velocity)>()?;
+    let error = world.register_group_checked::<(Mass, Velocity)>().unwrap_err();
+    assert!(matches!(error, Error::Component(ComponentError::GroupedComponentError(_))));
+    assert!(world.query_group_checked::<(&Position, &Mass)>().is_err());
+    // a group can't answer a query on some of it's components, which entities outside of it have too
+    world.spawn().insert_checked(Position(1, 1))?;
+    assert_eq!(world.query_typed::<&Position>().len(), 2);
+    let error = world.query_group_checked::<&Position>().map(|_| ()).unwrap_err();
+    assert!(matches!(error, Error::Query(QueryError::UngroupedQueryError(_))));
+    assert!(world.query_group_checked::<(&Position, &Velocity, &Mass)>().is_err());
+
+    world.register_group::<(Mass,)>();
+    assert_eq!(world.query_group::<&Mass>().next().unwrap().0, 1);
+
+    world.clear_entities();
+    assert_eq!(world.query_group::<(&Position, &Velocity)>().len(), 0);
+    world.spawn().insert_checked(Position(3, 3))?.insert_checked(Velocity(0, 0))?;
+    assert_eq!(grouped(&world), vec![(Position(3, 3), Velocity(0, 0))]);
+
+    // the group is forgotten along with the component
+    world.unregister_component::<Velocity>();
+    assert!(world.query_group_checked::<(&Position, &Velocity)>().is_err());
+    world.register_group_checked::<(Position,)>()?;
+    assert_eq!(world.query_group::<&Position>().next().unwrap().0, 3);
+
+    Ok(())
+}
+
+#[test]
+fn replaced_components_keep_their_place_in_the_group() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.register_group::<(Position, Velocity)>();
+    for i in 0..4 {
+        world.spawn().insert_checked(Position(i, 0))?.insert_checked(Velocity(i, 0))?;
+    }
+    world.spawn().insert_checked(Position(9, 9))?;
+
+    world.insert_component_into_entity_checked(Position(20, 2), 1)?;
+    world.insert_component_into_entity_checked(Velocity(0, 3), 2)?;
+    world.delete_component_from_ent::<Velocity>(0);
+    assert_eq!(grouped(&world), queried(&world));
+    assert_eq!(grouped(&world).len(), 3);
+
+    // grouped components are stored in order, so they can't be shared
+    let shared = Shared::new(Velocity(1, 1));
+    let error = world.insert_shared_checked(4, &shared).unwrap_err();
+    assert!(matches!(error, Error::Component(ComponentError::SharedGroupedComponentError(_))));
+    world.insert_shared_checked(4, &Shared::new(Mass(1)))?;
+    assert!(world.register_group_checked::<(Mass,)>().is_err());
+
+    Ok(())
+}
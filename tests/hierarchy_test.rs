@@ -0,0 +1,128 @@
+use sceller::prelude::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Name(&'static str);
+
+#[test]
+fn reparenting_updates_both_sides() -> Result<()> {
+    let mut world = World::new();
+    let a = world.spawn().insert_checked(Name("a"))?.current_entity();
+    let b = world.spawn().insert_checked(Name("b"))?.current_entity();
+    let c = world.spawn().insert_checked(Name("c"))?.current_entity();
+
+    world.set_parent(c, a);
+    assert_eq!(world.children(a), vec![c]);
+
+    world.set_parent(c, b);
+    assert_eq!(world.children(a), Vec::<Entity>::new());
+    assert_eq!(world.children(b), vec![c]);
+    assert_eq!(world.parent(c), Some(b));
+
+    world.remove_parent_checked(c)?;
+    assert_eq!(world.parent(c), None);
+    assert!(world.children(b).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn cycles_are_errors() -> Result<()> {
+    let mut world = World::new();
+    let a = world.spawn().insert_checked(Name("a"))?.current_entity();
+    let b = world.spawn().insert_checked(Name("b"))?.current_entity();
+
+    world.set_parent(b, a);
+
+    assert!(world.set_parent_checked(a, b).is_err());
+    assert!(world.set_parent_checked(a, a).is_err());
+    assert!(world.set_parent_checked(a, 20).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn despawn_recursive_keeps_siblings() -> Result<()> {
+    let mut world = World::new();
+    let root = world.spawn().insert_checked(Name("root"))?.current_entity();
+    let left = world.spawn().insert_checked(Name("left"))?.current_entity();
+    let right = world.spawn().insert_checked(Name("right"))?.current_entity();
+    let leaf = world.spawn().insert_checked(Name("leaf"))?.current_entity();
+
+    world.set_parent(left, root).set_parent(right, root).set_parent(leaf, left);
+    assert_eq!(world.descendants(root), vec![left, leaf, right]);
+
+    world.despawn_recursive(left);
+
+    assert_eq!(world.children(root), vec![right]);
+    let mut names = world.query_typed::<&Name>().map(|name| name.0).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["right", "root"]);
+
+    // reused slots don't inherit the old links
+    let new = world.spawn().insert_checked(Name("new"))?.current_entity();
+    assert_eq!(world.parent(new), None);
+    assert!(world.children(new).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn despawned_entities_leave_the_hierarchy() -> Result<()> {
+    let mut world = World::new();
+    let parent = world.spawn().insert_checked(Name("parent"))?.current_entity();
+    let child = world.spawn().insert_checked(Name("child"))?.current_entity();
+    let grandchild = world.spawn().insert_checked(Name("grandchild"))?.current_entity();
+    world.set_parent(child, parent).set_parent(grandchild, child);
+
+    // the id of the child is taken by an unrelated entity once it is despawned
+    world.despawn(child);
+    let innocent = world.spawn().insert_checked(Name("innocent"))?.current_entity();
+    assert_eq!(innocent, child);
+    assert!(!world.entity_has::<Children>(parent));
+    assert_eq!(world.parent(grandchild), None);
+    assert!(world.descendants(parent).is_empty());
+
+    world.despawn_recursive(parent);
+    assert!(!world.contains(parent));
+    assert_eq!(*world.get::<&Name>(innocent)?, Name("innocent"));
+    assert_eq!(*world.get::<&Name>(grandchild)?, Name("grandchild"));
+
+    Ok(())
+}
+
+struct Local(i32);
+#[derive(Debug, PartialEq, Eq)]
+struct Global(i32);
+
+fn add_to_parent(parent: Option<&Global>, local: &Local) -> Global {
+    Global(parent.map_or(0, |parent| parent.0) + local.0)
+}
+
+#[test]
+fn propagate_walks_hierarchy_down() -> Result<()> {
+    let mut world = World::new();
+    let root = world.spawn().insert_checked(Local(100))?.current_entity();
+    let child = world.spawn().insert_checked(Local(10))?.insert_checked(Global(-1))?.current_entity();
+    let grandchild = world.spawn().insert_checked(Local(1))?.current_entity();
+    let other = world.spawn().insert_checked(Name("no transform"))?.current_entity();
+    let orphan = world.spawn().insert_checked(Local(5))?.current_entity();
+    world.set_parent(child, root).set_parent(grandchild, child).set_parent(orphan, other);
+
+    world.add_system(propagate(add_to_parent));
+    world.run_schedule();
+
+    assert_eq!(*world.get::<&Global>(root)?, Global(100));
+    assert_eq!(*world.get::<&Global>(child)?, Global(110));
+    assert_eq!(*world.get::<&Global>(grandchild)?, Global(111));
+    // the parent has no Local, so the child is a root
+    assert_eq!(*world.get::<&Global>(orphan)?, Global(5));
+    assert!(world.get::<&Global>(other).is_err());
+
+    world.set_parent(grandchild, root);
+    world.get::<&mut Local>(root)?.0 = 200;
+    world.run_schedule();
+    assert_eq!(*world.get::<&Global>(child)?, Global(210));
+    assert_eq!(*world.get::<&Global>(grandchild)?, Global(201));
+
+    Ok(())
+}
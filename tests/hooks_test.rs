@@ -0,0 +1,126 @@
+use sceller::prelude::*;
+
+#[allow(dead_code)]
+struct Collider(f32);
+#[allow(dead_code)]
+struct Velocity(f32);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Event {
+    Added(Entity),
+    Removed(Entity),
+}
+
+struct Log(Vec<Event>);
+impl Resource for Log {}
+
+fn init_world() -> World {
+    let mut world = World::new();
+    world.insert_resource(Log(Vec::new()));
+    world.on_add::<Collider>(|world, entity| world.get_resource_mut::<Log>().unwrap().0.push(Event::Added(entity)));
+    world.on_remove::<Collider>(|world, entity| world.get_resource_mut::<Log>().unwrap().0.push(Event::Removed(entity)));
+    world
+}
+
+fn take_log(world: &World) -> Vec<Event> {
+    std::mem::take(&mut world.get_resource_mut::<Log>().unwrap().0)
+}
+
+#[test]
+fn hooks_follow_insert_and_remove() -> Result<()> {
+    let mut world = init_world();
+
+    world.spawn().insert_checked(Collider(1.0))?.insert_checked(Velocity(0.0))?;
+    world.spawn().insert_checked(Velocity(1.0))?;
+    world.run_hooks();
+    assert_eq!(take_log(&world), vec![Event::Added(0)]);
+
+    // replacing a component isn't adding it
+    world.insert_component_into_entity_checked(Collider(2.0), 0)?;
+    world.insert_component_into_entity_checked(Collider(3.0), 1)?;
+    assert_eq!(take_log(&world), vec![Event::Added(1)]);
+
+    world.delete_component_from_ent_checked::<Collider>(0)?;
+    world.delete_component_from_ent_checked::<Velocity>(1)?;
+    assert_eq!(take_log(&world), vec![Event::Removed(0)]);
+
+    world.take_component::<Collider>(1)?;
+    world.insert_component_into_entity_checked(Collider(3.0), 1)?;
+    world.despawn(1);
+    assert_eq!(take_log(&world), vec![Event::Removed(1), Event::Added(1), Event::Removed(1)]);
+
+    Ok(())
+}
+
+#[test]
+fn hooks_run_after_commands() -> Result<()> {
+    let mut world = init_world();
+    world.spawn().insert_checked(Collider(1.0))?;
+
+    world.run_system(|mut commands: Commands| {
+        commands.insert(0, Velocity(2.0));
+        commands.despawn(0);
+    });
+    assert_eq!(take_log(&world), vec![Event::Added(0), Event::Removed(0)]);
+
+    Ok(())
+}
+
+#[test]
+fn hooks_can_change_the_world() -> Result<()> {
+    let mut world = init_world();
+    // every collider gets a velocity, and losing it's collider despawns an entity
+    world.on_add::<Collider>(|world, entity| { world.insert_component_into_entity(Velocity(0.0), entity); });
+    world.on_remove::<Collider>(|world, entity| { world.despawn(entity); });
+
+    world.spawn().insert_checked(Collider(1.0))?;
+    world.spawn().insert_checked(Velocity(1.0))?;
+    world.run_hooks();
+    assert!(world.entity_has::<Velocity>(0));
+
+    world.unregister_component_checked::<Collider>()?;
+    assert!(!world.entity_has::<Velocity>(0));
+    assert_eq!(take_log(&world), vec![Event::Added(0), Event::Removed(0)]);
+
+    // hooks are kept when the World is cleared
+    world.clear();
+    world.insert_resource(Log(Vec::new()));
+    world.spawn().insert_checked(Collider(1.0))?;
+    world.run_hooks();
+    assert_eq!(take_log(&world), vec![Event::Added(0)]);
+
+    Ok(())
+}
+
+struct TargetOf(Entity);
+struct Follows(Entity);
+
+#[test]
+fn relationships_follow_despawned_targets() -> Result<()> {
+    let mut world = World::new();
+    world.register_relationship(|target: &TargetOf| target.0, RelationshipPolicy::Despawn);
+    world.register_relationship(|follows: &Follows| follows.0, RelationshipPolicy::Remove);
+
+    let enemy = world.spawn().insert_checked(Collider(1.0))?.current_entity();
+    let marker = world.spawn().insert_checked(Collider(0.0))?.insert_checked(TargetOf(enemy))?.current_entity();
+    // despawning the marker kills the target of this one in turn
+    let label = world.spawn().insert_checked(Collider(0.0))?.insert_checked(TargetOf(marker))?.current_entity();
+    let camera = world.spawn().insert_checked(Velocity(1.0))?.insert_checked(Follows(label))?.current_entity();
+
+    // despawned by a system, through commands
+    world.run_system(move |mut commands: Commands| commands.despawn(enemy));
+
+    for entity in [enemy, marker, label] {
+        assert!(world.get::<&Collider>(entity).is_err());
+    }
+    assert!(world.get::<&Velocity>(camera).is_ok());
+    assert!(world.get::<&Follows>(camera).is_err());
+
+    // ids reused by new entities aren't targets of the old relationships
+    let reused = world.spawn().insert_checked(Collider(2.0))?.current_entity();
+    let holder = world.spawn().insert_checked(TargetOf(reused))?.current_entity();
+    world.run_hooks();
+    assert!(world.get::<&TargetOf>(holder).is_ok());
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "inspector")]
+
+use sceller::prelude::*;
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Position { x: i32, y: i32 }
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Team {
+    Red,
+    Blue { captain: bool },
+}
+struct Mesh;
+
+#[test]
+fn inspect_entities() -> Result<()> {
+    let mut world = World::new();
+    world.register_serde_component::<Position>();
+    world.register_serde_component::<Team>();
+
+    world.spawn().insert_checked(Team::Red)?;
+    let player = world.spawn()
+        .insert_checked(Position { x: 1, y: -2 })?
+        .insert_checked(Team::Blue { captain: true })?
+        .insert_checked(Mesh)?
+        .current_entity();
+
+    let inspected = world.inspect_checked(player)?;
+    assert_eq!(inspected["entity"], 1);
+    assert_eq!(inspected["components"], json!([
+        { "type": std::any::type_name::<Team>(), "name": "Team", "value": { "Blue": { "captain": true } } },
+        { "type": std::any::type_name::<Position>(), "name": "Position", "value": { "x": 1, "y": -2 } },
+        { "type": std::any::type_name::<Mesh>(), "name": "Mesh", "value": null },
+    ]));
+
+    // components borrowed elsewhere and dead entities are errors
+    let position = world.get::<&mut Position>(player)?;
+    assert!(world.inspect_checked(player).is_err());
+    drop(position);
+    world.despawn(player);
+    assert!(world.inspect_checked(player).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use sceller::prelude::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Position(i32, i32);
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Health(u16);
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Level(String);
+impl Resource for Level {}
+struct NotJournaled;
+
+#[test]
+fn undo_and_redo_entity_changes() -> Result<()> {
+    let mut world = World::new();
+    world.journal_component::<Position>().journal_component::<Health>();
+
+    world.spawn().insert_checked(Position(0, 0))?.insert_checked(Health(10))?;
+    world.spawn().insert_checked(Position(5, 5))?.insert_checked(NotJournaled)?;
+    world.insert_component_into_entity_checked(Health(3), 0)?;
+    world.despawn_checked(1)?;
+    world.delete_component_from_ent_checked::<Position>(0)?;
+
+    let state = |world: &World| world.query_typed::<Or<(&Position, &Health)>>()
+        .map(|(pos, hp)| (pos.map(|pos| *pos), hp.map(|hp| *hp)))
+        .collect::<Vec<_>>();
+    assert_eq!(state(&world), vec![(None, Some(Health(3)))]);
+
+    assert!(world.undo());
+    assert_eq!(state(&world), vec![(Some(Position(0, 0)), Some(Health(3)))]);
+    assert!(world.undo());
+    assert_eq!(state(&world), vec![(Some(Position(0, 0)), Some(Health(3))), (Some(Position(5, 5)), None)]);
+    // only journaled types come back
+    assert!(!world.entity_has::<NotJournaled>(1));
+    assert!(world.undo());
+    assert_eq!(state(&world)[0], (Some(Position(0, 0)), Some(Health(10))));
+    assert!(world.undo());
+    assert!(world.undo());
+    assert_eq!(state(&world), vec![]);
+    assert!(!world.undo());
+
+    assert!(world.redo());
+    assert!(world.redo());
+    assert!(world.redo());
+    assert_eq!(state(&world), vec![(Some(Position(0, 0)), Some(Health(3))), (Some(Position(5, 5)), None)]);
+
+    // a new change forgets the steps that were undone
+    world.insert_component_into_entity_checked(Health(1), 1)?;
+    assert!(!world.redo());
+    assert!(world.undo());
+    assert!(!world.entity_has::<Health>(1));
+
+    Ok(())
+}
+
+#[test]
+fn compound_changes_are_one_step() -> Result<()> {
+    let mut world = World::new();
+    world.journal_component::<Parent>().journal_component::<Children>().journal_component::<Health>();
+
+    let parent = world.spawn().insert_checked(Health(1))?.current_entity();
+    let child = world.spawn().insert_checked(Health(2))?.current_entity();
+    world.set_parent_checked(child, parent)?;
+    assert_eq!(world.children(parent), vec![child]);
+
+    world.undo();
+    assert_eq!(world.parent(child), None);
+    assert!(world.children(parent).is_empty());
+
+    world.redo();
+    world.despawn_recursive_checked(parent)?;
+    assert!(!world.entity_has::<Health>(child));
+
+    world.undo();
+    assert_eq!(world.parent(child), Some(parent));
+    assert_eq!(*world.get::<&Health>(child)?, Health(2));
+
+    {
+        let mut commands = world.commands();
+        commands.insert(parent, Health(7));
+        commands.despawn(child);
+    }
+    world.apply_commands()?;
+    world.undo();
+    assert_eq!(*world.get::<&Health>(parent)?, Health(1));
+    assert_eq!(*world.get::<&Health>(child)?, Health(2));
+
+    Ok(())
+}
+
+#[test]
+fn undo_resources_without_hooks() -> Result<()> {
+    let mut world = World::new();
+    world.journal_resource::<Level>().journal_component::<Health>();
+    world.on_remove::<Health>(|world, _| world.insert_resource(Level(String::from("game over"))));
+
+    world.insert_resource(Level(String::from("first")));
+    world.spawn().insert_checked(Health(1))?;
+    world.despawn_checked(0)?;
+    assert_eq!(*world.get_resource::<Level>()?, Level(String::from("game over")));
+
+    // the hook's change is part of the despawn, and it doesn't run again when undoing it
+    world.undo();
+    assert_eq!(*world.get_resource::<Level>()?, Level(String::from("first")));
+    assert!(world.entity_has::<Health>(0));
+
+    world.undo();
+    world.undo();
+    assert!(world.get_resource::<Level>().is_err());
+
+    world.clear_journal();
+    assert!(!world.redo());
+
+    Ok(())
+}
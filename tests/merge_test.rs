@@ -0,0 +1,65 @@
+use sceller::prelude::*;
+
+#[derive(Debug, PartialEq)]
+struct Health(u32);
+struct Score(u32);
+impl Resource for Score {}
+struct Seed(u64);
+impl Resource for Seed {}
+
+fn world_with(score: u32) -> World {
+    let mut world = World::new();
+    world.insert_resource(Score(score));
+    world
+}
+
+#[test]
+fn merged_entities_are_remapped() -> eyre::Result<()> {
+    let mut world = world_with(1);
+    world.spawn().insert_checked(Health(100))?;
+    world.spawn().insert_checked(Health(50))?;
+    world.despawn(0);
+
+    let mut other = world_with(2);
+    let root = other.spawn().insert_checked(Health(1))?.current_entity();
+    let child = other.spawn().insert_checked(Health(2))?.current_entity();
+    let grandchild = other.spawn().insert_checked(Health(3))?.current_entity();
+    other.set_parent(child, root).set_parent(grandchild, child);
+    other.insert_resource(Seed(7));
+
+    let moved = world.merge_checked(other, MergePolicy::KeepExisting)?;
+    assert_eq!(moved.len(), 3);
+    assert_eq!(world.entity_count(), 4);
+    for (old, health) in [(root, 1), (child, 2), (grandchild, 3)] {
+        assert_eq!(*world.get::<&Health>(moved[&old])?, Health(health));
+    }
+    assert_eq!(world.parent(moved[&grandchild]), Some(moved[&child]));
+    assert_eq!(world.children(moved[&root]), vec![moved[&child]]);
+    assert_eq!(*world.get::<&Health>(1)?, Health(50));
+
+    assert_eq!(world.get_resource::<Score>()?.0, 1);
+    assert_eq!(world.get_resource::<Seed>()?.0, 7);
+
+    Ok(())
+}
+
+#[test]
+fn merge_policies() -> eyre::Result<()> {
+    let mut world = world_with(1);
+    world.merge_checked(world_with(2), MergePolicy::Replace)?;
+    assert_eq!(world.get_resource::<Score>()?.0, 2);
+
+    let mut other = world_with(3);
+    other.spawn().insert_checked(Health(1))?;
+    let error = world.merge_checked(other, MergePolicy::Fail).unwrap_err();
+    assert!(matches!(error, Error::Resources(ResourcesError::ConflictingResourceError(_))));
+    assert_eq!(world.get_resource::<Score>()?.0, 2);
+    assert_eq!(world.entity_count(), 0);
+
+    let mut other = World::new();
+    other.insert_resource(Seed(1));
+    world.merge_checked(other, MergePolicy::Fail)?;
+    assert_eq!(world.get_resource::<Seed>()?.0, 1);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use std::{cell::RefCell, rc::Rc};
+
+use sceller::prelude::*;
+
+struct Health;
+struct Armor;
+struct Dead;
+
+fn record(world: &mut World) -> Rc<RefCell<Vec<String>>> {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let events = log.clone();
+    world.observe(move |event: &StorageEvent, _| {
+        let name = |type_name: &str| type_name.rsplit("::").next().unwrap().to_string();
+        events.borrow_mut().push(match event {
+            StorageEvent::Spawned(event) => format!("spawned {}", event.entity),
+            StorageEvent::Despawned(event) => format!("despawned {}", event.entity),
+            StorageEvent::Inserted(event) => format!("inserted {} {}", name(event.type_name), event.entity),
+            StorageEvent::Removed(event) => format!("removed {} {}", name(event.type_name), event.entity),
+        });
+    });
+    log
+}
+
+#[test]
+fn structural_changes_are_observed_in_order() -> eyre::Result<()> {
+    let mut world = World::new();
+    let log = record(&mut world);
+
+    world.spawn().insert_checked(Health)?;
+    world.insert_component_into_entity_checked(Armor, 0)?;
+    assert_eq!(*log.borrow(), vec!["spawned 0", "inserted Health 0", "inserted Armor 0"]);
+    log.borrow_mut().clear();
+
+    // replacing a component isn't an insertion
+    world.insert_component_into_entity_checked(Armor, 0)?;
+    world.delete_component_from_ent::<Health>(0);
+    world.despawn(0);
+    assert_eq!(*log.borrow(), vec!["removed Health 0", "removed Armor 0", "despawned 0"]);
+
+    Ok(())
+}
+
+#[test]
+fn observers_keep_invariants_mid_frame() -> eyre::Result<()> {
+    let mut world = World::new();
+    // an entity without health is dead, as soon as it loses it
+    world.observe(|event: &ComponentRemoved, world| {
+        if event.is::<Health>() && world.contains(event.entity) {
+            world.insert_component_into_entity(Dead, event.entity);
+        }
+    });
+    let despawned = Rc::new(RefCell::new(Vec::new()));
+    let log = despawned.clone();
+    world.observe(move |event: &EntityDespawned, _| log.borrow_mut().push(event.entity));
+
+    world.spawn().insert_checked(Health)?.insert_checked(Armor)?;
+    world.spawn().insert_checked(Health)?;
+    world.take_component::<Health>(0)?;
+    assert!(world.entity_has::<Dead>(0));
+
+    // the last component of entity 1 is gone, so there is nothing left to mark as dead
+    world.take_component::<Health>(1)?;
+    assert!(!world.entity_has::<Dead>(1));
+    assert_eq!(*despawned.borrow(), vec![1]);
+
+    Ok(())
+}
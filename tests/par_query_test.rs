@@ -0,0 +1,118 @@
+#![cfg(feature = "rayon")]
+
+use sceller::prelude::*;
+use rayon::prelude::*;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Position(f32, f32);
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Velocity(f32, f32);
+struct Frozen;
+
+#[test]
+fn par_for_each_integrates() -> Result<()> {
+    let mut world = World::new();
+    for i in 0..1000 {
+        let entity = world.spawn();
+        entity.insert_checked(Position(0.0, 0.0))?;
+        if i % 3 != 0 {
+            entity.insert_checked(Velocity(i as f32, 1.0))?;
+        }
+        if i % 2 == 0 {
+            entity.insert_checked(Frozen)?;
+        }
+    }
+
+    world.par_for_each::<(&mut Position, &Velocity), _>(|(pos, vel)| {
+        pos.0 += vel.0;
+        pos.1 += vel.1;
+    });
+
+    let positions = world.query_typed::<&Position>().map(|pos| *pos).collect::<Vec<Position>>();
+    for (i, pos) in positions.iter().enumerate() {
+        if i % 3 != 0 {
+            assert_eq!(*pos, Position(i as f32, 1.0));
+        } else {
+            assert_eq!(*pos, Position(0.0, 0.0));
+        }
+    }
+
+    let moved_frozen = world.par_iter::<(&Position, &Frozen)>()
+        .filter(|(pos, _)| pos.1 != 0.0)
+        .count();
+    assert_eq!(moved_frozen, (0..1000).filter(|i| i % 3 != 0 && i % 2 == 0).count());
+
+    Ok(())
+}
+
+#[test]
+#[should_panic]
+fn par_query_same_component_twice() {
+    let mut world = World::new();
+    world.spawn().insert(Position(0.0, 0.0));
+
+    world.par_for_each::<(&mut Position, &Position), _>(|_| {});
+}
+
+#[test]
+fn non_send_components_stay_on_the_world_thread() {
+    use std::rc::Rc;
+
+    // not Send nor Sync, like a handle to a GPU resource
+    struct Texture(Rc<u32>);
+
+    let mut world = World::new();
+    let texture = Rc::new(7);
+    for i in 0..100 {
+        let entity = world.spawn();
+        entity.insert(Position(0.0, 0.0));
+        if i % 2 == 0 {
+            entity.insert(Texture(texture.clone()));
+        }
+    }
+
+    world.par_for_each::<(&mut Position, With<Texture>), _>(|(pos, _)| pos.0 += 1.0);
+    assert_eq!(world.query_typed::<&Position>().filter(|pos| pos.0 == 1.0).count(), 50);
+
+    // the textures are only used on the thread of the World
+    let total = world.query_typed::<&Texture>().map(|texture| *texture.0).sum::<u32>();
+    assert_eq!(total, 350);
+    assert_eq!(Rc::strong_count(&texture), 51);
+}
+
+#[test]
+fn main_thread_components_are_never_sent_to_the_pool() {
+    // Send and Sync, but only usable on the thread that created it
+    struct GpuBuffer(u32);
+
+    let mut world = World::new();
+    world.register_main_thread::<GpuBuffer>();
+    for i in 0..100 {
+        world.spawn().insert(Position(0.0, 0.0)).insert(GpuBuffer(i));
+    }
+    assert!(world.is_main_thread::<GpuBuffer>());
+    assert!(!world.is_main_thread::<Position>());
+
+    let caller = std::thread::current().id();
+    world.par_for_each::<(&mut Position, &GpuBuffer), _>(|(pos, buffer)| {
+        assert_eq!(std::thread::current().id(), caller);
+        pos.0 = buffer.0 as f32;
+    });
+    assert_eq!(world.query_typed::<&Position>().map(|pos| pos.0).sum::<f32>(), 4950.0);
+
+    assert!(world.par_iter_checked::<(&Position, &GpuBuffer)>().is_err());
+    // only filtering on the component doesn't access it
+    assert_eq!(world.par_iter_checked::<(&Position, With<GpuBuffer>)>().unwrap().count(), 100);
+}
+
+#[test]
+#[should_panic]
+fn par_iter_main_thread_component() {
+    struct GpuBuffer;
+
+    let mut world = World::new();
+    world.register_main_thread::<GpuBuffer>();
+    world.spawn().insert(GpuBuffer);
+
+    let _ = world.par_iter::<&GpuBuffer>();
+}
@@ -0,0 +1,112 @@
+use sceller::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Position(i32, i32);
+#[derive(Debug, Clone, PartialEq)]
+struct Health(u8);
+#[derive(Debug, Clone, PartialEq)]
+struct Burning;
+struct NotRecorded;
+
+fn init_world() -> World {
+    let mut world = World::new();
+    world.record_component::<Position>()
+        .record_component::<Health>()
+        .record_component::<Burning>();
+    world
+}
+
+fn positions(world: &World) -> Vec<(Entity, Position, Option<Health>)> {
+    world.iter_entities()
+        .map(|(entity, _)| entity)
+        .filter(|entity| world.entity_has::<Position>(*entity))
+        .map(|entity| (
+            entity,
+            world.get::<&Position>(entity).unwrap().clone(),
+            world.get::<&Health>(entity).ok().map(|health| health.clone()),
+        ))
+        .collect()
+}
+
+#[test]
+fn replay_rebuilds_the_recorded_entities() {
+    let mut world = init_world();
+    world.spawn().insert(Position(0, 0)).insert(Health(5));
+    world.enable_recording();
+    assert!(world.is_recording());
+
+    world.spawn().insert(Position(1, 1)).insert(NotRecorded);
+    world.spawn_batch(vec![(Position(2, 2), Health(1)), (Position(3, 3), Health(2))]);
+    world.insert_component_into_entity(Health(9), 0);
+    world.get::<&mut Position>(1).unwrap().0 = 10;
+    // only noticed once the component is inserted again
+    let position = world.get::<&Position>(1).unwrap().clone();
+    world.insert_component_into_entity(position, 1);
+    world.despawn(2);
+    world.commands().entity(3).remove::<Health>();
+    world.apply_commands().unwrap();
+
+    let log = world.mutation_log().unwrap().clone();
+    assert!(matches!(log.iter().next(), Some(Mutation::Spawn(0))));
+    assert!(log.iter().any(|mutation| matches!(mutation, Mutation::Remove { entity: 3, .. })));
+    assert!(log.iter().all(|mutation| match mutation {
+        Mutation::Insert { component, .. } => component.name() != std::any::type_name::<NotRecorded>(),
+        _ => true,
+    }));
+
+    let mut replayed = World::new();
+    replayed.replay(&log);
+    assert_eq!(positions(&replayed), positions(&world));
+    assert_eq!(positions(&replayed)[1].1, Position(10, 1));
+    assert!(!replayed.contains(2));
+
+    assert_eq!(world.disable_recording().unwrap().len(), log.len());
+    assert!(!world.is_recording());
+    assert!(world.mutation_log().is_none());
+}
+
+#[test]
+fn replay_does_not_run_hooks_twice() {
+    let mut world = init_world();
+    world.on_add::<Burning>(|world, entity| {
+        let health = world.get::<&Health>(entity).map(|health| health.0).unwrap_or(0);
+        world.insert_component_into_entity(Health(health.saturating_sub(1)), entity);
+    });
+    world.enable_recording();
+    world.spawn().insert(Position(0, 0)).insert(Health(3));
+    world.insert_component_into_entity(Burning, 0);
+    assert_eq!(world.get::<&Health>(0).unwrap().0, 2);
+
+    let log = world.disable_recording().unwrap();
+    let mut replayed = init_world();
+    replayed.on_add::<Burning>(|world, entity| {
+        world.insert_component_into_entity(Health(0), entity);
+    });
+    replayed.replay(&log);
+    assert_eq!(replayed.get::<&Health>(0).unwrap().0, 2);
+    assert!(replayed.entity_has::<Burning>(0));
+
+    // the replay is a single step of the journal
+    replayed.journal_component::<Health>();
+    replayed.replay(&log);
+    assert!(replayed.undo());
+}
+
+#[test]
+fn recording_follows_rollbacks() {
+    let mut world = init_world();
+    world.register_rollback_component::<Position>();
+    world.spawn().insert(Position(0, 0));
+    let snapshot = world.rollback_snapshot();
+
+    world.enable_recording();
+    world.spawn().insert(Position(1, 0));
+    world.rollback(&snapshot);
+    world.get::<&mut Position>(0).unwrap().0 = 4;
+
+    let log = world.disable_recording().unwrap();
+    let mut replayed = World::new();
+    replayed.replay(&log);
+    assert_eq!(replayed.iter_entities().map(|(entity, _)| entity).collect::<Vec<Entity>>(), vec![0]);
+    assert_eq!(*replayed.get::<&Position>(0).unwrap(), Position(0, 0));
+}
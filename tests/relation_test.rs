@@ -0,0 +1,172 @@
+use sceller::prelude::*;
+
+struct Likes;
+struct ChildOf;
+struct Targets;
+
+#[test]
+fn cleared_relations_never_point_to_reused_ids() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.register_relation::<Likes>(RelationshipPolicy::Clear);
+
+    let cake = world.spawn().insert_checked(Name::new("cake"))?.current_entity();
+    let alice = world.spawn().insert_checked(Relation::<Likes>::new(cake))?.current_entity();
+    world.run_hooks();
+
+    world.despawn(cake);
+    // the next entity takes the id of the cake
+    let rock = world.spawn().insert_checked(Name::new("rock"))?.current_entity();
+    assert_eq!(rock, cake);
+    assert_eq!(world.get::<&Relation<Likes>>(alice)?.target(), None);
+    assert!(world.relations_to(rock).is_empty());
+
+    world.get::<&mut Relation<Likes>>(alice)?.set_target(rock);
+    assert!(!world.get::<&Relation<Likes>>(alice)?.is_broken());
+    assert_eq!(world.relations_to(rock).len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn policies_apply_through_chains_of_relations() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.register_relation::<ChildOf>(RelationshipPolicy::Despawn);
+    world.register_relation::<Targets>(RelationshipPolicy::Remove);
+
+    let root = world.spawn().insert_checked(Name::new("root"))?.current_entity();
+    let child = world.spawn().insert_checked(Relation::<ChildOf>::new(root))?.current_entity();
+    let grandchild = world.spawn().insert_checked(Relation::<ChildOf>::new(child))?.current_entity();
+    let turret = world.spawn()
+        .insert_checked(Name::new("turret"))?
+        .insert_checked(Relation::<Targets>::new(grandchild))?
+        .current_entity();
+    world.run_hooks();
+
+    world.despawn(root);
+    assert!(!world.contains(child));
+    assert!(!world.contains(grandchild));
+    assert!(world.contains(turret));
+    assert!(world.get::<&Relation<Targets>>(turret).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn relations_to_finds_every_kind_of_relationship() -> eyre::Result<()> {
+    struct Follows(Entity);
+
+    let mut world = World::new();
+    world.register_relation::<Likes>(RelationshipPolicy::Clear);
+    world.register_relation::<Targets>(RelationshipPolicy::Remove);
+    world.register_relationship(|follows: &Follows| follows.0, RelationshipPolicy::Remove);
+
+    let player = world.spawn().insert_checked(Name::new("player"))?.current_entity();
+    let other = world.spawn().insert_checked(Name::new("other"))?.current_entity();
+    let camera = world.spawn().insert_checked(Follows(player))?.current_entity();
+    let enemy = world.spawn()
+        .insert_checked(Relation::<Targets>::new(player))?
+        .insert_checked(Relation::<Likes>::new(other))?
+        .current_entity();
+    let fan = world.spawn().insert_checked(Relation::<Likes>::new(player))?.current_entity();
+
+    let relations = world.relations_to(player);
+    let holders = relations.iter().map(|relation| relation.holder).collect::<Vec<Entity>>();
+    assert_eq!(holders, vec![fan, enemy, camera]);
+    assert!(relations[0].is::<Relation<Likes>>());
+    assert!(relations[1].is::<Relation<Targets>>());
+    assert!(relations[2].is::<Follows>());
+    assert_eq!(world.relations_to(other).len(), 1);
+
+    // registering a relation again replaces it's policy, without listing it twice
+    world.register_relation::<Likes>(RelationshipPolicy::Remove);
+    assert_eq!(world.relations_to(player).len(), 3);
+    world.despawn(player);
+    assert!(world.get::<&Relation<Likes>>(fan).is_err());
+    assert!(world.get::<&Relation<Targets>>(enemy).is_err());
+    assert!(world.get::<&Follows>(camera).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn despawns_only_clean_up_the_holders_linked_to_them() -> eyre::Result<()> {
+    struct Follows(Entity);
+
+    let mut world = World::new();
+    world.register_relationship(|follows: &Follows| follows.0, RelationshipPolicy::Remove);
+
+    let player = world.spawn().insert_checked(Name::new("player"))?.current_entity();
+    let enemy = world.spawn().insert_checked(Name::new("enemy"))?.current_entity();
+    let camera = world.spawn().insert_checked(Follows(player))?.current_entity();
+    world.run_hooks();
+
+    // the camera follows the enemy instead, so despawning the player leaves it alone
+    world.get::<&mut Follows>(camera)?.0 = enemy;
+    world.despawn(player);
+    assert_eq!(world.get::<&Follows>(camera)?.0, enemy);
+
+    // the id of the enemy is taken by another entity before the World cleans up after it
+    let entities = world.spawn().insert_checked(Name::new("rock"))?;
+    entities.despawn(enemy);
+    let newcomer = entities.create_entity().insert_checked(Name::new("newcomer"))?.current_entity();
+    assert_eq!(newcomer, enemy);
+    world.run_hooks();
+    assert!(world.get::<&Follows>(camera).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn relations_retargeted_in_place_follow_their_new_target() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.register_relation::<Likes>(RelationshipPolicy::Clear);
+    world.register_relation::<Targets>(RelationshipPolicy::Remove);
+
+    let cake = world.spawn().insert_checked(Name::new("cake"))?.current_entity();
+    let pie = world.spawn().insert_checked(Name::new("pie"))?.current_entity();
+    let alice = world.spawn()
+        .insert_checked(Relation::<Likes>::new(cake))?
+        .insert_checked(Relation::<Targets>::new(cake))?
+        .current_entity();
+    world.run_hooks();
+
+    world.get::<&mut Relation<Likes>>(alice)?.set_target(pie);
+    assert_eq!(world.relations_to(pie).len(), 1);
+    assert!(world.relations_to(cake)[0].is::<Relation<Targets>>());
+
+    world.despawn(cake);
+    assert_eq!(world.get::<&Relation<Likes>>(alice)?.target(), Some(pie));
+    assert!(world.get::<&Relation<Targets>>(alice).is_err());
+
+    world.despawn(pie);
+    assert!(world.get::<&Relation<Likes>>(alice)?.is_broken());
+    assert!(world.relations_to(pie).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn clearing_the_world_forgets_the_holders_of_relationships() -> eyre::Result<()> {
+    struct Follows(Entity);
+
+    let mut world = World::new();
+    world.register_relationship(|follows: &Follows| follows.0, RelationshipPolicy::Despawn);
+    let player = world.spawn().insert_checked(Name::new("player"))?.current_entity();
+    world.spawn().insert_checked(Follows(player))?;
+    world.run_hooks();
+
+    world.clear();
+    let rock = world.spawn().insert_checked(Name::new("rock"))?.current_entity();
+    let tree = world.spawn().insert_checked(Name::new("tree"))?.current_entity();
+    let camera = world.spawn().insert_checked(Follows(tree))?.current_entity();
+    assert_eq!(rock, player);
+    assert!(world.relations_to(rock).is_empty());
+
+    // the relationship is still registered, and only knows the entities spawned since
+    world.despawn(rock);
+    assert!(world.contains(camera));
+    world.despawn(tree);
+    assert!(!world.contains(camera));
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "serde")]
+
+use sceller::prelude::*;
+use sceller::replication::ReplicationMessage;
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+struct Position(i32, i32);
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+struct Health(u16);
+#[derive(Debug)]
+struct NotReplicated;
+
+fn world() -> World {
+    let mut world = World::new();
+    world.register_replicated_component::<Position>()
+        .register_replicated_component::<Health>();
+    world
+}
+
+fn send(server: &mut World, client: &mut World) -> Vec<ReplicationMessage> {
+    let messages = server.drain_replication_messages();
+    let sent = ron::to_string(&messages).unwrap();
+    for message in ron::from_str::<Vec<ReplicationMessage>>(&sent).unwrap() {
+        client.apply_replication_message(&message);
+    }
+    messages
+}
+
+#[test]
+fn replicate_spawns_updates_and_despawns() {
+    let mut server = world();
+    let mut client = world();
+    // so that the ids on both sides differ
+    client.spawn().insert(NotReplicated);
+
+    let knight = server.spawn().insert(Position(1, 2)).insert(Health(10)).insert(NotReplicated).current_entity();
+    let archer = server.spawn().insert(Position(5, 5)).current_entity();
+    let messages = send(&mut server, &mut client);
+    assert_eq!(messages.len(), 2);
+    assert!(matches!(messages[0], ReplicationMessage::Spawn { entity, ref components } if entity == knight && components.len() == 2));
+
+    assert_eq!(client.query_typed::<(&Position, &Health)>().count(), 1);
+    assert_eq!(client.query_typed::<&Position>().count(), 2);
+    assert_eq!(client.query_typed::<&NotReplicated>().count(), 1);
+
+    // nothing changed
+    assert!(send(&mut server, &mut client).is_empty());
+
+    server.get::<&mut Health>(knight).unwrap().0 = 4;
+    server.delete_component_from_ent::<Position>(knight);
+    server.insert_component_into_entity(Health(1), archer);
+    let messages = send(&mut server, &mut client);
+    assert_eq!(messages.len(), 2);
+    assert!(matches!(&messages[0], ReplicationMessage::Update { removed, .. } if removed.len() == 1));
+
+    let mut healths = client.query_typed::<&Health>().map(|health| health.0).collect::<Vec<u16>>();
+    healths.sort();
+    assert_eq!(healths, vec![1, 4]);
+    assert_eq!(client.query_typed::<&Position>().count(), 1);
+
+    server.despawn(archer);
+    let messages = send(&mut server, &mut client);
+    assert_eq!(messages, vec![ReplicationMessage::Despawn { entity: archer }]);
+    assert_eq!(client.query_typed::<&Health>().count(), 1);
+    assert_eq!(client.query_typed::<&Position>().count(), 0);
+}
+
+#[test]
+fn replication_errors() {
+    let mut server = world();
+    let mut client = World::new();
+    server.spawn().insert(Health(3));
+
+    let messages = server.drain_replication_messages();
+    assert!(client.apply_replication_message_checked(&messages[0]).is_err());
+
+    let update = ReplicationMessage::Update { entity: 7, changed: Default::default(), removed: vec![] };
+    assert!(server.apply_replication_message_checked(&update).is_err());
+    assert!(server.apply_replication_message_checked(&ReplicationMessage::Despawn { entity: 7 }).is_err());
+}
+
+#[test]
+fn cleared_worlds_forget_remote_entities() {
+    let mut server = world();
+    let mut client = world();
+    let knight = server.spawn().insert(Health(10)).current_entity();
+    send(&mut server, &mut client);
+
+    client.clear();
+    let rock = client.spawn().insert(Health(99)).current_entity();
+    assert_eq!(rock, knight);
+
+    server.get::<&mut Health>(knight).unwrap().0 = 4;
+    let messages = server.drain_replication_messages();
+    assert!(client.apply_replication_message_checked(&messages[0]).is_err());
+    assert_eq!(client.get::<&Health>(rock).unwrap().0, 99);
+}
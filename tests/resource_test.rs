@@ -35,6 +35,21 @@ fn delete_resource() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn init_resources() {
+    let mut world = init_world();
+
+    world.init_resource::<Frames>();
+    world.init_resource::<Area>();
+    assert_eq!(world.get_resource::<Frames>().unwrap().0, 0);
+    assert_eq!(world.get_resource::<Area>().unwrap().0, 144.0);
+
+    // existing resources are left alone
+    world.insert_resource(SizeResource(2.0));
+    world.init_resource::<Area>();
+    assert_eq!(world.get_resource::<Area>().unwrap().0, 144.0);
+}
+
 fn init_world() -> World {
     let mut world = World::new();
 
@@ -43,4 +58,61 @@ fn init_world() -> World {
 }
 
 #[derive(Debug, PartialEq)]
-struct SizeResource(f32);
\ No newline at end of file
+struct SizeResource(f32);
+impl Resource for SizeResource {}
+
+#[derive(Default)]
+struct Frames(u32);
+impl Resource for Frames {}
+
+struct Area(f32);
+impl Resource for Area {}
+
+impl FromWorld for Area {
+    fn from_world(world: &mut World) -> Self {
+        let size = world.get_resource::<SizeResource>().unwrap().0;
+        Area(size * size)
+    }
+}
+
+#[test]
+fn resource_scopes() -> eyre::Result<()> {
+    let mut world = init_world();
+    world.spawn().insert(SizeResource(1.0));
+
+    let total = world.resource_scope(|world, size: &mut SizeResource| {
+        // the resource is taken out of the World during the scope
+        assert!(world.get_resource::<SizeResource>().is_err());
+        world.insert_resource(Frames(3));
+
+        size.0 += world.get::<&SizeResource>(0).unwrap().0;
+        size.0
+    });
+    assert_eq!(total, 13.0);
+    assert_eq!(*world.get_resource::<SizeResource>()?, SizeResource(13.0));
+    assert_eq!(world.get_resource::<Frames>()?.0, 3);
+
+    // the scoped resource replaces the one inserted during the scope, which is reported
+    let result = world.resource_scope_checked(|world, size: &mut SizeResource| {
+        size.0 += 1.0;
+        world.insert_resource(SizeResource(0.0));
+    });
+    assert!(matches!(result, Err(Error::Resources(ResourcesError::ScopedResourceInsertedError(_)))));
+    assert_eq!(*world.get_resource::<SizeResource>()?, SizeResource(14.0));
+
+    // the resource is put back when the closure panics
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        world.resource_scope(|_, size: &mut SizeResource| {
+            size.0 = 0.0;
+            panic!("the scope failed");
+        })
+    }));
+    assert!(panicked.is_err());
+    assert_eq!(*world.get_resource::<SizeResource>()?, SizeResource(0.0));
+
+    let mut called = false;
+    assert!(world.resource_scope_checked(|_, _: &mut Area| called = true).is_err());
+    assert!(!called);
+
+    Ok(())
+}
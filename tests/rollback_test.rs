@@ -0,0 +1,91 @@
+use sceller::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Position(i32, i32);
+#[derive(Debug, Clone, PartialEq)]
+struct Velocity(i32, i32);
+struct Sprite;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Frame(u32);
+impl Resource for Frame {}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Input(i32);
+impl Resource for Input {}
+
+fn movement(mut frame: ResMut<Frame>, input: Res<Input>, query: FnQuery<(&mut Position, &mut Velocity)>) {
+    frame.0 += 1;
+    for (mut pos, mut vel) in query {
+        vel.0 += input.0;
+        pos.0 += vel.0;
+        pos.1 += vel.1;
+    }
+}
+
+fn init_world() -> World {
+    let mut world = World::new();
+    world.register_rollback_component::<Position>();
+    world.register_rollback_component::<Velocity>();
+    world.register_rollback_resource::<Frame>();
+    world.register_rollback_resource::<Input>();
+
+    world.spawn().insert(Position(0, 0)).insert(Velocity(1, 0)).insert(Sprite);
+    world.spawn().insert(Position(5, 5)).insert(Velocity(0, -1));
+    world.insert_resource(Frame(0));
+    world.insert_resource(Input(0));
+    world
+}
+
+#[test]
+fn rollback_resimulates_frames() -> Result<()> {
+    let mut world = init_world();
+    let confirmed = world.rollback_snapshot();
+    assert_eq!(confirmed.component_count(), 4);
+
+    // predicted frames without input
+    for _ in 0..3 {
+        world.run_system(movement);
+    }
+    world.despawn(1);
+
+    // the real input arrives late, so the frames are simulated again from the confirmed one
+    world.rollback(&confirmed.clone());
+    *world.get_resource_mut::<Input>()? = Input(1);
+    for _ in 0..3 {
+        world.run_system(movement);
+    }
+
+    assert_eq!(world.get_resource::<Frame>()?.0, 3);
+    assert_eq!(*world.get::<&Position>(0)?, Position(9, 0));
+    assert_eq!(*world.get::<&Position>(1)?, Position(11, 2));
+    // components that can't be cloned aren't part of the snapshot
+    assert!(!world.entity_has::<Sprite>(0));
+
+    // the snapshot isn't changed by the frames simulated after restoring it
+    world.rollback(&confirmed);
+    assert_eq!(*world.get::<&Velocity>(0)?, Velocity(1, 0));
+    assert_eq!(world.get_resource::<Input>()?.0, 0);
+
+    Ok(())
+}
+
+#[test]
+fn rollback_removes_newer_entities_and_resources() -> Result<()> {
+    let mut world = World::new();
+    world.register_rollback_component::<Position>();
+    world.register_rollback_resource::<Frame>();
+    world.spawn().insert(Position(1, 1));
+
+    let snapshot = world.rollback_snapshot();
+    world.spawn().insert(Position(2, 2));
+    world.insert_resource(Frame(10));
+
+    world.rollback(&snapshot);
+    assert_eq!(snapshot.entities().collect::<Vec<Entity>>(), vec![0]);
+    assert!(!world.contains(1));
+    assert!(world.get_resource::<Frame>().is_err());
+    assert_eq!(world.query().auto::<Position>().len(), 1);
+
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use sceller::prelude::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Position(i32, i32);
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Velocity(i32, i32);
+
+struct Log(Vec<&'static str>);
+impl Resource for Log {}
+
+#[test]
+fn stages_run_in_order() {
+    let mut world = World::new();
+    world.insert_resource(Log(Vec::new()));
+
+    world.schedule_mut().add_stage_after(Stage::Update, Stage::Custom("Physics"));
+
+    world.add_system_to_stage(Stage::PostUpdate, post_update);
+    world.add_system_to_stage(Stage::Custom("Physics"), physics);
+    world.add_system(update);
+    world.add_system_to_stage(Stage::PreUpdate, pre_update);
+
+    world.run_schedule();
+    world.run_schedule();
+
+    let log = world.get_resource::<Log>().unwrap();
+    assert_eq!(log.0, vec![
+        "pre_update", "update", "physics", "post_update",
+        "pre_update", "update", "physics", "post_update",
+    ]);
+}
+
+fn pre_update(mut log: ResMut<Log>) { log.0.push("pre_update"); }
+fn update(mut log: ResMut<Log>) { log.0.push("update"); }
+fn physics(mut log: ResMut<Log>) { log.0.push("physics"); }
+fn post_update(mut log: ResMut<Log>) { log.0.push("post_update"); }
+
+#[test]
+fn commands_applied_between_stages() -> Result<()> {
+    let mut world = World::new();
+    world.register_component::<Velocity>();
+    world.spawn().insert_checked(Position(0, 0))?;
+
+    world.add_system_to_stage(Stage::PreUpdate, add_velocities);
+    world.add_system(move_positions);
+
+    world.run_schedule();
+
+    let query = world.query();
+    let position = query.auto::<Position>().into_iter().next().unwrap();
+    assert_eq!(*position, Position(1, 2));
+
+    Ok(())
+}
+
+fn add_velocities(mut commands: Commands, positions: FnQuery<&Position>) {
+    assert_eq!(positions.iter().count(), 1);
+    commands.insert(0, Velocity(1, 2));
+}
+
+fn move_positions(query: FnQuery<(&mut Position, &Velocity)>) {
+    for (mut pos, vel) in query.iter() {
+        pos.0 += vel.0;
+        pos.1 += vel.1;
+    }
+}
+
+#[test]
+fn missing_stage_is_an_error() {
+    let mut schedule = Schedule::empty();
+
+    assert!(schedule.add_system_to_stage_checked(Stage::Update, update).is_err());
+    assert!(schedule.add_stage_checked(Stage::Update).is_ok());
+    assert!(schedule.add_stage_checked(Stage::Update).is_err());
+    assert!(schedule.add_system_to_stage_checked(Stage::Update, update).is_ok());
+}
+
+#[test]
+fn restart_after_clear() -> Result<()> {
+    let mut world = World::new();
+    world.register_component::<Velocity>();
+    world.spawn().insert_checked(Position(0, 0))?.insert_checked(Velocity(1, 1))?;
+    world.add_system(move_positions);
+    world.run_schedule();
+
+    // restarting the level only keeps the systems
+    world.clear();
+    world.spawn().insert_checked(Position(5, 5))?.insert_checked(Velocity(2, 0))?;
+    world.run_schedule();
+
+    let positions = world.query_typed::<&Position>().map(|pos| *pos).collect::<Vec<Position>>();
+    assert_eq!(positions, vec![Position(7, 5)]);
+
+    world.clear_entities();
+    assert_eq!(world.query_typed::<&Position>().count(), 0);
+    world.run_schedule();
+
+    Ok(())
+}
+
+fn fixed_update(mut log: ResMut<Log>, mut commands: Commands) {
+    log.0.push("fixed_update");
+    commands.spawn().insert(Position(0, 0));
+}
+
+#[test]
+fn fixed_update_runs_once_per_step() {
+    let mut world = World::new();
+    world.insert_resource(Log(Vec::new()));
+    world.add_system(update);
+    world.add_system_to_stage(Stage::FixedUpdate, fixed_update);
+
+    // the first frame has no time to spend
+    world.run_schedule();
+    {
+        let mut time = world.get_resource_mut::<Time>().unwrap();
+        assert_eq!(time.frame_count(), 1);
+        time.set_fixed_step(std::time::Duration::from_secs(1));
+        time.advance(std::time::Duration::from_millis(2400));
+    }
+    world.run_schedule();
+    world.get_resource_mut::<Time>().unwrap().advance(std::time::Duration::from_millis(700));
+    world.run_schedule();
+
+    assert_eq!(world.get_resource::<Log>().unwrap().0, vec![
+        "update", "fixed_update", "fixed_update", "update", "fixed_update", "update",
+    ]);
+    // the commands of every step are applied
+    assert_eq!(world.query_typed::<&Position>().count(), 3);
+
+    let time = world.get_resource::<Time>().unwrap();
+    assert_eq!(time.frame_count(), 5);
+    assert!(time.elapsed() >= std::time::Duration::from_millis(3100));
+    assert!(time.accumulated() < time.fixed_step());
+}
+
+fn startup(mut log: ResMut<Log>, mut commands: Commands) {
+    log.0.push("startup");
+    commands.spawn().insert(Position(1, 1)).insert(Velocity(1, 0));
+}
+
+#[test]
+fn startup_systems_run_once() -> Result<()> {
+    let mut world = World::new();
+    world.insert_resource(Log(Vec::new()));
+    world.add_system(update);
+    world.add_startup_system(startup);
+    world.add_system_to_stage(Stage::PreUpdate, move_positions);
+
+    world.run_schedule();
+    world.run_schedule();
+    assert_eq!(world.get_resource::<Log>()?.0, vec!["startup", "update", "update"]);
+    // the entities spawned at startup exist in the first frame
+    assert_eq!(*world.get::<&Position>(0)?, Position(3, 1));
+
+    // clearing the world starts over
+    world.clear();
+    world.insert_resource(Log(Vec::new()));
+    world.run_schedule();
+    assert_eq!(world.get_resource::<Log>()?.0, vec!["startup", "update"]);
+    assert_eq!(world.schedule_mut().startup_stage().systems().len(), 1);
+
+    Ok(())
+}
+
+fn input(mut log: ResMut<Log>) { log.0.push("input"); }
+fn collision(mut log: ResMut<Log>) { log.0.push("collision"); }
+
+#[test]
+fn systems_are_sorted_by_their_ordering() -> Result<()> {
+    let mut world = World::new();
+    world.insert_resource(Log(Vec::new()));
+
+    world.add_system(collision.after("physics").run_if(resource_exists::<Log>()));
+    world.add_system(physics.in_set("physics").after(input));
+    world.add_system(update);
+    world.add_system(input.before(update));
+    // labels of systems in other stages are ignored
+    world.add_system_to_stage(Stage::PostUpdate, post_update.before(input));
+
+    world.run_schedule();
+    assert_eq!(world.get_resource::<Log>()?.0, vec!["input", "physics", "collision", "update", "post_update"]);
+
+    let names = world.schedule_mut().stages()[2].systems().iter()
+        .map(|system| system.name().rsplit("::").next().unwrap())
+        .collect::<Vec<&str>>();
+    assert_eq!(names, vec!["input", "physics", "collision", "update"]);
+
+    // a cycle through a set is reported with the systems forming it
+    let error = world.schedule_mut()
+        .add_system_to_stage_checked(Stage::Update, pre_update.after(collision).before(input))
+        .err().unwrap();
+    match error {
+        Error::Schedule(ScheduleError::OrderingCycleError(cycle)) => {
+            assert_eq!(cycle.len(), 4);
+            assert!(!cycle.iter().any(|name| name.ends_with("::update")));
+        },
+        _ => panic!("expected a cycle"),
+    }
+    assert_eq!(world.schedule_mut().stages()[2].systems().len(), 4);
+
+    Ok(())
+}
+
+fn slow(mut log: ResMut<Log>) {
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    log.0.push("slow");
+}
+
+#[test]
+fn diagnostics_time_every_system() -> Result<()> {
+    let mut world = World::new();
+    world.insert_resource(Log(Vec::new()));
+    world.add_system(input);
+    world.add_system(slow);
+    world.add_system_to_stage(Stage::PostUpdate, collision);
+
+    // nothing is recorded until the diagnostics are enabled
+    world.run_schedule();
+    assert!(world.get_resource::<Diagnostics>().is_err());
+
+    world.enable_diagnostics();
+    world.run_schedule();
+    world.run_schedule();
+
+    let diagnostics = world.get_resource::<Diagnostics>()?;
+    assert_eq!(diagnostics.frame_count(), 2);
+    let frame = diagnostics.frame().iter()
+        .map(|timing| timing.system.rsplit("::").next().unwrap())
+        .collect::<Vec<&str>>();
+    assert_eq!(frame, vec!["input", "slow", "collision"]);
+
+    assert!(diagnostics.slowest().unwrap().system.ends_with("::slow"));
+    let over = diagnostics.over_budget(std::time::Duration::from_millis(4));
+    assert_eq!(over.len(), 1);
+    assert!(diagnostics.frame_time() >= std::time::Duration::from_millis(5));
+
+    let stats = diagnostics.stats(slow).unwrap();
+    assert_eq!(stats.runs, 2);
+    assert!(stats.average() >= std::time::Duration::from_millis(5));
+    assert!(diagnostics.stats(update).is_none());
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "scripting")]
+
+use std::{cell::RefCell, rc::Rc};
+
+use sceller::prelude::*;
+use sceller::scripting::{ScriptError, ScriptWorld};
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Health(u32);
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Team {
+    Red,
+    Blue,
+}
+#[derive(Debug)]
+struct Hidden;
+
+fn init_world() -> World {
+    let mut world = World::new();
+    world.register_serde_component::<Health>();
+    world.register_serde_component::<Team>();
+    world
+}
+
+#[test]
+fn scripts_change_components_by_name() {
+    let shared = Rc::new(RefCell::new(init_world()));
+    shared.borrow_mut().on_add::<Team>(|world, entity| {
+        world.insert_component_into_entity(Health(100), entity);
+    });
+    let script = ScriptWorld::from_shared(shared.clone());
+
+    let red = script.spawn(&json!({ "Team": "Red" })).unwrap();
+    let blue = script.spawn(&json!({ "Team": "Blue", "Health": 5 })).unwrap();
+    shared.borrow_mut().spawn().insert(Hidden);
+
+    // the hook ran when the team was inserted
+    assert_eq!(script.get(red, "Health").unwrap(), json!(100));
+    assert_eq!(script.get(blue, "Team").unwrap(), json!("Blue"));
+    assert_eq!(script.query(&["Team"]).unwrap(), vec![red, blue]);
+
+    script.set(red, std::any::type_name::<Team>(), json!("Blue")).unwrap();
+    assert_eq!(*shared.borrow().get::<&Team>(red).unwrap(), Team::Blue);
+
+    script.remove(blue, "Health").unwrap();
+    assert!(!script.has(blue, "Health").unwrap());
+    assert_eq!(script.query(&["Team", "Health"]).unwrap(), vec![red]);
+
+    script.despawn(red).unwrap();
+    assert!(!shared.borrow().contains(red));
+
+    let mut names = script.component_names().unwrap();
+    names.sort();
+    assert_eq!(names, vec![std::any::type_name::<Health>(), std::any::type_name::<Team>()]);
+}
+
+#[test]
+fn script_errors() {
+    let mut world = init_world();
+    world.register_component::<Hidden>();
+    let script = ScriptWorld::new(world);
+    let entity = script.spawn(&json!({ "Health": 1 })).unwrap();
+
+    assert!(matches!(script.get(entity, "Mana"), Err(Error::Script(ScriptError::UnknownComponentError(_)))));
+    assert!(matches!(script.set(entity, "Hidden", json!(null)), Err(Error::Script(ScriptError::NotReflectableError(_)))));
+    assert!(matches!(script.spawn(&json!([1, 2])), Err(Error::Script(ScriptError::NotAnObjectError))));
+    assert!(script.get(entity, "Team").is_err());
+    assert!(script.set(entity + 1, "Health", json!(3)).is_err());
+
+    // nothing is spawned when a component can't be deserialized
+    assert!(script.spawn(&json!({ "Health": 2, "Team": "Green" })).is_err());
+    assert_eq!(script.query(&["Health"]).unwrap(), vec![entity]);
+
+    let borrowed = script.world_mut();
+    assert!(matches!(script.get(entity, "Health"), Err(Error::Script(ScriptError::WorldBorrowedError))));
+    drop(borrowed);
+    assert_eq!(script.get(entity, "Health").unwrap(), json!(1));
+}
@@ -0,0 +1,196 @@
+#![cfg(feature = "serde")]
+
+use sceller::prelude::*;
+use sceller::scene::{EntityMap, MapEntities, Scene};
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+struct Position(i32, i32);
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+struct Health(u16);
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+enum Team {
+    Red,
+    Blue { captain: bool },
+}
+#[derive(Debug)]
+struct NotSaved;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct PlayerResource(String);
+impl Resource for PlayerResource {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+struct Target(Entity);
+
+impl MapEntities for Target {
+    fn map_entities(&mut self, map: &EntityMap) {
+        self.0 = map.map(self.0);
+    }
+}
+
+#[test]
+fn save_and_load_world() -> Result<()> {
+    let world = init_world()?;
+    let save = world.save_to_ron()?;
+
+    let mut loaded = World::new();
+    register(&mut loaded);
+    loaded.load_from_ron(&save)?;
+
+    let healths = loaded.query_typed::<(&Health, &Team)>()
+        .map(|(hp, team)| (*hp, *team))
+        .collect::<Vec<_>>();
+    assert_eq!(healths.len(), 2);
+    assert!(healths.contains(&(Health(15), Team::Red)));
+    assert!(healths.contains(&(Health(12), Team::Blue { captain: true })));
+
+    assert_eq!(loaded.query_typed::<&Position>().count(), 3);
+    assert_eq!(*loaded.get_resource::<PlayerResource>()?, PlayerResource("Loafoflead".to_owned()));
+
+    // the same snapshot is produced from the loaded world, so indexes were kept
+    assert_eq!(loaded.snapshot()?, world.snapshot()?);
+
+    Ok(())
+}
+
+#[test]
+fn unregistered_types_are_errors() -> Result<()> {
+    let world = init_world()?;
+    let save = world.save_to_ron()?;
+
+    let mut loaded = World::new();
+    loaded.register_serde_component::<Position>();
+
+    assert!(loaded.load_from_ron(&save).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn spawn_scenes_with_references() -> Result<()> {
+    let scene = Scene::from_ron(r#"(
+        entities: [
+            (id: 10, components: { "Position": "(1, 2)", "Target": "(11)" }),
+            (id: 11, components: { "Position": "(3, 4)", "Target": "(10)", "Health": "(7)" }),
+            (id: 12, components: {}),
+            (id: 13, components: { "Target": "(0)" }),
+        ],
+    )"#)?;
+
+    let mut world = init_world()?;
+    world.register_serde_component_with_entities::<Target>();
+
+    let first = world.spawn_scene(&scene)?;
+    let second = world.spawn_scene(&scene)?;
+    assert_eq!(first.len(), 3);
+    assert_eq!(second.get(12), None);
+
+    for map in [&first, &second] {
+        let (a, b) = (map.get(10).unwrap(), map.get(11).unwrap());
+        assert_eq!(*world.get::<&Target>(a)?, Target(b));
+        assert_eq!(*world.get::<(&Target, &Health)>(b)?.0, Target(a));
+        // entity 0 isn't part of the scene, so it keeps pointing to the world's entity 0
+        assert_eq!(*world.get::<&Target>(map.get(13).unwrap())?, Target(0));
+    }
+    assert_eq!(world.query_typed::<&Position>().count(), 7);
+
+    // a scene written back out can be read again
+    assert_eq!(Scene::from_ron(&scene.to_ron()?)?, scene);
+
+    Ok(())
+}
+
+#[test]
+fn scenes_with_unregistered_types_spawn_nothing() -> Result<()> {
+    let scene = Scene::from(init_world()?.snapshot()?);
+
+    let mut world = World::new();
+    world.register_serde_component::<Position>();
+    world.register_serde_component::<Health>();
+
+    assert!(matches!(
+        world.spawn_scene(&scene),
+        Err(Error::Serialization(sceller::serialization::SerializationError::UnregisteredTypeError(_)))
+    ));
+
+    // nothing was spawned by the failed attempt, so the first slots are still free
+    world.register_serde_component::<Team>();
+    let spawned = world.spawn_scene(&scene)?;
+    assert_eq!((spawned.get(0), spawned.get(2)), (Some(0), Some(2)));
+    assert_eq!(world.query_typed::<(&Position, &Health, &Team)>().count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn diff_against_snapshot() -> Result<()> {
+    let mut world = init_world()?;
+    let before = world.snapshot()?;
+    assert!(world.diff(&before)?.is_empty());
+
+    world.get::<&mut Position>(0)?.0 = 1;
+    world.insert_component_into_entity_checked(Health(1), 1)?;
+    world.delete_component_from_ent_checked::<Team>(2)?;
+    world.spawn().insert_checked(NotSaved)?;
+    world.spawn().insert_checked(Team::Red)?;
+
+    let diff = world.diff(&before)?;
+    assert_eq!(diff.added, vec![4]);
+    assert!(diff.removed.is_empty());
+
+    let changed = diff.changed.iter()
+        .map(|change| (change.entity, change.before.as_deref(), change.after.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(changed, vec![
+        (0, Some("(0,0)"), Some("(1,0)")),
+        (1, None, Some("(1)")),
+        (2, Some("Blue(captain:true)"), None),
+    ]);
+    assert!(diff.changed[2].component.ends_with("Team"));
+
+    world.despawn(0);
+    assert_eq!(before.diff(&world.snapshot()?).removed, vec![0]);
+
+    Ok(())
+}
+
+fn register(world: &mut World) {
+    world.register_serde_component::<Position>();
+    world.register_serde_component::<Health>();
+    world.register_serde_component::<Team>();
+    world.register_serde_resource::<PlayerResource>();
+}
+
+fn init_world() -> Result<World> {
+    let mut world = World::new();
+    register(&mut world);
+
+    world.spawn().insert_checked(Position(0, 0))?.insert_checked(Health(15))?.insert_checked(Team::Red)?;
+    world.spawn().insert_checked(Position(12, 10))?.insert_checked(NotSaved)?;
+    world.spawn().insert_checked(Position(6, 6))?.insert_checked(Health(12))?.insert_checked(Team::Blue { captain: true })?;
+
+    world.insert_resource(PlayerResource("Loafoflead".to_owned()));
+
+    Ok(world)
+}
+
+#[test]
+fn type_registry_reflects_registered_types() -> Result<()> {
+    let world = init_world()?;
+    let save = world.save_to_ron()?;
+    let mut loaded = World::new();
+    register(&mut loaded);
+    loaded.load_from_ron(&save)?;
+
+    // the functions registered before loading survive the entities being replaced
+    let team = loaded.type_registry().get_by_name("Team").unwrap();
+    let value = (team.serialize.unwrap())(&*loaded.get::<&Team>(2)?)?;
+    let value = (team.deserialize.unwrap())(&value)?;
+    assert_eq!(value.downcast_ref::<Team>(), Some(&Team::Blue { captain: true }));
+
+    let not_saved = world.type_registry().get_by_name("NotSaved").unwrap();
+    assert!(not_saved.serialize.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use sceller::prelude::*;
+
+#[derive(Debug, PartialEq)]
+struct Sprite(&'static str);
+#[derive(Debug, PartialEq)]
+struct Position(i32);
+struct Wall;
+
+#[test]
+fn queries_find_shared_components() -> eyre::Result<()> {
+    let mut world = World::new();
+    let grass = Shared::new(Sprite("grass"));
+    for x in 0..4 {
+        let tile = world.spawn().insert_checked(Position(x))?.current_entity();
+        world.insert_shared_checked(tile, &grass)?;
+    }
+    world.spawn().insert_checked(Position(10))?.insert_checked(Sprite("player"))?;
+    assert_eq!(grass.holders(), 5);
+
+    let sprites = world.query_typed::<(&Position, &Sprite)>().map(|(position, sprite)| (position.0, sprite.0)).collect::<Vec<_>>();
+    assert_eq!(sprites, vec![(0, "grass"), (1, "grass"), (2, "grass"), (3, "grass"), (10, "player")]);
+
+    // mutating the sprite of one tile changes it for every tile
+    world.get::<&mut Sprite>(2)?.0 = "snow";
+    assert_eq!(grass.borrow().0, "snow");
+    assert_eq!(world.query_typed::<&Sprite>().filter(|sprite| sprite.0 == "snow").count(), 4);
+    world.run_system(|query: FnQuery<(&Position, &Sprite)>| {
+        for (position, sprite) in query {
+            assert_eq!(sprite.0 == "snow", position.0 < 10);
+        }
+    });
+
+    // an owned component replaces the shared one of that entity only
+    world.insert_component_into_entity_checked(Sprite("water"), 0)?;
+    assert_eq!(grass.holders(), 4);
+    assert_eq!(world.get::<&Sprite>(1)?.0, "snow");
+
+    Ok(())
+}
+
+#[test]
+fn removing_shared_components() -> eyre::Result<()> {
+    let mut world = World::new();
+    let grass = Shared::new(Sprite("grass"));
+    for _ in 0..2 {
+        let tile = world.spawn().insert_checked(Position(0))?.current_entity();
+        world.insert_shared_checked(tile, &grass)?;
+    }
+
+    let error = world.take_component::<Sprite>(0).unwrap_err();
+    assert!(matches!(error, Error::Component(ComponentError::SharedComponentError { entity: 0, .. })));
+    assert!(world.entity_has::<Sprite>(0));
+
+    let mut despawned = world.despawn(0);
+    assert!(despawned.take_shared::<Sprite>().unwrap().ptr_eq(&grass));
+    assert_eq!(grass.holders(), 2);
+
+    // the last holder gets the value back
+    drop(grass);
+    assert_eq!(world.take_component::<Sprite>(1)?, Sprite("grass"));
+
+    let wall = Shared::new(Wall);
+    let error = world.insert_shared_checked(1, &wall).unwrap_err();
+    assert!(matches!(error, Error::Component(ComponentError::SharedTagError(_))));
+
+    Ok(())
+}
+
+#[test]
+fn shared_components_stay_shared_when_moved() -> eyre::Result<()> {
+    let mut world = World::new();
+    let mut other = World::new();
+    let grass = Shared::new(Sprite("grass"));
+    let tile = world.spawn().insert_checked(Position(0))?.current_entity();
+    world.insert_shared_checked(tile, &grass)?;
+
+    let moved = world.transfer_entity_checked(&mut other, tile)?;
+    other.get::<&mut Sprite>(moved)?.0 = "sand";
+    assert_eq!(grass.borrow().0, "sand");
+    assert_eq!(grass.holders(), 2);
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use sceller::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum GameState {
+    Menu,
+    Playing,
+    Paused,
+}
+
+struct Button;
+struct Log(Vec<&'static str>);
+impl Resource for Log {}
+
+#[test]
+fn transitions_apply_next_frame() {
+    let mut world = World::new();
+    world.insert_resource(Log(Vec::new()));
+    world.add_state(GameState::Playing);
+
+    world.add_state_system(OnUpdate(GameState::Playing), pause);
+    world.add_state_system(OnUpdate(GameState::Playing), log_playing);
+    world.add_state_system(OnEnter(GameState::Paused), |mut log: ResMut<Log>| log.0.push("paused"));
+
+    world.run_schedule();
+    // the pause is queued, but every system of this frame still saw 'Playing'
+    assert_eq!(*world.get_resource::<States<GameState>>().unwrap().current(), GameState::Playing);
+    assert_eq!(world.get_resource::<States<GameState>>().unwrap().next(), Some(&GameState::Paused));
+
+    world.run_schedule();
+    assert_eq!(*world.get_resource::<States<GameState>>().unwrap().current(), GameState::Paused);
+    assert_eq!(world.get_resource::<Log>().unwrap().0, vec!["playing", "paused"]);
+}
+
+fn pause(mut state: ResMut<States<GameState>>) {
+    state.set(GameState::Paused);
+}
+
+fn log_playing(mut log: ResMut<Log>) {
+    log.0.push("playing");
+}
+
+#[test]
+fn enter_and_exit_apply_commands() {
+    let mut world = World::new();
+    world.add_state(GameState::Menu);
+
+    world.add_state_system(OnEnter(GameState::Menu), |mut commands: Commands| {
+        commands.spawn().insert(Button);
+    });
+    world.add_state_system(OnExit(GameState::Menu), |mut commands: Commands, buttons: FnQuery<&Button>| {
+        for (entity, _) in buttons.iter_with_ids() {
+            commands.despawn(entity);
+        }
+    });
+
+    world.run_schedule();
+    assert_eq!(world.query_typed::<&Button>().count(), 1);
+
+    world.get_resource_mut::<States<GameState>>().unwrap().set(GameState::Playing);
+    world.run_schedule();
+    assert_eq!(world.query_typed::<&Button>().count(), 0);
+}
+
+#[test]
+fn missing_state_is_an_error() {
+    let mut world = World::new();
+
+    assert!(world.add_state_system_checked(OnEnter(GameState::Menu), log_playing).is_err());
+    assert!(world.add_state_system_checked(OnUpdate(GameState::Menu), log_playing).is_err());
+    assert!(world.insert_state_resource_checked(GameState::Menu, Log(Vec::new())).is_err());
+    assert!(world.get_resource::<Log>().is_err());
+}
+
+struct PathCache(Vec<u32>);
+impl Resource for PathCache {}
+
+#[test]
+fn state_resources_are_removed_on_exit() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.insert_resource(Log(Vec::new()));
+    world.add_state(GameState::Playing);
+    world.insert_state_resource(GameState::Playing, PathCache(vec![1, 2]));
+
+    // the systems exiting the state can still read it's resources
+    world.add_state_system(OnExit(GameState::Playing), |cache: Res<PathCache>, mut log: ResMut<Log>| {
+        if cache.0.len() == 2 {
+            log.0.push("exit");
+        }
+    });
+
+    world.run_schedule();
+    assert!(world.get_resource::<PathCache>().is_ok());
+
+    world.get_resource_mut::<States<GameState>>()?.set(GameState::Paused);
+    world.run_schedule();
+    assert!(world.get_resource::<PathCache>().is_err());
+    assert_eq!(world.get_resource::<Log>()?.0, vec!["exit"]);
+
+    Ok(())
+}
+
+#[test]
+fn state_resources_follow_the_last_state_they_were_inserted_for() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.add_state(GameState::Menu);
+    world.insert_state_resource(GameState::Menu, PathCache(Vec::new()));
+    world.insert_state_resource(GameState::Playing, PathCache(vec![3]));
+
+    // the systems entering a state can scope resources to it through commands
+    world.add_state_system(OnEnter(GameState::Paused), |mut commands: Commands| {
+        commands.add(|world| {
+            world.insert_state_resource_checked(GameState::Paused, Log(vec!["paused"]))?;
+            Ok(())
+        });
+    });
+
+    world.get_resource_mut::<States<GameState>>()?.set(GameState::Playing);
+    world.run_schedule();
+    assert_eq!(world.get_resource::<PathCache>()?.0, vec![3]);
+
+    world.get_resource_mut::<States<GameState>>()?.set(GameState::Paused);
+    world.run_schedule();
+    assert!(world.get_resource::<PathCache>().is_err());
+    assert_eq!(world.get_resource::<Log>()?.0, vec!["paused"]);
+
+    world.get_resource_mut::<States<GameState>>()?.set(GameState::Menu);
+    world.run_schedule();
+    assert!(world.get_resource::<Log>().is_err());
+
+    Ok(())
+}
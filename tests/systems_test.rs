@@ -8,10 +8,11 @@ struct Health(u16);
 struct Enemy;
 
 struct PlayerResource(String);
+impl Resource for PlayerResource {}
 
 #[test]
 fn test_systems() -> Result<()> {
-	let world = init_world()?;
+	let mut world = init_world()?;
 	
 	world.run_system(test);
 	world.run_system(test2);
@@ -21,29 +22,142 @@ fn test_systems() -> Result<()> {
 }
 
 fn test(res: Res<PlayerResource>, qry: FnQuery<(&Health, &Position)>) {
-	assert_eq!(res.get().0, String::from("Loafoflead"));
+	assert_eq!(res.0, String::from("Loafoflead"));
 	
 	let mut iter = qry.into_iter();
 
 	let thing = iter.next().unwrap();
-	assert_eq!(thing.0.0, 12);
-	assert_eq!(*thing.1, Position(6, 6));
+	assert_eq!(thing.0.0, 15);
+	assert_eq!(*thing.1, Position(0, 0));
 
 	let thing = iter.next().unwrap();
 	assert_eq!(thing.0.0, 6);
 	assert_eq!(*thing.1, Position(12, 10));
 
 	let thing = iter.next().unwrap();
-	assert_eq!(thing.0.0, 15);
-	assert_eq!(*thing.1, Position(0, 0));
+	assert_eq!(thing.0.0, 12);
+	assert_eq!(*thing.1, Position(6, 6));
 }
 
-fn test2(_qr: FnQuery<&mut Health>, resmut: ResMut<PlayerResource>) {
-	resmut.get().0 = "Hi".to_owned();
+fn test2(_qr: FnQuery<&mut Health>, mut resmut: ResMut<PlayerResource>) {
+	resmut.0 = "Hi".to_owned();
 }
 
 fn assure_test2(res: Res<PlayerResource>) {
-	assert_eq!(res.get().0, "Hi".to_owned());
+	assert_eq!(res.0, "Hi".to_owned());
+}
+
+struct Counter(u32);
+impl Resource for Counter {}
+
+#[test]
+fn deref_resources() -> Result<()> {
+	let mut world = init_world()?;
+	world.insert_resource(Counter(1));
+
+	world.run_system(|mut counter: ResMut<Counter>, player: Res<PlayerResource>| {
+		let count = &mut counter.0;
+		*count += player.0.len() as u32;
+		*count *= 2;
+	});
+
+	assert_eq!(world.get_resource::<Counter>().unwrap().0, 22);
+
+	Ok(())
+}
+
+#[test]
+fn many_parameter_systems() -> Result<()> {
+	let mut world = init_world()?;
+	world.insert_resource(Counter(0));
+
+	world.run_system(many_params);
+	world.apply_commands()?;
+
+	assert_eq!(world.get_resource::<Counter>().unwrap().0, 3);
+	assert_eq!(world.query().auto::<Enemy>().len(), 0);
+
+	Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn many_params(
+	mut commands: Commands,
+	mut counter: ResMut<Counter>,
+	player: Res<PlayerResource>,
+	healths: FnQuery<&Health>,
+	positions: FnQuery<&Position>,
+	enemies: FnQuery<&Enemy>,
+	both: FnQuery<(&Health, &Position)>,
+	_health_again: FnQuery<&Health>,
+	_player_again: Res<PlayerResource>,
+	_positions_again: FnQuery<&Position>,
+	_enemy_healths: FnQuery<(&Health, With<Enemy>)>,
+	_both_again: FnQuery<(&Health, &Position)>,
+) {
+	assert_eq!(player.0, String::from("Loafoflead"));
+	assert_eq!(healths.iter().len(), positions.iter().len());
+	assert_eq!(both.iter().len(), 3);
+
+	for (entity, _) in enemies.iter_with_ids() {
+		commands.despawn(entity);
+	}
+	counter.0 += healths.iter().len() as u32;
+}
+
+struct Missing;
+impl Resource for Missing {}
+
+#[test]
+fn checked_systems() -> Result<()> {
+	let mut world = init_world()?;
+
+	let err = world.run_system_checked(|_res: Res<Counter>| -> () { panic!("should not run") }).unwrap_err();
+	assert!(err.to_string().contains("Counter"));
+
+	let err = world.run_system_checked(|_query: FnQuery<(&Health, &Missing)>| -> () { panic!("should not run") }).unwrap_err();
+	assert!(err.to_string().contains("Missing"));
+
+	world.run_system_checked(|counter: Option<ResMut<Counter>>, res: Res<PlayerResource>| {
+		assert!(counter.is_none());
+		assert_eq!(res.0, "Loafoflead");
+	})?;
+
+	world.insert_resource(Counter(0));
+	world.run_system_checked(|counter: Option<ResMut<Counter>>| {
+		counter.unwrap().0 += 1;
+	})?;
+	assert_eq!(world.get_resource::<Counter>().unwrap().0, 1);
+
+	let system = BoxedSystem::new(|_res: Res<Missing>| {});
+	assert!(world.run_system_checked(system).is_err());
+
+	Ok(())
+}
+
+#[test]
+fn conflicting_systems() -> Result<()> {
+	let mut world = init_world()?;
+
+	let err = world.run_system_checked(|_a: FnQuery<&mut Health>, _b: FnQuery<(&Position, &Health)>| -> () { panic!("should not run") }).unwrap_err();
+	let message = err.to_string();
+	assert!(message.contains("Health") && message.contains("#1") && message.contains("#2"));
+
+	let err = world.run_system_checked(|_a: FnQuery<(&mut Position, &Position)>| -> () { panic!("should not run") }).unwrap_err();
+	assert!(err.to_string().contains("Position"));
+
+	let err = world.run_system_checked(|_a: Res<PlayerResource>, _b: Option<ResMut<PlayerResource>>| -> () { panic!("should not run") }).unwrap_err();
+	assert!(err.to_string().contains("PlayerResource"));
+
+	// shared borrows, tag filters and separate kinds of values don't conflict
+	world.run_system_checked(|_a: FnQuery<(&Health, &Position)>, _b: FnQuery<(&mut Enemy, &Health)>, _c: CachedQuery<With<Enemy>>| {})?;
+	world.run_system_checked(|_a: FnQuery<&mut Position>, _b: FnQuery<(&mut Health, With<Position>)>, _c: Res<PlayerResource>| {})?;
+
+	let system = BoxedSystem::new(|_a: FnQuery<&mut Health>, _b: FnQuery<&Health>| {});
+	assert!(system.check_access().is_err());
+	assert_eq!(system.access().len(), 2);
+
+	Ok(())
 }
 
 fn init_world() -> Result<World> {
@@ -56,4 +170,125 @@ fn init_world() -> Result<World> {
     world.insert_resource(PlayerResource("Loafoflead".to_owned()));
 
     Ok(world)
-}
\ No newline at end of file
+}
+fn lowest_health(healths: FnQuery<&Health>) -> Option<u16> {
+	healths.iter().map(|health| health.0).min()
+}
+
+fn double(In(health): In<Option<u16>>) -> Result<u16> {
+	health.map(|health| health * 2).ok_or_else(|| ResourcesError::NonexistentResourceError.into())
+}
+
+fn heal_all(In(amount): In<Result<u16>>, healths: FnQuery<&mut Health>, mut log: ResMut<PlayerResource>) {
+	match amount {
+		Ok(amount) => healths.iter().for_each(|mut health| health.0 += amount),
+		Err(error) => log.0 = error.to_string(),
+	}
+}
+
+#[test]
+fn piped_systems() -> Result<()> {
+	let mut world = init_world()?;
+	let healths = |world: &World| world.query_typed::<&Health>().map(|health| health.0).collect::<Vec<u16>>();
+	let before = healths(&world);
+	let lowest = *before.iter().min().unwrap();
+
+	// the first system borrows Health immutably and the last one mutably, which is fine since they run one after the other
+	let piped = lowest_health.pipe(double).pipe(heal_all);
+	assert!(piped.check_access().is_ok());
+	world.run_system_checked(piped)?;
+	assert_eq!(healths(&world), before.iter().map(|health| health + lowest * 2).collect::<Vec<u16>>());
+
+	world.clear_entities();
+	world.register_component::<Health>();
+	world.run_system(lowest_health.pipe(double).pipe(heal_all));
+	assert!(world.get_resource::<PlayerResource>()?.0.contains("resource"));
+
+	// the conflicts inside each system are still found
+	fn conflicting(In(_): In<Option<u16>>, _: FnQuery<&mut Health>, _: FnQuery<&Health>) {}
+	assert!(lowest_health.pipe(conflicting).check_access().is_err());
+
+	Ok(())
+}
+
+fn damage_enemies(enemies: FnQuery<(&mut Health, With<Enemy>)>) -> eyre::Result<()> {
+	for (mut health, _) in enemies {
+		health.0 = health.0.checked_sub(10).ok_or_else(|| eyre::eyre!("enemy died"))?;
+	}
+	Ok(())
+}
+
+fn take_resource(player: Res<PlayerResource>, mut commands: Commands) -> Result<()> {
+	let name = player.0.clone();
+	commands.add(move |world| {
+		world.delete_resource::<PlayerResource>()?;
+		world.insert_resource(PlayerResource(name + "!"));
+		Ok(())
+	});
+	Err(ResourcesError::NonexistentResourceError.into())
+}
+
+#[test]
+fn fallible_systems() -> Result<()> {
+	let mut world = init_world()?;
+	world.init_resource::<SystemErrors>();
+	world.set_error_handler(collect_errors);
+
+	world.run_system(damage_enemies);
+	world.run_system(damage_enemies);
+	// the commands of a failed system are still applied
+	world.run_system(take_resource);
+	assert_eq!(world.get_resource::<PlayerResource>()?.0, "Loafoflead!");
+
+	let errors = world.system_errors().unwrap();
+	assert_eq!(errors.0.len(), 2);
+	assert_eq!(errors.0[0].error.to_string(), "enemy died");
+	assert!(errors.0[0].system.contains("damage_enemies"));
+	assert!(errors.0[1].error.to_string().contains("resource"));
+	drop(errors);
+
+	// errors are handled the same way when the system is run by the schedule
+	world.add_system(damage_enemies);
+	world.run_schedule();
+	assert_eq!(world.system_errors().unwrap().0.len(), 3);
+
+	Ok(())
+}
+
+#[test]
+#[should_panic(expected = "enemy died")]
+fn fallible_systems_panic_by_default() {
+	let mut world = init_world().unwrap();
+	world.run_system(damage_enemies);
+	world.run_system(damage_enemies);
+}
+
+#[test]
+fn system_metas_describe_access() {
+	let mut world = World::new();
+	world.add_startup_system(take_resource);
+	world.add_system(damage_enemies);
+	world.add_system_to_stage(Stage::PostUpdate, test);
+
+	let metas = world.system_metas();
+	assert_eq!(metas.iter().map(|meta| meta.stage).collect::<Vec<_>>(), vec![Some(Stage::Startup), Some(Stage::Update), Some(Stage::PostUpdate)]);
+
+	let damage = &metas[1];
+	assert!(damage.name.contains("damage_enemies"));
+	assert!(damage.writes_component::<Health>());
+	// filters don't borrow the component
+	assert!(!damage.reads_component::<Enemy>());
+	assert_eq!(damage.writes().count(), 1);
+
+	let display = &metas[2];
+	assert!(display.reads_resource::<PlayerResource>() && !display.writes_resource::<PlayerResource>());
+	assert!(display.writes().next().is_none());
+	assert_eq!(display.reads().map(|access| access.name).collect::<Vec<_>>().len(), 3);
+	assert!(display.conflicts_with(damage));
+	assert!(!display.conflicts_with(&metas[0]));
+
+	let meta = SystemMeta::of(&test);
+	assert_eq!(meta.stage, None);
+	assert_eq!(meta.params.len(), 2);
+	assert_eq!(meta.access().count(), 3);
+}
@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+
+use sceller::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Position(i32);
+struct Velocity(i32);
+struct Frozen;
+
+fn changed_positions(world: &World) -> Vec<Entity> {
+    let ids = RefCell::new(Vec::new());
+    world.query().query_fn(|query: FnQuery<Changed<Position>>| {
+        *ids.borrow_mut() = query.iter_with_ids().map(|(entity, _)| entity).collect();
+    });
+    ids.into_inner()
+}
+
+fn movement(query: FnQuery<(&mut Position, &Velocity)>) {
+    for (mut pos, vel) in query {
+        pos.0 += vel.0;
+    }
+}
+
+#[test]
+fn run_n_advances_the_tick_once_per_run() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert(Position(0)).insert(Velocity(2));
+    let mut schedule = Schedule::new();
+    schedule.add_system(movement);
+
+    assert_eq!(world.tick(), 0);
+    assert!(world.get_resource::<Tick>().is_err());
+
+    schedule.run_n(&mut world, 5)?;
+    assert_eq!(world.tick(), 5);
+    assert_eq!(world.get_resource::<Tick>()?.0, 5);
+    assert_eq!(world.get::<&Position>(0)?.0, 10);
+
+    schedule.run_n(&mut world, 0)?;
+    assert_eq!(world.tick(), 5);
+
+    Ok(())
+}
+
+#[test]
+fn changes_are_seen_during_the_next_tick_only() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert(Position(0));
+    world.spawn().insert(Position(0));
+    world.spawn().insert(Position(0)).insert(Frozen);
+
+    // everything was inserted during tick 0
+    world.advance_tick();
+    assert_eq!(changed_positions(&world), vec![0, 1, 2]);
+    world.advance_tick();
+    assert!(changed_positions(&world).is_empty());
+
+    // reading doesn't count as a change, replacing and borrowing mutably do
+    let _ = world.get::<&Position>(0)?;
+    world.get::<&mut Position>(1)?.0 += 1;
+    world.insert_component_into_entity_checked(Position(5), 2)?;
+    world.advance_tick();
+    assert_eq!(changed_positions(&world), vec![1, 2]);
+
+    // changing a component again during the tick it is seen as changed doesn't hide it
+    world.get::<&mut Position>(1)?.0 += 1;
+    assert_eq!(changed_positions(&world), vec![1, 2]);
+    world.advance_tick();
+    assert_eq!(changed_positions(&world), vec![1]);
+    assert_eq!(world.changed_tick::<Position>(1), Some(3));
+    assert_eq!(world.changed_tick::<Velocity>(1), None);
+
+    Ok(())
+}
+
+#[test]
+fn every_mutable_access_is_a_change() -> eyre::Result<()> {
+    let mut world = World::new();
+    for _ in 0..4 {
+        world.spawn().insert(Position(0));
+    }
+    world.spawn().insert(Velocity(0));
+    world.advance_tick();
+    world.advance_tick();
+
+    for pos in world.query().auto_mut::<Position>().into_iter().take(1) {
+        drop(pos);
+    }
+    world.query().auto_mut::<Position>().get(1)?.0 += 1;
+    world.query().with_component::<Position>().run_entity()?[2].get_component_mut::<Position>()?.0 += 1;
+    world.run_system(|query: FnQuery<&mut Position>| {
+        query.for_each(|_| {});
+    });
+    world.advance_tick();
+    assert_eq!(changed_positions(&world), vec![0, 1, 2, 3]);
+
+    world.advance_tick();
+    world.run_system(|query: FnQuery<Or<(&mut Position, &Velocity)>>| {
+        let _ = query.get(3).unwrap();
+    });
+    world.advance_tick();
+    assert_eq!(changed_positions(&world), vec![3]);
+
+    world.advance_tick();
+    drop(world.column_mut::<Position>()?);
+    world.advance_tick();
+    assert_eq!(changed_positions(&world), vec![0, 1, 2, 3]);
+
+    Ok(())
+}
+
+#[test]
+fn changed_filters_or_and_cached_queries() -> eyre::Result<()> {
+    let mut world = World::new();
+    world.spawn().insert(Position(0)).insert(Velocity(0));
+    world.spawn().insert(Position(0)).insert(Velocity(0));
+    world.spawn().insert(Velocity(0));
+    world.advance_tick();
+    world.advance_tick();
+
+    world.get::<&mut Position>(0)?.0 = 1;
+    world.get::<&mut Velocity>(2)?.0 = 1;
+    world.advance_tick();
+
+    world.run_system(|query: FnQuery<Or<(Changed<Position>, Changed<Velocity>)>>| {
+        let changed = query.iter_with_ids().collect::<Vec<_>>();
+        assert_eq!(changed, vec![(0, (Some(()), None)), (2, (None, Some(())))]);
+    });
+
+    // a cached query looks for the changed entities again once the tick advances
+    assert_eq!(world.query_cached::<(&Velocity, Changed<Velocity>)>().len(), 1);
+    world.advance_tick();
+    assert_eq!(world.query_cached::<(&Velocity, Changed<Velocity>)>().len(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn run_steps_is_deterministic() -> eyre::Result<()> {
+    fn simulate() -> World {
+        let mut world = World::new();
+        world.spawn().insert(Position(0)).insert(Velocity(3));
+        world.spawn().insert(Position(1)).insert(Velocity(-1));
+        world.add_system(movement);
+        world.add_system_to_stage(Stage::FixedUpdate, |mut commands: Commands, tick: Res<Tick>| {
+            if tick.0.is_multiple_of(10) {
+                commands.spawn().insert(Position(tick.0 as i32));
+            }
+        });
+        world.run_steps(25);
+        world
+    }
+
+    let (first, second) = (simulate(), simulate());
+    let positions = |world: &World| world.query_typed::<&Position>().map(|pos| *pos).collect::<Vec<Position>>();
+    assert_eq!(positions(&first), vec![Position(75), Position(-24), Position(10), Position(20)]);
+    assert_eq!(positions(&first), positions(&second));
+    assert_eq!(first.tick(), 25);
+    assert_eq!(first.get_resource::<Time>()?.frame_count(), 25);
+
+    Ok(())
+}
+
+#[test]
+fn clearing_the_world_keeps_the_tick() {
+    let mut world = World::new();
+    world.spawn().insert(Position(0));
+    world.run_steps(3);
+
+    world.clear();
+    assert_eq!(world.tick(), 3);
+    world.spawn().insert(Position(0));
+    assert_eq!(world.changed_tick::<Position>(0), Some(3));
+}
@@ -12,6 +12,20 @@ fn test_debug() -> eyre::Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_debug_dump() -> eyre::Result<()> {
+    let mut world = init_world()?;
+    world.despawn(1);
+
+    let dump = world.to_string();
+    assert!(dump.starts_with("World: 2 entities, 1 archetypes, 3 component types, 1 resources"));
+    assert!(dump.contains("  Bar  2\n  Egg  0\n  Foo  2\n"));
+    assert!(dump.contains("  0  Bar, Foo\n  2  Bar, Foo\n"));
+    assert!(!dump.contains("  1  "));
+    assert!(dump.ends_with("Resources\n  CoolResource\n"));
+    Ok(())
+}
+
 #[test]
 fn test_queries() -> eyre::Result<()> {
     let world = init_world()?;
@@ -61,11 +75,14 @@ fn init_world() -> eyre::Result<World> {
     Ok(world)
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 struct Foo(u8);
+#[allow(dead_code)]
 #[derive(Debug)]
 struct Bar(u32);
 
+#[allow(dead_code)]
 #[derive(Debug)]
 struct Egg(char);
 
@@ -73,4 +90,6 @@ struct Egg(char);
 struct CoolResource {
     eggs: i32,
     egg_name: String,
-}
\ No newline at end of file
+}
+
+impl Resource for CoolResource {}
\ No newline at end of file